@@ -0,0 +1,286 @@
+//! The chain specification file.
+//!
+//! A declarative description of a chain, meant to be version-controlled so that appchain
+//! deployments are reproducible. It is generated by `katana init` and consumed by
+//! `katana --chain <PATH>`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use alloy_primitives::{Address, U256};
+use anyhow::{ensure, Context, Result};
+use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
+use katana_core::service::messaging::{
+    MessagingConfig, CONFIG_CHAIN_ETHEREUM, CONFIG_CHAIN_STARKNET,
+};
+use katana_primitives::chain::ChainId;
+use katana_primitives::chain_spec::{self, ChainSpec, FeeContracts, SettlementLayer};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::genesis::allocation::DevAllocationsGenerator;
+use katana_primitives::genesis::constant::{
+    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
+    DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::utils::{parse_genesis, parse_seed};
+
+/// The default file name of a chain specification file.
+pub const DEFAULT_CHAIN_SPEC_FILE: &str = "chain.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainSpecFile {
+    /// The chain id. Either a Cairo short string, or a `0x`-prefixed hex string.
+    pub id: String,
+    /// The fee token contracts.
+    pub fee_contracts: FeeContracts,
+    /// The predeployed, prefunded accounts.
+    pub accounts: AccountsConfig,
+    /// Path to a genesis file to build the genesis block from. Relative paths are resolved
+    /// against the directory of the chain specification file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis: Option<PathBuf>,
+    /// The settlement layer of the chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settlement: Option<SettlementLayer>,
+    /// The messaging configuration with the settlement chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messaging: Option<MessagingConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountsConfig {
+    /// The number of accounts to generate.
+    pub total: u16,
+    /// The seed used to generate the accounts.
+    pub seed: String,
+    /// The initial fee tokens balance of every account.
+    pub balance: U256,
+}
+
+impl Default for AccountsConfig {
+    fn default() -> Self {
+        Self {
+            total: 10,
+            seed: "0".to_string(),
+            balance: U256::from(DEFAULT_PREFUNDED_ACCOUNT_BALANCE),
+        }
+    }
+}
+
+impl ChainSpecFile {
+    /// Creates a new chain specification with the given chain id and default values for the rest.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            fee_contracts: FeeContracts {
+                eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+                strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+            },
+            accounts: AccountsConfig::default(),
+            genesis: None,
+            settlement: None,
+            messaging: None,
+        }
+    }
+
+    /// Load the chain specification from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read(path)
+            .with_context(|| format!("failed to read chain spec file {}", path.display()))?;
+
+        let mut spec: Self = serde_json::from_slice(&content)
+            .with_context(|| format!("failed to parse chain spec file {}", path.display()))?;
+
+        // resolve the genesis file path relative to the chain spec file
+        if let Some(genesis) = spec.genesis.as_mut() {
+            if genesis.is_relative() {
+                let base = path.parent().unwrap_or(Path::new("."));
+                *genesis = base.join(&genesis);
+            }
+        }
+
+        spec.validate().with_context(|| format!("invalid chain spec file {}", path.display()))?;
+        Ok(spec)
+    }
+
+    /// Checks that the chain id is valid, and that the messaging configuration targets the
+    /// settlement layer if both are set.
+    pub fn validate(&self) -> Result<()> {
+        self.id()?;
+
+        if let (Some(settlement), Some(messaging)) = (&self.settlement, &self.messaging) {
+            check_messaging(settlement, messaging)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the chain specification to a JSON file.
+    pub fn store(&self, path: impl AsRef<Path>) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Used as clap value parser for [ChainSpecFile].
+    pub fn parse(value: &str) -> Result<Self> {
+        let path = PathBuf::from(shellexpand::full(value)?.into_owned());
+        Self::load(path)
+    }
+
+    /// Returns the parsed chain id.
+    pub fn id(&self) -> Result<ChainId> {
+        ChainId::parse(&self.id).with_context(|| format!("invalid chain id '{}'", self.id))
+    }
+
+    /// Builds the [ChainSpec] described by this file.
+    pub fn chain_spec(&self) -> Result<ChainSpec> {
+        let mut chain_spec = chain_spec::DEV_UNALLOCATED.clone();
+
+        chain_spec.id = self.id()?;
+        chain_spec.fee_contracts = self.fee_contracts.clone();
+        chain_spec.settlement = self.settlement.clone();
+
+        if let Some(path) = &self.genesis {
+            let path = path.to_str().context("genesis path is not valid UTF-8")?;
+            chain_spec.genesis = parse_genesis(path)?;
+        } else {
+            chain_spec.genesis.sequencer_address = *DEFAULT_SEQUENCER_ADDRESS;
+        }
+
+        let accounts = DevAllocationsGenerator::new(self.accounts.total)
+            .with_seed(parse_seed(&self.accounts.seed))
+            .with_balance(self.accounts.balance)
+            .generate();
+
+        chain_spec.genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
+
+        Ok(chain_spec)
+    }
+}
+
+/// Ensures that the messaging configuration targets the settlement layer of the chain, which is
+/// where its messages are settled.
+pub fn check_messaging(settlement: &SettlementLayer, messaging: &MessagingConfig) -> Result<()> {
+    let (chain, rpc_url, same_contract) = match settlement {
+        SettlementLayer::Ethereum { rpc_url, core_contract } => {
+            let contract = Address::from_str(&messaging.contract_address);
+            (CONFIG_CHAIN_ETHEREUM, rpc_url, contract.is_ok_and(|c| c == *core_contract))
+        }
+        SettlementLayer::Starknet { rpc_url, core_contract } => {
+            let contract = Felt::from_str(&messaging.contract_address).map(ContractAddress::from);
+            (CONFIG_CHAIN_STARKNET, rpc_url, contract.is_ok_and(|c| c == *core_contract))
+        }
+    };
+
+    ensure!(
+        messaging.chain == chain,
+        "messaging chain '{}' is not the settlement chain '{chain}'",
+        messaging.chain
+    );
+    ensure!(
+        Url::parse(&messaging.rpc_url).is_ok_and(|url| url == *rpc_url),
+        "messaging RPC URL '{}' is not the settlement chain RPC URL '{rpc_url}'",
+        messaging.rpc_url
+    );
+    ensure!(
+        same_contract,
+        "messaging contract '{}' is not the settlement core contract",
+        messaging.contract_address
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::address;
+    use katana_primitives::contract::ContractAddress;
+
+    use super::*;
+
+    #[test]
+    fn store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_CHAIN_SPEC_FILE);
+
+        let mut spec = ChainSpecFile::new("MY_APPCHAIN");
+        spec.accounts.total = 3;
+        spec.fee_contracts.eth = address!("0x1337");
+        spec.settlement = Some(SettlementLayer::Starknet {
+            rpc_url: "http://localhost:5050".parse().unwrap(),
+            core_contract: address!("0x420"),
+        });
+
+        spec.store(&path).unwrap();
+        let loaded = ChainSpecFile::load(&path).unwrap();
+        assert_eq!(loaded, spec);
+
+        let chain_spec = loaded.chain_spec().unwrap();
+        assert_eq!(chain_spec.id, ChainId::parse("MY_APPCHAIN").unwrap());
+        assert_eq!(chain_spec.fee_contracts.eth, address!("0x1337"));
+        assert_eq!(chain_spec.genesis.accounts().count(), 3);
+        assert_eq!(chain_spec.settlement, spec.settlement);
+
+        // the fee token must be deployed at the configured address
+        let states = chain_spec.state_updates();
+        assert!(states.state_updates.deployed_contracts.contains_key(&address!("0x1337")));
+    }
+
+    #[test]
+    fn messaging_must_target_settlement_layer() {
+        let mut spec = ChainSpecFile::new("MY_APPCHAIN");
+        spec.settlement = Some(SettlementLayer::Starknet {
+            rpc_url: "http://localhost:5050".parse().unwrap(),
+            core_contract: address!("0x420"),
+        });
+
+        let mut messaging = MessagingConfig {
+            chain: "starknet".to_string(),
+            rpc_url: "http://localhost:5050".to_string(),
+            contract_address: "0x420".to_string(),
+            ..Default::default()
+        };
+        spec.messaging = Some(messaging.clone());
+        spec.validate().unwrap();
+
+        messaging.contract_address = "0x421".to_string();
+        spec.messaging = Some(messaging.clone());
+        assert!(spec.validate().is_err());
+
+        messaging.contract_address = "0x420".to_string();
+        messaging.chain = "ethereum".to_string();
+        spec.messaging = Some(messaging.clone());
+        assert!(spec.validate().is_err());
+
+        messaging.chain = "starknet".to_string();
+        messaging.rpc_url = "http://localhost:5051".to_string();
+        spec.messaging = Some(messaging);
+        assert!(spec.validate().is_err());
+
+        // a file with an inconsistent messaging configuration is rejected
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_CHAIN_SPEC_FILE);
+        spec.store(&path).unwrap();
+        assert!(ChainSpecFile::load(&path).is_err());
+    }
+
+    #[test]
+    fn resolve_relative_genesis_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_CHAIN_SPEC_FILE);
+
+        let mut spec = ChainSpecFile::new("0x1234");
+        spec.genesis = Some(PathBuf::from("genesis.json"));
+        spec.store(&path).unwrap();
+
+        let loaded = ChainSpecFile::load(&path).unwrap();
+        assert_eq!(loaded.genesis, Some(dir.path().join("genesis.json")));
+    }
+}
@@ -0,0 +1,442 @@
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::{Args, ValueEnum};
+use katana_core::service::messaging::{
+    MessagingConfig, CONFIG_CHAIN_ETHEREUM, CONFIG_CHAIN_STARKNET,
+};
+use katana_primitives::chain::ChainId;
+use katana_primitives::chain_spec::SettlementLayer;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::Felt;
+use url::Url;
+
+use super::chain_spec::{check_messaging, ChainSpecFile, DEFAULT_CHAIN_SPEC_FILE};
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    #[arg(short, long)]
+    #[arg(value_name = "PATH")]
+    #[arg(default_value = DEFAULT_CHAIN_SPEC_FILE)]
+    #[arg(help = "Path of the chain specification file to generate.")]
+    output: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "Overwrite the output file if it already exists.")]
+    force: bool,
+
+    #[arg(long)]
+    #[arg(help = "The chain ID.")]
+    #[arg(long_help = "The chain ID. If a raw hex string (`0x` prefix) is provided, then it'd \
+                       used as the actual chain ID. Otherwise, it's represented as the raw \
+                       ASCII values. If not provided, all values that weren't passed as \
+                       arguments are prompted interactively.")]
+    id: Option<String>,
+
+    #[arg(long = "fee-token.eth", value_name = "ADDRESS")]
+    #[arg(help = "The address of the ETH fee token.")]
+    eth_fee_token: Option<Felt>,
+
+    #[arg(long = "fee-token.strk", value_name = "ADDRESS")]
+    #[arg(help = "The address of the STRK fee token.")]
+    strk_fee_token: Option<Felt>,
+
+    #[arg(long = "accounts", value_name = "NUM")]
+    #[arg(help = "Number of pre-funded accounts to generate.")]
+    total_accounts: Option<u16>,
+
+    #[arg(long)]
+    #[arg(help = "Specify the seed for randomness of accounts to be predeployed.")]
+    seed: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    #[arg(help = "Path to the genesis file to reference from the chain specification.")]
+    genesis: Option<PathBuf>,
+
+    #[arg(long = "settlement.chain", value_enum)]
+    #[arg(requires_all = ["settlement_rpc_url", "settlement_core_contract"])]
+    #[arg(help = "The settlement chain.")]
+    settlement_chain: Option<SettlementChain>,
+
+    #[arg(long = "settlement.rpc-url", value_name = "URL")]
+    #[arg(requires = "settlement_chain")]
+    #[arg(help = "The RPC URL of the settlement chain.")]
+    settlement_rpc_url: Option<Url>,
+
+    #[arg(long = "settlement.core-contract", value_name = "ADDRESS")]
+    #[arg(requires = "settlement_chain")]
+    #[arg(help = "The address of the core contract on the settlement chain.")]
+    settlement_core_contract: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    #[arg(value_parser = MessagingConfig::parse)]
+    #[arg(help = "Path to the messaging configuration to embed in the chain specification.")]
+    messaging: Option<MessagingConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SettlementChain {
+    Ethereum,
+    Starknet,
+}
+
+impl InitArgs {
+    pub fn execute(self) -> Result<()> {
+        if self.output.exists() && !self.force {
+            bail!("{} already exists, use `--force` to overwrite it", self.output.display());
+        }
+
+        let spec = if self.id.is_some() { self.spec()? } else { self.prompt()? };
+        spec.store(&self.output)?;
+
+        println!("Chain specification written to {}", self.output.display());
+        println!("Start the chain with: katana --chain {}", self.output.display());
+
+        Ok(())
+    }
+
+    /// Builds the chain specification solely from the arguments.
+    fn spec(&self) -> Result<ChainSpecFile> {
+        let id = self.id.clone().context("missing chain id")?;
+        let mut spec = ChainSpecFile::new(id);
+        spec.id()?;
+
+        if let Some(address) = self.eth_fee_token {
+            spec.fee_contracts.eth = address.into();
+        }
+        if let Some(address) = self.strk_fee_token {
+            spec.fee_contracts.strk = address.into();
+        }
+        if let Some(total) = self.total_accounts {
+            spec.accounts.total = total;
+        }
+        if let Some(seed) = &self.seed {
+            spec.accounts.seed = seed.clone();
+        }
+
+        spec.genesis = self.genesis.clone();
+        spec.messaging = self.messaging.clone();
+
+        if let Some(chain) = self.settlement_chain {
+            // clap guarantees both are present when the settlement chain is set
+            let rpc_url = self.settlement_rpc_url.clone().expect("required by clap");
+            let core_contract = self.settlement_core_contract.as_deref().expect("required by clap");
+            spec.settlement = Some(settlement_layer(chain, rpc_url, core_contract)?);
+        }
+
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Builds the chain specification by prompting for the values that weren't provided as
+    /// arguments.
+    fn prompt(&self) -> Result<ChainSpecFile> {
+        let mut prompt = Prompt::new(io::stdin().lock(), io::stdout());
+
+        let settlement = match self.settlement_chain {
+            Some(chain) => {
+                let rpc_url = self.settlement_rpc_url.clone().expect("required by clap");
+                let contract = self.settlement_core_contract.as_deref().expect("required by clap");
+                Some(settlement_layer(chain, rpc_url, contract)?)
+            }
+            None => None,
+        };
+
+        // fail before asking anything if both were passed as arguments
+        if let (Some(settlement), Some(messaging)) = (&settlement, &self.messaging) {
+            check_messaging(settlement, messaging)?;
+        }
+
+        let id = prompt.ask_with("Chain id", None, |id| {
+            ChainId::parse(&id).with_context(|| format!("invalid chain id '{id}'"))?;
+            Ok(id)
+        })?;
+
+        let mut spec = ChainSpecFile::new(id);
+
+        spec.fee_contracts.eth = match self.eth_fee_token {
+            Some(address) => address.into(),
+            None => prompt.ask_address("ETH fee token address", spec.fee_contracts.eth)?,
+        };
+
+        spec.fee_contracts.strk = match self.strk_fee_token {
+            Some(address) => address.into(),
+            None => prompt.ask_address("STRK fee token address", spec.fee_contracts.strk)?,
+        };
+
+        spec.accounts.total = match self.total_accounts {
+            Some(total) => total,
+            None => prompt.ask_parse("Number of prefunded accounts", spec.accounts.total)?,
+        };
+
+        spec.accounts.seed = match &self.seed {
+            Some(seed) => seed.clone(),
+            None => prompt.ask("Accounts seed", Some(&spec.accounts.seed))?,
+        };
+
+        spec.genesis = match &self.genesis {
+            Some(path) => Some(path.clone()),
+            None => prompt.ask_optional("Genesis file (optional)")?.map(PathBuf::from),
+        };
+
+        spec.settlement = match settlement {
+            Some(settlement) => Some(settlement),
+            None => prompt_settlement_layer(&mut prompt, self.messaging.as_ref())?,
+        };
+
+        spec.messaging = match &self.messaging {
+            Some(config) => Some(config.clone()),
+            None => {
+                prompt.ask_optional_with("Messaging configuration file (optional)", |path| {
+                    let config = MessagingConfig::load(&path).with_context(|| {
+                        format!("failed to load messaging configuration from {path}")
+                    })?;
+
+                    if let Some(settlement) = &spec.settlement {
+                        check_messaging(settlement, &config)?;
+                    }
+
+                    Ok(config)
+                })?
+            }
+        };
+
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+/// Prompts for the settlement layer. If the messaging configuration is already known, every
+/// answer is checked against it as soon as it is given.
+fn prompt_settlement_layer<R: BufRead, W: Write>(
+    prompt: &mut Prompt<R, W>,
+    messaging: Option<&MessagingConfig>,
+) -> Result<Option<SettlementLayer>> {
+    let question = "Settlement chain, `ethereum` or `starknet` (optional)";
+    let Some(chain) = prompt.ask_optional_with(question, |chain| {
+        let chain =
+            <SettlementChain as ValueEnum>::from_str(&chain, true).map_err(anyhow::Error::msg)?;
+
+        if let Some(messaging) = messaging {
+            let name = match chain {
+                SettlementChain::Ethereum => CONFIG_CHAIN_ETHEREUM,
+                SettlementChain::Starknet => CONFIG_CHAIN_STARKNET,
+            };
+            ensure!(messaging.chain == name, "messaging chain is '{}'", messaging.chain);
+        }
+
+        Ok(chain)
+    })?
+    else {
+        return Ok(None);
+    };
+
+    let rpc_url: Url = prompt.ask_with("Settlement chain RPC URL", None, |v| {
+        let url: Url = v.parse()?;
+
+        if let Some(messaging) = messaging {
+            let same_url = Url::parse(&messaging.rpc_url).is_ok_and(|u| u == url);
+            ensure!(same_url, "messaging RPC URL is '{}'", messaging.rpc_url);
+        }
+
+        Ok(url)
+    })?;
+
+    let settlement = prompt.ask_with("Settlement core contract address", None, |address| {
+        let settlement = settlement_layer(chain, rpc_url.clone(), &address)?;
+
+        if let Some(messaging) = messaging {
+            check_messaging(&settlement, messaging)?;
+        }
+
+        Ok(settlement)
+    })?;
+
+    Ok(Some(settlement))
+}
+
+fn settlement_layer(
+    chain: SettlementChain,
+    rpc_url: Url,
+    core_contract: &str,
+) -> Result<SettlementLayer> {
+    Ok(match chain {
+        SettlementChain::Ethereum => SettlementLayer::Ethereum {
+            rpc_url,
+            core_contract: core_contract.parse().context("invalid Ethereum address")?,
+        },
+        SettlementChain::Starknet => SettlementLayer::Starknet {
+            rpc_url,
+            core_contract: ContractAddress::from(
+                Felt::from_str(core_contract).context("invalid Starknet address")?,
+            ),
+        },
+    })
+}
+
+/// A minimal line-based prompt.
+struct Prompt<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer }
+    }
+
+    /// Reads a single trimmed line. Returns `None` if the line is empty.
+    fn read_line(&mut self, question: &str, default: Option<&str>) -> Result<Option<String>> {
+        match default {
+            Some(default) => write!(self.writer, "{question} [{default}]: ")?,
+            None => write!(self.writer, "{question}: ")?,
+        }
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            bail!("unexpected end of input");
+        }
+
+        let line = line.trim();
+        Ok(if line.is_empty() { None } else { Some(line.to_string()) })
+    }
+
+    /// Asks until a valid answer is given, validating it with `f`.
+    fn ask_with<T>(
+        &mut self,
+        question: &str,
+        default: Option<&str>,
+        mut f: impl FnMut(String) -> Result<T>,
+    ) -> Result<T> {
+        loop {
+            let answer = match (self.read_line(question, default)?, default) {
+                (Some(answer), _) => answer,
+                (None, Some(default)) => default.to_string(),
+                (None, None) => continue,
+            };
+
+            match f(answer) {
+                Ok(value) => return Ok(value),
+                Err(error) => writeln!(self.writer, "Invalid value: {error:#}")?,
+            }
+        }
+    }
+
+    fn ask(&mut self, question: &str, default: Option<&str>) -> Result<String> {
+        self.ask_with(question, default, Ok)
+    }
+
+    fn ask_optional(&mut self, question: &str) -> Result<Option<String>> {
+        self.ask_optional_with(question, Ok)
+    }
+
+    /// Asks until either a valid answer or no answer is given, validating it with `f`.
+    fn ask_optional_with<T>(
+        &mut self,
+        question: &str,
+        mut f: impl FnMut(String) -> Result<T>,
+    ) -> Result<Option<T>> {
+        loop {
+            let Some(answer) = self.read_line(question, None)? else {
+                return Ok(None);
+            };
+
+            match f(answer) {
+                Ok(value) => return Ok(Some(value)),
+                Err(error) => writeln!(self.writer, "Invalid value: {error:#}")?,
+            }
+        }
+    }
+
+    fn ask_parse<T>(&mut self, question: &str, default: T) -> Result<T>
+    where
+        T: FromStr + ToString,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let default = default.to_string();
+        self.ask_with(question, Some(&default), |value| Ok(value.parse::<T>()?))
+    }
+
+    fn ask_address(&mut self, question: &str, default: ContractAddress) -> Result<ContractAddress> {
+        let default = format!("{:#x}", default.0);
+        self.ask_with(question, Some(&default), |value| Ok(Felt::from_str(&value)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_with_defaults() {
+        let input = b"\nfoo\n1\n42\n" as &[u8];
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        // an empty answer falls back to the default value
+        assert_eq!(prompt.ask_parse("accounts", 10u16).unwrap(), 10);
+        // an invalid answer is asked again
+        assert_eq!(prompt.ask_parse("accounts", 10u16).unwrap(), 1);
+        assert_eq!(prompt.ask_parse("accounts", 10u16).unwrap(), 42);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("accounts [10]: "));
+        assert!(output.contains("Invalid value"));
+    }
+
+    #[test]
+    fn prompt_settlement() {
+        // an invalid chain and core contract address are asked again
+        let input = b"solana\nstarknet\nhttp://localhost:5050\nnot-an-address\n0x420\n" as &[u8];
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let settlement = prompt_settlement_layer(&mut prompt, None).unwrap();
+        assert_eq!(
+            settlement,
+            Some(SettlementLayer::Starknet {
+                rpc_url: Url::parse("http://localhost:5050").unwrap(),
+                core_contract: ContractAddress::from(Felt::from(0x420)),
+            })
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Invalid value").count(), 2);
+
+        // no settlement chain is set if the question isn't answered
+        let mut prompt = Prompt::new(b"\n" as &[u8], Vec::new());
+        assert_eq!(prompt_settlement_layer(&mut prompt, None).unwrap(), None);
+    }
+
+    #[test]
+    fn prompt_settlement_against_messaging() {
+        let messaging = MessagingConfig {
+            chain: CONFIG_CHAIN_STARKNET.to_string(),
+            rpc_url: "http://localhost:5050".to_string(),
+            contract_address: "0x420".to_string(),
+            ..Default::default()
+        };
+
+        // every answer that doesn't match the messaging configuration is asked again
+        let input =
+            b"ethereum\nstarknet\nhttp://localhost:6060\nhttp://localhost:5050\n0x1\n0x420\n"
+                as &[u8];
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input, &mut output);
+
+        let settlement = prompt_settlement_layer(&mut prompt, Some(&messaging)).unwrap();
+        assert_eq!(
+            settlement,
+            Some(SettlementLayer::Starknet {
+                rpc_url: Url::parse("http://localhost:5050").unwrap(),
+                core_contract: ContractAddress::from(Felt::from(0x420)),
+            })
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Invalid value").count(), 3);
+    }
+}
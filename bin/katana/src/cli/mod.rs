@@ -1,4 +1,6 @@
+mod chain_spec;
 mod db;
+mod init;
 mod node;
 
 use anyhow::Result;
@@ -22,6 +24,7 @@ impl Cli {
             return match cmd {
                 Commands::Completions(args) => args.execute(),
                 Commands::Db(args) => args.execute(),
+                Commands::Init(args) => args.execute(),
             };
         }
 
@@ -36,6 +39,9 @@ enum Commands {
 
     #[command(about = "Database utilities")]
     Db(db::DbArgs),
+
+    #[command(about = "Generate a chain specification file to start a chain from")]
+    Init(init::InitArgs),
 }
 
 #[derive(Debug, Args)]
@@ -20,6 +20,7 @@ use clap::{Args, Parser};
 use console::Style;
 use dojo_utils::parse::parse_socket_address;
 use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
+use katana_core::service::da::DataAvailabilityConfig;
 use katana_core::service::messaging::MessagingConfig;
 use katana_node::config::db::DbConfig;
 use katana_node::config::dev::{DevConfig, FixedL1GasPriceConfig};
@@ -34,7 +35,7 @@ use katana_node::config::rpc::{
 use katana_node::config::{Config, SequencingConfig};
 use katana_primitives::block::{BlockHashOrNumber, GasPrices};
 use katana_primitives::chain::ChainId;
-use katana_primitives::chain_spec::{self, ChainSpec};
+use katana_primitives::chain_spec::{self, ChainSpec, SettlementLayer};
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::genesis::allocation::{DevAllocationsGenerator, GenesisAccountAlloc};
@@ -43,11 +44,13 @@ use katana_primitives::genesis::constant::{
     DEFAULT_PREFUNDED_ACCOUNT_BALANCE, DEFAULT_UDC_ADDRESS,
 };
 use katana_primitives::genesis::Genesis;
-use tracing::{info, Subscriber};
+use katana_primitives::utils::class_cache::{ClassCache, CLASS_CACHE_DIR_ENV};
+use tracing::{info, warn, Subscriber};
 use tracing_log::LogTracer;
 use tracing_subscriber::{fmt, EnvFilter};
 use url::Url;
 
+use super::chain_spec::{check_messaging, ChainSpecFile};
 use crate::utils::{parse_block_hash_or_number, parse_genesis, parse_seed};
 
 #[derive(Parser, Debug)]
@@ -74,6 +77,14 @@ pub struct NodeArgs {
                        initialized Katana database.")]
     pub db_dir: Option<PathBuf>,
 
+    #[arg(long, env = CLASS_CACHE_DIR_ENV)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Directory path of the compiled class cache.")]
+    #[arg(long_help = "Directory path of the compiled class cache. Sierra classes compiled to \
+                       CASM are stored there and reused across restarts, instead of being \
+                       compiled again. The directory is created if it doesn't exist yet.")]
+    pub class_cache_dir: Option<PathBuf>,
+
     #[arg(long = "fork.rpc-url", value_name = "URL", alias = "rpc-url")]
     #[arg(help = "The Starknet RPC provider to fork the network from.")]
     pub fork_rpc_url: Option<Url>,
@@ -107,6 +118,18 @@ pub struct NodeArgs {
                        The configuration file details and examples can be found here: https://book.dojoengine.org/toolchain/katana/reference#messaging")]
     pub messaging: Option<MessagingConfig>,
 
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(value_parser = katana_core::service::da::DataAvailabilityConfig::parse)]
+    #[arg(help = "Publish the state diff of every block as EIP-4844 blobs.")]
+    #[arg(long_help = "Publish the state diff of every mined block as EIP-4844 blobs, along \
+                       with their KZG commitments, to the sink described in the given JSON \
+                       configuration file. The sink can either be a local directory (`{ \
+                       \"type\": \"file\", \"path\": \"...\" }`) or an HTTP endpoint (`{ \
+                       \"type\": \"http\", \"url\": \"...\" }`). Set `checkpoint` to a file \
+                       path to resume from the last published block after a restart.")]
+    pub da: Option<DataAvailabilityConfig>,
+
     #[command(flatten)]
     #[command(next_help_heading = "Server options")]
     pub server: ServerOptions,
@@ -173,6 +196,16 @@ pub struct StarknetOptions {
     #[arg(value_parser = parse_genesis)]
     #[arg(conflicts_with_all(["fork_rpc_url", "seed", "total_accounts"]))]
     pub genesis: Option<Genesis>,
+
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(value_parser = ChainSpecFile::parse)]
+    #[arg(conflicts_with_all(["genesis", "fork_rpc_url", "seed", "total_accounts", "chain_id"]))]
+    #[arg(help = "Start the chain from a chain specification file.")]
+    #[arg(long_help = "Start the chain from a chain specification file, as generated by `katana \
+                       init`. The file describes the chain id, fee tokens, prefunded accounts, \
+                       genesis, settlement layer and messaging configuration of the chain.")]
+    pub chain: Option<ChainSpecFile>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -227,6 +260,20 @@ pub(crate) const LOG_TARGET: &str = "katana::cli";
 impl NodeArgs {
     pub fn execute(self) -> Result<()> {
         self.init_logging()?;
+
+        if let Some(dir) = &self.class_cache_dir {
+            let cache = ClassCache::new(dir).context("failed to open the class cache directory")?;
+            // The cache may only have been set already if the node is started more than once in
+            // the same process, in which case it keeps the first directory.
+            if ClassCache::set_global(cache).is_err() {
+                warn!(
+                    target: LOG_TARGET,
+                    dir = %dir.display(),
+                    "Class cache already set, ignoring the given directory."
+                );
+            }
+        }
+
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
@@ -264,7 +311,7 @@ impl NodeArgs {
     fn init_logging(&self) -> Result<()> {
         const DEFAULT_LOG_FILTER: &str = "info,tasks=debug,executor=trace,forking::backend=trace,\
                                           server=debug,blockifier=off,jsonrpsee_server=off,\
-                                          hyper=off,messaging=debug,node=error";
+                                          hyper=off,messaging=debug,da=debug,node=error";
 
         LogTracer::init()?;
 
@@ -290,9 +337,10 @@ impl NodeArgs {
         let forking = self.forking_config()?;
         let execution = self.execution_config();
         let sequencing = self.sequencer_config();
-        let messaging = self.messaging.clone();
+        let messaging = self.messaging_config(&chain)?;
+        let da = self.da.clone();
 
-        Ok(Config { metrics, db, dev, rpc, chain, execution, sequencing, messaging, forking })
+        Ok(Config { metrics, db, dev, rpc, chain, execution, sequencing, messaging, da, forking })
     }
 
     fn sequencer_config(&self) -> SequencingConfig {
@@ -301,9 +349,10 @@ impl NodeArgs {
 
     fn rpc_config(&self) -> RpcConfig {
         let mut apis = HashSet::from([ApiKind::Starknet, ApiKind::Torii, ApiKind::Saya]);
-        // only enable `katana` API in dev mode
+        // only enable `dev` and `katana` APIs in dev mode
         if self.dev {
             apis.insert(ApiKind::Dev);
+            apis.insert(ApiKind::Katana);
         }
 
         RpcConfig {
@@ -315,7 +364,30 @@ impl NodeArgs {
         }
     }
 
+    fn messaging_config(&self, chain: &ChainSpec) -> Result<Option<MessagingConfig>> {
+        let from_chain_spec = self.starknet.chain.as_ref().and_then(|c| c.messaging.clone());
+        let messaging = self.messaging.clone().or(from_chain_spec);
+
+        if let (Some(settlement), Some(messaging)) = (&chain.settlement, &messaging) {
+            check_messaging(settlement, messaging)?;
+        }
+
+        Ok(messaging)
+    }
+
     fn chain_spec(&self) -> Result<ChainSpec> {
+        if let Some(file) = &self.starknet.chain {
+            #[allow(unused_mut)]
+            let mut chain_spec = file.chain_spec()?;
+
+            #[cfg(feature = "slot")]
+            if self.slot.controller {
+                katana_slot_controller::add_controller_account(&mut chain_spec.genesis)?;
+            }
+
+            return Ok(chain_spec);
+        }
+
         let mut chain_spec = chain_spec::DEV_UNALLOCATED.clone();
 
         if let Some(id) = self.starknet.environment.chain_id {
@@ -405,7 +477,11 @@ impl NodeArgs {
 fn print_intro(args: &NodeArgs, chain: &ChainSpec) {
     let mut accounts = chain.genesis.accounts().peekable();
     let account_class_hash = accounts.peek().map(|e| e.1.class_hash());
-    let seed = &args.starknet.seed;
+    // the seed of the accounts generated from a chain specification file is the one in the file
+    let seed = match &args.starknet.chain {
+        Some(file) => &file.accounts.seed,
+        None => &args.starknet.seed,
+    };
 
     if args.json_log {
         info!(
@@ -414,6 +490,7 @@ fn print_intro(args: &NodeArgs, chain: &ChainSpec) {
             serde_json::json!({
                 "accounts": accounts.map(|a| serde_json::json!(a)).collect::<Vec<_>>(),
                 "seed": format!("{}", seed),
+                "settlement": chain.settlement,
             })
         )
     } else {
@@ -436,6 +513,10 @@ fn print_intro(args: &NodeArgs, chain: &ChainSpec) {
         print_genesis_contracts(chain, account_class_hash);
         print_genesis_accounts(accounts);
 
+        if let Some(settlement) = &chain.settlement {
+            print_settlement_layer(settlement);
+        }
+
         println!(
             r"
 
@@ -483,6 +564,28 @@ PREDEPLOYED CONTRACTS
     }
 }
 
+fn print_settlement_layer(settlement: &SettlementLayer) {
+    let (chain, rpc_url, core_contract) = match settlement {
+        SettlementLayer::Ethereum { rpc_url, core_contract } => {
+            ("Ethereum", rpc_url, core_contract.to_string())
+        }
+        SettlementLayer::Starknet { rpc_url, core_contract } => {
+            ("Starknet", rpc_url, core_contract.to_string())
+        }
+    };
+
+    println!(
+        r"
+
+SETTLEMENT LAYER
+==================
+
+| Chain           | {chain}
+| RPC URL         | {rpc_url}
+| Core Contract   | {core_contract}"
+    );
+}
+
 fn print_genesis_accounts<'a, Accounts>(accounts: Accounts)
 where
     Accounts: Iterator<Item = (&'a ContractAddress, &'a GenesisAccountAlloc)>,
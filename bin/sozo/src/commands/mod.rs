@@ -21,6 +21,7 @@ pub(crate) mod migrate;
 pub(crate) mod model;
 pub(crate) mod options;
 pub(crate) mod test;
+pub(crate) mod trace;
 
 use build::BuildArgs;
 use call::CallArgs;
@@ -33,6 +34,7 @@ use inspect::InspectArgs;
 use migrate::MigrateArgs;
 use model::ModelArgs;
 use test::TestArgs;
+use trace::TraceArgs;
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
@@ -63,6 +65,8 @@ pub enum Commands {
     Model(Box<ModelArgs>),
     #[command(about = "Inspect events emitted by the world")]
     Events(Box<EventsArgs>),
+    #[command(about = "Print the call tree of a transaction with the storage it accessed")]
+    Trace(Box<TraceArgs>),
 }
 
 impl fmt::Display for Commands {
@@ -81,6 +85,7 @@ impl fmt::Display for Commands {
             Commands::Init(_) => write!(f, "Init"),
             Commands::Model(_) => write!(f, "Model"),
             Commands::Events(_) => write!(f, "Events"),
+            Commands::Trace(_) => write!(f, "Trace"),
         }
     }
 }
@@ -107,6 +112,7 @@ pub fn run(command: Commands, config: &Config) -> Result<()> {
         Commands::Init(args) => args.run(config),
         Commands::Model(args) => args.run(config),
         Commands::Events(args) => args.run(config),
+        Commands::Trace(args) => args.run(config),
     }
 }
 
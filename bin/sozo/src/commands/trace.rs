@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use dojo_world::contracts::ContractInfo;
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_rpc_api::katana::KatanaApiClient;
+use katana_rpc_types::trace::{CallFrame, DetailedTxTrace};
+use scarb::core::Config;
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::Felt;
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+
+#[derive(Debug, Args)]
+#[command(about = "Print the call tree of a transaction with the storage it accessed. Requires a \
+                   Katana node running in dev mode.")]
+pub struct TraceArgs {
+    #[arg(help = "The hash of the transaction to trace.")]
+    pub transaction_hash: Felt,
+
+    #[arg(long)]
+    #[arg(help = "Print the trace as raw json")]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+}
+
+impl TraceArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let profile_config = ws.load_profile_config()?;
+
+        // Tags of the local contracts are used to give a readable name to the addresses.
+        let tags: HashMap<Felt, String> = match ws.read_manifest_profile()? {
+            Some(manifest) => {
+                let contracts: HashMap<String, ContractInfo> = (&manifest).into();
+                contracts.into_values().map(|c| (c.address, c.tag)).collect()
+            }
+            None => HashMap::new(),
+        };
+
+        config.tokio_handle().block_on(async {
+            let url = self.starknet.url(profile_config.env.as_ref())?;
+            let client = HttpClientBuilder::default().build(url.as_str())?;
+
+            let trace = client.trace_transaction_detailed(self.transaction_hash).await?;
+
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&trace)?);
+            } else {
+                print!("{}", render_trace(&trace, &tags));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Renders a [`DetailedTxTrace`] as a tree, one section per transaction stage.
+fn render_trace(trace: &DetailedTxTrace, tags: &HashMap<Felt, String>) -> String {
+    let mut out = String::new();
+
+    let block = trace.block_number.map(|n| n.to_string()).unwrap_or_else(|| "pending".to_string());
+
+    let _ = writeln!(out, "{} {:#066x}", "Transaction".bright_black(), trace.transaction_hash);
+    let _ = writeln!(out, "{} {}", "Block".bright_black(), block);
+    let _ = writeln!(
+        out,
+        "{} {} steps, {} l1 gas, {} l1 data gas, {} fee",
+        "Resources".bright_black(),
+        trace.n_steps,
+        trace.l1_gas,
+        trace.l1_data_gas,
+        trace.actual_fee
+    );
+
+    if let Some(error) = &trace.revert_error {
+        let _ = writeln!(out, "{} {}", "Reverted".red(), error);
+    }
+
+    let stages = [
+        ("validate", &trace.validate_invocation),
+        ("execute", &trace.execute_invocation),
+        ("fee transfer", &trace.fee_transfer_invocation),
+    ];
+
+    for (stage, frame) in stages {
+        if let Some(frame) = frame {
+            let _ = writeln!(out, "\n{}", stage.bold());
+            render_frame(&mut out, frame, tags, "");
+        }
+    }
+
+    out
+}
+
+fn render_frame(out: &mut String, frame: &CallFrame, tags: &HashMap<Felt, String>, indent: &str) {
+    let address: Felt = frame.contract_address.into();
+    let contract = tags.get(&address).cloned().unwrap_or_else(|| format!("{:#x}", address));
+    let function = frame.function_name.clone().unwrap_or_else(|| format!("{:#x}", frame.selector));
+
+    let status = if frame.failed { " FAILED".red().to_string() } else { String::new() };
+    let _ = writeln!(
+        out,
+        "{indent}{}::{}{} {}",
+        contract.green(),
+        function.cyan(),
+        status,
+        format!("({} steps, {} gas)", frame.n_steps, frame.gas_consumed).bright_black()
+    );
+
+    let child = format!("{indent}  ");
+
+    // The values the node can't derive from the trace are printed as `?`.
+    let value = |v: Option<Felt>| v.map(|v| format!("{v:#x}")).unwrap_or_else(|| "?".to_string());
+    for access in &frame.storage {
+        let (before, after) = (value(access.before), value(access.after));
+        let _ = match access.is_changed() {
+            Some(true) => {
+                writeln!(
+                    out,
+                    "{child}{} {:#x}: {before} -> {after}",
+                    "changed".yellow(),
+                    access.key
+                )
+            }
+            Some(false) => {
+                writeln!(out, "{child}{} {:#x}: {after}", "unchanged".blue(), access.key)
+            }
+            None => writeln!(out, "{child}{} {:#x}: {before} -> {after}", "accessed", access.key),
+        };
+    }
+
+    for event in &frame.events {
+        let keys = event.keys.iter().map(|k| format!("{:#x}", k)).collect::<Vec<_>>().join(", ");
+        let data = event.data.iter().map(|d| format!("{:#x}", d)).collect::<Vec<_>>().join(", ");
+        let _ = writeln!(out, "{child}{} keys: [{keys}] data: [{data}]", "event".magenta());
+    }
+
+    for call in &frame.calls {
+        render_frame(out, call, tags, &child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::trace::{CallType, EntryPointType};
+    use katana_primitives::transaction::TxType;
+    use katana_rpc_types::trace::StorageAccess;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn frame(address: Felt, function_name: Option<&str>, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            contract_address: address.into(),
+            caller_address: Felt::ZERO.into(),
+            class_hash: None,
+            call_type: CallType::Call,
+            entry_point_type: EntryPointType::External,
+            selector: felt!("0x1234"),
+            function_name: function_name.map(|n| n.to_string()),
+            calldata: vec![],
+            retdata: vec![],
+            events: vec![],
+            storage: vec![
+                StorageAccess {
+                    key: felt!("0x10"),
+                    before: Some(felt!("0x1")),
+                    after: Some(felt!("0x2")),
+                },
+                StorageAccess {
+                    key: felt!("0x11"),
+                    before: Some(felt!("0x3")),
+                    after: Some(felt!("0x3")),
+                },
+                StorageAccess { key: felt!("0x12"), before: Some(felt!("0x4")), after: None },
+            ],
+            storage_read_values: vec![],
+            n_steps: 42,
+            gas_consumed: 0,
+            failed: false,
+            calls,
+        }
+    }
+
+    #[test]
+    fn render_trace_tree() {
+        colored::control::set_override(false);
+
+        let inner = frame(felt!("0xb"), None, vec![]);
+        let outer = frame(felt!("0xa"), Some("spawn"), vec![inner]);
+
+        let trace = DetailedTxTrace {
+            transaction_hash: felt!("0x99"),
+            block_number: Some(3),
+            r#type: TxType::Invoke,
+            actual_fee: 0,
+            l1_gas: 0,
+            l1_data_gas: 0,
+            n_steps: 84,
+            revert_error: None,
+            validate_invocation: None,
+            execute_invocation: Some(outer),
+            fee_transfer_invocation: None,
+        };
+
+        let tags = HashMap::from([(felt!("0xa"), "ns-actions".to_string())]);
+        let rendered = render_trace(&trace, &tags);
+
+        assert!(rendered.contains("Block 3"));
+        assert!(rendered.contains("ns-actions::spawn (42 steps, 0 gas)"));
+        assert!(rendered.contains("  changed 0x10: 0x1 -> 0x2"));
+        assert!(rendered.contains("  unchanged 0x11: 0x3"));
+        assert!(rendered.contains("  accessed 0x12: 0x4 -> ?"));
+        assert!(rendered.contains("  0xb::0x1234 (42 steps, 0 gas)"));
+        assert!(!rendered.contains("validate"));
+    }
+}
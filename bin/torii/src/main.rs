@@ -11,7 +11,7 @@
 //!   for more info.
 
 use std::cmp;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -19,9 +19,10 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand};
 use dojo_metrics::exporters::prometheus::PrometheusRecorder;
 use dojo_utils::parse::{parse_socket_address, parse_url};
+use dojo_world::contracts::naming::compute_selector_from_names;
 use dojo_world::contracts::world::WorldContractReader;
 use sqlx::sqlite::{
     SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
@@ -29,18 +30,28 @@ use sqlx::sqlite::{
 use sqlx::SqlitePool;
 use starknet::core::types::Felt;
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::JsonRpcClient;
+use starknet::providers::{JsonRpcClient, Provider};
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::Sender;
 use tokio_stream::StreamExt;
 use torii_core::engine::{Engine, EngineConfig, IndexingFlags, Processors};
 use torii_core::executor::Executor;
+use torii_core::export::{self, ExportFormat, ExportOptions};
+use torii_core::processors::custom_event::CustomEventProcessor;
 use torii_core::processors::store_transaction::StoreTransactionProcessor;
 use torii_core::simple_broker::SimpleBroker;
-use torii_core::sql::Sql;
+use torii_core::snapshot;
+use torii_core::sql::history::EntityHistoryConfig;
+use torii_core::sql::search::SearchConfig;
+use torii_core::sql::{world_scope, Sql};
+use torii_core::token_metadata::{
+    TokenMetadataConfig, TokenMetadataResolver, DEFAULT_IPFS_GATEWAY,
+};
 use torii_core::types::{Contract, ContractType, Model, ToriiConfig};
+use torii_core::webhooks::Webhooks;
 use torii_server::proxy::Proxy;
+use torii_server::sql::SqlEndpoint;
 use tracing::{error, info};
 use tracing_subscriber::{fmt, EnvFilter};
 use url::{form_urlencoded, Url};
@@ -51,9 +62,13 @@ pub(crate) const LOG_TARGET: &str = "torii::cli";
 #[derive(Parser, Debug)]
 #[command(name = "torii", author, version, about, long_about = None)]
 struct Args {
-    /// The world to index
+    /// The worlds to index (comma-separated list). Worlds registering a model in the same
+    /// namespace share it as long as they register it with the same schema, a registration with
+    /// another schema being rejected. The first world is the one whose metadata gRPC returns when
+    /// a request doesn't select any, other requests covering every world unless filtered.
     #[arg(short, long = "world", env = "DOJO_WORLD_ADDRESS")]
-    world_address: Option<Felt>,
+    #[arg(value_delimiter = ',')]
+    world_addresses: Option<Vec<Felt>>,
 
     /// The sequencer rpc endpoint to index.
     #[arg(long, value_name = "URL", default_value = ":5050", value_parser = parse_url)]
@@ -138,6 +153,48 @@ struct Args {
     #[arg(long, action = ArgAction::Set, default_value_t = true)]
     index_raw_events: bool,
 
+    /// Whether or not to resolve the metadata of ERC721 and ERC1155 tokens from their URI
+    #[arg(long, action = ArgAction::Set, default_value_t = false)]
+    index_token_metadata: bool,
+
+    /// IPFS gateway used to resolve the metadata of tokens
+    #[arg(long, value_name = "URL", default_value = DEFAULT_IPFS_GATEWAY)]
+    ipfs_gateway: String,
+
+    /// Directory where the images of tokens are cached. They're served at
+    /// `/static/<contract_address>/<token_id>/image`
+    #[arg(long, value_name = "PATH")]
+    artifacts_path: Option<PathBuf>,
+
+    /// Models whose entities are versioned to be queried at a past block (comma-separated list
+    /// of `namespace-name`)
+    #[arg(long, value_name = "MODELS")]
+    #[arg(value_delimiter = ',')]
+    historical_models: Option<Vec<String>>,
+
+    /// Number of blocks for which the versions of the historical models are kept, all of them if
+    /// not specified
+    #[arg(long, value_name = "BLOCKS")]
+    history_retention: Option<u64>,
+
+    /// Model members searchable with the `SEARCH` operator, of type `ByteArray` or `felt252`
+    /// (comma-separated list of `namespace-name.member`)
+    #[arg(long, value_name = "MEMBERS")]
+    #[arg(value_delimiter = ',')]
+    search_members: Option<Vec<String>>,
+
+    /// Serve read-only SQL queries at `/sql`, and the tables of the database at `/sql/schema`
+    #[arg(long)]
+    sql: bool,
+
+    /// Time in ms after which a query of the SQL endpoint is interrupted
+    #[arg(long, value_name = "MS", default_value = "5000")]
+    sql_timeout: u64,
+
+    /// Maximum number of rows returned by a query of the SQL endpoint
+    #[arg(long, value_name = "ROWS", default_value = "10000")]
+    sql_row_limit: usize,
+
     /// ERC contract addresses to index
     #[arg(long, value_parser = parse_erc_contracts)]
     #[arg(conflicts_with = "config")]
@@ -146,12 +203,100 @@ struct Args {
     /// Configuration file
     #[arg(long)]
     config: Option<PathBuf>,
+
+    /// Snapshot created by `torii snapshot create` to start from, instead of indexing from the
+    /// first block. Either a file or an http(s) URL, to bootstrap a database that doesn't exist
+    /// yet
+    #[arg(long, value_name = "FILE|URL")]
+    bootstrap_from: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage the snapshots of a database
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+    /// Export the entities of models, the events and the transactions of a database to Parquet or
+    /// CSV files
+    Export(ExportArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum SnapshotCommand {
+    /// Write a compressed snapshot of a database, which Torii can be bootstrapped from. The
+    /// snapshot is always taken at the current head of the database, the block up to which all
+    /// of its contracts are indexed; a past block can't be selected
+    Create(SnapshotCreateArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct SnapshotCreateArgs {
+    /// Database filepath
+    #[arg(short, long)]
+    database: String,
+
+    /// The sequencer rpc endpoint of the indexed chain, whose id is recorded in the snapshot.
+    #[arg(long, value_name = "URL", default_value = ":5050", value_parser = parse_url)]
+    rpc: Url,
+
+    /// Path of the snapshot to write
+    #[arg(short, long, value_name = "PATH")]
+    output: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExportArgs {
+    /// Database filepath
+    #[arg(short, long)]
+    database: String,
+
+    /// Models to export, all the registered models if not set
+    #[arg(long, value_delimiter = ',', value_name = "NAMESPACE-NAME")]
+    models: Vec<String>,
+
+    /// Format of the exported files
+    #[arg(long, value_name = "parquet|csv", default_value = "parquet")]
+    #[arg(value_parser = ExportFormat::from_str)]
+    format: ExportFormat,
+
+    /// Block at which the data is exported, the latest state by default. Models updated after it
+    /// must be indexed with history, see `historical_models`
+    #[arg(long, value_name = "BLOCK")]
+    at_block: Option<u64>,
+
+    /// Only export the rows updated after this block, e.g. the block of a previous export
+    #[arg(long, value_name = "BLOCK")]
+    since_block: Option<u64>,
+
+    /// Directory of the exported files, one per table
+    #[arg(short, long, value_name = "PATH")]
+    output: PathBuf,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    let filter_layer = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info,hyper_reverse_proxy=off"));
+
+    let subscriber = fmt::Subscriber::builder().with_env_filter(filter_layer).finish();
+
+    // Set the global subscriber
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Failed to set the global tracing subscriber");
+
+    match args.command {
+        Some(Command::Snapshot(SnapshotCommand::Create(args))) => {
+            return create_snapshot(args).await;
+        }
+        Some(Command::Export(args)) => return export_data(args).await,
+        None => {}
+    }
+
     let mut config = if let Some(path) = args.config {
         ToriiConfig::load_from_path(&path)?
     } else {
@@ -164,16 +309,17 @@ async fn main() -> anyhow::Result<()> {
         config
     };
 
-    let world_address = verify_single_world_address(args.world_address, &mut config)?;
-
-    let filter_layer = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info,hyper_reverse_proxy=off"));
-
-    let subscriber = fmt::Subscriber::builder().with_env_filter(filter_layer).finish();
+    if let Some(historical_models) = args.historical_models {
+        config.historical_models = historical_models;
+    }
+    if args.history_retention.is_some() {
+        config.history_retention = args.history_retention;
+    }
+    if let Some(search_members) = args.search_members {
+        config.search_members = search_members;
+    }
 
-    // Set the global subscriber
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set the global tracing subscriber");
+    let world_addresses = verify_world_addresses(args.world_addresses, &mut config)?;
 
     // Setup cancellation for graceful shutdown
     let (shutdown_tx, _) = broadcast::channel(1);
@@ -188,6 +334,21 @@ async fn main() -> anyhow::Result<()> {
     let database_path =
         if args.database.is_empty() { tempfile.path().to_str().unwrap() } else { &args.database };
 
+    let provider: Arc<_> = JsonRpcClient::new(HttpTransport::new(args.rpc)).into();
+
+    if let Some(source) = &args.bootstrap_from {
+        if std::fs::metadata(database_path).map(|metadata| metadata.len() > 0).unwrap_or(false) {
+            anyhow::bail!(
+                "Only a new database can be bootstrapped, {database_path} already exists."
+            );
+        }
+
+        let chain_id = provider.chain_id().await?;
+        let snapshot =
+            snapshot::bootstrap(source, database_path, chain_id, &world_addresses).await?;
+        info!(target: LOG_TARGET, block = snapshot.block_number, "Bootstrapped from snapshot.");
+    }
+
     let mut options =
         SqliteConnectOptions::from_str(database_path)?.create_if_missing(true).with_regexp();
 
@@ -204,11 +365,12 @@ async fn main() -> anyhow::Result<()> {
     sqlx::query(&format!("PRAGMA threads = {};", thread_count)).execute(&pool).await?;
 
     sqlx::migrate!("../../crates/torii/migrations").run(&pool).await?;
+    world_scope::scope_model_tables(&pool).await?;
 
-    let provider: Arc<_> = JsonRpcClient::new(HttpTransport::new(args.rpc)).into();
-
-    // Get world address
-    let world = WorldContractReader::new(world_address, provider.clone());
+    let worlds = world_addresses
+        .iter()
+        .map(|address| WorldContractReader::new(*address, provider.clone()))
+        .collect();
 
     let contracts =
         config.contracts.iter().map(|contract| (contract.address, contract.r#type)).collect();
@@ -218,12 +380,82 @@ async fn main() -> anyhow::Result<()> {
         executor.run().await.unwrap();
     });
 
-    let db = Sql::new(pool.clone(), sender.clone(), &contracts).await?;
+    let mut db = Sql::new(pool.clone(), sender.clone(), &contracts).await?;
+
+    if args.index_token_metadata {
+        let (mut resolver, resolver_tx) = TokenMetadataResolver::new(
+            TokenMetadataConfig {
+                ipfs_gateway: args.ipfs_gateway,
+                artifacts_dir: args.artifacts_path.clone(),
+                ..Default::default()
+            },
+            sender.clone(),
+            shutdown_tx.clone(),
+        )?;
+        tokio::spawn(async move {
+            resolver.run().await.unwrap();
+        });
+        db.set_token_metadata_resolver(resolver_tx);
+
+        // The resolutions still queued when torii stopped are queued again in the background.
+        let (db, provider) = (db.clone(), provider.clone());
+        tokio::spawn(async move {
+            if let Err(e) = db.queue_unresolved_token_metadata(provider.as_ref()).await {
+                error!(target: LOG_TARGET, error = %e, "Queuing unresolved token metadata.");
+            }
+        });
+    }
 
-    let processors = Processors {
+    if !config.historical_models.is_empty() {
+        let models = config
+            .historical_models
+            .iter()
+            .map(|model| {
+                let (namespace, name) = model.split_once('-').ok_or_else(|| {
+                    anyhow::anyhow!("Invalid historical model {}, expected namespace-name", model)
+                })?;
+                Ok(compute_selector_from_names(namespace, name))
+            })
+            .collect::<anyhow::Result<HashSet<_>>>()?;
+
+        db.set_entity_history(EntityHistoryConfig { models, retention: config.history_retention });
+    }
+
+    if !config.search_members.is_empty() {
+        db.set_search(SearchConfig::from_members(&config.search_members)?).await?;
+    }
+
+    // also drops the indexes removed from the config
+    db.set_model_indexes(config.indexes.clone()).await?;
+
+    if !config.webhooks.is_empty() {
+        let mut webhooks = Webhooks::new(
+            config.webhooks.clone(),
+            pool.clone(),
+            sender.clone(),
+            shutdown_tx.clone(),
+        )?;
+        tokio::spawn(async move {
+            webhooks.run().await.unwrap();
+        });
+    }
+
+    let mut processors = Processors {
         transaction: vec![Box::new(StoreTransactionProcessor)],
         ..Processors::default()
     };
+    for contract in config.contracts.iter().filter(|c| c.r#type == ContractType::CUSTOM) {
+        let abi = contract.abi.as_ref().with_context(|| {
+            format!(
+                "Custom contract {:#x} requires an `abi`, set in the config file",
+                contract.address
+            )
+        })?;
+        let name = contract.name.clone().unwrap_or_else(|| format!("{:#x}", contract.address));
+        for processor in CustomEventProcessor::from_abi_file(&name, abi)? {
+            processors.register_contract_event_processor(contract.address, Box::new(processor));
+        }
+    }
 
     let (block_tx, block_rx) = tokio::sync::mpsc::channel(100);
 
@@ -236,7 +468,7 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let mut engine: Engine<Arc<JsonRpcClient<HttpTransport>>> = Engine::new(
-        world,
+        worlds,
         db.clone(),
         provider.clone(),
         processors,
@@ -254,10 +486,16 @@ async fn main() -> anyhow::Result<()> {
         Arc::new(contracts),
     );
 
+    // The first world is the one whose metadata is served when a gRPC request doesn't select any.
     let shutdown_rx = shutdown_tx.subscribe();
-    let (grpc_addr, grpc_server) =
-        torii_grpc::server::new(shutdown_rx, &pool, block_rx, world_address, Arc::clone(&provider))
-            .await?;
+    let (grpc_addr, rest_api, grpc_server) = torii_grpc::server::new(
+        shutdown_rx,
+        &pool,
+        block_rx,
+        world_addresses[0],
+        Arc::clone(&provider),
+    )
+    .await?;
 
     let mut libp2p_relay_server = torii_relay::server::Relay::new(
         db,
@@ -270,7 +508,28 @@ async fn main() -> anyhow::Result<()> {
     )
     .expect("Failed to start libp2p relay server");
 
-    let proxy_server = Arc::new(Proxy::new(args.addr, args.allowed_origins, Some(grpc_addr), None));
+    let sql_endpoint = if args.sql {
+        // queries of the endpoint run on their own connections, which can't write
+        let options = SqliteConnectOptions::from_str(database_path)?.read_only(true).with_regexp();
+        let read_only_pool = SqlitePoolOptions::new().connect_with(options).await?;
+        Some(SqlEndpoint::new(
+            read_only_pool,
+            Duration::from_millis(args.sql_timeout),
+            args.sql_row_limit,
+        ))
+    } else {
+        None
+    };
+
+    let proxy_server = Arc::new(Proxy::new(
+        args.addr,
+        args.allowed_origins,
+        Some(grpc_addr),
+        None,
+        args.artifacts_path,
+        sql_endpoint,
+        Some(rest_api),
+    ));
 
     let graphql_server = spawn_rebuilding_graphql_server(
         shutdown_tx.clone(),
@@ -287,6 +546,7 @@ async fn main() -> anyhow::Result<()> {
     let explorer_url = format!("https://worlds.dev/torii?url={}", encoded);
     info!(target: LOG_TARGET, endpoint = %endpoint, "Starting torii endpoint.");
     info!(target: LOG_TARGET, endpoint = %gql_endpoint, "Serving Graphql playground.");
+    info!(target: LOG_TARGET, endpoint = %format!("{endpoint}/api"), "Serving REST API.");
     info!(target: LOG_TARGET, url = %explorer_url, "Serving World Explorer.");
 
     if args.explorer {
@@ -321,24 +581,91 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-// Verifies that the world address is defined at most once
-// and returns the world address
-fn verify_single_world_address(
-    world_address: Option<Felt>,
+async fn create_snapshot(args: SnapshotCreateArgs) -> anyhow::Result<()> {
+    let provider = JsonRpcClient::new(HttpTransport::new(args.rpc));
+    let chain_id = provider.chain_id().await?;
+
+    let snapshot = snapshot::create(&args.database, &args.output, chain_id).await?;
+    info!(
+        target: LOG_TARGET,
+        path = %args.output.display(),
+        block = snapshot.block_number,
+        contracts = snapshot.contracts.len(),
+        "Created snapshot."
+    );
+
+    Ok(())
+}
+
+async fn export_data(args: ExportArgs) -> anyhow::Result<()> {
+    let options = ExportOptions {
+        models: args.models,
+        format: args.format,
+        output: args.output,
+        at_block: args.at_block,
+        since_block: args.since_block,
+    };
+
+    let exported = export::export(&args.database, &options).await?;
+    for table in &exported.tables {
+        info!(
+            target: LOG_TARGET,
+            path = %table.path.display(),
+            rows = table.rows,
+            "Exported table."
+        );
+    }
+    info!(
+        target: LOG_TARGET,
+        format = %options.format,
+        block = exported.block_number,
+        "Exported data, the next export can start after the block."
+    );
+
+    Ok(())
+}
+
+// Verifies that the world addresses are defined either from the arguments or the config,
+// each of them once, and returns them
+fn verify_world_addresses(
+    world_addresses: Option<Vec<Felt>>,
     config: &mut ToriiConfig,
-) -> anyhow::Result<Felt> {
-    let world_from_config =
-        config.contracts.iter().find(|c| c.r#type == ContractType::WORLD).map(|c| c.address);
-
-    match (world_address, world_from_config) {
-        (Some(_), Some(_)) => Err(anyhow::anyhow!("World address specified multiple times")),
-        (Some(addr), _) => {
-            config.contracts.push_front(Contract { address: addr, r#type: ContractType::WORLD });
-            Ok(addr)
+) -> anyhow::Result<Vec<Felt>> {
+    let worlds_from_config = config
+        .contracts
+        .iter()
+        .filter(|c| c.r#type == ContractType::WORLD)
+        .map(|c| c.address)
+        .collect::<Vec<_>>();
+
+    let world_addresses = match world_addresses {
+        Some(_) if !worlds_from_config.is_empty() => {
+            return Err(anyhow::anyhow!("World address specified multiple times"));
+        }
+        Some(addresses) => {
+            for address in addresses.iter().rev() {
+                config.contracts.push_front(Contract {
+                    address: *address,
+                    r#type: ContractType::WORLD,
+                    abi: None,
+                    name: None,
+                });
+            }
+            addresses
         }
-        (_, Some(addr)) => Ok(addr),
-        (None, None) => Err(anyhow::anyhow!("World address not specified")),
+        None => worlds_from_config,
+    };
+
+    if world_addresses.is_empty() {
+        return Err(anyhow::anyhow!("World address not specified"));
     }
+
+    let mut unique = HashSet::new();
+    if let Some(address) = world_addresses.iter().find(|address| !unique.insert(**address)) {
+        return Err(anyhow::anyhow!("World address {:#x} specified multiple times", address));
+    }
+
+    Ok(world_addresses)
 }
 
 async fn spawn_rebuilding_graphql_server(
@@ -377,13 +704,13 @@ fn parse_erc_contracts(s: &str) -> anyhow::Result<Vec<Contract>> {
                 let r#type = r#type.parse::<ContractType>()?;
                 let address = Felt::from_str(address)
                     .with_context(|| format!("Expected address, found {}", address))?;
-                contracts.push(Contract { address, r#type });
+                contracts.push(Contract { address, r#type, abi: None, name: None });
             }
             [address] => {
                 let r#type = ContractType::WORLD;
                 let address = Felt::from_str(address)
                     .with_context(|| format!("Expected address, found {}", address))?;
-                contracts.push(Contract { address, r#type });
+                contracts.push(Contract { address, r#type, abi: None, name: None });
             }
             _ => return Err(anyhow::anyhow!("Invalid contract format")),
         }
@@ -5,7 +5,7 @@ use crate::error::BindgenResult;
 use crate::plugins::{BindgenModelGenerator, Buffer};
 
 const ERC_TORII_TPL: &str = "// Type definition for ERC__Balance struct
-export type ERC__Type = 'ERC20' | 'ERC721';
+export type ERC__Type = 'ERC20' | 'ERC721' | 'ERC1155';
 export interface ERC__Balance {
     fieldOrder: string[];
     balance: string;
@@ -122,7 +122,13 @@ pub fn get_default_test_config(sequencing: SequencingConfig) -> Config {
         port: 0,
         addr: DEFAULT_RPC_ADDR,
         max_connections: DEFAULT_RPC_MAX_CONNECTIONS,
-        apis: HashSet::from([ApiKind::Starknet, ApiKind::Dev, ApiKind::Saya, ApiKind::Torii]),
+        apis: HashSet::from([
+            ApiKind::Starknet,
+            ApiKind::Dev,
+            ApiKind::Saya,
+            ApiKind::Torii,
+            ApiKind::Katana,
+        ]),
     };
 
     Config { sequencing, rpc, dev, chain, ..Default::default() }
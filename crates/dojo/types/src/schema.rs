@@ -183,11 +183,20 @@ impl Ty {
                 }
             }
             Ty::Enum(e) => {
+                if felts.is_empty() {
+                    return Err(PrimitiveError::MissingFieldElement);
+                }
+
                 let value = felts.remove(0);
-                e.option = Some(value.to_u8().ok_or_else(|| PrimitiveError::ValueOutOfRange {
-                    r#type: type_name::<u8>(),
-                    value,
-                })?);
+                e.option = Some(
+                    value
+                        .to_u8()
+                        .filter(|option| (*option as usize) < e.options.len())
+                        .ok_or_else(|| PrimitiveError::ValueOutOfRange {
+                            r#type: type_name::<u8>(),
+                            value,
+                        })?,
+                );
 
                 match &e.options[e.option.unwrap() as usize].ty {
                     // Skip deserializing the enum option if it has no type - unit type
@@ -203,6 +212,10 @@ impl Ty {
                 }
             }
             Ty::Array(items_ty) => {
+                if felts.is_empty() {
+                    return Err(PrimitiveError::MissingFieldElement);
+                }
+
                 let value = felts.remove(0);
                 let arr_len: u32 = value.to_u32().ok_or_else(|| {
                     PrimitiveError::ValueOutOfRange { r#type: type_name::<u32>(), value }
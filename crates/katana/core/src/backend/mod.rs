@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use gas_oracle::L1GasOracle;
 use katana_executor::{ExecutionOutput, ExecutionResult, ExecutorFactory};
@@ -27,6 +28,7 @@ pub mod storage;
 use self::storage::Blockchain;
 use crate::env::BlockContextGenerator;
 use crate::service::block_producer::{BlockProductionError, MinedBlockOutcome};
+use crate::service::metrics::ExecutorMetrics;
 use crate::utils::get_current_timestamp;
 
 pub(crate) const LOG_TARGET: &str = "katana::core::backend";
@@ -51,6 +53,8 @@ impl<EF: ExecutorFactory> Backend<EF> {
         block_env: &BlockEnv,
         execution_output: ExecutionOutput,
     ) -> Result<MinedBlockOutcome, BlockProductionError> {
+        let started_at = Instant::now();
+
         // we optimistically allocate the maximum amount possible
         let mut txs = Vec::with_capacity(execution_output.transactions.len());
         let mut traces = Vec::with_capacity(execution_output.transactions.len());
@@ -58,10 +62,16 @@ impl<EF: ExecutorFactory> Backend<EF> {
 
         // only include successful transactions in the block
         for (tx, res) in execution_output.transactions {
-            if let ExecutionResult::Success { receipt, trace, .. } = res {
-                receipts.push(ReceiptWithTxHash::new(tx.hash, receipt));
-                traces.push(trace);
-                txs.push(tx);
+            match res {
+                ExecutionResult::Success { receipt, trace, .. } => {
+                    receipts.push(ReceiptWithTxHash::new(tx.hash, receipt));
+                    traces.push(trace);
+                    txs.push(tx);
+                }
+
+                ExecutionResult::Failed { error } => {
+                    ExecutorMetrics::of_error(error.kind()).errors_total.increment(1);
+                }
             }
         }
 
@@ -90,7 +100,12 @@ impl<EF: ExecutorFactory> Backend<EF> {
         )?;
 
         info!(target: LOG_TARGET, %block_number, %tx_count, "Block mined.");
-        Ok(MinedBlockOutcome { block_number, txs: tx_hashes, stats: execution_output.stats })
+        Ok(MinedBlockOutcome {
+            block_number,
+            txs: tx_hashes,
+            stats: execution_output.stats,
+            build_time: started_at.elapsed(),
+        })
     }
 
     pub fn update_block_env(&self, block_env: &mut BlockEnv) {
@@ -46,6 +46,10 @@ pub struct MinedBlockOutcome {
     pub block_number: u64,
     pub txs: Vec<TxHash>,
     pub stats: ExecutionStats,
+    /// The time it took to build the block out of its executed transactions and commit it. It
+    /// doesn't include the execution of the transactions, so that it is measured over the same span
+    /// for both the interval and instant mining modes.
+    pub build_time: Duration,
 }
 
 #[derive(Debug, Clone)]
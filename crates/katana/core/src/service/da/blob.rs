@@ -0,0 +1,177 @@
+use c_kzg::{ethereum_kzg_settings, Blob, KzgCommitment, KzgProof, BYTES_PER_BLOB};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::da::blob;
+use katana_primitives::da::eip4844::BLOB_LEN;
+use katana_primitives::da::encoding::encode_state_updates;
+use katana_primitives::state::StateUpdates;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::DataAvailabilityResult;
+
+/// The version byte prepended to the hash of a KZG commitment, as defined in EIP-4844.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The size in bytes of a blob field element.
+const BYTES_PER_FIELD_ELEMENT: usize = BYTES_PER_BLOB / BLOB_LEN;
+
+/// The blobs holding the state diff of a single block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockBlobs {
+    /// The number of the block whose state diff is encoded in the blobs.
+    pub block_number: BlockNumber,
+    /// The blobs, in order. Concatenating their decoded content yields the encoded state diff.
+    pub blobs: Vec<BlobWithCommitment>,
+}
+
+/// An EIP-4844 blob along with its KZG commitment and proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobWithCommitment {
+    /// The blob data, hex encoded.
+    #[serde(with = "hex_bytes")]
+    pub data: Vec<u8>,
+    /// The KZG commitment of the blob.
+    #[serde(with = "hex_bytes")]
+    pub commitment: Vec<u8>,
+    /// The KZG proof of the blob against its commitment.
+    #[serde(with = "hex_bytes")]
+    pub proof: Vec<u8>,
+    /// The versioned hash of the commitment, as referenced by blob-carrying transactions.
+    #[serde(with = "hex_bytes")]
+    pub versioned_hash: Vec<u8>,
+}
+
+impl BlockBlobs {
+    /// Encodes the state updates of a block into as many blobs as needed.
+    pub fn new(
+        block_number: BlockNumber,
+        state_updates: StateUpdates,
+    ) -> DataAvailabilityResult<Self> {
+        // The encoding always contains at least the contract updates and the declarations
+        // counts, so even an empty state diff yields one blob.
+        let blobs = encode_state_updates(state_updates)
+            .chunks(BLOB_LEN)
+            .map(|chunk| BlobWithCommitment::new(chunk.to_vec()))
+            .collect::<DataAvailabilityResult<Vec<_>>>()?;
+
+        Ok(Self { block_number, blobs })
+    }
+
+    /// Recovers the encoded state diff from the blobs.
+    ///
+    /// The result includes the zero padding of the last blob, which is ignored when decoding it
+    /// back with [`decode_state_updates`](katana_primitives::da::encoding::decode_state_updates).
+    pub fn recover(&self) -> Vec<BigUint> {
+        self.blobs.iter().flat_map(|b| b.recover()).collect()
+    }
+}
+
+impl BlobWithCommitment {
+    /// Creates a blob from at most [`BLOB_LEN`] field elements.
+    ///
+    /// The elements are interpreted as the coefficients of the blob polynomial, and the blob
+    /// stores its evaluations, as done by Starknet.
+    fn new(mut elements: Vec<BigUint>) -> DataAvailabilityResult<Self> {
+        debug_assert!(elements.len() <= BLOB_LEN);
+        elements.resize(BLOB_LEN, BigUint::default());
+
+        let evaluations = blob::transform(elements);
+
+        let mut data = Vec::with_capacity(BYTES_PER_BLOB);
+        for element in evaluations {
+            let bytes = element.to_bytes_be();
+            data.extend(std::iter::repeat(0u8).take(BYTES_PER_FIELD_ELEMENT - bytes.len()));
+            data.extend(bytes);
+        }
+
+        let settings = ethereum_kzg_settings();
+        let blob = Blob::from_bytes(&data)?;
+        let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings)?.to_bytes();
+        let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings)?.to_bytes();
+
+        let mut versioned_hash = Sha256::digest(commitment.as_slice()).to_vec();
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+        Ok(Self {
+            data,
+            commitment: commitment.as_slice().to_vec(),
+            proof: proof.as_slice().to_vec(),
+            versioned_hash,
+        })
+    }
+
+    /// Recovers the field elements stored in the blob.
+    fn recover(&self) -> Vec<BigUint> {
+        let evaluations =
+            self.data.chunks(BYTES_PER_FIELD_ELEMENT).map(BigUint::from_bytes_be).collect();
+        blob::recover(evaluations)
+    }
+}
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s.trim_start_matches("0x")).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::da::encoding::decode_state_updates;
+    use katana_primitives::{address, felt};
+
+    use super::*;
+
+    #[test]
+    fn blobs_roundtrip() {
+        let state_updates = StateUpdates {
+            nonce_updates: BTreeMap::from([(address!("0x1"), felt!("0x2"))]),
+            storage_updates: BTreeMap::from([(
+                address!("0x1"),
+                BTreeMap::from([(felt!("0x100"), felt!("0x200"))]),
+            )]),
+            ..Default::default()
+        };
+
+        let blobs = BlockBlobs::new(5, state_updates.clone()).unwrap();
+        assert_eq!(blobs.block_number, 5);
+        assert_eq!(blobs.blobs.len(), 1);
+
+        let blob = &blobs.blobs[0];
+        assert_eq!(blob.data.len(), BYTES_PER_BLOB);
+        assert_eq!(blob.commitment.len(), 48);
+        assert_eq!(blob.proof.len(), 48);
+        assert_eq!(blob.versioned_hash[0], VERSIONED_HASH_VERSION_KZG);
+
+        // the proof must be valid for the blob and its commitment
+        let valid = KzgProof::verify_blob_kzg_proof(
+            &Blob::from_bytes(&blob.data).unwrap(),
+            &c_kzg::Bytes48::from_bytes(&blob.commitment).unwrap(),
+            &c_kzg::Bytes48::from_bytes(&blob.proof).unwrap(),
+            ethereum_kzg_settings(),
+        )
+        .unwrap();
+        assert!(valid);
+
+        let decoded = decode_state_updates(&blobs.recover()).unwrap();
+        assert_eq!(decoded, state_updates);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let blobs = BlockBlobs::new(0, StateUpdates::default()).unwrap();
+        let json = serde_json::to_string(&blobs).unwrap();
+        let decoded: BlockBlobs = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, blobs);
+    }
+}
@@ -0,0 +1,197 @@
+//! Data availability module.
+//!
+//! The data availability (DA) service publishes the state diff of every mined block so that the
+//! chain state can be reconstructed by third parties without access to the node's database.
+//!
+//! Each block's state diff is encoded following Starknet's on-chain DA format (see
+//! [`katana_primitives::da::encoding`]) and packed into one or more EIP-4844 blobs. Every blob is
+//! accompanied by its KZG commitment and proof, computed with the Ethereum trusted setup, and the
+//! resulting [`BlockBlobs`] record is handed over to a [`DataAvailabilitySink`].
+//!
+//! Katana ships with two sinks: [`FileSink`], which writes one JSON file per block in a local
+//! directory, and [`HttpSink`], which POSTs the same JSON document to an HTTP endpoint. Any other
+//! destination can be plugged in by implementing [`DataAvailabilitySink`] and building the
+//! service with [`DataAvailabilityService::with_sink`].
+//!
+//! To start Katana with the DA service enabled, the option `--da` must be used with a
+//! configuration file following the [`DataAvailabilityConfig`] format.
+
+mod blob;
+mod service;
+mod sink;
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::StreamExt;
+use katana_executor::ExecutorFactory;
+use serde::Deserialize;
+use tracing::{error, info};
+use url::Url;
+
+pub use self::blob::{BlobWithCommitment, BlockBlobs};
+pub use self::service::{DataAvailabilityOutcome, DataAvailabilityService};
+pub use self::sink::{DataAvailabilitySink, FileSink, HttpSink};
+
+pub(crate) const LOG_TARGET: &str = "da";
+
+pub type DataAvailabilityResult<T> = Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Missing state update for block {0}")]
+    MissingStateUpdate(u64),
+    #[error("The interval must be greater than 0")]
+    ZeroInterval,
+    #[error("Invalid checkpoint: {0}")]
+    InvalidCheckpoint(String),
+    #[error("KZG error: {0:?}")]
+    Kzg(c_kzg::Error),
+    #[error(transparent)]
+    Provider(#[from] katana_provider::error::ProviderError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl From<c_kzg::Error> for Error {
+    fn from(e: c_kzg::Error) -> Self {
+        Self::Kzg(e)
+    }
+}
+
+/// The destination of the published blobs.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    /// Write the blobs of each block to a JSON file in the given directory.
+    File { path: PathBuf },
+    /// POST the blobs of each block as JSON to the given endpoint.
+    Http { url: Url },
+}
+
+/// The config used to initialize the data availability service.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DataAvailabilityConfig {
+    /// Where the blobs are published to.
+    pub sink: SinkConfig,
+    /// The interval, in seconds, at which the service checks for newly mined blocks. Must be
+    /// greater than 0.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// The first block whose state diff is published.
+    #[serde(default)]
+    pub from_block: u64,
+    /// The file in which the last published block is saved. When set, the service resumes from
+    /// the block following the saved one on restart, instead of starting again from
+    /// `from_block`.
+    #[serde(default)]
+    pub checkpoint: Option<PathBuf>,
+}
+
+fn default_interval() -> u64 {
+    1
+}
+
+impl DataAvailabilityConfig {
+    /// Load the config from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        let buf = std::fs::read(path)?;
+        let config: Self = serde_json::from_slice(&buf)?;
+
+        if config.interval == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                Error::ZeroInterval.to_string(),
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// This is used as the clap `value_parser` implementation
+    pub fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|e| e.to_string())
+    }
+}
+
+#[allow(missing_debug_implementations)]
+#[must_use = "DataAvailabilityTask does nothing unless polled"]
+pub struct DataAvailabilityTask<EF: ExecutorFactory> {
+    service: DataAvailabilityService<EF>,
+}
+
+impl<EF: ExecutorFactory> DataAvailabilityTask<EF> {
+    pub fn new(service: DataAvailabilityService<EF>) -> Self {
+        Self { service }
+    }
+}
+
+impl<EF: ExecutorFactory> Future for DataAvailabilityTask<EF> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        while let Poll::Ready(Some(outcome)) = this.service.poll_next_unpin(cx) {
+            match outcome {
+                DataAvailabilityOutcome::Published { block_number, blob_count } => {
+                    info!(target: LOG_TARGET, %block_number, %blob_count, "Published state diff.");
+                }
+
+                DataAvailabilityOutcome::Failed { block_number, error } => {
+                    error!(target: LOG_TARGET, %block_number, %error, "Publishing state diff.");
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let json = r#"{ "sink": { "type": "file", "path": "/tmp/da" } }"#;
+        let config: DataAvailabilityConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.sink, SinkConfig::File { path: PathBuf::from("/tmp/da") });
+        assert_eq!(config.interval, 1);
+        assert_eq!(config.from_block, 0);
+        assert_eq!(config.checkpoint, None);
+
+        let json = r#"{
+            "sink": { "type": "http", "url": "http://localhost:8080/blobs" },
+            "interval": 5,
+            "from_block": 10,
+            "checkpoint": "/tmp/da-checkpoint"
+        }"#;
+        let config: DataAvailabilityConfig = serde_json::from_str(json).unwrap();
+        let url = Url::parse("http://localhost:8080/blobs").unwrap();
+        assert_eq!(config.sink, SinkConfig::Http { url });
+        assert_eq!(config.interval, 5);
+        assert_eq!(config.from_block, 10);
+        assert_eq!(config.checkpoint, Some(PathBuf::from("/tmp/da-checkpoint")));
+    }
+
+    #[test]
+    fn load_rejects_zero_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("da.json");
+        std::fs::write(
+            &path,
+            r#"{ "sink": { "type": "file", "path": "/tmp/da" }, "interval": 0 }"#,
+        )
+        .unwrap();
+
+        let err = DataAvailabilityConfig::load(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
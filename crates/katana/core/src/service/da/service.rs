@@ -0,0 +1,351 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{Future, FutureExt, Stream};
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use tokio::time::{interval_at, Instant, Interval};
+
+use super::sink::{self, DataAvailabilitySink};
+use super::{BlockBlobs, DataAvailabilityConfig, DataAvailabilityResult, Error};
+use crate::backend::Backend;
+
+type PublishingFuture = Pin<Box<dyn Future<Output = DataAvailabilityResult<usize>> + Send>>;
+
+/// Service that publishes the state diff of every mined block, in order, to a
+/// [`DataAvailabilitySink`].
+#[allow(missing_debug_implementations)]
+pub struct DataAvailabilityService<EF: ExecutorFactory> {
+    /// The interval at which the service checks for newly mined blocks.
+    interval: Interval,
+    backend: Arc<Backend<EF>>,
+    sink: Arc<dyn DataAvailabilitySink>,
+    /// The file in which the last published block is saved.
+    checkpoint: Option<PathBuf>,
+    /// The next block to be published.
+    next_block: BlockNumber,
+    /// The future publishing `next_block`.
+    publish_fut: Option<PublishingFuture>,
+}
+
+#[derive(Debug)]
+pub enum DataAvailabilityOutcome {
+    /// The blobs of the block have been accepted by the sink.
+    Published { block_number: BlockNumber, blob_count: usize },
+    /// Publishing the block has failed, it will be retried on the next tick.
+    Failed { block_number: BlockNumber, error: Error },
+}
+
+impl<EF: ExecutorFactory> DataAvailabilityService<EF> {
+    /// Creates a new service publishing to the sink described by the config.
+    pub fn new(
+        config: DataAvailabilityConfig,
+        backend: Arc<Backend<EF>>,
+    ) -> DataAvailabilityResult<Self> {
+        let sink = sink::from_config(config.sink.clone())?;
+        Self::with_sink(config, backend, Arc::from(sink))
+    }
+
+    /// Creates a new service publishing to a custom sink. The sink field of the config is ignored.
+    ///
+    /// Publishing starts from the block following the one saved in the checkpoint file, if any,
+    /// and from `from_block` otherwise.
+    pub fn with_sink(
+        config: DataAvailabilityConfig,
+        backend: Arc<Backend<EF>>,
+        sink: Arc<dyn DataAvailabilitySink>,
+    ) -> DataAvailabilityResult<Self> {
+        if config.interval == 0 {
+            return Err(Error::ZeroInterval);
+        }
+
+        let next_block = match config.checkpoint.as_deref().map(read_checkpoint).transpose()? {
+            Some(Some(last_block)) => config.from_block.max(last_block + 1),
+            _ => config.from_block,
+        };
+
+        let duration = Duration::from_secs(config.interval);
+        let mut interval = interval_at(Instant::now() + duration, duration);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        Ok(Self {
+            interval,
+            backend,
+            sink,
+            checkpoint: config.checkpoint,
+            next_block,
+            publish_fut: None,
+        })
+    }
+
+    async fn publish(
+        block_number: BlockNumber,
+        backend: Arc<Backend<EF>>,
+        sink: Arc<dyn DataAvailabilitySink>,
+        checkpoint: Option<PathBuf>,
+    ) -> DataAvailabilityResult<usize> {
+        let state_updates = backend
+            .blockchain
+            .provider()
+            .state_update(block_number.into())?
+            .ok_or(Error::MissingStateUpdate(block_number))?;
+
+        // Encoding and computing the KZG commitments is CPU bound.
+        let blobs =
+            tokio::task::spawn_blocking(move || BlockBlobs::new(block_number, state_updates))
+                .await
+                .expect("blob encoding task panicked")?;
+
+        sink.publish(&blobs).await?;
+
+        if let Some(path) = checkpoint {
+            tokio::fs::write(path, block_number.to_string()).await?;
+        }
+
+        Ok(blobs.blobs.len())
+    }
+}
+
+/// Reads the last published block saved in the checkpoint file, if it exists.
+fn read_checkpoint(path: &Path) -> DataAvailabilityResult<Option<BlockNumber>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => content
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidCheckpoint(path.display().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl<EF: ExecutorFactory> Stream for DataAvailabilityService<EF> {
+    type Item = DataAvailabilityOutcome;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+
+        loop {
+            if let Some(mut fut) = pin.publish_fut.take() {
+                return match fut.poll_unpin(cx) {
+                    Poll::Ready(Ok(blob_count)) => {
+                        let block_number = pin.next_block;
+                        pin.next_block += 1;
+
+                        // Catch up with the already mined blocks without waiting for the next tick.
+                        pin.interval.reset_immediately();
+
+                        Poll::Ready(Some(DataAvailabilityOutcome::Published {
+                            block_number,
+                            blob_count,
+                        }))
+                    }
+
+                    Poll::Ready(Err(error)) => {
+                        let block_number = pin.next_block;
+                        Poll::Ready(Some(DataAvailabilityOutcome::Failed { block_number, error }))
+                    }
+
+                    Poll::Pending => {
+                        pin.publish_fut = Some(fut);
+                        Poll::Pending
+                    }
+                };
+            }
+
+            // The interval is polled until it's pending, so that the task is woken up on the next
+            // tick even if there was no new block on this one.
+            if pin.interval.poll_tick(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            // Failing to read the latest block is reported like a failed publication, and retried
+            // on the next tick.
+            let provider = pin.backend.blockchain.provider();
+            let latest = match BlockNumberProvider::latest_number(provider) {
+                Ok(latest) => latest,
+                Err(error) => {
+                    let outcome = DataAvailabilityOutcome::Failed {
+                        block_number: pin.next_block,
+                        error: error.into(),
+                    };
+                    return Poll::Ready(Some(outcome));
+                }
+            };
+
+            if pin.next_block <= latest {
+                pin.publish_fut = Some(Box::pin(Self::publish(
+                    pin.next_block,
+                    pin.backend.clone(),
+                    pin.sink.clone(),
+                    pin.checkpoint.clone(),
+                )));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use katana_executor::implementation::noop::NoopExecutorFactory;
+    use katana_primitives::block::{
+        Block, FinalityStatus, GasPrices, Header, SealedBlockWithStatus,
+    };
+    use katana_primitives::chain_spec;
+    use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::block::BlockWriter;
+
+    use super::*;
+    use crate::backend::gas_oracle::L1GasOracle;
+    use crate::backend::storage::Blockchain;
+    use crate::service::da::SinkConfig;
+
+    /// Records the published blocks.
+    #[derive(Default)]
+    struct RecordingSink {
+        blocks: Mutex<Vec<BlockNumber>>,
+    }
+
+    #[async_trait]
+    impl DataAvailabilitySink for RecordingSink {
+        async fn publish(&self, blobs: &BlockBlobs) -> DataAvailabilityResult<()> {
+            self.blocks.lock().unwrap().push(blobs.block_number);
+            Ok(())
+        }
+    }
+
+    fn mine_empty_block(backend: &Backend<NoopExecutorFactory>, number: BlockNumber) {
+        let block = SealedBlockWithStatus {
+            status: FinalityStatus::AcceptedOnL2,
+            block: Block {
+                header: Header { number, timestamp: number, ..Default::default() },
+                body: Vec::new(),
+            }
+            .seal(),
+        };
+        backend
+            .blockchain
+            .provider()
+            .insert_block_with_states_and_receipts(
+                block,
+                StateUpdatesWithDeclaredClasses::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap();
+    }
+
+    fn test_backend() -> Arc<Backend<NoopExecutorFactory>> {
+        let blockchain =
+            Blockchain::new_with_chain(DbProvider::new_ephemeral(), &chain_spec::DEV).unwrap();
+        Arc::new(Backend {
+            chain_spec: chain_spec::DEV.clone(),
+            blockchain,
+            block_context_generator: Default::default(),
+            executor_factory: Arc::new(NoopExecutorFactory::new()),
+            gas_oracle: L1GasOracle::fixed(GasPrices::default(), GasPrices::default()),
+        })
+    }
+
+    #[tokio::test]
+    async fn publishes_blocks_mined_after_idle_ticks() {
+        let backend = test_backend();
+
+        let sink = Arc::new(RecordingSink::default());
+        let config = DataAvailabilityConfig {
+            sink: SinkConfig::File { path: "unused".into() },
+            interval: 1,
+            from_block: 0,
+            checkpoint: None,
+        };
+        let mut service =
+            DataAvailabilityService::with_sink(config, backend.clone(), sink.clone()).unwrap();
+        let timeout = Duration::from_secs(10);
+
+        let outcome = tokio::time::timeout(timeout, service.next()).await.unwrap().unwrap();
+        assert!(matches!(outcome, DataAvailabilityOutcome::Published { block_number: 0, .. }));
+
+        // the next blocks are mined while the service has already ticked several times without
+        // any new block
+        let miner = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                for number in 1..=2 {
+                    tokio::time::sleep(Duration::from_millis(2500)).await;
+                    mine_empty_block(&backend, number);
+                }
+            }
+        });
+
+        for expected in 1..=2 {
+            let outcome = tokio::time::timeout(timeout, service.next()).await.unwrap().unwrap();
+            match outcome {
+                DataAvailabilityOutcome::Published { block_number, .. } => {
+                    assert_eq!(block_number, expected)
+                }
+                DataAvailabilityOutcome::Failed { error, .. } => panic!("{error}"),
+            }
+        }
+
+        miner.await.unwrap();
+        assert_eq!(*sink.blocks.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_checkpoint() {
+        let backend = test_backend();
+        for number in 1..=2 {
+            mine_empty_block(&backend, number);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DataAvailabilityConfig {
+            sink: SinkConfig::File { path: "unused".into() },
+            interval: 1,
+            from_block: 0,
+            checkpoint: Some(dir.path().join("checkpoint")),
+        };
+        let timeout = Duration::from_secs(10);
+
+        let sink = Arc::new(RecordingSink::default());
+        let mut service =
+            DataAvailabilityService::with_sink(config.clone(), backend.clone(), sink.clone())
+                .unwrap();
+        for _ in 0..=1 {
+            tokio::time::timeout(timeout, service.next()).await.unwrap().unwrap();
+        }
+        drop(service);
+        assert_eq!(std::fs::read_to_string(dir.path().join("checkpoint")).unwrap(), "1");
+
+        // the restarted service publishes the blocks following the last published one
+        let sink = Arc::new(RecordingSink::default());
+        let mut service =
+            DataAvailabilityService::with_sink(config, backend.clone(), sink.clone()).unwrap();
+        let outcome = tokio::time::timeout(timeout, service.next()).await.unwrap().unwrap();
+        assert!(matches!(outcome, DataAvailabilityOutcome::Published { block_number: 2, .. }));
+        assert_eq!(*sink.blocks.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn rejects_zero_interval() {
+        let config = DataAvailabilityConfig {
+            sink: SinkConfig::File { path: "unused".into() },
+            interval: 0,
+            from_block: 0,
+            checkpoint: None,
+        };
+        let sink = Arc::new(RecordingSink::default());
+        let result = DataAvailabilityService::with_sink(config, test_backend(), sink);
+        assert!(matches!(result, Err(Error::ZeroInterval)));
+    }
+}
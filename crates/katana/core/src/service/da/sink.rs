@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use url::Url;
+
+use super::{BlockBlobs, DataAvailabilityResult, SinkConfig};
+
+/// A destination to which the blobs of every mined block are published.
+#[async_trait]
+pub trait DataAvailabilitySink: Send + Sync {
+    /// Publishes the blobs of a single block.
+    ///
+    /// Blocks are published in order, and a block is only retried (indefinitely) if this method
+    /// returns an error.
+    async fn publish(&self, blobs: &BlockBlobs) -> DataAvailabilityResult<()>;
+}
+
+/// Creates the sink described by the config.
+pub(super) fn from_config(
+    config: SinkConfig,
+) -> DataAvailabilityResult<Box<dyn DataAvailabilitySink>> {
+    match config {
+        SinkConfig::File { path } => Ok(Box::new(FileSink::new(path)?)),
+        SinkConfig::Http { url } => Ok(Box::new(HttpSink::new(url))),
+    }
+}
+
+/// Writes the blobs of each block to `<dir>/<block number>.json`.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    dir: PathBuf,
+}
+
+impl FileSink {
+    /// Creates a new sink writing to `dir`, creating the directory if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> DataAvailabilityResult<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the path of the file holding the blobs of the given block.
+    pub fn path(&self, block_number: u64) -> PathBuf {
+        self.dir.join(format!("{block_number}.json"))
+    }
+}
+
+#[async_trait]
+impl DataAvailabilitySink for FileSink {
+    async fn publish(&self, blobs: &BlockBlobs) -> DataAvailabilityResult<()> {
+        let content = serde_json::to_vec(blobs)?;
+        tokio::fs::write(self.path(blobs.block_number), content).await?;
+        Ok(())
+    }
+}
+
+/// POSTs the blobs of each block as a JSON document to an HTTP endpoint.
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    url: Url,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(url: Url) -> Self {
+        Self { url, client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl DataAvailabilitySink for HttpSink {
+    async fn publish(&self, blobs: &BlockBlobs) -> DataAvailabilityResult<()> {
+        self.client.post(self.url.clone()).json(blobs).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::state::StateUpdates;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn file_sink_writes_one_file_per_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = FileSink::new(dir.path().join("blobs")).unwrap();
+
+        let blobs = BlockBlobs::new(7, StateUpdates::default()).unwrap();
+        sink.publish(&blobs).await.unwrap();
+
+        let content = std::fs::read(sink.path(7)).unwrap();
+        let written: BlockBlobs = serde_json::from_slice(&content).unwrap();
+        assert_eq!(written, blobs);
+    }
+}
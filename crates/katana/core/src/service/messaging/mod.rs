@@ -51,7 +51,7 @@ use futures::StreamExt;
 use katana_executor::ExecutorFactory;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
 pub use self::service::{MessagingOutcome, MessagingService};
@@ -59,9 +59,9 @@ pub use self::service::{MessagingOutcome, MessagingService};
 use self::starknet::StarknetMessaging;
 
 pub(crate) const LOG_TARGET: &str = "messaging";
-pub(crate) const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
+pub const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
 #[cfg(feature = "starknet-messaging")]
-pub(crate) const CONFIG_CHAIN_STARKNET: &str = "starknet";
+pub const CONFIG_CHAIN_STARKNET: &str = "starknet";
 
 type MessengerResult<T> = Result<T, Error>;
 
@@ -94,7 +94,7 @@ impl From<TransportError> for Error {
 }
 
 /// The config used to initialize the messaging service.
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MessagingConfig {
     /// The settlement chain.
     pub chain: String,
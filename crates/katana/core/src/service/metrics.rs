@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
 use dojo_metrics::Metrics;
-use metrics::Counter;
+use metrics::{Counter, Histogram};
+use parking_lot::Mutex;
 
 #[derive(Metrics)]
 #[metrics(scope = "block_producer")]
@@ -8,4 +12,31 @@ pub(crate) struct BlockProducerMetrics {
     pub(crate) l1_gas_processed_total: Counter,
     /// The amount of Cairo steps processed in a block.
     pub(crate) cairo_steps_processed_total: Counter,
+    /// The time it takes to build and commit a block, excluding the execution of its transactions.
+    pub(crate) block_build_time_seconds: Histogram,
+    /// The number of transactions included in a block.
+    pub(crate) txs_per_block: Histogram,
+    /// The number of Cairo steps used by a block.
+    pub(crate) cairo_steps_per_block: Histogram,
+    /// The amount of L1 gas used by a block.
+    pub(crate) l1_gas_per_block: Histogram,
+}
+
+/// Metrics for the transaction executor, labeled by the kind of error (ie `kind`).
+#[derive(Metrics, Clone)]
+#[metrics(scope = "executor")]
+pub(crate) struct ExecutorMetrics {
+    /// The number of transactions that failed to be executed.
+    pub(crate) errors_total: Counter,
+}
+
+impl ExecutorMetrics {
+    /// Returns the metrics labeled with a kind of error, whose handles are only registered the
+    /// first time the kind is encountered.
+    pub(crate) fn of_error(kind: &'static str) -> Self {
+        static METRICS: OnceLock<Mutex<HashMap<&'static str, ExecutorMetrics>>> = OnceLock::new();
+
+        let mut metrics = METRICS.get_or_init(Default::default).lock();
+        metrics.entry(kind).or_insert_with(|| Self::new_with_labels(&[("kind", kind)])).clone()
+    }
 }
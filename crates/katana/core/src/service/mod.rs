@@ -15,8 +15,9 @@ use self::block_producer::BlockProducer;
 use self::metrics::BlockProducerMetrics;
 
 pub mod block_producer;
+pub mod da;
 pub mod messaging;
-mod metrics;
+pub(crate) mod metrics;
 
 pub(crate) const LOG_TARGET: &str = "node";
 
@@ -79,6 +80,11 @@ where
                         this.metrics.l1_gas_processed_total.increment(gas_used as u64);
                         this.metrics.cairo_steps_processed_total.increment(steps_used as u64);
 
+                        this.metrics.block_build_time_seconds.record(outcome.build_time);
+                        this.metrics.txs_per_block.record(outcome.txs.len() as f64);
+                        this.metrics.l1_gas_per_block.record(gas_used as f64);
+                        this.metrics.cairo_steps_per_block.record(steps_used as f64);
+
                         // remove mined transactions from the pool
                         this.pool.remove_transactions(&outcome.txs);
                     }
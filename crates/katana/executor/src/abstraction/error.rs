@@ -72,3 +72,28 @@ pub enum ExecutionError {
     #[error("{0}")]
     Other(String),
 }
+
+impl ExecutionError {
+    /// Returns a short, stable identifier of the error variant. Suitable to be used as a metric
+    /// label as it doesn't include any of the error's runtime values.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ConstructorExecutionFailed { .. } => "constructor_execution_failed",
+            Self::ClassAlreadyDeclared(_) => "class_already_declared",
+            Self::EntryPointNotFound(_) => "entry_point_not_found",
+            Self::InvalidInput { .. } => "invalid_input",
+            Self::RecursionDepthExceeded => "recursion_depth_exceeded",
+            Self::ContractNotDeployed(_) => "contract_not_deployed",
+            Self::InvalidNonce { .. } => "invalid_nonce",
+            Self::InsufficientBalance { .. } => "insufficient_balance",
+            Self::ActualFeeExceedsMaxFee { .. } => "actual_fee_exceeds_max_fee",
+            Self::MaxFeeTooLow { .. } => "max_fee_too_low",
+            Self::UndeclaredClass(_) => "undeclared_class",
+            Self::FeeTransferError(_) => "fee_transfer_error",
+            Self::ExecutionFailed { .. } => "execution_failed",
+            Self::TransactionValidationFailed { .. } => "transaction_validation_failed",
+            Self::TransactionReverted { .. } => "transaction_reverted",
+            Self::Other(_) => "other",
+        }
+    }
+}
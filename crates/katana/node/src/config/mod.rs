@@ -9,6 +9,7 @@ use db::DbConfig;
 use dev::DevConfig;
 use execution::ExecutionConfig;
 use fork::ForkingConfig;
+use katana_core::service::da::DataAvailabilityConfig;
 use katana_core::service::messaging::MessagingConfig;
 use katana_primitives::chain_spec::ChainSpec;
 use metrics::MetricsConfig;
@@ -40,6 +41,9 @@ pub struct Config {
     /// Messaging options.
     pub messaging: Option<MessagingConfig>,
 
+    /// Data availability options.
+    pub da: Option<DataAvailabilityConfig>,
+
     /// Sequencing options.
     pub sequencing: SequencingConfig,
 
@@ -15,6 +15,7 @@ pub enum ApiKind {
     Torii,
     Dev,
     Saya,
+    Katana,
 }
 
 /// Configuration for the RPC server.
@@ -28,6 +28,7 @@ use katana_core::constants::{
 };
 use katana_core::env::BlockContextGenerator;
 use katana_core::service::block_producer::BlockProducer;
+use katana_core::service::da::DataAvailabilityConfig;
 use katana_core::service::messaging::MessagingConfig;
 use katana_db::mdbx::DbEnv;
 use katana_executor::implementation::blockifier::BlockifierFactory;
@@ -39,12 +40,14 @@ use katana_pool::TxPool;
 use katana_primitives::block::GasPrices;
 use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
 use katana_rpc::dev::DevApi;
+use katana_rpc::katana::KatanaApi;
 use katana_rpc::metrics::RpcServerMetrics;
 use katana_rpc::saya::SayaApi;
 use katana_rpc::starknet::forking::ForkedClient;
 use katana_rpc::starknet::StarknetApi;
 use katana_rpc::torii::ToriiApi;
 use katana_rpc_api::dev::DevApiServer;
+use katana_rpc_api::katana::KatanaApiServer;
 use katana_rpc_api::saya::SayaApiServer;
 use katana_rpc_api::starknet::{StarknetApiServer, StarknetTraceApiServer, StarknetWriteApiServer};
 use katana_rpc_api::torii::ToriiApiServer;
@@ -94,6 +97,7 @@ pub struct Node {
     pub metrics_config: Option<MetricsConfig>,
     pub sequencing_config: SequencingConfig,
     pub messaging_config: Option<MessagingConfig>,
+    pub da_config: Option<DataAvailabilityConfig>,
     forked_client: Option<ForkedClient>,
 }
 
@@ -133,6 +137,7 @@ impl Node {
             self.task_manager.task_spawner(),
             block_producer.clone(),
             self.messaging_config.clone(),
+            self.da_config.clone(),
         );
 
         // --- build and start the pipeline
@@ -252,6 +257,7 @@ pub async fn build(mut config: Config) -> Result<Node> {
         rpc_config: config.rpc,
         metrics_config: config.metrics,
         messaging_config: config.messaging,
+        da_config: config.da,
         sequencing_config: config.sequencing,
         task_manager: TaskManager::current(),
     };
@@ -307,6 +313,10 @@ pub async fn spawn<EF: ExecutorFactory>(
         methods.merge(SayaApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
     }
 
+    if config.apis.contains(&ApiKind::Katana) {
+        methods.merge(KatanaApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
+    }
+
     let cors = CorsLayer::new()
             // Allow `POST` when accessing the resource
             .allow_methods([Method::POST, Method::GET])
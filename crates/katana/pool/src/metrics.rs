@@ -0,0 +1,23 @@
+use core::fmt;
+
+use dojo_metrics::Metrics;
+use metrics::{Counter, Gauge, Histogram};
+
+#[derive(Metrics, Clone)]
+#[metrics(scope = "pool")]
+pub(crate) struct PoolMetrics {
+    /// The number of transactions currently in the pool.
+    pub(crate) size: Gauge,
+    /// The number of transactions received by the pool.
+    pub(crate) transactions_received_total: Counter,
+    /// The number of transactions rejected by the pool.
+    pub(crate) transactions_rejected_total: Counter,
+    /// The time a transaction spent in the pool before being removed from it.
+    pub(crate) transaction_age_seconds: Histogram,
+}
+
+impl fmt::Debug for PoolMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolMetrics").finish_non_exhaustive()
+    }
+}
@@ -8,6 +8,7 @@ use parking_lot::RwLock;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
+use crate::metrics::PoolMetrics;
 use crate::ordering::PoolOrd;
 use crate::pending::PendingTransactions;
 use crate::subscription::Subscription;
@@ -42,6 +43,9 @@ struct Inner<T, V, O: PoolOrd> {
 
     /// the ordering mechanism used to order the txs in the pool
     ordering: O,
+
+    /// metrics for recording the pool operations
+    metrics: PoolMetrics,
 }
 
 impl<T, V, O> Pool<T, V, O>
@@ -59,6 +63,7 @@ where
                 transactions: Default::default(),
                 subscribers: Default::default(),
                 listeners: Default::default(),
+                metrics: PoolMetrics::default(),
             }),
         }
     }
@@ -138,6 +143,7 @@ where
         let id = TxId::new(tx.sender(), tx.nonce());
 
         info!(target: "pool", hash = format!("{hash:#x}"), "Transaction received.");
+        self.inner.metrics.transactions_received_total.increment(1);
 
         match self.inner.validator.validate(tx) {
             Ok(outcome) => {
@@ -148,7 +154,12 @@ where
                         let tx = PendingTx::new(id, tx, priority);
 
                         // insert the tx in the pool
-                        self.inner.transactions.write().insert(tx.clone());
+                        {
+                            let mut txs = self.inner.transactions.write();
+                            txs.insert(tx.clone());
+                            self.inner.metrics.size.set(txs.len() as f64);
+                        }
+
                         self.notify(tx);
 
                         Ok(hash)
@@ -158,6 +169,7 @@ where
                     // `getTransactionStatus`
                     ValidationOutcome::Invalid { error, .. } => {
                         warn!(target: "pool", hash = format!("{hash:#x}"), %error, "Invalid transaction.");
+                        self.inner.metrics.transactions_rejected_total.increment(1);
                         Err(PoolError::InvalidTransaction(Box::new(error)))
                     }
 
@@ -214,7 +226,17 @@ where
     fn remove_transactions(&self, hashes: &[TxHash]) {
         // retain only transactions that aren't included in the list
         let mut txs = self.inner.transactions.write();
-        txs.retain(|t| !hashes.contains(&t.tx.hash()))
+        txs.retain(|t| {
+            if hashes.contains(&t.tx.hash()) {
+                let age = t.added_at.elapsed();
+                self.inner.metrics.transaction_age_seconds.record(age);
+                false
+            } else {
+                true
+            }
+        });
+
+        self.inner.metrics.size.set(txs.len() as f64);
     }
 
     fn size(&self) -> usize {
@@ -323,12 +345,14 @@ mod tests {
     use katana_primitives::contract::{ContractAddress, Nonce};
     use katana_primitives::transaction::TxHash;
     use katana_primitives::Felt;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
 
     use super::test_utils::*;
     use super::Pool;
     use crate::ordering::FiFo;
     use crate::tx::PoolTransaction;
-    use crate::validation::NoopValidator;
+    use crate::validation::error::InvalidTransactionError;
+    use crate::validation::{NoopValidator, ValidationOutcome, ValidationResult, Validator};
     use crate::TransactionPool;
 
     /// Tx pool that uses a noop validator and a first-come-first-serve ordering.
@@ -461,6 +485,79 @@ mod tests {
         });
     }
 
+    /// Validator that decides the outcome of a tx based on its nonce: `0` is valid, `1` is
+    /// dependent and anything else is invalid.
+    struct NonceValidator;
+
+    impl Validator for NonceValidator {
+        type Transaction = PoolTx;
+
+        fn validate(&self, tx: PoolTx) -> ValidationResult<PoolTx> {
+            let outcome = if tx.nonce() == Nonce::ZERO {
+                ValidationOutcome::Valid(tx)
+            } else if tx.nonce() == Nonce::ONE {
+                ValidationOutcome::Dependent {
+                    tx,
+                    tx_nonce: Nonce::ONE,
+                    current_nonce: Nonce::ZERO,
+                }
+            } else {
+                let error = InvalidTransactionError::InvalidNonce {
+                    address: tx.sender(),
+                    current_nonce: Nonce::ZERO,
+                    tx_nonce: tx.nonce(),
+                };
+                ValidationOutcome::Invalid { tx, error }
+            };
+
+            Ok(outcome)
+        }
+    }
+
+    #[test]
+    fn pool_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        // the metrics handles are bound to the recorder that is set when the pool is created
+        let pool = metrics::with_local_recorder(&recorder, || {
+            Pool::new(NonceValidator, FiFo::<PoolTx>::new())
+        });
+
+        let valid = [PoolTx::new().with_nonce(Nonce::ZERO), PoolTx::new().with_nonce(Nonce::ZERO)];
+        let dependent = PoolTx::new().with_nonce(Nonce::ONE);
+        let invalid = PoolTx::new().with_nonce(Nonce::TWO);
+
+        valid.iter().for_each(|tx| {
+            pool.add_transaction(tx.clone()).unwrap();
+        });
+        assert!(pool.add_transaction(dependent).is_err());
+        assert!(pool.add_transaction(invalid).is_err());
+
+        pool.remove_transactions(&[valid[0].hash()]);
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let metric = |name: &str| {
+            snapshot
+                .iter()
+                .find(|(key, ..)| key.key().name().ends_with(name))
+                .map(|(.., value)| value.clone())
+                .unwrap_or_else(|| panic!("metric {name} not recorded"))
+        };
+
+        assert_eq!(metric("transactions_received_total"), DebugValue::Counter(4));
+        // dependent txs may become valid later on, so they aren't counted as rejected
+        assert_eq!(metric("transactions_rejected_total"), DebugValue::Counter(1));
+
+        let DebugValue::Gauge(size) = metric("size") else { panic!("size must be a gauge") };
+        assert_eq!(size.0, 1.0);
+
+        let DebugValue::Histogram(ages) = metric("transaction_age_seconds") else {
+            panic!("transaction age must be a histogram")
+        };
+        assert_eq!(ages.len(), 1);
+    }
+
     #[tokio::test]
     #[ignore = "Txs dependency management not fully implemented yet"]
     async fn dependent_txs_linear_insertion() {
@@ -4,6 +4,7 @@ use alloy_primitives::U256;
 use lazy_static::lazy_static;
 use starknet::core::utils::cairo_short_string_to_felt;
 use starknet_crypto::Felt;
+use url::Url;
 
 use crate::block::{Block, Header};
 use crate::chain::ChainId;
@@ -25,7 +26,6 @@ use crate::utils::split_u256;
 use crate::version::{ProtocolVersion, CURRENT_STARKNET_VERSION};
 
 /// A chain specification.
-// TODO: include l1 core contract
 // TODO: create a chain spec and genesis builder to abstract inserting aux classes
 #[derive(Debug, Clone)]
 pub struct ChainSpec {
@@ -37,12 +37,15 @@ pub struct ChainSpec {
     pub fee_contracts: FeeContracts,
     /// The protocol version.
     pub version: ProtocolVersion,
+    /// The chain's settlement layer, if any.
+    pub settlement: Option<SettlementLayer>,
 }
 
 /// Tokens that can be used for transaction fee payments in the chain. As
 /// supported on Starknet.
 // TODO: include both l1 and l2 addresses
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeeContracts {
     /// L2 ETH fee token address. Used for paying pre-V3 transactions.
     pub eth: ContractAddress,
@@ -50,6 +53,26 @@ pub struct FeeContracts {
     pub strk: ContractAddress,
 }
 
+/// The layer on which the chain settles its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "chain", rename_all = "snake_case"))]
+pub enum SettlementLayer {
+    Ethereum {
+        /// The JSON-RPC endpoint of the Ethereum node.
+        rpc_url: Url,
+        /// The address of the chain's core contract on Ethereum.
+        core_contract: alloy_primitives::Address,
+    },
+
+    Starknet {
+        /// The JSON-RPC endpoint of the Starknet node.
+        rpc_url: Url,
+        /// The address of the chain's core contract on Starknet.
+        core_contract: ContractAddress,
+    },
+}
+
 impl ChainSpec {
     pub fn block(&self) -> Block {
         let header = Header {
@@ -108,7 +131,7 @@ impl ChainSpec {
         }
 
         //-- Fee tokens
-        add_default_fee_tokens(&mut states, &self.genesis);
+        add_default_fee_tokens(&mut states, &self.fee_contracts, &self.genesis);
         // -- UDC
         add_default_udc(&mut states);
 
@@ -142,11 +165,16 @@ lazy_static! {
         let id = ChainId::parse("KATANA").unwrap();
         let genesis = Genesis::default();
         let fee_contracts = FeeContracts { eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS, strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS };
-        ChainSpec { id, genesis, fee_contracts, version: CURRENT_STARKNET_VERSION }
+        let version = CURRENT_STARKNET_VERSION;
+        ChainSpec { id, genesis, fee_contracts, version, settlement: None }
     };
 }
 
-fn add_default_fee_tokens(states: &mut StateUpdatesWithDeclaredClasses, genesis: &Genesis) {
+fn add_default_fee_tokens(
+    states: &mut StateUpdatesWithDeclaredClasses,
+    fee_contracts: &FeeContracts,
+    genesis: &Genesis,
+) {
     // declare erc20 token contract
     states
         .declared_compiled_classes
@@ -159,7 +187,7 @@ fn add_default_fee_tokens(states: &mut StateUpdatesWithDeclaredClasses, genesis:
         "Ether",
         "ETH",
         18,
-        DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+        fee_contracts.eth,
         DEFAULT_LEGACY_ERC20_CLASS_HASH,
         &genesis.allocations,
     );
@@ -170,7 +198,7 @@ fn add_default_fee_tokens(states: &mut StateUpdatesWithDeclaredClasses, genesis:
         "Starknet Token",
         "STRK",
         18,
-        DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+        fee_contracts.strk,
         DEFAULT_LEGACY_ERC20_CLASS_HASH,
         &genesis.allocations,
     );
@@ -367,6 +395,7 @@ mod tests {
                 eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
                 strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
             },
+            settlement: None,
         };
 
         // setup expected storage values
@@ -3,7 +3,6 @@ use std::io::{self, Read, Write};
 use std::mem;
 
 use anyhow::{Context, Result};
-use katana_cairo::lang::starknet_classes::casm_contract_class::CasmContractClass;
 use katana_cairo::starknet_api::deprecated_contract_class::{
     ContractClassAbiEntry, EntryPoint, EntryPointType, TypedParameter,
 };
@@ -27,6 +26,7 @@ use crate::class::{
     ClassHash, CompiledClassHash, DeprecatedCompiledClass, FlattenedSierraClass,
     SierraCompiledClass, SierraProgram,
 };
+use crate::utils::class_cache::compile_class;
 use crate::Felt;
 
 /// Converts the legacy inner compiled class type [DeprecatedCompiledClass] into its RPC equivalent
@@ -148,7 +148,7 @@ pub fn flattened_sierra_to_compiled_class(
     let entry_points_by_type = class.entry_points_by_type.clone();
     let sierra = SierraProgram { program, entry_points_by_type };
 
-    let casm = CasmContractClass::from_contract_class(class, true, usize::MAX)?;
+    let casm = compile_class(class, || Ok(class_hash))?;
     let compiled_hash = casm.compiled_class_hash();
 
     let class = crate::class::CompiledClass::Class(SierraCompiledClass { casm, sierra });
@@ -159,8 +159,8 @@ pub fn flattened_sierra_to_compiled_class(
 pub fn compiled_class_hash_from_flattened_sierra_class(
     contract_class: &FlattenedSierraClass,
 ) -> Result<Felt> {
-    let contract_class = rpc_to_cairo_contract_class(contract_class)?;
-    let casm = CasmContractClass::from_contract_class(contract_class, true, usize::MAX)?;
+    let class = rpc_to_cairo_contract_class(contract_class)?;
+    let casm = compile_class(class, || Ok(contract_class.class_hash()))?;
     let compiled_class: CompiledClass = serde_json::from_str(&serde_json::to_string(&casm)?)?;
     Ok(compiled_class.class_hash()?)
 }
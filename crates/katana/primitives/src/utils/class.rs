@@ -1,8 +1,9 @@
 use anyhow::Result;
-use katana_cairo::lang::starknet_classes::casm_contract_class::CasmContractClass;
 use katana_cairo::lang::starknet_classes::contract_class::ContractClass;
+use serde::Deserialize;
 use serde_json::Value;
 
+use super::class_cache::compile_class;
 use crate::class::{
     CompiledClass, DeprecatedCompiledClass, SierraClass, SierraCompiledClass, SierraProgram,
 };
@@ -22,14 +23,15 @@ pub fn parse_compiled_class(artifact: Value) -> Result<CompiledClass> {
     }
 }
 
-pub fn parse_compiled_class_v1(class: Value) -> Result<SierraCompiledClass> {
-    let class: ContractClass = serde_json::from_value(class)?;
+pub fn parse_compiled_class_v1(artifact: Value) -> Result<SierraCompiledClass> {
+    let class = ContractClass::deserialize(&artifact)?;
 
     let program = class.extract_sierra_program()?;
     let entry_points_by_type = class.entry_points_by_type.clone();
     let sierra = SierraProgram { program, entry_points_by_type };
 
-    let casm = CasmContractClass::from_contract_class(class, true, usize::MAX)?;
+    let class_hash = || Ok(serde_json::from_value::<SierraClass>(artifact)?.class_hash()?);
+    let casm = compile_class(class, class_hash)?;
 
     Ok(SierraCompiledClass { casm, sierra })
 }
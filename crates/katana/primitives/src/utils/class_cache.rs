@@ -0,0 +1,160 @@
+//! A content-addressed, on-disk cache for Sierra classes compiled to CASM.
+//!
+//! Compiling Sierra to CASM is by far the most expensive step of loading a class, and the same
+//! classes are compiled over and over again; on every start from a genesis with many classes, on
+//! every declare of an already known class, and when fetching classes from a forked network.
+//!
+//! Entries are keyed by the class hash and the version of the compiler that produced them, so a
+//! cache directory can safely be shared between restarts, multiple nodes and CI runs. Writes are
+//! atomic (write to a temporary file, then rename), so concurrent writers never leave a partially
+//! written entry behind.
+//!
+//! The cache is opt-in. It is enabled either by setting the [`CLASS_CACHE_DIR_ENV`] environment
+//! variable to the cache directory, or programmatically through [`ClassCache::set_global`] (eg.
+//! from Katana's `--class-cache-dir` option). Once enabled, every Sierra compilation performed
+//! through [`compile_class`] is served from, and stored into, the global cache.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fs, io};
+
+use anyhow::Result;
+use katana_cairo::lang::starknet_classes::casm_contract_class::CasmContractClass;
+use katana_cairo::lang::starknet_classes::compiler_version::current_compiler_version_id;
+use katana_cairo::lang::starknet_classes::contract_class::ContractClass;
+
+use crate::class::ClassHash;
+
+/// The environment variable used to set the directory of the global [`ClassCache`].
+pub const CLASS_CACHE_DIR_ENV: &str = "KATANA_CLASS_CACHE_DIR";
+
+/// The cache explicitly set through [`ClassCache::set_global`].
+static GLOBAL_CACHE: OnceLock<ClassCache> = OnceLock::new();
+/// The cache set through the [`CLASS_CACHE_DIR_ENV`] environment variable.
+static ENV_CACHE: OnceLock<Option<ClassCache>> = OnceLock::new();
+
+/// Returns the version of the Sierra to CASM compiler used by Katana.
+///
+/// It is taken from the `cairo-lang-starknet-classes` crate itself, as it is part of the cache key
+/// and a compiler upgrade may produce a different CASM for the same class.
+pub fn compiler_version() -> String {
+    current_compiler_version_id().to_string()
+}
+
+/// An on-disk cache of compiled classes.
+#[derive(Debug, Clone)]
+pub struct ClassCache {
+    /// The directory where the entries of the current compiler version are stored.
+    dir: PathBuf,
+}
+
+impl ClassCache {
+    /// Opens the cache rooted at `path`, creating the directory if it doesn't exist yet.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let dir = path.as_ref().join(compiler_version());
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Sets the process-wide cache used by [`compile_class`], taking precedence over the one set
+    /// through the [`CLASS_CACHE_DIR_ENV`] environment variable.
+    ///
+    /// Returns the given cache back if the global cache has already been set.
+    pub fn set_global(cache: ClassCache) -> Result<(), ClassCache> {
+        GLOBAL_CACHE.set(cache)
+    }
+
+    /// Returns the process-wide cache, if any.
+    ///
+    /// If it hasn't been set explicitly, the cache is initialized from the [`CLASS_CACHE_DIR_ENV`]
+    /// environment variable on first use.
+    pub fn global() -> Option<&'static ClassCache> {
+        GLOBAL_CACHE.get().or_else(|| {
+            ENV_CACHE
+                .get_or_init(|| env::var_os(CLASS_CACHE_DIR_ENV).and_then(|d| Self::new(d).ok()))
+                .as_ref()
+        })
+    }
+
+    /// Returns the cached compiled class of the given class hash, if any.
+    ///
+    /// Unreadable or corrupted entries are treated as a cache miss.
+    pub fn get(&self, class_hash: ClassHash) -> Option<CasmContractClass> {
+        let bytes = fs::read(self.entry_path(class_hash)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Stores the compiled class of the given class hash in the cache.
+    pub fn insert(&self, class_hash: ClassHash, casm: &CasmContractClass) -> io::Result<()> {
+        let path = self.entry_path(class_hash);
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+
+        fs::write(&tmp, serde_json::to_vec(casm)?)?;
+
+        if let Err(error) = fs::rename(&tmp, &path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    fn entry_path(&self, class_hash: ClassHash) -> PathBuf {
+        self.dir.join(format!("{class_hash:#x}.json"))
+    }
+}
+
+/// Compiles the given Sierra class to CASM, going through the global [`ClassCache`] if one is set.
+///
+/// `class_hash` must return the hash of `class`, as it is used as the cache key. It is only called
+/// when a cache is set, so callers that don't already know the hash don't pay for computing it
+/// otherwise. Failing to write to the cache is not an error, the compiled class is still returned.
+pub fn compile_class<F>(class: ContractClass, class_hash: F) -> Result<CasmContractClass>
+where
+    F: FnOnce() -> Result<ClassHash>,
+{
+    let Some(cache) = ClassCache::global() else {
+        return Ok(CasmContractClass::from_contract_class(class, true, usize::MAX)?);
+    };
+
+    let class_hash = class_hash()?;
+    if let Some(casm) = cache.get(class_hash) {
+        return Ok(casm);
+    }
+
+    let casm = CasmContractClass::from_contract_class(class, true, usize::MAX)?;
+    let _ = cache.insert(class_hash, &casm);
+
+    Ok(casm)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_cairo::lang::starknet_classes::contract_class::ContractClass;
+
+    use super::{compiler_version, CasmContractClass, ClassCache};
+    use crate::Felt;
+
+    #[test]
+    fn insert_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ClassCache::new(dir.path()).unwrap();
+
+        let artifact = include_str!("../../../contracts/build/default_account.json");
+        let class: ContractClass = serde_json::from_str(artifact).unwrap();
+        let casm = CasmContractClass::from_contract_class(class, true, usize::MAX).unwrap();
+
+        let hash = Felt::from(1337u32);
+        assert!(cache.get(hash).is_none());
+
+        cache.insert(hash, &casm).unwrap();
+
+        // a cache opened at the same location must see the entry
+        let cache = ClassCache::new(dir.path()).unwrap();
+        let cached = cache.get(hash).expect("entry must exist");
+        assert_eq!(cached.compiled_class_hash(), casm.compiled_class_hash());
+
+        // entries are scoped to the compiler version
+        assert!(dir.path().join(compiler_version()).join(format!("{hash:#x}.json")).exists());
+    }
+}
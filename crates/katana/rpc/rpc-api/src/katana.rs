@@ -0,0 +1,21 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_primitives::transaction::TxHash;
+use katana_rpc_types::trace::DetailedTxTrace;
+
+/// Katana specific APIs.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "katana"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "katana"))]
+pub trait KatanaApi {
+    /// Returns the call tree of a transaction, including the storage slots accessed by each call
+    /// and their values right before and after the transaction.
+    ///
+    /// The transactions preceding the traced one in its block are re-executed on every call to
+    /// read those values, so the cost of this method grows with the position of the transaction
+    /// in its block.
+    #[method(name = "traceTransactionDetailed")]
+    async fn trace_transaction_detailed(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<DetailedTxTrace>;
+}
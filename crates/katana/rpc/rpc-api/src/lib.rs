@@ -1,4 +1,5 @@
 pub mod dev;
+pub mod katana;
 pub mod saya;
 pub mod starknet;
 pub mod torii;
@@ -1,6 +1,13 @@
+use std::collections::{HashMap, HashSet};
+
 use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
-use katana_primitives::trace::{CallInfo, TxExecInfo};
-use katana_primitives::transaction::TxHash;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use katana_primitives::event::OrderedEvent as PrimitiveOrderedEvent;
+use katana_primitives::trace::{self, CallInfo, TxExecInfo};
+use katana_primitives::transaction::{TxHash, TxType};
+use katana_primitives::Felt;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{
     CallType, ComputationResources, EntryPointType, OrderedEvent, OrderedMessage,
@@ -104,3 +111,326 @@ pub struct TxExecutionInfo {
     /// The transaction execution trace.
     pub trace: TxExecInfo,
 }
+
+/// The type returned by the `katana_traceTransactionDetailed` RPC method.
+///
+/// Unlike the standard `starknet_traceTransaction`, every frame of the call tree carries the
+/// storage slots it accessed together with their values right before and after the frame.
+///
+/// The execution trace records which slots each frame accessed, but not the values it wrote. Only
+/// the frames accessing a slot can write it though, so the values around a frame are derived from
+/// the values around the whole transaction: the first frame accessing a slot, outside of the inner
+/// calls of another frame accessing it, starts from the value before the transaction and the last
+/// one ends with the value after it. The values that can't be derived this way are left unset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedTxTrace {
+    /// The transaction hash.
+    pub transaction_hash: TxHash,
+    /// The number of the block the transaction is included in; [None] if it is still pending.
+    pub block_number: Option<BlockNumber>,
+    /// The transaction type.
+    pub r#type: TxType,
+    /// The fee charged for the transaction.
+    pub actual_fee: u128,
+    /// The total L1 gas consumed by the transaction.
+    pub l1_gas: u128,
+    /// The total L1 data gas consumed by the transaction.
+    pub l1_data_gas: u128,
+    /// The total number of Cairo steps of the transaction.
+    pub n_steps: usize,
+    /// Error string for reverted transactions; [None] if the execution was successful.
+    pub revert_error: Option<String>,
+    /// Call tree of the validation stage; [None] for `L1Handler`.
+    pub validate_invocation: Option<CallFrame>,
+    /// Call tree of the execution stage; [None] for `Declare`.
+    pub execute_invocation: Option<CallFrame>,
+    /// Call tree of the fee transfer; [None] for `L1Handler`.
+    pub fee_transfer_invocation: Option<CallFrame>,
+}
+
+/// A single frame of a [`DetailedTxTrace`] call tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallFrame {
+    /// The contract address whose storage is used by the call.
+    pub contract_address: ContractAddress,
+    /// The contract address which the call is initiated from.
+    pub caller_address: ContractAddress,
+    /// The class hash of the code being executed.
+    pub class_hash: Option<ClassHash>,
+    /// The call type.
+    pub call_type: trace::CallType,
+    /// The entry point type.
+    pub entry_point_type: trace::EntryPointType,
+    /// The entry point selector.
+    pub selector: Felt,
+    /// The entry point name, if it could be resolved from the class ABI.
+    pub function_name: Option<String>,
+    /// The call input.
+    pub calldata: Vec<Felt>,
+    /// The call output.
+    pub retdata: Vec<Felt>,
+    /// The events emitted directly by this frame.
+    pub events: Vec<PrimitiveOrderedEvent>,
+    /// The storage slots accessed by this frame, sorted by key, with their values around the
+    /// frame.
+    pub storage: Vec<StorageAccess>,
+    /// The values read from storage by this frame, in read order.
+    pub storage_read_values: Vec<Felt>,
+    /// The number of Cairo steps used by this frame, including its inner calls.
+    pub n_steps: usize,
+    /// The gas consumed by this frame, including its inner calls.
+    pub gas_consumed: u128,
+    /// True if the frame execution has failed.
+    pub failed: bool,
+    /// The inner calls triggered by this frame.
+    pub calls: Vec<CallFrame>,
+}
+
+/// A storage slot accessed by a [`CallFrame`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageAccess {
+    /// The storage key.
+    pub key: StorageKey,
+    /// The value of the slot right before the frame; [None] if it can't be derived from the trace.
+    pub before: Option<StorageValue>,
+    /// The value of the slot right after the frame, including its inner calls; [None] if it can't
+    /// be derived from the trace.
+    pub after: Option<StorageValue>,
+}
+
+impl StorageAccess {
+    /// Returns whether the slot value was changed by the frame; [None] if one of its values is
+    /// unknown.
+    pub fn is_changed(&self) -> Option<bool> {
+        Some(self.before? != self.after?)
+    }
+}
+
+impl DetailedTxTrace {
+    /// Creates the detailed trace of a transaction from its execution info.
+    ///
+    /// `resolver` is used to populate the per-frame data that is not part of [`TxExecInfo`], ie
+    /// the storage values and the entry point names.
+    pub fn new<R: FrameResolver>(
+        transaction_hash: TxHash,
+        block_number: Option<BlockNumber>,
+        info: TxExecInfo,
+        resolver: &mut R,
+    ) -> Self {
+        let mut frame = |call: Option<CallInfo>| call.map(|c| CallFrame::new(c, resolver));
+
+        let mut validate_invocation = frame(info.validate_call_info);
+        let mut execute_invocation = frame(info.execute_call_info);
+        let mut fee_transfer_invocation = frame(info.fee_transfer_call_info);
+
+        // The constructor of a deployed account is executed before its validation.
+        let stages = if info.r#type == TxType::DeployAccount {
+            [&mut execute_invocation, &mut validate_invocation, &mut fee_transfer_invocation]
+        } else {
+            [&mut validate_invocation, &mut execute_invocation, &mut fee_transfer_invocation]
+        };
+        resolve_storage_values(stages.into_iter().filter_map(|f| f.as_mut()), resolver);
+
+        Self {
+            transaction_hash,
+            block_number,
+            r#type: info.r#type,
+            actual_fee: info.actual_fee,
+            l1_gas: info.actual_resources.total_gas_consumed.l1_gas,
+            l1_data_gas: info.actual_resources.total_gas_consumed.l1_data_gas,
+            n_steps: info.actual_resources.vm_resources.n_steps,
+            revert_error: info.revert_error,
+            validate_invocation,
+            execute_invocation,
+            fee_transfer_invocation,
+        }
+    }
+}
+
+impl CallFrame {
+    /// Builds the frame, and recursively all of its inner frames, from a [`CallInfo`].
+    pub fn new<R: FrameResolver>(info: CallInfo, resolver: &mut R) -> Self {
+        let function_name = info
+            .class_hash
+            .and_then(|hash| resolver.function_name(hash, info.entry_point_selector));
+
+        let mut keys = info.accessed_storage_keys.into_iter().collect::<Vec<_>>();
+        keys.sort();
+
+        // the values are set once the whole call tree is known
+        let storage =
+            keys.into_iter().map(|key| StorageAccess { key, before: None, after: None }).collect();
+
+        let calls = info.inner_calls.into_iter().map(|c| CallFrame::new(c, resolver)).collect();
+
+        Self {
+            contract_address: info.contract_address,
+            caller_address: info.caller_address,
+            class_hash: info.class_hash,
+            call_type: info.call_type,
+            entry_point_type: info.entry_point_type,
+            selector: info.entry_point_selector,
+            function_name,
+            calldata: info.calldata,
+            retdata: info.retdata,
+            events: info.events,
+            storage,
+            storage_read_values: info.storage_read_values,
+            n_steps: info.execution_resources.n_steps,
+            gas_consumed: info.gas_consumed,
+            failed: info.failed,
+            calls,
+        }
+    }
+
+    /// Returns the storage slots accessed by this frame whose value is known to be changed by it.
+    pub fn storage_changed(&self) -> impl Iterator<Item = &StorageAccess> {
+        self.storage.iter().filter(|s| s.is_changed() == Some(true))
+    }
+}
+
+type Slot = (ContractAddress, StorageKey);
+
+/// Sets the storage values of the frames of a transaction, given in execution order, which can be
+/// derived from the values around the whole transaction.
+///
+/// A slot can only be written by the frames accessing it. The accesses made within the inner
+/// calls of a frame accessing the same slot are part of it, so only the outermost accesses are
+/// considered: the first one starts from the value before the transaction and the last one ends
+/// with the value after it.
+fn resolve_storage_values<'a, R: FrameResolver>(
+    frames: impl Iterator<Item = &'a mut CallFrame>,
+    resolver: &mut R,
+) {
+    /// Calls `f` on the outermost accesses of every slot, in execution order.
+    fn visit(
+        frame: &mut CallFrame,
+        enclosing: &mut HashSet<Slot>,
+        f: &mut impl FnMut(Slot, &mut StorageAccess),
+    ) {
+        let mut opened = Vec::new();
+        for access in &mut frame.storage {
+            let slot = (frame.contract_address, access.key);
+            if enclosing.insert(slot) {
+                f(slot, access);
+                opened.push(slot);
+            }
+        }
+
+        for call in &mut frame.calls {
+            visit(call, enclosing, f);
+        }
+
+        for slot in opened {
+            enclosing.remove(&slot);
+        }
+    }
+
+    let mut frames = frames.collect::<Vec<_>>();
+
+    // count the outermost accesses of every slot, to know which one is the last
+    let mut counts = HashMap::<Slot, usize>::new();
+    for frame in frames.iter_mut() {
+        visit(frame, &mut HashSet::new(), &mut |slot, _| *counts.entry(slot).or_default() += 1);
+    }
+
+    let mut seen = HashMap::<Slot, usize>::new();
+    for frame in frames.iter_mut() {
+        visit(frame, &mut HashSet::new(), &mut |slot, access| {
+            let (before_tx, after_tx) = resolver.storage(slot.0, slot.1);
+            let seen = seen.entry(slot).or_default();
+
+            if *seen == 0 {
+                access.before = Some(before_tx);
+            }
+            *seen += 1;
+            if *seen == counts[&slot] {
+                access.after = Some(after_tx);
+            }
+        });
+    }
+}
+
+/// Provides the data needed to build a [`CallFrame`] that is not recorded in the execution trace.
+pub trait FrameResolver {
+    /// Returns the value of a storage slot right before and after the whole transaction.
+    fn storage(
+        &mut self,
+        address: ContractAddress,
+        key: StorageKey,
+    ) -> (StorageValue, StorageValue);
+
+    /// Returns the name of the entry point of a class, if known.
+    fn function_name(&mut self, class_hash: ClassHash, selector: Felt) -> Option<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every slot is worth 1 before the transaction and 2 after it.
+    struct Resolver;
+
+    impl FrameResolver for Resolver {
+        fn storage(&mut self, _: ContractAddress, _: StorageKey) -> (StorageValue, StorageValue) {
+            (Felt::ONE, Felt::TWO)
+        }
+
+        fn function_name(&mut self, _: ClassHash, _: Felt) -> Option<String> {
+            None
+        }
+    }
+
+    fn frame(address: u64, keys: &[u64], calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            contract_address: Felt::from(address).into(),
+            caller_address: ContractAddress::default(),
+            class_hash: None,
+            call_type: trace::CallType::Call,
+            entry_point_type: trace::EntryPointType::External,
+            selector: Felt::ZERO,
+            function_name: None,
+            calldata: vec![],
+            retdata: vec![],
+            events: vec![],
+            storage: keys
+                .iter()
+                .map(|&key| StorageAccess { key: key.into(), before: None, after: None })
+                .collect(),
+            storage_read_values: vec![],
+            n_steps: 0,
+            gas_consumed: 0,
+            failed: false,
+            calls,
+        }
+    }
+
+    fn values(frame: &CallFrame) -> Vec<(Option<StorageValue>, Option<StorageValue>)> {
+        frame.storage.iter().map(|s| (s.before, s.after)).collect()
+    }
+
+    #[test]
+    fn storage_values_of_frames() {
+        let (one, two) = (Some(Felt::ONE), Some(Felt::TWO));
+
+        // 0xa accesses the slot 1 alone, and the slot 2 around a library call accessing it too
+        let library_call = frame(0xa, &[2], vec![]);
+        let execute = frame(0xa, &[1, 2], vec![library_call, frame(0xb, &[1], vec![])]);
+        // 0xb accesses the slot 1 in both the execute and the fee transfer stages
+        let fee_transfer = frame(0xb, &[1], vec![]);
+
+        let mut execute = Some(execute);
+        let mut fee_transfer = Some(fee_transfer);
+        let stages = [&mut execute, &mut fee_transfer];
+        resolve_storage_values(stages.into_iter().filter_map(|f| f.as_mut()), &mut Resolver);
+
+        let (execute, fee_transfer) = (execute.unwrap(), fee_transfer.unwrap());
+        assert_eq!(values(&execute), vec![(one, two), (one, two)]);
+        assert_eq!(values(&execute.calls[0]), vec![(None, None)]);
+        assert_eq!(values(&execute.calls[1]), vec![(one, None)]);
+        assert_eq!(values(&fee_transfer), vec![(None, two)]);
+
+        assert_eq!(execute.storage_changed().count(), 2);
+        assert_eq!(fee_transfer.storage_changed().count(), 0);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_core::backend::Backend;
+use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
+use katana_executor::ExecutorFactory;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use katana_primitives::env::BlockEnv;
+use katana_primitives::trace::{CallInfo, TxExecInfo};
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx, TxHash, TxWithHash,
+};
+use katana_primitives::Felt;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use katana_provider::traits::transaction::{TransactionProvider, TransactionTraceProvider};
+use katana_rpc_api::katana::KatanaApiServer;
+use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::trace::{DetailedTxTrace, FrameResolver};
+use katana_tasks::TokioTaskSpawner;
+use starknet::core::types::contract::AbiEntry;
+use starknet::core::utils::get_selector_from_name;
+
+#[allow(missing_debug_implementations)]
+pub struct KatanaApi<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+    block_producer: BlockProducer<EF>,
+}
+
+impl<EF: ExecutorFactory> Clone for KatanaApi<EF> {
+    fn clone(&self) -> Self {
+        Self { backend: Arc::clone(&self.backend), block_producer: self.block_producer.clone() }
+    }
+}
+
+impl<EF: ExecutorFactory> KatanaApi<EF> {
+    pub fn new(backend: Arc<Backend<EF>>, block_producer: BlockProducer<EF>) -> Self {
+        Self { backend, block_producer }
+    }
+
+    async fn on_io_blocking_task<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(Self) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let this = self.clone();
+        TokioTaskSpawner::new().unwrap().spawn_blocking(move || func(this)).await.unwrap()
+    }
+
+    /// Returns the pending state if the sequencer is running in _interval_ mode. Otherwise `None`.
+    fn pending_executor(&self) -> Option<PendingExecutor> {
+        match &*self.block_producer.producer.read() {
+            BlockProducerMode::Instant(_) => None,
+            BlockProducerMode::Interval(producer) => Some(producer.executor()),
+        }
+    }
+
+    fn detailed_trace(&self, tx_hash: TxHash) -> Result<DetailedTxTrace, StarknetApiError> {
+        use StarknetApiError::{BlockNotFound, TxnHashNotFound};
+
+        let provider = self.backend.blockchain.provider();
+
+        // Check in the pending block first, which is executed on top of the latest mined state.
+        if let Some(executor) = self.pending_executor() {
+            // The pending block is only locked while its transactions are copied.
+            let pending = {
+                let pending_block = executor.read();
+                let transactions = pending_block.transactions();
+
+                transactions.iter().position(|(t, _)| t.hash == tx_hash).map(|idx| {
+                    let executed = transactions[..=idx].iter().map(|(t, _)| t.clone()).collect();
+                    let trace = transactions[idx].1.trace().cloned();
+                    (executed, trace, pending_block.block_env(), pending_block.state())
+                })
+            };
+
+            if let Some((executed, trace, env, classes)) = pending {
+                let trace = trace.ok_or(TxnHashNotFound)?;
+                let state = provider.latest()?;
+                let mut resolver = self.state_resolver(state, env, executed, &trace, classes)?;
+                return Ok(DetailedTxTrace::new(tx_hash, None, trace, &mut resolver));
+            }
+        }
+
+        let (block_number, _) =
+            provider.transaction_block_num_and_hash(tx_hash)?.ok_or(TxnHashNotFound)?;
+        let trace = provider.transaction_execution(tx_hash)?.ok_or(TxnHashNotFound)?;
+
+        // The genesis block has no parent state, but it has no transactions either.
+        let parent = block_number.checked_sub(1).ok_or(TxnHashNotFound)?;
+        let state = provider.historical(parent.into())?.ok_or(BlockNotFound)?;
+        let env = provider.block_env_at(block_number.into())?.ok_or(BlockNotFound)?;
+
+        let mut transactions =
+            provider.transactions_by_block(block_number.into())?.ok_or(BlockNotFound)?;
+        let idx = transactions.iter().position(|t| t.hash == tx_hash).ok_or(TxnHashNotFound)?;
+        transactions.truncate(idx + 1);
+
+        let classes = provider.historical(block_number.into())?.ok_or(BlockNotFound)?;
+        let mut resolver = self.state_resolver(state, env, transactions, &trace, classes)?;
+        Ok(DetailedTxTrace::new(tx_hash, Some(block_number), trace, &mut resolver))
+    }
+
+    /// Re-executes the transactions of a block up to the traced one, which is the last of
+    /// `transactions`, to read the storage slots it accessed right before and after it.
+    ///
+    /// The state of the whole block can't be used, as the slots may also be written by the
+    /// other transactions of the block.
+    fn state_resolver(
+        &self,
+        state: Box<dyn StateProvider>,
+        env: BlockEnv,
+        mut transactions: Vec<TxWithHash>,
+        trace: &TxExecInfo,
+        classes: Box<dyn StateProvider>,
+    ) -> Result<StateResolver, StarknetApiError> {
+        let traced = transactions.pop().expect("the traced transaction must be provided");
+        let to_executable = |tx: TxWithHash| executable_tx(tx, classes.as_ref());
+
+        let mut executor = self.backend.executor_factory.with_state_and_block_env(state, env);
+        executor
+            .execute_transactions(
+                transactions.into_iter().map(to_executable).collect::<Result<_, _>>()?,
+            )
+            .map_err(anyhow::Error::from)?;
+
+        let slots = accessed_storage(trace);
+        let read = |state: &dyn StateProvider| {
+            slots
+                .iter()
+                .map(|&(address, key)| {
+                    let value = state.storage(address, key)?.unwrap_or_default();
+                    Ok(((address, key), value))
+                })
+                .collect::<Result<HashMap<_, _>, StarknetApiError>>()
+        };
+
+        let before = read(executor.state().as_ref())?;
+        executor.execute_transactions(vec![to_executable(traced)?]).map_err(anyhow::Error::from)?;
+        let after = read(executor.state().as_ref())?;
+
+        Ok(StateResolver::new(before, after, classes))
+    }
+}
+
+/// Converts a transaction of a block back into an executable one, fetching the class declared by
+/// a declare transaction from `classes`.
+fn executable_tx(
+    tx: TxWithHash,
+    classes: &dyn StateProvider,
+) -> Result<ExecutableTxWithHash, StarknetApiError> {
+    let transaction = match tx.transaction {
+        Tx::Invoke(tx) => ExecutableTx::Invoke(tx),
+        Tx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+        Tx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+        Tx::Declare(tx) => {
+            let class_hash = tx.class_hash();
+            let compiled_class =
+                classes.class(class_hash)?.ok_or(StarknetApiError::ClassHashNotFound)?;
+            let sierra_class = classes.sierra_class(class_hash)?;
+            ExecutableTx::Declare(DeclareTxWithClass {
+                sierra_class,
+                compiled_class,
+                transaction: tx,
+            })
+        }
+    };
+
+    Ok(ExecutableTxWithHash { hash: tx.hash, transaction })
+}
+
+/// Returns the storage slots accessed by every call of a transaction.
+fn accessed_storage(trace: &TxExecInfo) -> HashSet<(ContractAddress, StorageKey)> {
+    fn collect(call: &CallInfo, slots: &mut HashSet<(ContractAddress, StorageKey)>) {
+        slots.extend(call.accessed_storage_keys.iter().map(|key| (call.contract_address, *key)));
+        call.inner_calls.iter().for_each(|call| collect(call, slots));
+    }
+
+    let mut slots = HashSet::new();
+    [&trace.validate_call_info, &trace.execute_call_info, &trace.fee_transfer_call_info]
+        .into_iter()
+        .flatten()
+        .for_each(|call| collect(call, &mut slots));
+    slots
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> KatanaApiServer for KatanaApi<EF> {
+    async fn trace_transaction_detailed(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<DetailedTxTrace> {
+        self.on_io_blocking_task(move |this| Ok(this.detailed_trace(transaction_hash)?)).await
+    }
+}
+
+type StorageSlots = HashMap<(ContractAddress, StorageKey), StorageValue>;
+
+/// A [`FrameResolver`] that returns the storage values read before and after the transaction, and
+/// resolves entry point names from the Sierra ABI of the classes.
+struct StateResolver {
+    before: StorageSlots,
+    after: StorageSlots,
+    // The state to read the classes from.
+    classes: Box<dyn StateProvider>,
+    // Cache of the entry point names of every class encountered so far.
+    names: HashMap<ClassHash, HashMap<Felt, String>>,
+}
+
+impl StateResolver {
+    fn new(before: StorageSlots, after: StorageSlots, classes: Box<dyn StateProvider>) -> Self {
+        Self { before, after, classes, names: HashMap::new() }
+    }
+
+    fn entry_points(&self, class_hash: ClassHash) -> HashMap<Felt, String> {
+        fn collect(entry: &AbiEntry, names: &mut HashMap<Felt, String>) {
+            let name = match entry {
+                AbiEntry::Function(f) | AbiEntry::L1Handler(f) => &f.name,
+                AbiEntry::Constructor(c) => &c.name,
+                AbiEntry::Interface(i) => {
+                    i.items.iter().for_each(|item| collect(item, names));
+                    return;
+                }
+                _ => return,
+            };
+
+            if let Ok(selector) = get_selector_from_name(name) {
+                names.insert(selector, name.clone());
+            }
+        }
+
+        let mut names = HashMap::new();
+
+        // Legacy classes don't have a Sierra ABI, their entry points are left unresolved.
+        let Ok(Some(class)) = self.classes.sierra_class(class_hash) else { return names };
+        let Ok(abi) = serde_json::from_str::<Vec<AbiEntry>>(&class.abi) else { return names };
+
+        abi.iter().for_each(|entry| collect(entry, &mut names));
+        names
+    }
+}
+
+impl FrameResolver for StateResolver {
+    fn storage(
+        &mut self,
+        address: ContractAddress,
+        key: StorageKey,
+    ) -> (StorageValue, StorageValue) {
+        let before = self.before.get(&(address, key)).copied();
+        let after = self.after.get(&(address, key)).copied();
+        (before.unwrap_or_default(), after.unwrap_or_default())
+    }
+
+    fn function_name(&mut self, class_hash: ClassHash, selector: Felt) -> Option<String> {
+        if !self.names.contains_key(&class_hash) {
+            let names = self.entry_points(class_hash);
+            self.names.insert(class_hash, names);
+        }
+
+        self.names.get(&class_hash).and_then(|names| names.get(&selector)).cloned()
+    }
+}
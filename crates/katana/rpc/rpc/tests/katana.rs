@@ -0,0 +1,113 @@
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_node::config::SequencingConfig;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::katana::KatanaApiClient;
+use katana_rpc_types::trace::DetailedTxTrace;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{Call, Felt};
+use starknet::core::utils::get_storage_var_address;
+use starknet::macros::{felt, selector};
+
+#[tokio::test]
+async fn trace_transaction_detailed() {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    let recipient = felt!("0x1337");
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![recipient, felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, account.provider()).await.unwrap();
+
+    let trace = client.trace_transaction_detailed(res.transaction_hash).await.unwrap();
+    assert_eq!(trace.transaction_hash, res.transaction_hash);
+    assert!(trace.block_number.is_some());
+
+    // the account class is a Sierra class, so its entry points must be resolved from the ABI
+    let execute = trace.execute_invocation.expect("invoke must have an execute invocation");
+    assert_eq!(execute.function_name.as_deref(), Some("__execute__"));
+    assert_eq!(execute.contract_address, account.address().into());
+
+    let transfer = &execute.calls[0];
+    assert_eq!(transfer.selector, selector!("transfer"));
+    assert_eq!(transfer.contract_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+
+    // the recipient balance slot must be reported as changed in the transfer frame
+    let balance_key = get_storage_var_address("ERC20_balances", &[recipient]).unwrap();
+    let balance = transfer
+        .storage_changed()
+        .find(|s| s.key == balance_key)
+        .expect("recipient balance must be changed");
+    assert_eq!(balance.after.unwrap() - balance.before.unwrap(), Felt::ONE);
+
+    // the sender balance is also written by the fee transfer, so the transfer frame only starts
+    // from the value before the transaction and the fee transfer frame ends with the one after it
+    let sender_key = get_storage_var_address("ERC20_balances", &[account.address()]).unwrap();
+    let sender = transfer.storage.iter().find(|s| s.key == sender_key).unwrap();
+    assert!(sender.before.is_some() && sender.after.is_none());
+
+    let fee_transfer = trace.fee_transfer_invocation.expect("invoke must charge a fee");
+    let sender = fee_transfer.storage.iter().find(|s| s.key == sender_key).unwrap();
+    assert!(sender.before.is_none() && sender.after.is_some());
+}
+
+#[tokio::test]
+async fn trace_transaction_detailed_in_shared_block() {
+    let config =
+        get_default_test_config(SequencingConfig { no_mining: true, ..Default::default() });
+    let sequencer = TestSequencer::start(config).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+
+    // both transfers are included in the same block
+    let recipient = felt!("0x1337");
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![recipient, felt!("0x1"), Felt::ZERO],
+    };
+
+    let mut hashes = Vec::new();
+    for _ in 0..2 {
+        let res = account.execute_v1(vec![call.clone()]).send().await.unwrap();
+        TransactionWaiter::new(res.transaction_hash, account.provider()).await.unwrap();
+        hashes.push(res.transaction_hash);
+    }
+
+    let balance_key = get_storage_var_address("ERC20_balances", &[recipient]).unwrap();
+    let recipient_balance = |trace: DetailedTxTrace| {
+        let execute = trace.execute_invocation.expect("invoke must have an execute invocation");
+        let balance = execute.calls[0]
+            .storage_changed()
+            .find(|s| s.key == balance_key)
+            .expect("recipient balance must be changed")
+            .clone();
+        (balance.before.unwrap(), balance.after.unwrap())
+    };
+
+    // the values are the ones of each transaction, not of the whole block
+    let trace = client.trace_transaction_detailed(hashes[0]).await.unwrap();
+    assert!(trace.block_number.is_none());
+    assert_eq!(recipient_balance(trace), (Felt::ZERO, Felt::ONE));
+
+    let trace = client.trace_transaction_detailed(hashes[1]).await.unwrap();
+    assert_eq!(recipient_balance(trace), (Felt::ONE, Felt::TWO));
+
+    // and they don't change once the block is mined
+    client.generate_block().await.unwrap();
+
+    let trace = client.trace_transaction_detailed(hashes[1]).await.unwrap();
+    assert!(trace.block_number.is_some());
+    assert_eq!(recipient_balance(trace), (Felt::ONE, Felt::TWO));
+}
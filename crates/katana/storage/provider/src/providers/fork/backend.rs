@@ -24,6 +24,7 @@ use starknet::core::types::{BlockId, ContractClass as RpcContractClass, Starknet
 use starknet::providers::{Provider, ProviderError as StarknetProviderError};
 use tracing::{error, trace};
 
+use super::metrics::ForkCacheMetrics;
 use crate::error::ProviderError;
 use crate::providers::in_memory::cache::CacheStateDb;
 use crate::traits::contract::ContractClassProvider;
@@ -374,11 +375,11 @@ impl BackendHandle {
 /// cache to avoid fetching it again. This is shared across multiple instances of
 /// [`ForkedStateDb`](super::state::ForkedStateDb).
 #[derive(Clone, Debug)]
-pub struct SharedStateProvider(pub(crate) Arc<CacheStateDb<BackendHandle>>);
+pub struct SharedStateProvider(pub(crate) Arc<CacheStateDb<BackendHandle>>, ForkCacheMetrics);
 
 impl SharedStateProvider {
     pub(crate) fn new_with_backend(backend: BackendHandle) -> Self {
-        Self(Arc::new(CacheStateDb::new(backend)))
+        Self(Arc::new(CacheStateDb::new(backend)), ForkCacheMetrics::default())
     }
 }
 
@@ -408,9 +409,12 @@ impl StateProvider for SharedStateProvider {
             .map(|i| i.nonce)
             .filter(|n| n != &Nonce::ZERO)
         {
+            self.1.nonce.record(true);
             return Ok(nonce);
         }
 
+        self.1.nonce.record(false);
+
         if let Some(nonce) = handle_not_found_err(self.0.get_nonce(address)).map_err(|error| {
             error!(target: LOG_TARGET, %address, %error, "Fetching nonce.");
             error
@@ -430,9 +434,12 @@ impl StateProvider for SharedStateProvider {
         if let value @ Some(_) =
             self.0.storage.read().get(&address).and_then(|s| s.get(&storage_key))
         {
+            self.1.storage.record(true);
             return Ok(value.copied());
         }
 
+        self.1.storage.record(false);
+
         let value =
             handle_not_found_err(self.0.get_storage(address, storage_key)).map_err(|error| {
                 error!(target: LOG_TARGET, %address, storage_key = %format!("{storage_key:#x}"), %error, "Fetching storage value.");
@@ -462,9 +469,12 @@ impl StateProvider for SharedStateProvider {
             .map(|i| i.class_hash)
             .filter(|h| h != &ClassHash::ZERO)
         {
+            self.1.class_hash.record(true);
             return Ok(hash);
         }
 
+        self.1.class_hash.record(false);
+
         if let Some(hash) =
             handle_not_found_err(self.0.get_class_hash_at(address)).map_err(|error| {
                 error!(target: LOG_TARGET, %address, %error, "Fetching class hash.");
@@ -482,9 +492,12 @@ impl StateProvider for SharedStateProvider {
 impl ContractClassProvider for SharedStateProvider {
     fn sierra_class(&self, hash: ClassHash) -> ProviderResult<Option<FlattenedSierraClass>> {
         if let class @ Some(_) = self.0.shared_contract_classes.sierra_classes.read().get(&hash) {
+            self.1.sierra_class.record(true);
             return Ok(class.cloned());
         }
 
+        self.1.sierra_class.record(false);
+
         let Some(class) = handle_not_found_err(self.0.get_class_at(hash)).map_err(|error| {
             error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching sierra class.");
             error
@@ -511,9 +524,12 @@ impl ContractClassProvider for SharedStateProvider {
         hash: ClassHash,
     ) -> ProviderResult<Option<CompiledClassHash>> {
         if let hash @ Some(_) = self.0.compiled_class_hashes.read().get(&hash) {
+            self.1.compiled_class_hash.record(true);
             return Ok(hash.cloned());
         }
 
+        self.1.compiled_class_hash.record(false);
+
         if let Some(hash) =
             handle_not_found_err(self.0.get_compiled_class_hash(hash)).map_err(|error| {
                 error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching compiled class hash.");
@@ -529,9 +545,12 @@ impl ContractClassProvider for SharedStateProvider {
 
     fn class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledClass>> {
         if let Some(class) = self.0.shared_contract_classes.compiled_classes.read().get(&hash) {
+            self.1.class.record(true);
             return Ok(Some(class.clone()));
         }
 
+        self.1.class.record(false);
+
         let Some(class) = handle_not_found_err(self.0.get_class_at(hash)).map_err(|error| {
             error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching class.");
             error
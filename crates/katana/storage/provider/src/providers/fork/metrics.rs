@@ -0,0 +1,54 @@
+use core::fmt;
+
+use dojo_metrics::Metrics;
+use metrics::Counter;
+
+/// Metrics for the forked network cache, labeled by the type of the cached item (ie `item`).
+#[derive(Metrics, Clone)]
+#[metrics(scope = "fork.cache")]
+pub(crate) struct CacheMetrics {
+    /// The number of lookups served from the cache.
+    pub(crate) hits_total: Counter,
+    /// The number of lookups that required fetching from the forked network.
+    pub(crate) misses_total: Counter,
+}
+
+impl CacheMetrics {
+    pub(crate) fn record(&self, hit: bool) {
+        if hit {
+            self.hits_total.increment(1);
+        } else {
+            self.misses_total.increment(1);
+        }
+    }
+}
+
+/// Cache metrics for each type of item that is fetched from the forked network.
+#[derive(Clone)]
+pub(crate) struct ForkCacheMetrics {
+    pub(crate) nonce: CacheMetrics,
+    pub(crate) storage: CacheMetrics,
+    pub(crate) class_hash: CacheMetrics,
+    pub(crate) sierra_class: CacheMetrics,
+    pub(crate) compiled_class_hash: CacheMetrics,
+    pub(crate) class: CacheMetrics,
+}
+
+impl Default for ForkCacheMetrics {
+    fn default() -> Self {
+        Self {
+            nonce: CacheMetrics::new_with_labels(&[("item", "nonce")]),
+            storage: CacheMetrics::new_with_labels(&[("item", "storage")]),
+            class_hash: CacheMetrics::new_with_labels(&[("item", "class_hash")]),
+            sierra_class: CacheMetrics::new_with_labels(&[("item", "sierra_class")]),
+            compiled_class_hash: CacheMetrics::new_with_labels(&[("item", "compiled_class_hash")]),
+            class: CacheMetrics::new_with_labels(&[("item", "class")]),
+        }
+    }
+}
+
+impl fmt::Debug for ForkCacheMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForkCacheMetrics").finish_non_exhaustive()
+    }
+}
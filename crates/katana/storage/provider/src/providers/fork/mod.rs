@@ -1,4 +1,5 @@
 pub mod backend;
+mod metrics;
 pub mod state;
 
 use std::collections::BTreeMap;
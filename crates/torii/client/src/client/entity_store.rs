@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use dojo_types::schema::Struct;
+use starknet::core::types::Felt;
+use torii_grpc::types::schema::{Entity, EntityDiff, EntityDiffUpdate};
+
+/// Reassembles the entities of a subscription receiving diffs, see
+/// [`Client::on_entity_diffs`](super::Client::on_entity_diffs).
+///
+/// The store is seeded with the entities retrieved from Torii, and updated with the diffs of the
+/// subscription.
+#[derive(Debug, Default)]
+pub struct EntityStore {
+    /// The entities by world and hashed keys, with the sequence number of their last applied diff,
+    /// `None` for the ones retrieved from Torii.
+    entities: HashMap<(Felt, Felt), (Option<u64>, Entity)>,
+}
+
+/// The outcome of applying an update of the subscription to an [`EntityStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppliedUpdate {
+    /// The entity, with all of its known models.
+    Updated(Entity),
+    /// The entity has been deleted.
+    Deleted { world_address: Felt, hashed_keys: Felt },
+    /// A diff of the entity was missed, it has been removed from the store and must be retrieved
+    /// again.
+    Stale { world_address: Felt, hashed_keys: Felt },
+    /// All the entities have been removed from the store and must be retrieved again.
+    Resync,
+}
+
+impl EntityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an entity retrieved from Torii, replacing the one held if any.
+    pub fn insert(&mut self, entity: Entity) {
+        self.entities.insert((entity.world_address, entity.hashed_keys), (None, entity));
+    }
+
+    pub fn get(&self, world_address: Felt, hashed_keys: Felt) -> Option<&Entity> {
+        self.entities.get(&(world_address, hashed_keys)).map(|(_, entity)| entity)
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values().map(|(_, entity)| entity)
+    }
+
+    pub fn apply(&mut self, update: EntityDiffUpdate) -> AppliedUpdate {
+        match update {
+            EntityDiffUpdate::Diff(diff) => self.apply_diff(diff),
+            EntityDiffUpdate::Resync => {
+                self.entities.clear();
+                AppliedUpdate::Resync
+            }
+        }
+    }
+
+    fn apply_diff(&mut self, diff: EntityDiff) -> AppliedUpdate {
+        let (world_address, hashed_keys) = (diff.world_address, diff.hashed_keys);
+        let key = (world_address, hashed_keys);
+
+        // entities retrieved from Torii already hold the previous diffs
+        let in_sequence = match self.entities.get(&key) {
+            Some((Some(sequence), _)) => *sequence == diff.previous_sequence,
+            Some((None, _)) => true,
+            None => diff.previous_sequence == 0,
+        };
+        if !in_sequence {
+            self.entities.remove(&key);
+            return AppliedUpdate::Stale { world_address, hashed_keys };
+        }
+
+        if diff.deleted {
+            self.entities.remove(&key);
+            return AppliedUpdate::Deleted { world_address, hashed_keys };
+        }
+
+        let (sequence, entity) = self
+            .entities
+            .entry(key)
+            .or_insert_with(|| (None, Entity { hashed_keys, models: vec![], world_address }));
+        *sequence = Some(diff.sequence);
+
+        entity.models.retain(|model| !diff.deleted_models.contains(&model.name));
+        for model in diff.models {
+            match entity.models.iter_mut().find(|m| m.name == model.name) {
+                Some(known) => merge_members(known, model),
+                None => entity.models.push(model),
+            }
+        }
+
+        AppliedUpdate::Updated(entity.clone())
+    }
+}
+
+fn merge_members(model: &mut Struct, diff: Struct) {
+    for member in diff.children {
+        match model.children.iter_mut().find(|m| m.name == member.name) {
+            Some(known) => *known = member,
+            None => model.children.push(member),
+        }
+    }
+}
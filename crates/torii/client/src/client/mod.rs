@@ -1,3 +1,4 @@
+pub mod entity_store;
 pub mod error;
 
 use std::sync::Arc;
@@ -5,12 +6,15 @@ use std::sync::Arc;
 use dojo_types::WorldMetadata;
 use dojo_world::contracts::WorldContractReader;
 use futures::lock::Mutex;
+use futures::Stream;
 use parking_lot::{RwLock, RwLockReadGuard};
 use starknet::core::types::Felt;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 use tokio::sync::RwLock as AsyncRwLock;
-use torii_grpc::client::{EntityUpdateStreaming, EventUpdateStreaming, IndexerUpdateStreaming};
+use torii_grpc::client::{
+    EntityDiffStreaming, EntityUpdateStreaming, EventUpdateStreaming, IndexerUpdateStreaming,
+};
 use torii_grpc::proto::world::{RetrieveEntitiesResponse, RetrieveEventsResponse};
 use torii_grpc::types::schema::Entity;
 use torii_grpc::types::{EntityKeysClause, Event, EventQuery, Query};
@@ -93,11 +97,40 @@ impl Client {
     /// type of entites matching keys and/or models.
     pub async fn entities(&self, query: Query) -> Result<Vec<Entity>, Error> {
         let mut grpc_client = self.inner.write().await;
-        let RetrieveEntitiesResponse { entities, total_count: _ } =
+        let RetrieveEntitiesResponse { entities, total_count: _, next_cursor: _ } =
             grpc_client.retrieve_entities(query).await?;
         Ok(entities.into_iter().map(TryInto::try_into).collect::<Result<Vec<Entity>, _>>()?)
     }
 
+    /// Retrieves the entities matching the query parameter page by page, each page following the
+    /// cursor returned with the previous one. The stream ends after the last page.
+    ///
+    /// Unlike offsets, cursors keep the pages consistent while the entities are being indexed.
+    pub fn entities_pages(
+        &self,
+        query: Query,
+    ) -> impl Stream<Item = Result<Vec<Entity>, Error>> + '_ {
+        futures::stream::try_unfold(Some(query), move |query| async move {
+            let Some(mut query) = query else {
+                return Ok::<_, Error>(None);
+            };
+
+            let mut grpc_client = self.inner.write().await;
+            let RetrieveEntitiesResponse { entities, total_count: _, next_cursor } =
+                grpc_client.retrieve_entities(query.clone()).await?;
+            let entities =
+                entities.into_iter().map(TryInto::try_into).collect::<Result<Vec<Entity>, _>>()?;
+
+            // the offset only applies to the first page, the next ones start after the cursor
+            let next_query = (!next_cursor.is_empty()).then(|| {
+                query.offset = 0;
+                query.cursor = Some(next_cursor);
+                query
+            });
+            Ok(Some((entities, next_query)))
+        })
+    }
+
     /// Similary to entities, this function retrieves event messages matching the query parameter.
     pub async fn event_messages(
         &self,
@@ -105,7 +138,7 @@ impl Client {
         historical: bool,
     ) -> Result<Vec<Entity>, Error> {
         let mut grpc_client = self.inner.write().await;
-        let RetrieveEntitiesResponse { entities, total_count: _ } =
+        let RetrieveEntitiesResponse { entities, total_count: _, next_cursor: _ } =
             grpc_client.retrieve_event_messages(query, historical).await?;
         Ok(entities.into_iter().map(TryInto::try_into).collect::<Result<Vec<Entity>, _>>()?)
     }
@@ -114,7 +147,8 @@ impl Client {
     /// If the keys are empty, it will return all events.
     pub async fn starknet_events(&self, query: EventQuery) -> Result<Vec<Event>, Error> {
         let mut grpc_client = self.inner.write().await;
-        let RetrieveEventsResponse { events } = grpc_client.retrieve_events(query).await?;
+        let RetrieveEventsResponse { events, next_cursor: _ } =
+            grpc_client.retrieve_events(query).await?;
         Ok(events.into_iter().map(Event::from).collect::<Vec<Event>>())
     }
 
@@ -128,6 +162,17 @@ impl Client {
         Ok(stream)
     }
 
+    /// A direct stream to grpc subscribe entities, only receiving their changed models and
+    /// members. The entities can be reassembled with an [`EntityStore`](entity_store::EntityStore).
+    pub async fn on_entity_diffs(
+        &self,
+        clauses: Vec<EntityKeysClause>,
+    ) -> Result<EntityDiffStreaming, Error> {
+        let mut grpc_client = self.inner.write().await;
+        let stream = grpc_client.subscribe_entity_diffs(clauses).await?;
+        Ok(stream)
+    }
+
     /// Update the entities subscription
     pub async fn update_entity_subscription(
         &self,
@@ -150,6 +195,19 @@ impl Client {
         Ok(stream)
     }
 
+    /// A direct stream to grpc subscribe event messages, only receiving their changed models and
+    /// members. The event messages can be reassembled with an
+    /// [`EntityStore`](entity_store::EntityStore).
+    pub async fn on_event_message_diffs(
+        &self,
+        clauses: Vec<EntityKeysClause>,
+        historical: bool,
+    ) -> Result<EntityDiffStreaming, Error> {
+        let mut grpc_client = self.inner.write().await;
+        let stream = grpc_client.subscribe_event_message_diffs(clauses, historical).await?;
+        Ok(stream)
+    }
+
     /// Update the event messages subscription
     pub async fn update_event_message_subscription(
         &self,
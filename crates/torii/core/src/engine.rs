@@ -1,10 +1,11 @@
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use bitflags::bitflags;
 use dojo_world::contracts::world::WorldContractReader;
 use futures_util::future::{join_all, try_join_all};
@@ -24,20 +25,34 @@ use tokio::task::JoinSet;
 use tokio::time::{sleep, Instant};
 use tracing::{debug, error, info, trace, warn};
 
+use crate::processors::erc1155_transfer_batch::Erc1155TransferBatchProcessor;
+use crate::processors::erc1155_transfer_single::Erc1155TransferSingleProcessor;
+use crate::processors::erc1155_uri::Erc1155UriProcessor;
 use crate::processors::erc20_legacy_transfer::Erc20LegacyTransferProcessor;
 use crate::processors::erc20_transfer::Erc20TransferProcessor;
+use crate::processors::erc4906_batch_metadata_update::Erc4906BatchMetadataUpdateProcessor;
+use crate::processors::erc4906_metadata_update::Erc4906MetadataUpdateProcessor;
 use crate::processors::erc721_legacy_transfer::Erc721LegacyTransferProcessor;
 use crate::processors::erc721_transfer::Erc721TransferProcessor;
 use crate::processors::event_message::EventMessageProcessor;
+use crate::processors::initialize_contract::InitializeContractProcessor;
 use crate::processors::metadata_update::MetadataUpdateProcessor;
+use crate::processors::owner_update::OwnerUpdateProcessor;
 use crate::processors::raw_event::RawEventProcessor;
+use crate::processors::register_contract::RegisterContractProcessor;
 use crate::processors::register_event::RegisterEventProcessor;
 use crate::processors::register_model::RegisterModelProcessor;
+use crate::processors::register_namespace::RegisterNamespaceProcessor;
 use crate::processors::store_del_record::StoreDelRecordProcessor;
 use crate::processors::store_set_record::StoreSetRecordProcessor;
 use crate::processors::store_update_member::StoreUpdateMemberProcessor;
 use crate::processors::store_update_record::StoreUpdateRecordProcessor;
+use crate::processors::upgrade_contract::UpgradeContractProcessor;
+use crate::processors::upgrade_event::UpgradeEventProcessor;
+use crate::processors::upgrade_model::UpgradeModelProcessor;
+use crate::processors::writer_update::WriterUpdateProcessor;
 use crate::processors::{BlockProcessor, EventProcessor, TransactionProcessor};
+use crate::sql::reorg::MAX_STORED_BLOCKS;
 use crate::sql::{Cursors, Sql};
 use crate::types::ContractType;
 
@@ -49,6 +64,8 @@ pub struct Processors<P: Provider + Send + Sync + std::fmt::Debug + 'static> {
     pub transaction: Vec<Box<dyn TransactionProcessor<P>>>,
     pub catch_all_event: Box<dyn EventProcessor<P>>,
     pub event_processors: HashMap<ContractType, EventProcessorMap<P>>,
+    /// Processors of the events of a single contract, taking precedence over the ones of its type.
+    pub contract_event_processors: HashMap<Felt, EventProcessorMap<P>>,
 }
 
 impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Default for Processors<P> {
@@ -60,6 +77,7 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Default for Processo
             // anymore.
             catch_all_event: Box::new(RawEventProcessor) as Box<dyn EventProcessor<P>>,
             event_processors: Self::initialize_event_processors(),
+            contract_event_processors: HashMap::new(),
         }
     }
 }
@@ -74,6 +92,14 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Processors<P> {
                 vec![
                     Box::new(RegisterModelProcessor) as Box<dyn EventProcessor<P>>,
                     Box::new(RegisterEventProcessor) as Box<dyn EventProcessor<P>>,
+                    Box::new(UpgradeModelProcessor),
+                    Box::new(UpgradeEventProcessor),
+                    Box::new(RegisterNamespaceProcessor),
+                    Box::new(RegisterContractProcessor),
+                    Box::new(UpgradeContractProcessor),
+                    Box::new(InitializeContractProcessor),
+                    Box::new(OwnerUpdateProcessor),
+                    Box::new(WriterUpdateProcessor),
                     Box::new(StoreSetRecordProcessor),
                     Box::new(StoreDelRecordProcessor),
                     Box::new(StoreUpdateRecordProcessor),
@@ -94,6 +120,16 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Processors<P> {
                 vec![
                     Box::new(Erc721TransferProcessor) as Box<dyn EventProcessor<P>>,
                     Box::new(Erc721LegacyTransferProcessor) as Box<dyn EventProcessor<P>>,
+                    Box::new(Erc4906MetadataUpdateProcessor) as Box<dyn EventProcessor<P>>,
+                    Box::new(Erc4906BatchMetadataUpdateProcessor) as Box<dyn EventProcessor<P>>,
+                ],
+            ),
+            (
+                ContractType::ERC1155,
+                vec![
+                    Box::new(Erc1155TransferSingleProcessor) as Box<dyn EventProcessor<P>>,
+                    Box::new(Erc1155TransferBatchProcessor) as Box<dyn EventProcessor<P>>,
+                    Box::new(Erc1155UriProcessor) as Box<dyn EventProcessor<P>>,
                 ],
             ),
         ];
@@ -121,6 +157,55 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Processors<P> {
     ) -> &HashMap<Felt, Vec<Box<dyn EventProcessor<P>>>> {
         self.event_processors.get(&contract_type).unwrap()
     }
+
+    /// Registers a processor for the events of all the contracts of the given type.
+    pub fn register_event_processor(
+        &mut self,
+        contract_type: ContractType,
+        processor: Box<dyn EventProcessor<P>>,
+    ) {
+        let key = get_selector_from_name(processor.event_key().as_str())
+            .expect("Event key is ASCII so this should never fail");
+        self.event_processors
+            .entry(contract_type)
+            .or_default()
+            .entry(key)
+            .or_default()
+            .push(processor);
+    }
+
+    /// Registers a processor for the events of a single contract.
+    pub fn register_contract_event_processor(
+        &mut self,
+        contract_address: Felt,
+        processor: Box<dyn EventProcessor<P>>,
+    ) {
+        let key = get_selector_from_name(processor.event_key().as_str())
+            .expect("Event key is ASCII so this should never fail");
+        self.contract_event_processors
+            .entry(contract_address)
+            .or_default()
+            .entry(key)
+            .or_default()
+            .push(processor);
+    }
+
+    /// The processors of an event, the ones registered for its contract if any, otherwise the
+    /// ones of the contract type.
+    pub fn get_processors_of_event(
+        &self,
+        contract_type: ContractType,
+        event: &Event,
+    ) -> Option<&Vec<Box<dyn EventProcessor<P>>>> {
+        self.contract_event_processors
+            .get(&event.from_address)
+            .and_then(|processors| processors.get(&event.keys[0]))
+            .or_else(|| {
+                self.event_processors
+                    .get(&contract_type)
+                    .and_then(|processors| processors.get(&event.keys[0]))
+            })
+    }
 }
 pub(crate) const LOG_TARGET: &str = "torii_core::engine";
 pub const QUERY_QUEUE_BATCH_SIZE: usize = 1000;
@@ -162,6 +247,8 @@ impl Default for EngineConfig {
 pub enum FetchDataResult {
     Range(FetchRangeResult),
     Pending(FetchPendingResult),
+    // The chain diverged from what has been indexed, holds the last block still shared with it.
+    Reorg(u64),
     None,
 }
 
@@ -171,7 +258,11 @@ pub struct FetchRangeResult {
     // NOTE: LinkedList might contains blocks in different order
     pub transactions: LinkedHashMap<(u64, Felt), Vec<EmittedEvent>>,
     pub blocks: BTreeMap<u64, u64>,
+    // block_number -> block_hash, for the last blocks of the range
+    pub block_hashes: BTreeMap<u64, Felt>,
     pub latest_block_number: u64,
+    // Whether a transaction indexed from the pending block is missing from the range
+    pub replaced_pending_block: bool,
 }
 
 #[derive(Debug)]
@@ -191,6 +282,9 @@ pub struct ParallelizedEvent {
 
 #[allow(missing_debug_implementations)]
 pub struct Engine<P: Provider + Send + Sync + std::fmt::Debug + 'static> {
+    /// The indexed worlds, by address.
+    worlds: Arc<HashMap<Felt, Arc<WorldContractReader<P>>>>,
+    /// The first indexed world, given to the processors of the events of the other contracts.
     world: Arc<WorldContractReader<P>>,
     db: Sql,
     provider: Arc<P>,
@@ -208,9 +302,11 @@ struct UnprocessedEvent {
 }
 
 impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
+    /// Creates an engine indexing the given worlds, of which there must be at least one, along
+    /// with the other `contracts`.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        world: WorldContractReader<P>,
+        worlds: Vec<WorldContractReader<P>>,
         db: Sql,
         provider: P,
         processors: Processors<P>,
@@ -219,8 +315,13 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         block_tx: Option<BoundedSender<u64>>,
         contracts: Arc<HashMap<Felt, ContractType>>,
     ) -> Self {
+        let worlds =
+            worlds.into_iter().map(|world| (world.address, Arc::new(world))).collect::<Vec<_>>();
+        let world = worlds.first().expect("At least one world must be indexed").1.clone();
+
         Self {
-            world: Arc::new(world),
+            worlds: Arc::new(worlds.into_iter().collect()),
+            world,
             db,
             provider: Arc::new(provider),
             processors: Arc::new(processors),
@@ -234,11 +335,17 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
 
     pub async fn start(&mut self) -> Result<()> {
         // use the start block provided by user if head is 0
-        let (head, _, _) = self.db.head(self.world.address).await?;
-        if head == 0 {
-            self.db.set_head(self.config.start_block, 0, 0, self.world.address).await?;
-        } else if self.config.start_block != 0 {
-            warn!(target: LOG_TARGET, "Start block ignored, stored head exists and will be used instead.");
+        for world in self.worlds.keys() {
+            let (head, _, _) = self.db.head(*world).await?;
+            if head == 0 {
+                self.db.set_head(self.config.start_block, 0, 0, *world).await?;
+            } else if self.config.start_block != 0 {
+                warn!(
+                    target: LOG_TARGET,
+                    world = %format!("{:#x}", world),
+                    "Start block ignored, stored head exists and will be used instead."
+                );
+            }
         }
 
         let mut backoff_delay = Duration::from_secs(1);
@@ -299,7 +406,11 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         let latest_block_number = self.provider.block_hash_and_number().await?.block_number;
 
         let from = cursors.head.unwrap_or(0);
-        let total_remaining_blocks = latest_block_number - from;
+        if let Some(fork_block) = self.detect_reorg(from, latest_block_number).await? {
+            return Ok(FetchDataResult::Reorg(fork_block));
+        }
+
+        let total_remaining_blocks = latest_block_number.saturating_sub(from);
         let blocks_to_process = total_remaining_blocks.min(self.config.blocks_chunk_size);
         let to = from + blocks_to_process;
 
@@ -308,12 +419,41 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
             let from = if from == 0 { from } else { from + 1 };
             let data = self.fetch_range(from, to, &cursors.cursor_map).await?;
             debug!(target: LOG_TARGET, duration = ?instant.elapsed(), from = %from, to = %to, "Fetched data for range.");
+            // Transactions indexed from the pending block must have been included in the range,
+            // otherwise the pending block they came from has been replaced.
+            if data.replaced_pending_block {
+                let head = cursors.head.unwrap_or(0);
+                warn!(target: LOG_TARGET, head = %head, "Pending block replaced, rolling back to head.");
+                return Ok(FetchDataResult::Reorg(head));
+            }
             FetchDataResult::Range(data)
         } else if self.config.index_pending {
             let data =
                 self.fetch_pending(latest_block_number + 1, cursors.last_pending_block_tx).await?;
             debug!(target: LOG_TARGET, duration = ?instant.elapsed(), latest_block_number = %latest_block_number, "Fetched pending data.");
             if let Some(data) = data {
+                if let Some(last_pending_block_tx) = data.last_pending_block_tx {
+                    if !data
+                        .pending_block
+                        .transactions
+                        .iter()
+                        .any(|t| t.transaction.transaction_hash() == &last_pending_block_tx)
+                    {
+                        // The pending block may have been sealed since it was fetched, in which
+                        // case the next range checks the transactions were included.
+                        if self
+                            .db
+                            .block_hash(from)
+                            .await?
+                            .is_some_and(|hash| hash != data.pending_block.parent_hash)
+                        {
+                            return Ok(FetchDataResult::None);
+                        }
+
+                        warn!(target: LOG_TARGET, head = %from, "Pending block replaced, rolling back to head.");
+                        return Ok(FetchDataResult::Reorg(from));
+                    }
+                }
                 FetchDataResult::Pending(data)
             } else {
                 FetchDataResult::None
@@ -354,6 +494,7 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         let task_result = join_all(fetch_all_events_tasks).await;
 
         let mut events = vec![];
+        let mut replaced_pending_block = false;
 
         for result in task_result {
             let result = result?;
@@ -389,6 +530,10 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
                     events.push(event);
                 }
             }
+
+            if last_contract_tx_tmp.is_some() {
+                replaced_pending_block = true;
+            }
         }
 
         // Transactions & blocks to process
@@ -413,8 +558,13 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
                 .push(event);
         }
 
+        // The hash of every block is recorded, as the fork point of a reorganization can be any
+        // of them. Only the last ones are kept though.
+        let first_recorded_block = to.saturating_sub(MAX_STORED_BLOCKS as u64 - 1).max(from);
+        block_set.extend(first_recorded_block..=to);
+
         let semaphore = Arc::new(Semaphore::new(self.config.max_concurrent_tasks));
-        let mut set: JoinSet<Result<(u64, u64), anyhow::Error>> = JoinSet::new();
+        let mut set: JoinSet<Result<(u64, u64, Felt), anyhow::Error>> = JoinSet::new();
 
         for block_number in block_set {
             let semaphore = semaphore.clone();
@@ -422,20 +572,31 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
             set.spawn(async move {
                 let _permit = semaphore.acquire().await.unwrap();
                 debug!("Fetching block timestamp for block number: {}", block_number);
-                let block_timestamp = get_block_timestamp(&provider, block_number).await?;
-                Ok((block_number, block_timestamp))
+                let (block_timestamp, block_hash) =
+                    get_block_timestamp_and_hash(&provider, block_number).await?;
+                Ok((block_number, block_timestamp, block_hash))
             });
         }
 
+        let mut block_hashes = BTreeMap::new();
         while let Some(result) = set.join_next().await {
-            let (block_number, block_timestamp) = result??;
+            let (block_number, block_timestamp, block_hash) = result??;
             blocks.insert(block_number, block_timestamp);
+            if block_number >= first_recorded_block {
+                block_hashes.insert(block_number, block_hash);
+            }
         }
 
         debug!("Transactions: {}", &transactions.len());
         debug!("Blocks: {}", &blocks.len());
 
-        Ok(FetchRangeResult { transactions, blocks, latest_block_number: to })
+        Ok(FetchRangeResult {
+            transactions,
+            blocks,
+            block_hashes,
+            latest_block_number: to,
+            replaced_pending_block,
+        })
     }
 
     async fn fetch_pending(
@@ -461,10 +622,69 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         }))
     }
 
+    // Checks that the indexed head is still part of the canonical chain, returning the fork point
+    // if it isn't.
+    async fn detect_reorg(&self, head: u64, latest_block_number: u64) -> Result<Option<u64>> {
+        let Some(head_hash) = self.db.block_hash(head).await? else {
+            return Ok(None);
+        };
+
+        // At the tip, the next block is pending so the head is checked directly. If the chain is
+        // now shorter than the indexed head, the head has been reorged out.
+        let canonical_hash = match head.cmp(&latest_block_number) {
+            Ordering::Less => Some(get_block_parent_hash(&self.provider, head + 1).await?),
+            Ordering::Equal => Some(get_block_timestamp_and_hash(&self.provider, head).await?.1),
+            Ordering::Greater => None,
+        };
+
+        if canonical_hash == Some(head_hash) {
+            return Ok(None);
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            head = %head,
+            latest_block_number = %latest_block_number,
+            indexed_hash = %format!("{:#x}", head_hash),
+            canonical_hash = ?canonical_hash.map(|h| format!("{:#x}", h)),
+            "Chain reorganization detected."
+        );
+
+        // Walk back the indexed blocks, starting from the latest canonical block, until one is
+        // still part of the canonical chain.
+        for (block_number, block_hash) in self.db.block_hashes().await? {
+            if block_number > latest_block_number {
+                continue;
+            }
+
+            let (_, canonical_hash) =
+                get_block_timestamp_and_hash(&self.provider, block_number).await?;
+            if canonical_hash == block_hash {
+                return Ok(Some(block_number));
+            }
+        }
+
+        Err(anyhow!(
+            "Chain reorganization deeper than the {} recorded blocks, the database has to be \
+             re-indexed.",
+            MAX_STORED_BLOCKS
+        ))
+    }
+
+    pub async fn rollback(&mut self, fork_block: u64) -> Result<()> {
+        let block_timestamp = get_block_timestamp(&self.provider, fork_block).await?;
+
+        self.db.rollback(&self.worlds, fork_block, block_timestamp).await?;
+
+        info!(target: LOG_TARGET, block_number = %fork_block, "Rolled back to block.");
+        Ok(())
+    }
+
     pub async fn process(&mut self, fetch_result: FetchDataResult) -> Result<()> {
         match fetch_result {
             FetchDataResult::Range(data) => self.process_range(data).await?,
             FetchDataResult::Pending(data) => self.process_pending(data).await?,
+            FetchDataResult::Reorg(fork_block) => self.rollback(fork_block).await?,
             FetchDataResult::None => {}
         };
 
@@ -554,10 +774,14 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         // Process parallelized events
         self.process_tasks().await?;
 
-        let last_block_timestamp =
-            get_block_timestamp(&self.provider, data.latest_block_number).await?;
-
-        self.db.reset_cursors(data.latest_block_number, cursor_map, last_block_timestamp)?;
+        for (block_number, block_hash) in data.block_hashes {
+            self.db.set_block_hash(block_number, block_hash)?;
+        }
+        self.db.reset_cursors(
+            data.latest_block_number,
+            cursor_map,
+            data.blocks[&data.latest_block_number],
+        )?;
 
         Ok(())
     }
@@ -570,7 +794,8 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
         let mut handles = Vec::new();
         for (task_id, events) in self.tasks.drain() {
             let db = self.db.clone();
-            let world = self.world.clone();
+            let worlds = self.worlds.clone();
+            let default_world = self.world.clone();
             let semaphore = semaphore.clone();
             let processors = self.processors.clone();
 
@@ -578,15 +803,19 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
                 let _permit = semaphore.acquire().await?;
                 let mut local_db = db.clone();
                 for (contract_type, ParallelizedEvent { event_id, event, block_number, block_timestamp }) in events {
-                    let contract_processors = processors.get_event_processor(contract_type);
-                    if let Some(processors) = contract_processors.get(&event.keys[0]) {
+                    if let Some(processors) = processors.get_processors_of_event(contract_type, &event) {
 
-                        let processor = processors.iter().find(|p| p.validate(&event)).expect("Must find atleast one processor for the event");
+                        // the events are queued once validated by one of their processors
+                        let Some(processor) = processors.iter().find(|p| p.validate(&event)) else {
+                            warn!(target: LOG_TARGET, task_id = %task_id, "Parallelized event not validated.");
+                            continue;
+                        };
 
                         debug!(target: LOG_TARGET, event_name = processor.event_key(), task_id = %task_id, "Processing parallelized event.");
 
+                        let world = worlds.get(&event.from_address).unwrap_or(&default_world);
                         if let Err(e) = processor
-                            .process(&world, &mut local_db, block_number, block_timestamp, &event_id, &event)
+                            .process(world, &mut local_db, block_number, block_timestamp, &event_id, &event)
                             .await
                         {
                             error!(target: LOG_TARGET, event_name = processor.event_key(), error = %e, task_id = %task_id, "Processing parallelized event.");
@@ -776,27 +1005,27 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
                 }
                 // ERC events needs to be processed inside there respective processor
                 // we store transfer events for ERC contracts regardless of this flag
-                ContractType::ERC20 | ContractType::ERC721 => {}
+                // custom events are stored in their own tables
+                ContractType::ERC20
+                | ContractType::ERC721
+                | ContractType::ERC1155
+                | ContractType::CUSTOM => {}
             }
         }
 
-        let event_key = event.keys[0];
+        let world = self.worlds.get(&event.from_address).unwrap_or(&self.world).clone();
 
-        let processors = self.processors.get_event_processor(contract_type);
-        let Some(processors) = processors.get(&event_key) else {
-            // if we dont have a processor for this event, we try the catch all processor
+        // if we dont have a processor validating this event, we try the catch all processor
+        let Some(processor) = self
+            .processors
+            .get_processors_of_event(contract_type, event)
+            .and_then(|processors| processors.iter().find(|p| p.validate(event)))
+        else {
             if self.processors.catch_all_event.validate(event) {
                 if let Err(e) = self
                     .processors
                     .catch_all_event
-                    .process(
-                        &self.world,
-                        &mut self.db,
-                        block_number,
-                        block_timestamp,
-                        event_id,
-                        event,
-                    )
+                    .process(&world, &mut self.db, block_number, block_timestamp, event_id, event)
                     .await
                 {
                     error!(target: LOG_TARGET, error = %e, "Processing catch all event processor.");
@@ -818,14 +1047,11 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
             return Ok(());
         };
 
-        let processor = processors
-            .iter()
-            .find(|p| p.validate(event))
-            .expect("Must find atleast one processor for the event");
-
         let task_identifier = match processor.event_key().as_str() {
             "StoreSetRecord" | "StoreUpdateRecord" | "StoreUpdateMember" | "StoreDelRecord" => {
                 let mut hasher = DefaultHasher::new();
+                // entities of different worlds can share the same id
+                event.from_address.hash(&mut hasher);
                 event.keys[0].hash(&mut hasher);
                 event.keys[1].hash(&mut hasher);
                 hasher.finish()
@@ -846,22 +1072,11 @@ impl<P: Provider + Send + Sync + std::fmt::Debug + 'static> Engine<P> {
             ));
         } else {
             // if we dont have a task identifier, we process the event immediately
-            if processor.validate(event) {
-                if let Err(e) = processor
-                    .process(
-                        &self.world,
-                        &mut self.db,
-                        block_number,
-                        block_timestamp,
-                        event_id,
-                        event,
-                    )
-                    .await
-                {
-                    error!(target: LOG_TARGET, event_name = processor.event_key(), error = ?e, "Processing event.");
-                }
-            } else {
-                warn!(target: LOG_TARGET, event_name = processor.event_key(), "Event not validated.");
+            if let Err(e) = processor
+                .process(&world, &mut self.db, block_number, block_timestamp, event_id, event)
+                .await
+            {
+                error!(target: LOG_TARGET, event_name = processor.event_key(), error = ?e, "Processing event.");
             }
         }
 
@@ -910,6 +1125,28 @@ where
     }
 }
 
+async fn get_block_timestamp_and_hash<P>(provider: &P, block_number: u64) -> Result<(u64, Felt)>
+where
+    P: Provider + Sync,
+{
+    match provider.get_block_with_tx_hashes(BlockId::Number(block_number)).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok((block.timestamp, block.block_hash)),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+            Err(anyhow!("Block {} is still pending.", block_number))
+        }
+    }
+}
+
+async fn get_block_parent_hash<P>(provider: &P, block_number: u64) -> Result<Felt>
+where
+    P: Provider + Sync,
+{
+    match provider.get_block_with_tx_hashes(BlockId::Number(block_number)).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(block.parent_hash),
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => Ok(block.parent_hash),
+    }
+}
+
 // event_id format: block_number:transaction_hash:event_idx
 pub fn get_transaction_hash_from_event_id(event_id: &str) -> String {
     event_id.split(':').nth(1).unwrap().to_string()
@@ -7,7 +7,7 @@ use anyhow::{Context, Result};
 use dojo_types::schema::{Struct, Ty};
 use sqlx::query::Query;
 use sqlx::sqlite::SqliteArguments;
-use sqlx::{FromRow, Pool, Sqlite, Transaction};
+use sqlx::{FromRow, Pool, Sqlite, SqliteConnection, Transaction};
 use starknet::core::types::{Felt, U256};
 use tokio::sync::broadcast::{Receiver, Sender};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
@@ -16,12 +16,16 @@ use tokio::time::Instant;
 use tracing::{debug, error};
 
 use crate::simple_broker::SimpleBroker;
-use crate::sql::utils::{felt_to_sql_string, sql_string_to_u256, u256_to_sql_string, I256};
+use crate::sql::utils::{
+    felt_to_sql_string, sql_string_to_felts, sql_string_to_u256, u256_to_sql_string, I256,
+};
 use crate::sql::FELT_DELIMITER;
 use crate::types::{
-    ContractCursor, ContractType, Entity as EntityUpdated, Event as EventEmitted,
-    EventMessage as EventMessageUpdated, Model as ModelRegistered, OptimisticEntity,
-    OptimisticEventMessage,
+    ContractCursor, ContractType, Entity as EntityUpdated, ErcBalance as ErcBalanceUpdated,
+    ErcTransfer, Event as EventEmitted, EventMessage as EventMessageUpdated,
+    Model as ModelRegistered, Namespace as NamespaceRegistered, OptimisticEntity,
+    OptimisticEventMessage, Permission as PermissionUpdated, Rollback,
+    Transaction as TransactionIndexed, WebhookDelivery, WorldContract as WorldContractUpdated,
 };
 
 pub(crate) const LOG_TARGET: &str = "torii_core::executor";
@@ -42,16 +46,42 @@ pub enum BrokerMessage {
     EntityUpdated(EntityUpdated),
     EventMessageUpdated(EventMessageUpdated),
     EventEmitted(EventEmitted),
+    NamespaceRegistered(NamespaceRegistered),
+    WorldContractUpdated(WorldContractUpdated),
+    PermissionUpdated(PermissionUpdated),
+    Rollback(Rollback),
+    TransactionIndexed(TransactionIndexed),
+    ErcTransfer(ErcTransfer),
+    ErcBalanceUpdated(ErcBalanceUpdated),
+    WebhookDeliveryStored(WebhookDelivery),
 }
 
 #[derive(Debug, Clone)]
 pub struct DeleteEntityQuery {
     pub entity_id: String,
+    pub world_address: String,
+    pub model_id: String,
     pub event_id: String,
     pub block_timestamp: String,
     pub ty: Ty,
 }
 
+#[derive(Debug, Clone)]
+pub struct EntityHistoryQuery {
+    pub entity_id: String,
+    pub world_address: String,
+    pub model_id: String,
+    pub event_id: String,
+    pub block_number: u64,
+    pub transaction_hash: String,
+    pub block_timestamp: String,
+    // the full schema of the model, to apply partial updates to the previous version
+    pub schema: Ty,
+    // `None` if the entity was deleted from the model
+    pub entity: Option<Ty>,
+    pub retention: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ApplyBalanceDiffQuery {
     pub erc_cache: HashMap<(ContractType, String), I256>,
@@ -82,9 +112,16 @@ pub struct UpdateCursorsQuery {
     pub pending_block_timestamp: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct RollbackQuery {
+    // the last block that is still part of the canonical chain
+    pub block_number: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct EventMessageQuery {
     pub entity_id: String,
+    pub world_address: String,
     pub model_id: String,
     pub keys_str: String,
     pub event_id: String,
@@ -98,12 +135,22 @@ pub enum QueryType {
     SetHead(SetHeadQuery),
     ResetCursors(ResetCursorsQuery),
     UpdateCursors(UpdateCursorsQuery),
+    Rollback(RollbackQuery),
     SetEntity(Ty),
     DeleteEntity(DeleteEntityQuery),
+    EntityHistory(EntityHistoryQuery),
     EventMessage(EventMessageQuery),
     ApplyBalanceDiff(ApplyBalanceDiffQuery),
+    // the table to replace with the one created by the statement
+    RebuildTable(String),
     RegisterModel,
+    RegisterNamespace,
+    SetWorldContract,
+    SetPermission,
     StoreEvent,
+    StoreTransaction,
+    StoreErcTransfer,
+    StoreWebhookDelivery,
     Execute,
     Other,
 }
@@ -371,6 +418,32 @@ impl<'c> Executor<'c> {
                     self.publish_queue.push(BrokerMessage::SetHead(cursor.clone()));
                 }
             }
+            QueryType::Rollback(rollback) => {
+                let head: i64 = rollback.block_number.try_into().expect("doesn't fit in i64");
+
+                sqlx::query("DELETE FROM blocks WHERE number > ?")
+                    .bind(head)
+                    .execute(&mut **tx)
+                    .await?;
+
+                // Pending cursors point to transactions that may no longer exist, so indexing
+                // restarts right after the fork point.
+                let cursors: Vec<ContractCursor> = sqlx::query_as(
+                    "UPDATE contracts SET head = ?, last_pending_block_tx = NULL, \
+                     last_pending_block_contract_tx = NULL RETURNING *",
+                )
+                .bind(head)
+                .fetch_all(&mut **tx)
+                .await?;
+
+                for cursor in cursors {
+                    self.publish_queue.push(BrokerMessage::SetHead(cursor));
+                }
+
+                self.publish_queue.push(BrokerMessage::Rollback(Rollback {
+                    block_number: rollback.block_number,
+                }));
+            }
             QueryType::SetEntity(entity) => {
                 let row = query.fetch_one(&mut **tx).await.with_context(|| {
                     format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
@@ -381,6 +454,7 @@ impl<'c> Executor<'c> {
 
                 let optimistic_entity = OptimisticEntity {
                     id: entity_updated.id.clone(),
+                    world_address: entity_updated.world_address.clone(),
                     keys: entity_updated.keys.clone(),
                     event_id: entity_updated.event_id.clone(),
                     executed_at: entity_updated.executed_at,
@@ -404,11 +478,12 @@ impl<'c> Executor<'c> {
 
                 let row = sqlx::query(
                     "UPDATE entities SET updated_at=CURRENT_TIMESTAMP, executed_at=?, event_id=? \
-                     WHERE id = ? RETURNING *",
+                     WHERE id = ? AND world_address = ? RETURNING *",
                 )
                 .bind(entity.block_timestamp)
                 .bind(entity.event_id)
-                .bind(entity.entity_id)
+                .bind(&entity.entity_id)
+                .bind(&entity.world_address)
                 .fetch_one(&mut **tx)
                 .await?;
                 let mut entity_updated = EntityUpdated::from_row(&row)?;
@@ -416,16 +491,31 @@ impl<'c> Executor<'c> {
                     Some(Ty::Struct(Struct { name: entity.ty.name(), children: vec![] }));
 
                 let count = sqlx::query_scalar::<_, i64>(
-                    "SELECT count(*) FROM entity_model WHERE entity_id = ?",
+                    "SELECT count(*) FROM entity_model WHERE entity_id = ? AND world_address = ?",
                 )
-                .bind(entity_updated.id.clone())
+                .bind(&entity.entity_id)
+                .bind(&entity.world_address)
                 .fetch_one(&mut **tx)
                 .await?;
 
+                // Keep a tombstone of the deleted model to restore it on a reorganization
+                sqlx::query(
+                    "INSERT OR REPLACE INTO deleted_entities (id, world_address, model_id, keys, \
+                     event_id) VALUES (?, ?, ?, ?, ?)",
+                )
+                .bind(&entity.entity_id)
+                .bind(&entity.world_address)
+                .bind(&entity.model_id)
+                .bind(&entity_updated.keys)
+                .bind(&entity_updated.event_id)
+                .execute(&mut **tx)
+                .await?;
+
                 // Delete entity if all of its models are deleted
                 if count == 0 {
-                    sqlx::query("DELETE FROM entities WHERE id = ?")
-                        .bind(entity_updated.id.clone())
+                    sqlx::query("DELETE FROM entities WHERE id = ? AND world_address = ?")
+                        .bind(&entity.entity_id)
+                        .bind(&entity.world_address)
                         .execute(&mut **tx)
                         .await?;
                     entity_updated.deleted = true;
@@ -433,6 +523,7 @@ impl<'c> Executor<'c> {
 
                 let optimistic_entity = OptimisticEntity {
                     id: entity_updated.id.clone(),
+                    world_address: entity_updated.world_address.clone(),
                     keys: entity_updated.keys.clone(),
                     event_id: entity_updated.event_id.clone(),
                     executed_at: entity_updated.executed_at,
@@ -445,6 +536,102 @@ impl<'c> Executor<'c> {
                 let broker_message = BrokerMessage::EntityUpdated(entity_updated);
                 self.publish_queue.push(broker_message);
             }
+            QueryType::EntityHistory(history) => {
+                let previous: Option<(String, String, bool)> = sqlx::query_as(
+                    "SELECT keys, data, deleted FROM entities_historical WHERE id = ? AND \
+                     world_address = ? AND model_id = ? ORDER BY event_id DESC LIMIT 1",
+                )
+                .bind(&history.entity_id)
+                .bind(&history.world_address)
+                .bind(&history.model_id)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+                // The entity may already be gone if it was deleted from all of its models.
+                let keys: Option<String> = sqlx::query_scalar(
+                    "SELECT keys FROM entities WHERE id = ? AND world_address = ?",
+                )
+                .bind(&history.entity_id)
+                .bind(&history.world_address)
+                .fetch_optional(&mut **tx)
+                .await?;
+                let keys = keys
+                    .or_else(|| previous.as_ref().map(|(keys, _, _)| keys.clone()))
+                    .unwrap_or_default();
+
+                let data = match history.entity {
+                    Some(entity) => {
+                        let previous = previous
+                            .as_ref()
+                            .filter(|(_, _, deleted)| !deleted)
+                            .map(|(_, data, _)| data.as_str());
+
+                        match entity_version(history.schema, entity, previous)? {
+                            Some(version) => version
+                                .serialize()?
+                                .iter()
+                                .map(|felt| format!("{:#x}", felt))
+                                .collect::<Vec<String>>()
+                                .join(FELT_DELIMITER),
+                            None => {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    entity_id = %history.entity_id,
+                                    model_id = %history.model_id,
+                                    "Partial update without a previous version, skipping history."
+                                );
+                                return Ok(());
+                            }
+                        }
+                    }
+                    None => String::new(),
+                };
+
+                let block_number: i64 = history
+                    .block_number
+                    .try_into()
+                    .context("Entity history block number doesn't fit in i64")?;
+
+                sqlx::query(
+                    "INSERT INTO entities_historical (id, world_address, keys, model_id, data, \
+                     deleted, event_id, block_number, transaction_hash, executed_at) VALUES (?, \
+                     ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&history.entity_id)
+                .bind(&history.world_address)
+                .bind(keys)
+                .bind(&history.model_id)
+                .bind(&data)
+                .bind(data.is_empty())
+                .bind(&history.event_id)
+                .bind(block_number)
+                .bind(history.transaction_hash)
+                .bind(history.block_timestamp)
+                .execute(&mut **tx)
+                .await?;
+
+                // The last version before the retention window is kept, it is still the state of
+                // the entity at the first blocks of the window.
+                if let Some(retention) = history.retention {
+                    let retention: i64 = retention
+                        .try_into()
+                        .context("Entity history retention doesn't fit in i64")?;
+                    let cutoff = block_number.saturating_sub(retention);
+
+                    sqlx::query(
+                        "DELETE FROM entities_historical WHERE id = ?1 AND world_address = ?2 AND \
+                         model_id = ?3 AND block_number < ?4 AND event_id < (SELECT MAX(event_id) \
+                         FROM entities_historical WHERE id = ?1 AND world_address = ?2 AND \
+                         model_id = ?3 AND block_number < ?4)",
+                    )
+                    .bind(&history.entity_id)
+                    .bind(&history.world_address)
+                    .bind(&history.model_id)
+                    .bind(cutoff)
+                    .execute(&mut **tx)
+                    .await?;
+                }
+            }
             QueryType::RegisterModel => {
                 let row = query.fetch_one(&mut **tx).await.with_context(|| {
                     format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
@@ -452,6 +639,31 @@ impl<'c> Executor<'c> {
                 let model_registered = ModelRegistered::from_row(&row)?;
                 self.publish_queue.push(BrokerMessage::ModelRegistered(model_registered));
             }
+            QueryType::RegisterNamespace => {
+                let row = query.fetch_one(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                let namespace = NamespaceRegistered::from_row(&row)?;
+                self.publish_queue.push(BrokerMessage::NamespaceRegistered(namespace));
+            }
+            QueryType::SetWorldContract => {
+                // Upgrading or initializing a contract whose registration wasn't indexed doesn't
+                // update any row.
+                let row = query.fetch_optional(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                if let Some(row) = row {
+                    let contract = WorldContractUpdated::from_row(&row)?;
+                    self.publish_queue.push(BrokerMessage::WorldContractUpdated(contract));
+                }
+            }
+            QueryType::SetPermission => {
+                let row = query.fetch_one(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                let permission = PermissionUpdated::from_row(&row)?;
+                self.publish_queue.push(BrokerMessage::PermissionUpdated(permission));
+            }
             QueryType::EventMessage(em_query) => {
                 // Must be executed first since other tables have foreign keys on event_messages.id.
                 let event_messages_row = query.fetch_one(&mut **tx).await.with_context(|| {
@@ -459,10 +671,11 @@ impl<'c> Executor<'c> {
                 })?;
 
                 let mut event_counter: i64 = sqlx::query_scalar::<_, i64>(
-                    "SELECT historical_counter FROM event_model WHERE entity_id = ? AND model_id \
-                     = ?",
+                    "SELECT historical_counter FROM event_model WHERE entity_id = ? AND \
+                     world_address = ? AND model_id = ?",
                 )
                 .bind(em_query.entity_id.clone())
+                .bind(em_query.world_address.clone())
                 .bind(em_query.model_id.clone())
                 .fetch_optional(&mut **tx)
                 .await
@@ -480,10 +693,12 @@ impl<'c> Executor<'c> {
                         .join("/");
 
                     sqlx::query(
-                        "INSERT INTO event_messages_historical (id, keys, event_id, data, \
-                         model_id, executed_at) VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
+                        "INSERT INTO event_messages_historical (id, world_address, keys, \
+                         event_id, data, model_id, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?) \
+                         RETURNING *",
                     )
                     .bind(em_query.entity_id.clone())
+                    .bind(em_query.world_address.clone())
                     .bind(em_query.keys_str.clone())
                     .bind(em_query.event_id.clone())
                     .bind(data)
@@ -494,11 +709,13 @@ impl<'c> Executor<'c> {
                 }
 
                 sqlx::query(
-                    "INSERT INTO event_model (entity_id, model_id, historical_counter) VALUES (?, \
-                     ?, ?) ON CONFLICT(entity_id, model_id) DO UPDATE SET \
+                    "INSERT INTO event_model (entity_id, world_address, model_id, \
+                     historical_counter) VALUES (?, ?, ?, ?) ON CONFLICT(entity_id, \
+                     world_address, model_id) DO UPDATE SET \
                      historical_counter=EXCLUDED.historical_counter",
                 )
                 .bind(em_query.entity_id.clone())
+                .bind(em_query.world_address.clone())
                 .bind(em_query.model_id.clone())
                 .bind(event_counter)
                 .execute(&mut **tx)
@@ -510,6 +727,7 @@ impl<'c> Executor<'c> {
 
                 let optimistic_event_message = OptimisticEventMessage {
                     id: event_message.id.clone(),
+                    world_address: event_message.world_address.clone(),
                     keys: event_message.keys.clone(),
                     event_id: event_message.event_id.clone(),
                     executed_at: event_message.executed_at,
@@ -530,6 +748,33 @@ impl<'c> Executor<'c> {
                 let event = EventEmitted::from_row(&row)?;
                 self.publish_queue.push(BrokerMessage::EventEmitted(event));
             }
+            QueryType::StoreTransaction => {
+                // Transactions already indexed are ignored and return no row.
+                let row = query.fetch_optional(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                if let Some(row) = row {
+                    let transaction = TransactionIndexed::from_row(&row)?;
+                    self.publish_queue.push(BrokerMessage::TransactionIndexed(transaction));
+                }
+            }
+            QueryType::StoreErcTransfer => {
+                let row = query.fetch_one(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                let transfer = ErcTransfer::from_row(&row)?;
+                self.publish_queue.push(BrokerMessage::ErcTransfer(transfer));
+            }
+            QueryType::StoreWebhookDelivery => {
+                // Deliveries already stored are ignored and return no row.
+                let row = query.fetch_optional(&mut **tx).await.with_context(|| {
+                    format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
+                })?;
+                if let Some(row) = row {
+                    let delivery = WebhookDelivery::from_row(&row)?;
+                    self.publish_queue.push(BrokerMessage::WebhookDeliveryStored(delivery));
+                }
+            }
             QueryType::ApplyBalanceDiff(apply_balance_diff) => {
                 debug!(target: LOG_TARGET, "Applying balance diff.");
                 let instant = Instant::now();
@@ -550,10 +795,23 @@ impl<'c> Executor<'c> {
                     res?;
                 }
             }
+            QueryType::RebuildTable(table) => {
+                rebuild_table(&mut **tx, &table, statement).await.with_context(|| {
+                    format!("Failed to rebuild table {} with: {:?}", table, statement)
+                })?;
+            }
             QueryType::Other => {
-                query.execute(&mut **tx).await.with_context(|| {
+                let res = query.execute(&mut **tx).await.map(|_| ()).with_context(|| {
                     format!("Failed to execute query: {:?}, args: {:?}", statement, arguments)
-                })?;
+                });
+
+                // the failure is then handled by the sender
+                match sender {
+                    Some(sender) => sender
+                        .send(res)
+                        .map_err(|_| anyhow::anyhow!("Failed to send query result"))?,
+                    None => res?,
+                }
             }
         }
 
@@ -579,9 +837,9 @@ impl<'c> Executor<'c> {
         for ((contract_type, id_str), balance) in erc_cache.iter() {
             let id = id_str.split(FELT_DELIMITER).collect::<Vec<&str>>();
             match contract_type {
-                ContractType::WORLD => unreachable!(),
-                ContractType::ERC721 => {
-                    // account_address/contract_address:id => ERC721
+                ContractType::WORLD | ContractType::CUSTOM => unreachable!(),
+                ContractType::ERC721 | ContractType::ERC1155 => {
+                    // account_address/contract_address:id => ERC721 & ERC1155
                     assert!(id.len() == 2);
                     let account_address = id[0];
                     let token_id = id[1];
@@ -652,22 +910,129 @@ impl<'c> Executor<'c> {
         }
 
         // write the new balance to the database
-        sqlx::query(
+        let row = sqlx::query(
             "INSERT OR REPLACE INTO balances (id, contract_address, account_address, token_id, \
-             balance) VALUES (?, ?, ?, ?, ?)",
+             balance) VALUES (?, ?, ?, ?, ?) RETURNING *",
         )
         .bind(id)
         .bind(contract_address)
         .bind(account_address)
         .bind(token_id)
         .bind(u256_to_sql_string(&balance))
-        .execute(&mut **tx)
+        .fetch_one(&mut **tx)
         .await?;
 
+        let balance = ErcBalanceUpdated::from_row(&row)?;
+        self.publish_queue.push(BrokerMessage::ErcBalanceUpdated(balance));
+
         Ok(())
     }
 }
 
+/// Applies a possibly partial update of a model to the previous version of an entity, stored as
+/// serialized felts. Returns `None` if the update is partial and there is no previous version.
+fn entity_version(schema: Ty, update: Ty, previous: Option<&str>) -> Result<Option<Ty>> {
+    let (Ty::Struct(schema_struct), Ty::Struct(update_struct)) = (&schema, &update) else {
+        return Ok(Some(update));
+    };
+
+    if update_struct.children.len() == schema_struct.children.len() {
+        return Ok(Some(update));
+    }
+
+    let Some(previous) = previous else {
+        return Ok(None);
+    };
+
+    let mut version = schema;
+    version.deserialize(&mut sql_string_to_felts(previous))?;
+
+    if let (Ty::Struct(version_struct), Ty::Struct(update_struct)) = (&mut version, update) {
+        for member in update_struct.children {
+            if let Some(previous_member) =
+                version_struct.children.iter_mut().find(|m| m.name == member.name)
+            {
+                previous_member.ty = member.ty;
+            }
+        }
+    }
+
+    Ok(Some(version))
+}
+
+/// Replaces `table` with the one created by `create_table`, keeping its rows. Used to change the
+/// constraints of the columns, which SQLite can't alter.
+///
+/// Dropping the table would cascade to the rows of its nested tables, which reference it, so they
+/// are rebuilt along with it: the new tables reference each other until they are renamed, which
+/// updates the references to the final names.
+async fn rebuild_table(conn: &mut SqliteConnection, table: &str, create_table: &str) -> Result<()> {
+    // the table comes first, the nested tables being named after their parent
+    let nested_prefix = format!("{table}$");
+    let tables: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND (name = ? OR substr(name, \
+         1, length(?)) = ?) ORDER BY name",
+    )
+    .bind(table)
+    .bind(&nested_prefix)
+    .bind(&nested_prefix)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let indexes: Vec<String> = sqlx::query_scalar(
+        "SELECT sql FROM sqlite_master WHERE type = 'index' AND sql IS NOT NULL AND (tbl_name = ? \
+         OR substr(tbl_name, 1, length(?)) = ?)",
+    )
+    .bind(table)
+    .bind(&nested_prefix)
+    .bind(&nested_prefix)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let with_new_names = |sql: &str| {
+        tables.iter().fold(sql.to_string(), |sql, (name, _)| {
+            sql.replace(&format!("[{name}]"), &format!("[{name}_new]"))
+        })
+    };
+
+    for (name, sql) in &tables {
+        let sql = if name == table {
+            create_table.replacen("CREATE TABLE IF NOT EXISTS", "CREATE TABLE", 1)
+        } else {
+            sql.clone()
+        };
+        sqlx::query(&with_new_names(&sql)).execute(&mut *conn).await?;
+
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(name)
+            .fetch_all(&mut *conn)
+            .await?;
+        let columns = columns.iter().map(|c| format!("[{c}]")).collect::<Vec<_>>().join(", ");
+        sqlx::query(&format!(
+            "INSERT INTO [{name}_new] ({columns}) SELECT {columns} FROM [{name}]"
+        ))
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    // the nested tables first, no table referencing the dropped ones anymore
+    for (name, _) in tables.iter().rev() {
+        sqlx::query(&format!("DROP TABLE [{name}]")).execute(&mut *conn).await?;
+    }
+
+    for (name, _) in &tables {
+        sqlx::query(&format!("ALTER TABLE [{name}_new] RENAME TO [{name}]"))
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    for index in indexes {
+        sqlx::query(&index).execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
 fn send_broker_message(message: BrokerMessage) {
     match message {
         BrokerMessage::SetHead(update) => SimpleBroker::publish(update),
@@ -675,5 +1040,66 @@ fn send_broker_message(message: BrokerMessage) {
         BrokerMessage::EntityUpdated(entity) => SimpleBroker::publish(entity),
         BrokerMessage::EventMessageUpdated(event) => SimpleBroker::publish(event),
         BrokerMessage::EventEmitted(event) => SimpleBroker::publish(event),
+        BrokerMessage::NamespaceRegistered(namespace) => SimpleBroker::publish(namespace),
+        BrokerMessage::WorldContractUpdated(contract) => SimpleBroker::publish(contract),
+        BrokerMessage::PermissionUpdated(permission) => SimpleBroker::publish(permission),
+        BrokerMessage::Rollback(rollback) => SimpleBroker::publish(rollback),
+        BrokerMessage::TransactionIndexed(transaction) => SimpleBroker::publish(transaction),
+        BrokerMessage::ErcTransfer(transfer) => SimpleBroker::publish(transfer),
+        BrokerMessage::ErcBalanceUpdated(balance) => SimpleBroker::publish(balance),
+        BrokerMessage::WebhookDeliveryStored(delivery) => SimpleBroker::publish(delivery),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::Member;
+
+    use super::*;
+
+    fn position(members: &[(&str, Option<u32>)]) -> Ty {
+        Ty::Struct(Struct {
+            name: "ns-Position".to_string(),
+            children: members
+                .iter()
+                .map(|(name, value)| Member {
+                    name: name.to_string(),
+                    ty: Ty::Primitive(Primitive::U32(*value)),
+                    key: *name == "player",
+                })
+                .collect(),
+        })
+    }
+
+    fn serialized(ty: &Ty) -> String {
+        ty.serialize()
+            .unwrap()
+            .iter()
+            .map(|felt| format!("{:#x}", felt))
+            .collect::<Vec<String>>()
+            .join(FELT_DELIMITER)
+    }
+
+    #[test]
+    fn test_entity_version() {
+        let schema = position(&[("player", None), ("x", None), ("y", None)]);
+
+        // a full update is the new version, whatever the previous one
+        let update = position(&[("player", Some(1)), ("x", Some(2)), ("y", Some(3))]);
+        assert_eq!(entity_version(schema.clone(), update.clone(), None).unwrap(), Some(update));
+
+        // a partial update needs the previous version to be applied to
+        let partial = position(&[("player", Some(1)), ("y", Some(4))]);
+        assert_eq!(entity_version(schema.clone(), partial.clone(), None).unwrap(), None);
+
+        let previous =
+            serialized(&position(&[("player", Some(1)), ("x", Some(2)), ("y", Some(3))]));
+        assert_eq!(
+            entity_version(schema.clone(), partial, Some(&previous)).unwrap(),
+            Some(position(&[("player", Some(1)), ("x", Some(2)), ("y", Some(4))]))
+        );
+
+        assert!(entity_version(schema, position(&[("x", Some(5))]), Some("0x1")).is_err());
     }
 }
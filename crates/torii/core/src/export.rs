@@ -0,0 +1,936 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use arrow_array::{ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::Ty;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use futures_util::TryStreamExt;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Row, Sqlite, Transaction};
+
+use crate::model::{build_sql_query, map_row_to_ty};
+use crate::sql::cache::ModelCache;
+use crate::sql::history::parse_event_id;
+use crate::sql::utils::sql_string_to_felts;
+
+/// Number of rows written at once, in a row group of the Parquet files.
+const BATCH_ROWS: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "parquet" => Ok(ExportFormat::Parquet),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(anyhow!("Unknown export format {s}, expected parquet or csv")),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Models to export, as `namespace-name`, all the registered models if empty.
+    pub models: Vec<String>,
+    pub format: ExportFormat,
+    /// Directory the files are written to, one per table.
+    pub output: PathBuf,
+    /// Block at which the data is exported. The models updated after it must be indexed with
+    /// history, their state at the block being read from their versions.
+    pub at_block: Option<u64>,
+    /// Only export the rows updated after this block, usually the block of a previous export.
+    /// Deletions aren't exported, except as missing versions of the models indexed with history.
+    pub since_block: Option<u64>,
+}
+
+/// The block the data was exported at, to start the next incremental export from, and the
+/// written files.
+#[derive(Debug, Clone)]
+pub struct ExportMetadata {
+    /// The requested block, or the block up to which all the contracts are indexed. Without a
+    /// requested block the latest state is exported, which may include later pending blocks.
+    pub block_number: u64,
+    pub tables: Vec<ExportedTable>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportedTable {
+    pub name: String,
+    pub path: PathBuf,
+    pub rows: usize,
+}
+
+/// Exports the entities of models, with their members flattened into columns, along with the
+/// events and the transactions, from the database at `database_path`. The files of a model keep
+/// the same columns as long as its schema doesn't change, nested members being stored in
+/// `parent.member` columns, the variant of an enum in its own column and arrays as JSON.
+pub async fn export(database_path: &str, options: &ExportOptions) -> Result<ExportMetadata> {
+    let connect_options = SqliteConnectOptions::from_str(database_path)?.read_only(true);
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(connect_options).await?;
+
+    let tags = if options.models.is_empty() {
+        let models: Vec<(String, String)> =
+            sqlx::query_as("SELECT namespace, name FROM models ORDER BY namespace, name")
+                .fetch_all(&pool)
+                .await?;
+        models.into_iter().map(|(namespace, name)| format!("{namespace}-{name}")).collect()
+    } else {
+        options.models.clone()
+    };
+
+    // the schemas are loaded first, the reads of the data all happening in one transaction
+    let cache = ModelCache::new(pool.clone());
+    let mut models = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let model = cache
+            .model(&compute_selector_from_tag(&tag))
+            .await
+            .with_context(|| format!("Model {tag} isn't registered"))?;
+        models.push((tag, model.schema));
+    }
+
+    std::fs::create_dir_all(&options.output)?;
+    let mut tx = pool.begin().await?;
+
+    let head: Option<i64> =
+        sqlx::query_scalar("SELECT MIN(head) FROM contracts").fetch_one(&mut *tx).await?;
+    let head = head.unwrap_or_default() as u64;
+    let block_number = match options.at_block {
+        Some(block) if block > head => {
+            return Err(anyhow!("Block {block} isn't indexed yet, the head is block {head}"));
+        }
+        Some(block) => block,
+        None => head,
+    };
+    if let Some(since) = options.since_block.filter(|since| *since > block_number) {
+        return Err(anyhow!("Block {since} is after the exported block {block_number}"));
+    }
+
+    let range = BlockRange { since: options.since_block, until: options.at_block };
+    let mut tables = Vec::with_capacity(models.len() + 2);
+    for (tag, schema) in &models {
+        tables.push(export_model(&mut tx, tag, schema, &range, options).await?);
+    }
+    tables.push(export_events(&mut tx, &range, options).await?);
+    tables.push(export_transactions(&mut tx, &range, options).await?);
+
+    tx.rollback().await?;
+    pool.close().await;
+
+    Ok(ExportMetadata { block_number, tables })
+}
+
+struct BlockRange {
+    since: Option<u64>,
+    until: Option<u64>,
+}
+
+impl BlockRange {
+    /// Condition on the ids of the rows of the range, ids starting with their block number. The
+    /// bounds are generated ids, which are inlined in the queries.
+    fn id_condition(&self, column: &str) -> Option<String> {
+        let mut conditions = Vec::new();
+        if let Some(since) = self.since {
+            conditions.push(format!("{column} >= '{:#064x}'", since + 1));
+        }
+        if let Some(until) = self.until {
+            conditions.push(format!("{column} < '{:#064x}'", until + 1));
+        }
+
+        (!conditions.is_empty()).then(|| conditions.join(" AND "))
+    }
+
+    fn where_clause(&self, column: &str) -> String {
+        self.id_condition(column).map_or(String::new(), |condition| format!(" WHERE {condition}"))
+    }
+}
+
+async fn export_model(
+    tx: &mut Transaction<'_, Sqlite>,
+    tag: &str,
+    schema: &Ty,
+    range: &BlockRange,
+    options: &ExportOptions,
+) -> Result<ExportedTable> {
+    let model_id = format!("{:#x}", compute_selector_from_tag(tag));
+    let mut columns = vec![
+        Column::new("internal_entity_id", ColumnType::String),
+        Column::new("internal_world_address", ColumnType::String),
+        Column::new("internal_keys", ColumnType::String),
+        Column::new("internal_event_id", ColumnType::String),
+        Column::new("internal_block_number", ColumnType::Int64),
+        Column::new("internal_executed_at", ColumnType::String),
+    ];
+    member_columns("", schema, &mut columns);
+    let mut table = Table::create(tag, columns, options)?;
+
+    let historical: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM entities_historical WHERE model_id = ?)")
+            .bind(&model_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+    // the state at a past block is the latest version of each entity up to the block
+    if let (Some(until), true) = (range.until, historical) {
+        let mut rows = sqlx::query_as::<_, (String, String, String, String, String, i64, String)>(
+            "SELECT h.id, h.world_address, h.keys, h.data, h.event_id, h.block_number, \
+             h.executed_at FROM entities_historical h WHERE h.model_id = ? AND NOT h.deleted AND \
+             h.block_number <= ? AND h.block_number > ? AND h.event_id = (SELECT MAX(event_id) \
+             FROM entities_historical WHERE id = h.id AND world_address = h.world_address AND \
+             model_id = h.model_id AND block_number <= ?) ORDER BY h.event_id",
+        )
+        .bind(&model_id)
+        .bind(until as i64)
+        .bind(range.since.map_or(-1, |since| since as i64))
+        .bind(until as i64)
+        .fetch(&mut **tx);
+
+        while let Some((id, world_address, keys, data, event_id, block_number, executed_at)) =
+            rows.try_next().await?
+        {
+            let mut ty = schema.clone();
+            ty.deserialize(&mut sql_string_to_felts(&data))?;
+
+            let mut row = vec![
+                Cell::String(id),
+                Cell::String(world_address),
+                Cell::String(keys),
+                Cell::String(event_id),
+                Cell::Int64(block_number),
+                Cell::String(executed_at),
+            ];
+            member_values(&ty, &mut row);
+            table.push(row)?;
+        }
+
+        return table.finish();
+    }
+
+    if let Some(until) = range.until {
+        let updated_later: bool = sqlx::query_scalar(&format!(
+            "SELECT EXISTS (SELECT 1 FROM [{tag}] WHERE entity_id IS NOT NULL AND event_id >= ?)"
+        ))
+        .bind(format!("{:#064x}", until + 1))
+        .fetch_one(&mut **tx)
+        .await?;
+        if updated_later {
+            return Err(anyhow!(
+                "Model {tag} was updated after the block and isn't indexed with history, its \
+                 state at the block is unknown"
+            ));
+        }
+    }
+
+    let condition = range.id_condition("event_id").map(|condition| {
+        format!(
+            "(entities.id, entities.world_address) IN (SELECT entity_id, world_address FROM \
+             [{tag}] WHERE {condition})"
+        )
+    });
+    let (query, arrays_queries, _) = build_sql_query(
+        &vec![schema.clone()],
+        "entities",
+        "entity_id",
+        condition.as_deref(),
+        condition.as_deref(),
+        None,
+        None,
+    )?;
+
+    let mut arrays_rows = HashMap::new();
+    for (name, array_query) in arrays_queries {
+        arrays_rows.insert(name, sqlx::query(&array_query).fetch_all(&mut **tx).await?);
+    }
+
+    // the version of the entities is the one of their row in the model table
+    let query = format!(
+        "SELECT entity.*, model.event_id AS internal_event_id, model.executed_at AS \
+         internal_executed_at FROM ({query}) entity JOIN [{tag}] model ON model.entity_id = \
+         entity.id AND model.world_address = entity.world_address ORDER BY model.event_id"
+    );
+    let mut rows = sqlx::query(&query).fetch(&mut **tx);
+
+    while let Some(entity) = rows.try_next().await? {
+        let mut ty = schema.clone();
+        map_row_to_ty("", &schema.name(), &mut ty, &entity, &arrays_rows)?;
+
+        let event_id = entity.try_get::<String, _>("internal_event_id")?;
+        let (block_number, _) = parse_event_id(&event_id)?;
+
+        let mut row = vec![
+            Cell::String(entity.try_get("id")?),
+            Cell::String(entity.try_get("world_address")?),
+            Cell::String(entity.try_get("keys")?),
+            Cell::String(event_id),
+            Cell::Int64(block_number as i64),
+            Cell::String(entity.try_get("internal_executed_at")?),
+        ];
+        member_values(&ty, &mut row);
+        table.push(row)?;
+    }
+
+    table.finish()
+}
+
+async fn export_events(
+    tx: &mut Transaction<'_, Sqlite>,
+    range: &BlockRange,
+    options: &ExportOptions,
+) -> Result<ExportedTable> {
+    let columns = vec![
+        Column::new("id", ColumnType::String),
+        Column::new("keys", ColumnType::String),
+        Column::new("data", ColumnType::String),
+        Column::new("transaction_hash", ColumnType::String),
+        Column::new("world_address", ColumnType::String),
+        Column::new("block_number", ColumnType::Int64),
+        Column::new("executed_at", ColumnType::String),
+    ];
+    let mut table = Table::create("events", columns, options)?;
+
+    let query = format!(
+        "SELECT id, keys, data, transaction_hash, world_address, executed_at FROM events{} ORDER \
+         BY id",
+        range.where_clause("id")
+    );
+    let mut rows =
+        sqlx::query_as::<_, (String, String, String, Option<String>, String, String)>(&query)
+            .fetch(&mut **tx);
+
+    while let Some((id, keys, data, transaction_hash, world_address, executed_at)) =
+        rows.try_next().await?
+    {
+        let (block_number, _) = parse_event_id(&id)?;
+        table.push(vec![
+            Cell::String(id),
+            Cell::String(keys),
+            Cell::String(data),
+            transaction_hash.map_or(Cell::Null, Cell::String),
+            Cell::String(world_address),
+            Cell::Int64(block_number as i64),
+            Cell::String(executed_at),
+        ])?;
+    }
+
+    table.finish()
+}
+
+async fn export_transactions(
+    tx: &mut Transaction<'_, Sqlite>,
+    range: &BlockRange,
+    options: &ExportOptions,
+) -> Result<ExportedTable> {
+    let names = [
+        "id",
+        "transaction_hash",
+        "sender_address",
+        "calldata",
+        "max_fee",
+        "signature",
+        "nonce",
+        "transaction_type",
+        "executed_at",
+    ];
+    let mut columns =
+        names.iter().map(|name| Column::new(name, ColumnType::String)).collect::<Vec<_>>();
+    columns.insert(names.len() - 1, Column::new("block_number", ColumnType::Int64));
+    let mut table = Table::create("transactions", columns, options)?;
+
+    let query = format!(
+        "SELECT {} FROM transactions{} ORDER BY id",
+        names.join(", "),
+        range.where_clause("id")
+    );
+    let mut rows = sqlx::query(&query).fetch(&mut **tx);
+
+    while let Some(transaction) = rows.try_next().await? {
+        let mut row = names
+            .iter()
+            .map(|name| Ok(Cell::String(transaction.try_get(*name)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let (block_number, _) = parse_event_id(&transaction.try_get::<String, _>("id")?)?;
+        row.insert(names.len() - 1, Cell::Int64(block_number as i64));
+        table.push(row)?;
+    }
+
+    table.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Boolean,
+    Int64,
+    UInt64,
+    String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Column {
+    name: String,
+    r#type: ColumnType,
+}
+
+impl Column {
+    fn new(name: &str, r#type: ColumnType) -> Self {
+        Self { name: name.to_string(), r#type }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Cell {
+    Null,
+    Boolean(bool),
+    Int64(i64),
+    UInt64(u64),
+    String(String),
+}
+
+impl Cell {
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Null => String::new(),
+            Cell::Boolean(value) => value.to_string(),
+            Cell::Int64(value) => value.to_string(),
+            Cell::UInt64(value) => value.to_string(),
+            Cell::String(value) => value.clone(),
+        }
+    }
+}
+
+// The columns of the members of `ty`. The integers which fit in an `i64` are stored as such, the
+// others with the encoding of the model tables.
+fn member_columns(path: &str, ty: &Ty, columns: &mut Vec<Column>) {
+    let child_path =
+        |name: &str| if path.is_empty() { name.to_string() } else { format!("{path}.{name}") };
+
+    match ty {
+        Ty::Primitive(primitive) => columns.push(Column::new(path, primitive_type(primitive))),
+        Ty::Struct(s) => {
+            for member in &s.children {
+                member_columns(&child_path(&member.name), &member.ty, columns);
+            }
+        }
+        Ty::Tuple(tys) => {
+            for (i, ty) in tys.iter().enumerate() {
+                member_columns(&child_path(&format!("_{i}")), ty, columns);
+            }
+        }
+        Ty::Enum(e) => {
+            columns.push(Column::new(path, ColumnType::String));
+            for option in &e.options {
+                member_columns(&child_path(&option.name), &option.ty, columns);
+            }
+        }
+        Ty::Array(_) | Ty::ByteArray(_) => columns.push(Column::new(path, ColumnType::String)),
+    }
+}
+
+// The values of the columns of `ty`, in the order of `member_columns`.
+fn member_values(ty: &Ty, values: &mut Vec<Cell>) {
+    match ty {
+        Ty::Primitive(primitive) => values.push(primitive_value(primitive)),
+        Ty::Struct(s) => {
+            for member in &s.children {
+                member_values(&member.ty, values);
+            }
+        }
+        Ty::Tuple(tys) => {
+            for ty in tys {
+                member_values(ty, values);
+            }
+        }
+        Ty::Enum(e) => {
+            values.push(e.option().map_or(Cell::Null, |option| Cell::String(option.name.clone())));
+            // the members of the other variants are null
+            for (i, option) in e.options.iter().enumerate() {
+                if e.option == Some(i as u8) {
+                    member_values(&option.ty, values);
+                } else {
+                    let mut columns = Vec::new();
+                    member_columns("", &option.ty, &mut columns);
+                    values.extend(columns.iter().map(|_| Cell::Null));
+                }
+            }
+        }
+        Ty::Array(tys) => {
+            values.push(Cell::String(Value::Array(tys.iter().map(ty_json).collect()).to_string()))
+        }
+        Ty::ByteArray(bytes) => values.push(Cell::String(bytes.clone())),
+    }
+}
+
+fn primitive_type(primitive: &Primitive) -> ColumnType {
+    match primitive {
+        Primitive::Bool(_) => ColumnType::Boolean,
+        Primitive::I8(_)
+        | Primitive::I16(_)
+        | Primitive::I32(_)
+        | Primitive::I64(_)
+        | Primitive::U8(_)
+        | Primitive::U16(_)
+        | Primitive::U32(_)
+        | Primitive::USize(_) => ColumnType::Int64,
+        Primitive::U64(_) => ColumnType::UInt64,
+        Primitive::I128(_)
+        | Primitive::U128(_)
+        | Primitive::U256(_)
+        | Primitive::Felt252(_)
+        | Primitive::ClassHash(_)
+        | Primitive::ContractAddress(_) => ColumnType::String,
+    }
+}
+
+fn primitive_value(primitive: &Primitive) -> Cell {
+    let cell = match primitive {
+        Primitive::Bool(value) => value.map(Cell::Boolean),
+        Primitive::I8(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::I16(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::I32(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::I64(value) => value.map(Cell::Int64),
+        Primitive::U8(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::U16(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::U32(value) | Primitive::USize(value) => value.map(|v| Cell::Int64(v.into())),
+        Primitive::U64(value) => value.map(Cell::UInt64),
+        _ => primitive.to_sql_value().ok().map(Cell::String),
+    };
+    cell.unwrap_or(Cell::Null)
+}
+
+// The elements of arrays, enums being `{ "variant": value }` or the name of their variant if it
+// has no value.
+fn ty_json(ty: &Ty) -> Value {
+    match ty {
+        Ty::Primitive(primitive) => match primitive_value(primitive) {
+            Cell::Null => Value::Null,
+            Cell::Boolean(value) => value.into(),
+            Cell::Int64(value) => value.into(),
+            Cell::UInt64(value) => value.into(),
+            Cell::String(value) => value.into(),
+        },
+        Ty::Struct(s) => Value::Object(
+            s.children.iter().map(|member| (member.name.clone(), ty_json(&member.ty))).collect(),
+        ),
+        Ty::Enum(e) => match e.option() {
+            Ok(option) if option.ty.as_tuple().is_some_and(|tys| tys.is_empty()) => {
+                option.name.clone().into()
+            }
+            Ok(option) => {
+                Value::Object([(option.name.clone(), ty_json(&option.ty))].into_iter().collect())
+            }
+            Err(_) => Value::Null,
+        },
+        Ty::Tuple(tys) | Ty::Array(tys) => Value::Array(tys.iter().map(ty_json).collect()),
+        Ty::ByteArray(bytes) => bytes.clone().into(),
+    }
+}
+
+enum TableWriter {
+    Parquet(ArrowWriter<File>, SchemaRef),
+    Csv(csv::Writer<File>),
+}
+
+/// A file being written, the rows being buffered into batches.
+struct Table {
+    name: String,
+    path: PathBuf,
+    columns: Vec<Column>,
+    writer: TableWriter,
+    batch: Vec<Vec<Cell>>,
+    rows: usize,
+}
+
+impl Table {
+    fn create(name: &str, columns: Vec<Column>, options: &ExportOptions) -> Result<Self> {
+        let path = options.output.join(format!("{name}.{}", options.format.extension()));
+        let file = File::create(&path)?;
+
+        let writer = match options.format {
+            ExportFormat::Parquet => {
+                let schema = Arc::new(arrow_schema(&columns));
+                let properties =
+                    WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+                TableWriter::Parquet(
+                    ArrowWriter::try_new(file, schema.clone(), Some(properties))?,
+                    schema,
+                )
+            }
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(columns.iter().map(|column| &column.name))?;
+                TableWriter::Csv(writer)
+            }
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            path,
+            columns,
+            writer,
+            batch: Vec::with_capacity(BATCH_ROWS),
+            rows: 0,
+        })
+    }
+
+    fn push(&mut self, row: Vec<Cell>) -> Result<()> {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.batch.push(row);
+        if self.batch.len() == BATCH_ROWS {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        match &mut self.writer {
+            TableWriter::Parquet(writer, schema) => {
+                let arrays = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column)| arrow_array(column.r#type, &self.batch, i))
+                    .collect();
+                writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+            }
+            TableWriter::Csv(writer) => {
+                for row in &self.batch {
+                    writer.write_record(row.iter().map(Cell::to_csv))?;
+                }
+            }
+        }
+
+        self.rows += self.batch.len();
+        self.batch.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<ExportedTable> {
+        self.flush()?;
+        match self.writer {
+            TableWriter::Parquet(writer, _) => {
+                writer.close()?;
+            }
+            TableWriter::Csv(mut writer) => writer.flush()?,
+        }
+
+        Ok(ExportedTable { name: self.name, path: self.path, rows: self.rows })
+    }
+}
+
+fn arrow_schema(columns: &[Column]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|column| {
+                let data_type = match column.r#type {
+                    ColumnType::Boolean => DataType::Boolean,
+                    ColumnType::Int64 => DataType::Int64,
+                    ColumnType::UInt64 => DataType::UInt64,
+                    ColumnType::String => DataType::Utf8,
+                };
+                Field::new(&column.name, data_type, true)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn arrow_array(r#type: ColumnType, rows: &[Vec<Cell>], column: usize) -> ArrayRef {
+    let cells = rows.iter().map(|row| &row[column]);
+    match r#type {
+        ColumnType::Boolean => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Boolean(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<BooleanArray>(),
+        ),
+        ColumnType::Int64 => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::Int64(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Int64Array>(),
+        ),
+        ColumnType::UInt64 => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::UInt64(value) => Some(*value),
+                    _ => None,
+                })
+                .collect::<UInt64Array>(),
+        ),
+        ColumnType::String => Arc::new(
+            cells
+                .map(|cell| match cell {
+                    Cell::String(value) => Some(value.as_str()),
+                    _ => None,
+                })
+                .collect::<StringArray>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct};
+    use dojo_world::contracts::abigen::model::Layout;
+    use starknet::core::types::Felt;
+    use starknet_crypto::poseidon_hash_many;
+    use tempfile::{NamedTempFile, TempDir};
+    use tokio::sync::broadcast;
+
+    use super::*;
+    use crate::executor::Executor;
+    use crate::sql::history::EntityHistoryConfig;
+    use crate::sql::utils::felts_to_sql_string;
+    use crate::sql::Sql;
+    use crate::types::ContractType;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    #[test]
+    fn test_flatten_members() {
+        let direction = Ty::Enum(Enum {
+            name: "Direction".to_string(),
+            option: Some(1),
+            options: vec![
+                EnumOption { name: "None".to_string(), ty: Ty::Tuple(vec![]) },
+                EnumOption { name: "Left".to_string(), ty: Ty::Primitive(Primitive::U8(Some(3))) },
+                EnumOption { name: "Right".to_string(), ty: Ty::Primitive(Primitive::U8(None)) },
+            ],
+        });
+        let schema = Ty::Struct(Struct {
+            name: "ns-Moves".to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(Some(Felt::ONE)))),
+                member("remaining", Ty::Primitive(Primitive::U64(Some(10)))),
+                member("last_direction", direction),
+                member(
+                    "position",
+                    Ty::Tuple(vec![
+                        Ty::Primitive(Primitive::I32(Some(-2))),
+                        Ty::Primitive(Primitive::Bool(Some(true))),
+                    ]),
+                ),
+                member(
+                    "history",
+                    Ty::Array(vec![
+                        Ty::Primitive(Primitive::U8(Some(1))),
+                        Ty::Primitive(Primitive::U8(Some(2))),
+                    ]),
+                ),
+                member("name", Ty::ByteArray("alice".to_string())),
+            ],
+        });
+
+        let mut columns = Vec::new();
+        member_columns("", &schema, &mut columns);
+        assert_eq!(
+            columns,
+            vec![
+                Column::new("player", ColumnType::String),
+                Column::new("remaining", ColumnType::UInt64),
+                Column::new("last_direction", ColumnType::String),
+                Column::new("last_direction.Left", ColumnType::Int64),
+                Column::new("last_direction.Right", ColumnType::Int64),
+                Column::new("position._0", ColumnType::Int64),
+                Column::new("position._1", ColumnType::Boolean),
+                Column::new("history", ColumnType::String),
+                Column::new("name", ColumnType::String),
+            ]
+        );
+
+        let mut values = Vec::new();
+        member_values(&schema, &mut values);
+        assert_eq!(
+            values,
+            vec![
+                Cell::String(format!("{:#064x}", Felt::ONE)),
+                Cell::UInt64(10),
+                Cell::String("Left".to_string()),
+                Cell::Int64(3),
+                Cell::Null,
+                Cell::Int64(-2),
+                Cell::Boolean(true),
+                Cell::String("[1,2]".to_string()),
+                Cell::String("alice".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_model() {
+        let tempfile = NamedTempFile::new().unwrap();
+        let path = tempfile.path().to_string_lossy().to_string();
+        let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+        sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+
+        let contracts = HashMap::from([(Felt::ZERO, ContractType::WORLD)]);
+        let mut db = Sql::new(pool.clone(), sender, &contracts).await.unwrap();
+
+        let position = |player: u8, x: Option<u32>| {
+            Ty::Struct(Struct {
+                name: "ns-Position".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        ty: Ty::Primitive(Primitive::ContractAddress(
+                            x.map(|_| Felt::from(player)),
+                        )),
+                        key: true,
+                    },
+                    member("x", Ty::Primitive(Primitive::U32(x))),
+                ],
+            })
+        };
+        db.register_model(
+            Felt::ZERO,
+            "ns",
+            position(0, None),
+            Layout::Fixed(vec![]),
+            Felt::ONE,
+            Felt::ONE,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+        let model_id = compute_selector_from_tag("ns-Position");
+        db.set_entity_history(EntityHistoryConfig {
+            models: HashSet::from([model_id]),
+            retention: None,
+        });
+
+        // the first player moves at the blocks 1 and 3, the second one at the block 2
+        for (block, player, x) in [(1, 1, 10), (2, 2, 20), (3, 1, 30)] {
+            let keys = vec![Felt::from(player)];
+            db.set_entity(
+                Felt::ZERO,
+                position(player, Some(x)),
+                &format!("{:#064x}:{:#x}:{:#04x}", block, Felt::ONE, 0),
+                1000 + block,
+                poseidon_hash_many(&keys),
+                model_id,
+                Some(&felts_to_sql_string(&keys)),
+            )
+            .await
+            .unwrap();
+        }
+        db.execute().await.unwrap();
+        sqlx::query("UPDATE contracts SET head = 3").execute(&pool).await.unwrap();
+
+        let output = TempDir::new().unwrap();
+        let export_rows = |at_block: Option<u64>, since_block: Option<u64>| {
+            let path = path.clone();
+            let output = output.path().to_path_buf();
+            async move {
+                let options = ExportOptions {
+                    models: vec!["ns-Position".to_string()],
+                    format: ExportFormat::Csv,
+                    output,
+                    at_block,
+                    since_block,
+                };
+                let metadata = export(&path, &options).await.unwrap();
+                let table = &metadata.tables[0];
+
+                let mut reader = csv::Reader::from_path(&table.path).unwrap();
+                let headers = reader.headers().unwrap().clone();
+                assert_eq!(
+                    headers.iter().collect::<Vec<_>>(),
+                    vec![
+                        "internal_entity_id",
+                        "internal_world_address",
+                        "internal_keys",
+                        "internal_event_id",
+                        "internal_block_number",
+                        "internal_executed_at",
+                        "player",
+                        "x",
+                    ]
+                );
+
+                let rows = reader
+                    .records()
+                    .map(|record| {
+                        let record = record.unwrap();
+                        (record[4].to_string(), record[6].to_string(), record[7].to_string())
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(rows.len(), table.rows);
+                rows
+            }
+        };
+        let row = |block: u64, player: u8, x: u32| {
+            (block.to_string(), format!("{:#064x}", Felt::from(player)), x.to_string())
+        };
+
+        // the versions at a past block
+        assert_eq!(export_rows(Some(2), None).await, vec![row(1, 1, 10), row(2, 2, 20)]);
+        // the versions updated within the blocks
+        assert_eq!(export_rows(Some(3), Some(2)).await, vec![row(3, 1, 30)]);
+        // the latest state of the entities updated after a block
+        assert_eq!(export_rows(None, Some(1)).await, vec![row(2, 2, 20), row(3, 1, 30)]);
+        assert!(export_rows(None, Some(3)).await.is_empty());
+
+        // the head isn't indexed yet
+        let options = ExportOptions {
+            models: vec!["ns-Position".to_string()],
+            format: ExportFormat::Csv,
+            output: output.path().to_path_buf(),
+            at_block: Some(4),
+            since_block: None,
+        };
+        assert!(export(&path, &options).await.is_err());
+    }
+}
@@ -3,9 +3,13 @@
 pub mod engine;
 pub mod error;
 pub mod executor;
+pub mod export;
 pub mod model;
 pub mod processors;
 pub mod simple_broker;
+pub mod snapshot;
 pub mod sql;
+pub mod token_metadata;
 pub mod types;
 pub mod utils;
+pub mod webhooks;
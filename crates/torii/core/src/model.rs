@@ -3,10 +3,11 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 use crypto_bigint::U256;
-use dojo_types::primitive::Primitive;
+use dojo_types::primitive::{Primitive, SqlType};
 use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
 use dojo_world::contracts::abigen::model::Layout;
 use dojo_world::contracts::model::ModelReader;
+use serde_json::{Map, Value};
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Pool, Row, Sqlite};
 use starknet::core::types::Felt;
@@ -396,9 +397,12 @@ pub fn build_sql_query(
         .iter()
         .map(|table| {
             let join_type = if table.is_optional { "LEFT JOIN" } else { "JOIN" };
-            let join_condition =
-                format!("{entities_table}.id = [{}].{entity_relation_column}", table.table_name);
-            format!(" {join_type} [{}] ON {join_condition}", table.table_name)
+            let table_name = format!("[{}]", table.table_name);
+            let join_condition = format!(
+                "{entities_table}.id = {table_name}.{entity_relation_column} AND \
+                 {entities_table}.world_address = {table_name}.world_address"
+            );
+            format!(" {join_type} {table_name} ON {join_condition}")
         })
         .collect::<Vec<_>>()
         .join(" ");
@@ -418,18 +422,20 @@ pub fn build_sql_query(
                 .iter()
                 .enumerate()
                 .map(|(i, table)| {
+                    let table_name = format!("[{}]", table.table_name);
                     if i == 0 {
                         format!(
-                            " JOIN [{}] ON {entities_table}.id = [{}].{entity_relation_column}",
-                            table.table_name, table.table_name
+                            " JOIN {table_name} ON {entities_table}.id = \
+                             {table_name}.{entity_relation_column} AND \
+                             {entities_table}.world_address = {table_name}.world_address"
                         )
                     } else {
                         let join_type = if table.is_optional { "LEFT JOIN" } else { "JOIN" };
+                        let parent_table = format!("[{}]", table.parent_table.as_ref().unwrap());
                         format!(
-                            " {join_type} [{}] ON [{}].full_array_id = [{}].full_array_id",
-                            table.table_name,
-                            table.table_name,
-                            table.parent_table.as_ref().unwrap()
+                            " {join_type} {table_name} ON {table_name}.full_array_id = \
+                             {parent_table}.full_array_id AND {table_name}.world_address = \
+                             {parent_table}.world_address"
                         )
                     }
                 })
@@ -439,16 +445,16 @@ pub fn build_sql_query(
             (
                 table,
                 format!(
-                    "SELECT {entities_table}.id, {entities_table}.keys{selections_clause} FROM \
-                     {entities_table}{join_clause}",
+                    "SELECT {entities_table}.id, {entities_table}.world_address, \
+                     {entities_table}.keys{selections_clause} FROM {entities_table}{join_clause}",
                 ),
             )
         })
         .collect();
 
     let mut query = format!(
-        "SELECT {entities_table}.id, {entities_table}.keys, {selections_clause} FROM \
-         {entities_table}{join_clause}"
+        "SELECT {entities_table}.id, {entities_table}.world_address, {entities_table}.keys, \
+         {selections_clause} FROM {entities_table}{join_clause}"
     );
     let mut count_query =
         format!("SELECT COUNT({entities_table}.id) FROM {entities_table}{join_clause}");
@@ -634,11 +640,60 @@ pub fn map_row_to_ty(
     Ok(())
 }
 
+/// JSON value of a model, as its members are read from the model tables: booleans and integers
+/// fitting in sqlite as JSON booleans and numbers, the other primitives as hex strings without
+/// leading zeros. Enums without data are mapped to the name of their option, the others to their
+/// option and its data, and tuples to objects whose keys are the indexes of their elements.
+pub fn ty_to_json(ty: &Ty) -> Value {
+    match ty {
+        Ty::Primitive(Primitive::Bool(value)) => value.map(Value::from).unwrap_or(Value::Null),
+        Ty::Primitive(primitive) => match primitive.to_sql_value() {
+            Ok(value) => match primitive.to_sql_type() {
+                SqlType::Integer => value.parse::<i64>().map(Value::from).unwrap_or(Value::Null),
+                SqlType::Text => match value.strip_prefix("0x") {
+                    Some(hex) => Value::from(format!("0x{:0>1}", hex.trim_start_matches('0'))),
+                    None => Value::from(value),
+                },
+            },
+            Err(_) => Value::Null,
+        },
+        Ty::ByteArray(bytes) => Value::from(bytes.clone()),
+        Ty::Struct(struct_) => Value::Object(
+            struct_
+                .children
+                .iter()
+                .map(|member| (member.name.clone(), ty_to_json(&member.ty)))
+                .collect(),
+        ),
+        Ty::Enum(enum_) => {
+            let Ok(option) = enum_.option() else {
+                return Value::Null;
+            };
+
+            if enum_.options.iter().all(|option| option.ty == Ty::Tuple(vec![])) {
+                return Value::from(option.name.clone());
+            }
+
+            let mut value = Map::new();
+            value.insert("option".to_string(), Value::from(option.name.clone()));
+            value.insert(option.name.clone(), ty_to_json(&option.ty));
+            Value::Object(value)
+        }
+        Ty::Tuple(tys) => Value::Object(
+            tys.iter().enumerate().map(|(idx, ty)| (format!("_{}", idx), ty_to_json(ty))).collect(),
+        ),
+        Ty::Array(tys) => Value::Array(tys.iter().map(ty_to_json).collect()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use dojo_types::primitive::Primitive;
     use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+    use serde_json::json;
+    use starknet::core::types::Felt;
 
-    use super::{build_sql_query, SqlModelMember};
+    use super::{build_sql_query, ty_to_json, SqlModelMember};
     use crate::model::parse_sql_model_members;
 
     #[test]
@@ -1065,18 +1120,93 @@ mod tests {
         .unwrap();
 
         let expected_query =
-            "SELECT entities.id, entities.keys, [Test-Position].external_player AS \
-             \"Test-Position.player\", [Test-Position$vec].external_x AS \"Test-Position$vec.x\", \
+            "SELECT entities.id, entities.world_address, entities.keys, \
+             [Test-Position].external_player AS \"Test-Position.player\", \
+             [Test-Position$vec].external_x AS \"Test-Position$vec.x\", \
              [Test-Position$vec].external_y AS \"Test-Position$vec.y\", \
              [Test-PlayerConfig$favorite_item].external_Some AS \
              \"Test-PlayerConfig$favorite_item.Some\", [Test-PlayerConfig].external_favorite_item \
              AS \"Test-PlayerConfig.favorite_item\" FROM entities JOIN [Test-Position] ON \
-             entities.id = [Test-Position].entity_id  JOIN [Test-PlayerConfig] ON entities.id = \
-             [Test-PlayerConfig].entity_id  JOIN [Test-Position$vec] ON entities.id = \
-             [Test-Position$vec].entity_id  LEFT JOIN [Test-PlayerConfig$favorite_item] ON \
-             entities.id = [Test-PlayerConfig$favorite_item].entity_id ORDER BY entities.event_id \
-             DESC";
+             entities.id = [Test-Position].entity_id AND entities.world_address = \
+             [Test-Position].world_address  JOIN [Test-PlayerConfig] ON entities.id = \
+             [Test-PlayerConfig].entity_id AND entities.world_address = \
+             [Test-PlayerConfig].world_address  JOIN [Test-Position$vec] ON entities.id = \
+             [Test-Position$vec].entity_id AND entities.world_address = \
+             [Test-Position$vec].world_address  LEFT JOIN [Test-PlayerConfig$favorite_item] ON \
+             entities.id = [Test-PlayerConfig$favorite_item].entity_id AND entities.world_address \
+             = [Test-PlayerConfig$favorite_item].world_address ORDER BY entities.event_id DESC";
         // todo: completely tests arrays
         assert_eq!(query.0, expected_query);
     }
+
+    #[test]
+    fn test_ty_to_json() {
+        let ty = Ty::Struct(Struct {
+            name: "ns-Moves".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(Felt::from(0x1234)))),
+                    key: true,
+                },
+                Member {
+                    name: "remaining".to_string(),
+                    ty: Ty::Primitive(Primitive::U8(Some(10))),
+                    key: false,
+                },
+                Member {
+                    name: "can_move".to_string(),
+                    ty: Ty::Primitive(Primitive::Bool(Some(true))),
+                    key: false,
+                },
+                Member {
+                    name: "last_direction".to_string(),
+                    ty: Ty::Enum(Enum {
+                        name: "Direction".to_string(),
+                        option: Some(1),
+                        options: vec![
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                    key: false,
+                },
+                Member {
+                    name: "target".to_string(),
+                    ty: Ty::Enum(Enum {
+                        name: "Target".to_string(),
+                        option: Some(1),
+                        options: vec![
+                            EnumOption { name: "None".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption {
+                                name: "Position".to_string(),
+                                ty: Ty::Tuple(vec![
+                                    Ty::Primitive(Primitive::U32(Some(1))),
+                                    Ty::Primitive(Primitive::U32(Some(2))),
+                                ]),
+                            },
+                        ],
+                    }),
+                    key: false,
+                },
+                Member {
+                    name: "path".to_string(),
+                    ty: Ty::Array(vec![Ty::Primitive(Primitive::Felt252(Some(Felt::from(3))))]),
+                    key: false,
+                },
+            ],
+        });
+
+        assert_eq!(
+            ty_to_json(&ty),
+            json!({
+                "player": "0x1234",
+                "remaining": 10,
+                "can_move": true,
+                "last_direction": "Right",
+                "target": { "option": "Position", "Position": { "_0": 1, "_1": 2 } },
+                "path": ["0x3"],
+            })
+        );
+    }
 }
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Error, Result};
+use async_trait::async_trait;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::contract::{
+    AbiEntry, AbiEnum, AbiEvent, AbiStruct, EventField, EventFieldKind, SierraClass, TypedAbiEvent,
+};
+use starknet::core::types::Event;
+use starknet::providers::Provider;
+use tracing::{debug, warn};
+
+use super::EventProcessor;
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::custom_event";
+
+/// Stores an event of a custom contract into its own table, decoded from the contract ABI.
+///
+/// The table is named `<contract name>-<event name>`, with a column per member of the event.
+#[derive(Debug)]
+pub struct CustomEventProcessor {
+    event_name: String,
+    table: String,
+    // struct of the event, whose members are flagged as keys or data
+    event: Ty,
+    table_registered: AtomicBool,
+    // the last event decoded by `validate`, which the engine processes right after, so that
+    // events are only decoded once
+    last_decoded: Mutex<Option<(Event, Ty)>>,
+}
+
+impl CustomEventProcessor {
+    /// Creates the processors of the events of a contract from its ABI, given either as the ABI
+    /// itself or as the Sierra class of the contract. Their tables are prefixed with
+    /// `contract_name`.
+    pub fn from_abi_file(contract_name: &str, path: &Path) -> Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read ABI file {}", path.display()))?;
+
+        let abi = match serde_json::from_str::<Vec<AbiEntry>>(&content) {
+            Ok(abi) => abi,
+            Err(_) => {
+                serde_json::from_str::<SierraClass>(&content)
+                    .with_context(|| format!("Invalid ABI file {}", path.display()))?
+                    .abi
+            }
+        };
+
+        Self::from_abi(contract_name, &abi)
+    }
+
+    /// Creates a processor for each struct event that the contract can emit.
+    ///
+    /// Events of components are only supported when flattened into the contract events, as their
+    /// nested events are emitted with the selector of the component as first key.
+    pub fn from_abi(contract_name: &str, abi: &[AbiEntry]) -> Result<Vec<Self>> {
+        let types = AbiTypes::new(abi);
+
+        // the events emitted by the contract are the variants of its root event enums, which
+        // aren't nested into other events
+        let nested = types
+            .event_enums
+            .values()
+            .flat_map(|event| event.iter().map(|variant| variant.r#type.as_str()))
+            .collect::<HashSet<_>>();
+        let roots = types.event_enums.keys().filter(|name| !nested.contains(*name));
+
+        let mut events = Vec::new();
+        for root in roots {
+            types.collect_events(root, &mut events)?;
+        }
+
+        let mut processors = Vec::with_capacity(events.len());
+        for (event_name, struct_name) in events {
+            let event = types
+                .event_struct(struct_name)
+                .with_context(|| format!("Unsupported members in event {struct_name}"))?;
+
+            processors.push(Self {
+                table: format!("{contract_name}-{event_name}"),
+                event_name: event_name.to_string(),
+                event,
+                table_registered: AtomicBool::new(false),
+                last_decoded: Mutex::new(None),
+            });
+        }
+
+        Ok(processors)
+    }
+
+    // Decodes the members of the event, which must use all its keys and data.
+    fn decode(&self, event: &Event) -> Result<Ty> {
+        let mut keys =
+            event.keys.get(1..).ok_or_else(|| anyhow!("Missing event selector"))?.to_vec();
+        let mut data = event.data.clone();
+
+        let mut decoded = self.event.clone();
+        if let Ty::Struct(event) = &mut decoded {
+            for member in &mut event.children {
+                let felts = if member.key { &mut keys } else { &mut data };
+                member.ty.deserialize(felts).with_context(|| {
+                    format!("Failed to decode member {} of {}", member.name, self.event_name)
+                })?;
+            }
+        }
+
+        if !keys.is_empty() || !data.is_empty() {
+            return Err(anyhow!(
+                "{} extra keys and {} extra data for {}",
+                keys.len(),
+                data.len(),
+                self.event_name
+            ));
+        }
+
+        Ok(decoded)
+    }
+}
+
+#[async_trait]
+impl<P> EventProcessor<P> for CustomEventProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        self.event_name.clone()
+    }
+
+    fn validate(&self, event: &Event) -> bool {
+        // the size of the members is only known once decoded, events not matching the ABI
+        // are left to the catch all processor
+        match self.decode(event) {
+            Ok(decoded) => {
+                *self.last_decoded.lock().unwrap() = Some((event.clone(), decoded));
+                true
+            }
+            Err(e) => {
+                debug!(target: LOG_TARGET, table = %self.table, error = %e, "Event not decoded.");
+                false
+            }
+        }
+    }
+
+    async fn process(
+        &self,
+        _world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        block_timestamp: u64,
+        event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let last_decoded = self.last_decoded.lock().unwrap().take();
+        let decoded = match last_decoded {
+            Some((decoded_event, decoded)) if decoded_event == *event => decoded,
+            _ => self.decode(event)?,
+        };
+
+        if !self.table_registered.swap(true, Ordering::Relaxed) {
+            db.register_custom_event_table(&self.table, event.from_address, &decoded).await?;
+        }
+        db.store_custom_event(
+            &self.table,
+            event.from_address,
+            &decoded,
+            event_id,
+            block_timestamp,
+        )?;
+
+        debug!(
+            target: LOG_TARGET,
+            table = %self.table,
+            event_id = %event_id,
+            "Stored custom event."
+        );
+
+        Ok(())
+    }
+}
+
+// The types declared in an ABI, referenced by their full path.
+struct AbiTypes<'a> {
+    structs: HashMap<&'a str, &'a AbiStruct>,
+    enums: HashMap<&'a str, &'a AbiEnum>,
+    event_structs: HashMap<&'a str, &'a Vec<EventField>>,
+    event_enums: HashMap<&'a str, &'a Vec<EventField>>,
+}
+
+impl<'a> AbiTypes<'a> {
+    fn new(abi: &'a [AbiEntry]) -> Self {
+        let mut types = Self {
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            event_structs: HashMap::new(),
+            event_enums: HashMap::new(),
+        };
+
+        let entries = abi.iter().flat_map(|entry| match entry {
+            AbiEntry::Interface(interface) => interface.items.iter().collect::<Vec<_>>(),
+            entry => vec![entry],
+        });
+        for entry in entries {
+            match entry {
+                AbiEntry::Struct(s) => {
+                    types.structs.insert(&s.name, s);
+                }
+                AbiEntry::Enum(e) => {
+                    types.enums.insert(&e.name, e);
+                }
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(e))) => {
+                    types.event_structs.insert(&e.name, &e.members);
+                }
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(e))) => {
+                    types.event_enums.insert(&e.name, &e.variants);
+                }
+                _ => {}
+            }
+        }
+
+        types
+    }
+
+    // Collects the `(event name, struct)` of the struct events emitted through an event enum.
+    fn collect_events(&self, name: &'a str, events: &mut Vec<(&'a str, &'a str)>) -> Result<()> {
+        for variant in self.event_enums[name] {
+            let r#type = variant.r#type.as_str();
+            match variant.kind {
+                EventFieldKind::Nested if self.event_structs.contains_key(r#type) => {
+                    events.push((&variant.name, r#type));
+                }
+                EventFieldKind::Flat if self.event_enums.contains_key(r#type) => {
+                    self.collect_events(r#type, events)?;
+                }
+                _ => {
+                    warn!(
+                        target: LOG_TARGET,
+                        event = %variant.name,
+                        r#type = %r#type,
+                        "Unsupported custom event, it won't be indexed."
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn event_struct(&self, name: &str) -> Result<Ty> {
+        let members = self.event_structs[name]
+            .iter()
+            .map(|member| {
+                Ok(Member {
+                    name: member.name.clone(),
+                    ty: self.ty(&member.r#type)?,
+                    key: matches!(member.kind, EventFieldKind::Key),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Ty::Struct(Struct { name: short_name(name), children: members }))
+    }
+
+    fn ty(&self, name: &str) -> Result<Ty> {
+        if let Some(primitive) = primitive(name) {
+            return Ok(Ty::Primitive(primitive));
+        }
+
+        if name == "core::byte_array::ByteArray" {
+            return Ok(Ty::ByteArray(String::new()));
+        }
+
+        for array in ["core::array::Array::<", "core::array::Span::<"] {
+            if let Some(item) = name.strip_prefix(array).and_then(|n| n.strip_suffix('>')) {
+                return Ok(Ty::Array(vec![self.ty(item)?]));
+            }
+        }
+
+        if let Some(items) = name.strip_prefix('(').and_then(|n| n.strip_suffix(')')) {
+            return Ok(Ty::Tuple(
+                split_tuple(items).into_iter().map(|item| self.ty(item)).collect::<Result<_>>()?,
+            ));
+        }
+
+        if let Some(s) = self.structs.get(name) {
+            let children = s
+                .members
+                .iter()
+                .map(|m| Ok(Member { name: m.name.clone(), ty: self.ty(&m.r#type)?, key: false }))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(Ty::Struct(Struct { name: short_name(name), children }));
+        }
+
+        if let Some(e) = self.enums.get(name) {
+            let options = e
+                .variants
+                .iter()
+                .map(|v| Ok(EnumOption { name: v.name.clone(), ty: self.ty(&v.r#type)? }))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(Ty::Enum(Enum { name: short_name(name), option: None, options }));
+        }
+
+        Err(anyhow!("Unsupported type {name}"))
+    }
+}
+
+fn primitive(name: &str) -> Option<Primitive> {
+    Some(match name {
+        "core::felt252" => Primitive::Felt252(None),
+        "core::bool" => Primitive::Bool(None),
+        "core::integer::i8" => Primitive::I8(None),
+        "core::integer::i16" => Primitive::I16(None),
+        "core::integer::i32" => Primitive::I32(None),
+        "core::integer::i64" => Primitive::I64(None),
+        "core::integer::i128" => Primitive::I128(None),
+        "core::integer::u8" => Primitive::U8(None),
+        "core::integer::u16" => Primitive::U16(None),
+        "core::integer::u32" => Primitive::U32(None),
+        "core::integer::u64" => Primitive::U64(None),
+        "core::integer::u128" => Primitive::U128(None),
+        "core::integer::u256" => Primitive::U256(None),
+        "core::integer::usize" => Primitive::USize(None),
+        "core::starknet::contract_address::ContractAddress" => Primitive::ContractAddress(None),
+        "core::starknet::class_hash::ClassHash" => Primitive::ClassHash(None),
+        _ => return None,
+    })
+}
+
+// Splits the items of a tuple type, which can themselves be generic or tuples.
+fn split_tuple(items: &str) -> Vec<&str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut split = Vec::new();
+    for (i, c) in items.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(items[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !items[start..].trim().is_empty() {
+        split.push(items[start..].trim());
+    }
+
+    split
+}
+
+fn short_name(path: &str) -> String {
+    path.rsplit("::").next().unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::Felt;
+
+    use super::*;
+
+    const ABI: &str = r#"[
+        {
+            "type": "struct",
+            "name": "marketplace::Price",
+            "members": [
+                { "name": "currency", "type": "core::starknet::contract_address::ContractAddress" },
+                { "name": "amount", "type": "core::integer::u256" }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "marketplace::Listed",
+            "kind": "struct",
+            "members": [
+                { "name": "listing_id", "type": "core::integer::u64", "kind": "key" },
+                { "name": "price", "type": "marketplace::Price", "kind": "data" },
+                { "name": "tags", "type": "core::array::Span::<core::felt252>", "kind": "data" }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "marketplace::Marketplace::Event",
+            "kind": "enum",
+            "variants": [
+                { "name": "Listed", "type": "marketplace::Listed", "kind": "nested" }
+            ]
+        }
+    ]"#;
+
+    #[test]
+    fn test_processors_from_abi() {
+        let abi: Vec<AbiEntry> = serde_json::from_str(ABI).unwrap();
+        let processors = CustomEventProcessor::from_abi("marketplace", &abi).unwrap();
+
+        assert_eq!(processors.len(), 1);
+        assert_eq!(processors[0].event_name, "Listed");
+        assert_eq!(processors[0].table, "marketplace-Listed");
+
+        let mut event = processors[0].event.clone();
+        let Ty::Struct(event) = &mut event else { panic!("event must be a struct") };
+        assert!(event.children[0].key);
+        assert!(!event.children[1].key);
+
+        let mut keys = vec![Felt::from(7)];
+        let mut data =
+            vec![Felt::from(0x1234), Felt::from(100), Felt::ZERO, Felt::ONE, Felt::from(0xab)];
+        event.children[0].ty.deserialize(&mut keys).unwrap();
+        event.children[1].ty.deserialize(&mut data).unwrap();
+        event.children[2].ty.deserialize(&mut data).unwrap();
+        assert!(keys.is_empty() && data.is_empty());
+        assert_eq!(
+            event.children[2].ty,
+            Ty::Array(vec![Ty::Primitive(Primitive::Felt252(Some(Felt::from(0xab))))])
+        );
+    }
+
+    #[test]
+    fn test_split_tuple() {
+        assert_eq!(
+            split_tuple("core::felt252, (core::bool, core::integer::u8)"),
+            vec!["core::felt252", "(core::bool, core::integer::u8)"]
+        );
+        assert!(split_tuple("").is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use cainome::cairo_serde::{ByteArray, CairoSerde, U256 as U256Cainome};
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{Event, U256};
+use starknet::providers::Provider;
+use tracing::debug;
+
+use super::EventProcessor;
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::erc1155_uri";
+
+#[derive(Default, Debug)]
+pub struct Erc1155UriProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for Erc1155UriProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "URI".to_string()
+    }
+
+    fn validate(&self, event: &Event) -> bool {
+        // ref: https://github.com/OpenZeppelin/cairo-contracts/blob/ba00ce76a93dcf25c081ab2698da20690b5a1cfb/packages/token/src/erc1155/erc1155.cairo#L85-L90
+        // key: [hash(URI), id.low, id.high]
+        // data: [...value (ByteArray)]
+        if event.keys.len() == 3 && !event.data.is_empty() {
+            return true;
+        }
+
+        false
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        _block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let token_address = event.from_address;
+
+        let token_id = U256Cainome::cairo_deserialize(&event.keys, 1)?;
+        let token_id = U256::from_words(token_id.low, token_id.high);
+
+        let uri = ByteArray::cairo_deserialize(&event.data, 0)?.to_string()?;
+
+        db.handle_erc1155_uri(token_address, token_id, &uri, world.provider()).await?;
+        debug!(target: LOG_TARGET, token_id = ?token_id, uri = %uri, "ERC1155 URI");
+
+        Ok(())
+    }
+}
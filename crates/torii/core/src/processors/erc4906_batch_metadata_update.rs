@@ -0,0 +1,65 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use cainome::cairo_serde::{CairoSerde, U256 as U256Cainome};
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{Event, U256};
+use starknet::providers::Provider;
+use tracing::debug;
+
+use super::EventProcessor;
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::erc4906_batch_metadata_update";
+
+#[derive(Default, Debug)]
+pub struct Erc4906BatchMetadataUpdateProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for Erc4906BatchMetadataUpdateProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "BatchMetadataUpdate".to_string()
+    }
+
+    fn validate(&self, event: &Event) -> bool {
+        // ref: https://eips.ethereum.org/EIPS/eip-4906
+        // key: [hash(BatchMetadataUpdate)]
+        // data: [from_token_id.low, from_token_id.high, to_token_id.low, to_token_id.high]
+        if event.keys.len() == 1 && event.data.len() == 4 {
+            return true;
+        }
+
+        false
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        _block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let token_address = event.from_address;
+
+        let from_token_id = U256Cainome::cairo_deserialize(&event.data, 0)?;
+        let from_token_id = U256::from_words(from_token_id.low, from_token_id.high);
+
+        let to_token_id = U256Cainome::cairo_deserialize(&event.data, 2)?;
+        let to_token_id = U256::from_words(to_token_id.low, to_token_id.high);
+
+        db.update_erc721_metadata_batch(
+            token_address,
+            from_token_id,
+            to_token_id,
+            world.provider(),
+        )
+        .await?;
+        debug!(target: LOG_TARGET, token_address = ?token_address, from_token_id = ?from_token_id, to_token_id = ?to_token_id, "ERC721 batch metadata update");
+
+        Ok(())
+    }
+}
@@ -1,29 +1,52 @@
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
 use dojo_world::contracts::world::WorldContractReader;
 use starknet::core::types::{Event, Felt, Transaction};
 use starknet::providers::Provider;
 
 use crate::sql::Sql;
 
+pub mod custom_event;
+pub mod erc1155_transfer_batch;
+pub mod erc1155_transfer_single;
+pub mod erc1155_uri;
 pub mod erc20_legacy_transfer;
 pub mod erc20_transfer;
+pub mod erc4906_batch_metadata_update;
+pub mod erc4906_metadata_update;
 pub mod erc721_legacy_transfer;
 pub mod erc721_transfer;
 pub mod event_message;
+pub mod initialize_contract;
 pub mod metadata_update;
+pub mod owner_update;
 pub mod raw_event;
+pub mod register_contract;
 pub mod register_event;
 pub mod register_model;
+pub mod register_namespace;
 pub mod store_del_record;
 pub mod store_set_record;
 pub mod store_transaction;
 pub mod store_update_member;
 pub mod store_update_record;
+pub mod upgrade_contract;
+pub mod upgrade_event;
+pub mod upgrade_model;
+pub mod writer_update;
 
 const MODEL_INDEX: usize = 0;
 const ENTITY_ID_INDEX: usize = 1;
 
+/// Decodes an event emitted by the world.
+///
+/// Torii version is coupled to the world version, so we can expect the event to be well formed.
+pub(crate) fn world_event(event: &Event, event_key: &str) -> WorldEvent {
+    WorldEvent::try_from(event)
+        .unwrap_or_else(|_| panic!("Expected {} event to be well formed.", event_key))
+}
+
 #[async_trait]
 pub trait EventProcessor<P>: Send + Sync
 where
@@ -0,0 +1,68 @@
+use anyhow::{Error, Ok, Result};
+use async_trait::async_trait;
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{Event, Felt};
+use starknet::providers::Provider;
+use tracing::info;
+
+use super::{world_event, EventProcessor};
+use crate::sql::Sql;
+use crate::types::PermissionKind;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::owner_update";
+
+#[derive(Default, Debug)]
+pub struct OwnerUpdateProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for OwnerUpdateProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "OwnerUpdated".to_string()
+    }
+
+    // We might not need this anymore, since we don't have fallback and all world events must
+    // be handled.
+    fn validate(&self, _event: &Event) -> bool {
+        true
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let event_key = <OwnerUpdateProcessor as EventProcessor<P>>::event_key(self);
+        let WorldEvent::OwnerUpdated(event) = world_event(event, &event_key) else {
+            unreachable!()
+        };
+
+        let contract: Felt = event.contract.into();
+
+        info!(
+            target: LOG_TARGET,
+            resource = %format!("{:#x}", event.resource),
+            contract = %format!("{:#x}", contract),
+            value = %event.value,
+            "Updated owner."
+        );
+
+        db.set_permission(
+            world.address,
+            event.resource,
+            contract,
+            PermissionKind::OWNER,
+            event.value,
+            block_timestamp,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{Error, Ok, Result};
+use async_trait::async_trait;
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{Event, Felt};
+use starknet::providers::Provider;
+use tracing::info;
+
+use super::{world_event, EventProcessor};
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::register_contract";
+
+#[derive(Default, Debug)]
+pub struct RegisterContractProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for RegisterContractProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "ContractRegistered".to_string()
+    }
+
+    // We might not need this anymore, since we don't have fallback and all world events must
+    // be handled.
+    fn validate(&self, _event: &Event) -> bool {
+        true
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let event_key = <RegisterContractProcessor as EventProcessor<P>>::event_key(self);
+        let WorldEvent::ContractRegistered(event) = world_event(event, &event_key) else {
+            unreachable!()
+        };
+
+        // Safe to unwrap, since it's coming from the chain.
+        let namespace = event.namespace.to_string().unwrap();
+        let name = event.name.to_string().unwrap();
+        let address: Felt = event.address.into();
+
+        info!(
+            target: LOG_TARGET,
+            namespace = %namespace,
+            name = %name,
+            address = %format!("{:#x}", address),
+            "Registered contract."
+        );
+
+        db.register_contract(
+            world.address,
+            &namespace,
+            &name,
+            address,
+            event.class_hash.into(),
+            event.salt,
+            block_timestamp,
+        )?;
+
+        Ok(())
+    }
+}
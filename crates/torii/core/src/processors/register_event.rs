@@ -87,6 +87,7 @@ where
         );
 
         db.register_model(
+            world.address,
             &namespace,
             schema,
             layout,
@@ -84,6 +84,7 @@ where
         );
 
         db.register_model(
+            world.address,
             &namespace,
             schema,
             layout,
@@ -0,0 +1,60 @@
+use anyhow::{Error, Ok, Result};
+use async_trait::async_trait;
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::Event;
+use starknet::providers::Provider;
+use tracing::info;
+
+use super::{world_event, EventProcessor};
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::register_namespace";
+
+#[derive(Default, Debug)]
+pub struct RegisterNamespaceProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for RegisterNamespaceProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "NamespaceRegistered".to_string()
+    }
+
+    // We might not need this anymore, since we don't have fallback and all world events must
+    // be handled.
+    fn validate(&self, _event: &Event) -> bool {
+        true
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let event_key = <RegisterNamespaceProcessor as EventProcessor<P>>::event_key(self);
+        let WorldEvent::NamespaceRegistered(event) = world_event(event, &event_key) else {
+            unreachable!()
+        };
+
+        // Safe to unwrap, since it's coming from the chain.
+        let namespace = event.namespace.to_string().unwrap();
+
+        info!(
+            target: LOG_TARGET,
+            namespace = %namespace,
+            hash = %format!("{:#x}", event.hash),
+            "Registered namespace."
+        );
+
+        db.register_namespace(world.address, &namespace, event.hash, block_timestamp)?;
+
+        Ok(())
+    }
+}
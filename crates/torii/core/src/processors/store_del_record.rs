@@ -29,7 +29,7 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
@@ -50,7 +50,7 @@ where
             }
         };
 
-        let model = db.model(event.selector).await?;
+        let model = db.world_model(world.address, event.selector).await?;
 
         info!(
             target: LOG_TARGET,
@@ -62,8 +62,15 @@ where
 
         let entity = model.schema;
 
-        db.delete_entity(event.entity_id, event.selector, entity, event_id, block_timestamp)
-            .await?;
+        db.delete_entity(
+            world.address,
+            event.entity_id,
+            event.selector,
+            entity,
+            event_id,
+            block_timestamp,
+        )
+        .await?;
 
         Ok(())
     }
@@ -30,7 +30,7 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
@@ -51,7 +51,7 @@ where
             }
         };
 
-        let model = db.model(event.selector).await?;
+        let model = db.world_model(world.address, event.selector).await?;
 
         info!(
             target: LOG_TARGET,
@@ -69,6 +69,7 @@ where
         entity.deserialize(&mut keys_and_unpacked)?;
 
         db.set_entity(
+            world.address,
             entity,
             event_id,
             block_timestamp,
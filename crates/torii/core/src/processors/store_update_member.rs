@@ -44,7 +44,7 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
@@ -55,7 +55,7 @@ where
         let entity_id = event.data[ENTITY_ID_INDEX];
         let member_selector = event.data[MEMBER_INDEX];
 
-        let model = db.model(model_id).await?;
+        let model = db.world_model(world.address, model_id).await?;
         let schema = model.schema;
 
         let mut member = schema
@@ -87,7 +87,7 @@ where
 
         let tag = naming::get_tag(&model.namespace, &model.name);
 
-        if !db.does_entity_exist(tag.clone(), entity_id).await? {
+        if !db.does_entity_exist(world.address, tag.clone(), entity_id).await? {
             warn!(
                 target: LOG_TARGET,
                 tag,
@@ -101,7 +101,16 @@ where
         member.ty.deserialize(&mut values)?;
         let wrapped_ty = Ty::Struct(Struct { name: schema.name(), children: vec![member] });
 
-        db.set_entity(wrapped_ty, event_id, block_timestamp, entity_id, model_id, None).await?;
+        db.set_entity(
+            world.address,
+            wrapped_ty,
+            event_id,
+            block_timestamp,
+            entity_id,
+            model_id,
+            None,
+        )
+        .await?;
         Ok(())
     }
 }
@@ -30,7 +30,7 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
@@ -54,7 +54,7 @@ where
         let model_selector = event.selector;
         let entity_id = event.entity_id;
 
-        let model = db.model(model_selector).await?;
+        let model = db.world_model(world.address, model_selector).await?;
 
         info!(
             target: LOG_TARGET,
@@ -77,7 +77,16 @@ where
         let mut values = event.values.to_vec();
         entity.deserialize(&mut values)?;
 
-        db.set_entity(entity, event_id, block_timestamp, entity_id, model_selector, None).await?;
+        db.set_entity(
+            world.address,
+            entity,
+            event_id,
+            block_timestamp,
+            entity_id,
+            model_selector,
+            None,
+        )
+        .await?;
         Ok(())
     }
 }
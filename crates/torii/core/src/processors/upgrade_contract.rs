@@ -0,0 +1,59 @@
+use anyhow::{Error, Ok, Result};
+use async_trait::async_trait;
+use dojo_world::contracts::abigen::world::Event as WorldEvent;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{Event, Felt};
+use starknet::providers::Provider;
+use tracing::info;
+
+use super::{world_event, EventProcessor};
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::upgrade_contract";
+
+#[derive(Default, Debug)]
+pub struct UpgradeContractProcessor;
+
+#[async_trait]
+impl<P> EventProcessor<P> for UpgradeContractProcessor
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    fn event_key(&self) -> String {
+        "ContractUpgraded".to_string()
+    }
+
+    // We might not need this anymore, since we don't have fallback and all world events must
+    // be handled.
+    fn validate(&self, _event: &Event) -> bool {
+        true
+    }
+
+    async fn process(
+        &self,
+        world: &WorldContractReader<P>,
+        db: &mut Sql,
+        _block_number: u64,
+        block_timestamp: u64,
+        _event_id: &str,
+        event: &Event,
+    ) -> Result<(), Error> {
+        let event_key = <UpgradeContractProcessor as EventProcessor<P>>::event_key(self);
+        let WorldEvent::ContractUpgraded(event) = world_event(event, &event_key) else {
+            unreachable!()
+        };
+
+        let class_hash: Felt = event.class_hash.into();
+
+        info!(
+            target: LOG_TARGET,
+            selector = %format!("{:#x}", event.selector),
+            class_hash = %format!("{:#x}", class_hash),
+            "Upgraded contract."
+        );
+
+        db.upgrade_contract(world.address, event.selector, class_hash, block_timestamp)?;
+
+        Ok(())
+    }
+}
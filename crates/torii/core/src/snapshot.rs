@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use starknet::core::types::Felt;
+use tracing::info;
+
+use crate::types::ContractType;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::snapshot";
+
+/// Table of the snapshot copies recording where they come from. It is dropped once a database is
+/// bootstrapped from the snapshot.
+const METADATA_TABLE: &str = "snapshot_metadata";
+
+/// The chain and the cursors a snapshot was taken at.
+#[derive(Debug, Clone)]
+pub struct SnapshotMetadata {
+    pub chain_id: Felt,
+    /// The block up to which all the contracts are indexed.
+    pub block_number: u64,
+    /// The indexed contracts, with the head of each of them.
+    pub contracts: Vec<(Felt, ContractType, Option<u64>)>,
+}
+
+/// Writes a gzip-compressed snapshot of the database at `database_path` to `output`, along with
+/// the chain it indexes. The copy is consistent even while Torii is indexing into the database.
+///
+/// The snapshot is always taken at the current head of the database: its block is the lowest head
+/// of the indexed contracts, and the database isn't rolled back to an earlier block.
+pub async fn create(
+    database_path: &str,
+    output: &Path,
+    chain_id: Felt,
+) -> Result<SnapshotMetadata> {
+    let copy_path = temporary_path(output, "sqlite");
+    let _ = std::fs::remove_file(&copy_path);
+
+    let options = SqliteConnectOptions::from_str(database_path)?.read_only(true);
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await?;
+    sqlx::query("VACUUM INTO ?").bind(copy_path.to_string_lossy()).execute(&pool).await?;
+    pool.close().await;
+
+    let copy = connect(&copy_path).await?;
+    let contracts = contracts(&copy).await?;
+    let block_number = contracts.iter().filter_map(|(_, _, head)| *head).min().unwrap_or_default();
+
+    sqlx::query(&format!(
+        "CREATE TABLE {METADATA_TABLE} (chain_id TEXT NOT NULL, block_number INTEGER NOT NULL, \
+         created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP)"
+    ))
+    .execute(&copy)
+    .await?;
+    sqlx::query(&format!("INSERT INTO {METADATA_TABLE} (chain_id, block_number) VALUES (?, ?)"))
+        .bind(format!("{:#x}", chain_id))
+        .bind(block_number as i64)
+        .execute(&copy)
+        .await?;
+    copy.close().await;
+
+    let output = output.to_path_buf();
+    let compressed_copy_path = copy_path.clone();
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let mut input = BufReader::new(File::open(&compressed_copy_path)?);
+        let mut encoder =
+            GzEncoder::new(BufWriter::new(File::create(&output)?), Compression::default());
+        std::io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    })
+    .await??;
+    std::fs::remove_file(&copy_path)?;
+
+    Ok(SnapshotMetadata { chain_id, block_number, contracts })
+}
+
+/// Creates the database at `database_path` from a snapshot, read from a file or downloaded from
+/// an `http(s)` URL. The snapshot must index the given worlds on the chain Torii is indexing.
+pub async fn bootstrap(
+    source: &str,
+    database_path: &str,
+    chain_id: Felt,
+    world_addresses: &[Felt],
+) -> Result<SnapshotMetadata> {
+    let database_path = PathBuf::from(database_path);
+    let compressed_path = if source.starts_with("http://") || source.starts_with("https://") {
+        let download_path = temporary_path(&database_path, "download");
+        info!(target: LOG_TARGET, url = %source, "Downloading snapshot.");
+        download(source, &download_path).await?;
+        download_path
+    } else {
+        PathBuf::from(source)
+    };
+
+    let decompressed_path = temporary_path(&database_path, "bootstrap");
+    let (input, output) = (compressed_path.clone(), decompressed_path.clone());
+    let decompressed = tokio::task::spawn_blocking(move || -> Result<()> {
+        let mut decoder = GzDecoder::new(BufReader::new(File::open(&input)?));
+        let mut output = BufWriter::new(File::create(&output)?);
+        std::io::copy(&mut decoder, &mut output)?;
+        output.flush()?;
+        Ok(())
+    })
+    .await?;
+    if compressed_path.as_os_str() != source {
+        std::fs::remove_file(&compressed_path)?;
+    }
+    decompressed.with_context(|| format!("Failed to decompress snapshot {source}"))?;
+
+    match verify(&decompressed_path, chain_id, world_addresses).await {
+        Ok(metadata) => {
+            std::fs::rename(&decompressed_path, &database_path)?;
+            Ok(metadata)
+        }
+        Err(error) => {
+            std::fs::remove_file(&decompressed_path)?;
+            Err(error)
+        }
+    }
+}
+
+// Checks that the snapshot matches the chain and worlds, and turns it back into a regular
+// database.
+async fn verify(path: &Path, chain_id: Felt, world_addresses: &[Felt]) -> Result<SnapshotMetadata> {
+    let pool = connect(path).await?;
+
+    let (snapshot_chain_id, block_number): (String, i64) =
+        sqlx::query_as(&format!("SELECT chain_id, block_number FROM {METADATA_TABLE}"))
+            .fetch_one(&pool)
+            .await
+            .context("Not a Torii snapshot")?;
+    let snapshot_chain_id = Felt::from_hex(&snapshot_chain_id)?;
+    if snapshot_chain_id != chain_id {
+        return Err(anyhow!(
+            "Snapshot of chain {:#x} can't be used to index chain {:#x}",
+            snapshot_chain_id,
+            chain_id
+        ));
+    }
+
+    let contracts = contracts(&pool).await?;
+    let snapshot_worlds = contracts
+        .iter()
+        .filter(|(_, r#type, _)| *r#type == ContractType::WORLD)
+        .map(|(address, _, _)| *address)
+        .collect::<HashSet<_>>();
+    if snapshot_worlds != world_addresses.iter().copied().collect::<HashSet<_>>() {
+        return Err(anyhow!(
+            "Snapshot indexes the worlds [{}], which don't match the worlds to index",
+            snapshot_worlds.iter().map(|w| format!("{:#x}", w)).collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    sqlx::query(&format!("DROP TABLE {METADATA_TABLE}")).execute(&pool).await?;
+    pool.close().await;
+
+    Ok(SnapshotMetadata { chain_id, block_number: block_number as u64, contracts })
+}
+
+async fn contracts(pool: &SqlitePool) -> Result<Vec<(Felt, ContractType, Option<u64>)>> {
+    let rows: Vec<(String, String, Option<i64>)> =
+        sqlx::query_as("SELECT contract_address, contract_type, head FROM contracts")
+            .fetch_all(pool)
+            .await?;
+
+    rows.into_iter()
+        .map(|(address, r#type, head)| {
+            Ok((Felt::from_hex(&address)?, r#type.parse()?, head.map(|head| head as u64)))
+        })
+        .collect()
+}
+
+async fn download(url: &str, path: &Path) -> Result<()> {
+    let mut response = reqwest::get(url).await?.error_for_status()?;
+    let mut file = BufWriter::new(File::create(path)?);
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+    }
+    file.flush()?;
+
+    Ok(())
+}
+
+async fn connect(path: &Path) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::new().filename(path);
+    Ok(SqlitePoolOptions::new().max_connections(1).connect_with(options).await?)
+}
+
+fn temporary_path(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{extension}.tmp"));
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    async fn indexed_database(dir: &TempDir) -> String {
+        let path = dir.path().join("indexer.db");
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE contracts (id TEXT NOT NULL PRIMARY KEY, contract_address TEXT NOT \
+             NULL, contract_type TEXT NOT NULL, head BIGINT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO contracts (id, contract_address, contract_type, head) VALUES ('0x1', \
+             '0x1', 'WORLD', 120), ('0x2', '0x2', 'ERC20', 100)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let database = indexed_database(&dir).await;
+        let output = dir.path().join("snapshot.db.gz");
+
+        let created = create(&database, &output, Felt::from(0x534e)).await.unwrap();
+        assert_eq!(created.block_number, 100);
+        assert_eq!(created.contracts.len(), 2);
+
+        let bootstrapped = dir.path().join("bootstrapped.db");
+        let bootstrapped = bootstrapped.to_str().unwrap();
+
+        // the chain and the worlds must be the ones of the snapshot
+        let error = bootstrap(output.to_str().unwrap(), bootstrapped, Felt::ONE, &[Felt::ONE])
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("chain"));
+        let error =
+            bootstrap(output.to_str().unwrap(), bootstrapped, Felt::from(0x534e), &[Felt::TWO])
+                .await
+                .unwrap_err();
+        assert!(error.to_string().contains("worlds"));
+        assert!(!Path::new(bootstrapped).exists());
+
+        let metadata =
+            bootstrap(output.to_str().unwrap(), bootstrapped, Felt::from(0x534e), &[Felt::ONE])
+                .await
+                .unwrap();
+        assert_eq!(metadata.block_number, 100);
+
+        let pool = connect(Path::new(bootstrapped)).await.unwrap();
+        let heads: Vec<(String, i64)> =
+            sqlx::query_as("SELECT id, head FROM contracts ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(heads, vec![("0x1".to_string(), 120), ("0x2".to_string(), 100)]);
+
+        // the database is a regular one again
+        let tables: Vec<(String,)> =
+            sqlx::query_as("SELECT name FROM sqlite_master WHERE name = ?")
+                .bind(METADATA_TABLE)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert!(tables.is_empty());
+    }
+}
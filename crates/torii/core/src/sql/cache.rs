@@ -33,11 +33,17 @@ pub struct Model {
 pub struct ModelCache {
     pool: SqlitePool,
     model_cache: RwLock<HashMap<Felt, Model>>,
+    /// The worlds which registered each model.
+    model_worlds: RwLock<HashMap<Felt, HashSet<Felt>>>,
 }
 
 impl ModelCache {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool, model_cache: RwLock::new(HashMap::new()) }
+        Self {
+            pool,
+            model_cache: RwLock::new(HashMap::new()),
+            model_worlds: RwLock::new(HashMap::new()),
+        }
     }
 
     pub async fn models(&self, selectors: &[Felt]) -> Result<Vec<Model>, Error> {
@@ -120,8 +126,53 @@ impl ModelCache {
         cache.insert(selector, model);
     }
 
+    /// Returns the worlds which registered the model.
+    pub async fn worlds(&self, selector: &Felt) -> Result<HashSet<Felt>, Error> {
+        if let Some(worlds) = self.model_worlds.read().await.get(selector) {
+            return Ok(worlds.clone());
+        }
+
+        let worlds: Vec<String> =
+            sqlx::query_scalar("SELECT world_address FROM world_models WHERE model_id = ?")
+                .bind(format!("{:#x}", selector))
+                .fetch_all(&self.pool)
+                .await?;
+        let worlds = worlds
+            .iter()
+            .map(|world| Felt::from_hex(world).map_err(ParseError::FromStr))
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        self.model_worlds.write().await.insert(*selector, worlds.clone());
+        Ok(worlds)
+    }
+
+    /// Sets whether the world registered the model, before the registration is stored.
+    pub async fn set_world(
+        &self,
+        selector: Felt,
+        world_address: Felt,
+        registered: bool,
+    ) -> Result<(), Error> {
+        // the registrations already stored are loaded first, so that they aren't overwritten
+        let mut worlds = self.worlds(&selector).await?;
+        if registered {
+            worlds.insert(world_address);
+        } else {
+            worlds.remove(&world_address);
+        }
+
+        self.model_worlds.write().await.insert(selector, worlds);
+        Ok(())
+    }
+
+    pub async fn remove(&self, selector: &Felt) {
+        self.model_cache.write().await.remove(selector);
+        self.model_worlds.write().await.remove(selector);
+    }
+
     pub async fn clear(&self) {
         self.model_cache.write().await.clear();
+        self.model_worlds.write().await.clear();
     }
 }
 
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Result};
+use dojo_types::schema::Ty;
+use starknet::core::types::Felt;
+
+use super::Sql;
+use crate::executor::{Argument, QueryMessage, QueryType};
+use crate::model::ty_to_json;
+use crate::utils::utc_dt_string_from_timestamp;
+
+impl Sql {
+    /// Creates the table storing the custom events decoded into `event`, or migrates it if the
+    /// event changed since it was created.
+    ///
+    /// Each member of the event is a column, primitives and enums being stored as in the model
+    /// tables and the other types as JSON. The columns of removed members are kept, with the
+    /// events stored before the upgrade.
+    pub async fn register_custom_event_table(
+        &mut self,
+        table: &str,
+        contract_address: Felt,
+        event: &Ty,
+    ) -> Result<()> {
+        let members = event.as_struct().ok_or_else(|| anyhow!("Event is not a struct"))?;
+
+        let mut columns = vec![
+            ("id".to_string(), "TEXT NOT NULL PRIMARY KEY".to_string()),
+            ("contract_address".to_string(), "TEXT NOT NULL".to_string()),
+            ("executed_at".to_string(), "DATETIME NOT NULL".to_string()),
+            ("created_at".to_string(), "DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP".to_string()),
+        ];
+        let fixed_columns = columns.len();
+        columns.extend(members.children.iter().map(|member| {
+            let sql_type = match &member.ty {
+                Ty::Primitive(primitive) => primitive.to_sql_type().to_string(),
+                _ => "TEXT".to_string(),
+            };
+            (format!("external_{}", member.name), sql_type)
+        }));
+
+        let existing: Vec<(String, String)> =
+            sqlx::query_as("SELECT name, type FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(&self.pool)
+                .await?;
+
+        // the columns of the removed members, which the rebuilt table keeps
+        let removed = existing
+            .iter()
+            .filter(|(name, _)| columns.iter().all(|(column, _)| column != name))
+            .cloned()
+            .collect::<Vec<_>>();
+        let added = columns[fixed_columns..]
+            .iter()
+            .filter(|(column, _)| existing.iter().all(|(name, _)| name != column))
+            .collect::<Vec<_>>();
+        let changed = columns[fixed_columns..].iter().any(|(column, sql_type)| {
+            existing.iter().any(|(name, ty)| name == column && !ty.eq_ignore_ascii_case(sql_type))
+        });
+
+        let create_table = format!(
+            "CREATE TABLE IF NOT EXISTS [{table}] ({})",
+            columns
+                .iter()
+                .chain(removed.iter())
+                .map(|(column, sql_type)| format!("[{column}] {sql_type}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        if existing.is_empty() {
+            self.executor.send(QueryMessage::other(create_table, vec![]))?;
+        } else if changed {
+            // sqlite can't change the type of a column, the table is created again
+            self.executor.send(QueryMessage::new(
+                create_table,
+                vec![],
+                QueryType::RebuildTable(table.to_string()),
+            ))?;
+        } else {
+            for (column, sql_type) in added {
+                self.executor.send(QueryMessage::other(
+                    format!("ALTER TABLE [{table}] ADD COLUMN [{column}] {sql_type}"),
+                    vec![],
+                ))?;
+            }
+        }
+        self.executor.send(QueryMessage::other(
+            "INSERT OR IGNORE INTO custom_event_tables (name, contract_address, event_name) \
+             VALUES (?, ?, ?)"
+                .to_string(),
+            vec![
+                Argument::String(table.to_string()),
+                Argument::FieldElement(contract_address),
+                Argument::String(event.name()),
+            ],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Stores a custom event in the table registered with
+    /// [`register_custom_event_table`](Self::register_custom_event_table).
+    pub fn store_custom_event(
+        &mut self,
+        table: &str,
+        contract_address: Felt,
+        event: &Ty,
+        event_id: &str,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let members = event.as_struct().ok_or_else(|| anyhow!("Event is not a struct"))?;
+
+        let mut columns =
+            vec!["id".to_string(), "contract_address".to_string(), "executed_at".to_string()];
+        let mut arguments = vec![
+            Argument::String(event_id.to_string()),
+            Argument::FieldElement(contract_address),
+            Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+        ];
+
+        for member in &members.children {
+            columns.push(format!("[external_{}]", member.name));
+            arguments.push(match &member.ty {
+                Ty::Primitive(primitive) => Argument::String(primitive.to_sql_value()?),
+                Ty::Enum(enum_) => Argument::String(enum_.to_sql_value()?),
+                Ty::ByteArray(bytes) => Argument::String(bytes.clone()),
+                ty => Argument::String(ty_to_json(ty).to_string()),
+            });
+        }
+
+        self.executor.send(QueryMessage::other(
+            format!(
+                "INSERT OR IGNORE INTO [{table}] ({}) VALUES ({})",
+                columns.join(", "),
+                vec!["?"; columns.len()].join(", ")
+            ),
+            arguments,
+        ))?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::mem;
+use std::str::FromStr;
 
 use anyhow::{Context, Result};
 use cainome::cairo_serde::{ByteArray, CairoSerde};
@@ -8,10 +9,11 @@ use starknet::core::utils::{get_selector_from_name, parse_cairo_short_string};
 use starknet::providers::Provider;
 use tracing::debug;
 
-use super::utils::{u256_to_sql_string, I256};
+use super::utils::{sql_string_to_u256, u256_to_sql_string, I256};
 use super::{Sql, FELT_DELIMITER};
 use crate::executor::{ApplyBalanceDiffQuery, Argument, QueryMessage, QueryType};
 use crate::sql::utils::{felt_and_u256_to_sql_string, felt_to_sql_string, felts_to_sql_string};
+use crate::token_metadata::TokenMetadataRequest;
 use crate::types::ContractType;
 use crate::utils::utc_dt_string_from_timestamp;
 
@@ -84,11 +86,12 @@ impl Sql {
         event_id: &str,
     ) -> Result<()> {
         // contract_address:id
-        let token_id = felt_and_u256_to_sql_string(&contract_address, &token_id);
+        let id = token_id;
+        let token_id = felt_and_u256_to_sql_string(&contract_address, &id);
         let token_exists: bool = self.local_cache.contains_token_id(&token_id);
 
         if !token_exists {
-            self.register_erc721_token_metadata(contract_address, &token_id, provider).await?;
+            self.register_erc721_token_metadata(contract_address, &token_id, id, provider).await?;
             self.execute().await?;
         }
 
@@ -132,6 +135,201 @@ impl Sql {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub async fn handle_erc1155_transfer<P: Provider + Sync>(
+        &mut self,
+        contract_address: Felt,
+        from_address: Felt,
+        to_address: Felt,
+        token_id: U256,
+        amount: U256,
+        provider: &P,
+        block_timestamp: u64,
+        event_id: &str,
+    ) -> Result<()> {
+        // contract_address:id
+        let id = token_id;
+        let token_id = felt_and_u256_to_sql_string(&contract_address, &id);
+        let token_exists: bool = self.local_cache.contains_token_id(&token_id);
+
+        if !token_exists {
+            self.register_erc1155_token_metadata(contract_address, &token_id, id, provider).await?;
+            self.execute().await?;
+        }
+
+        self.store_erc_transfer_event(
+            contract_address,
+            from_address,
+            to_address,
+            amount,
+            &token_id,
+            block_timestamp,
+            event_id,
+        )?;
+
+        // from_address/contract_address:id
+        if from_address != Felt::ZERO {
+            let from_balance_id =
+                format!("{}{FELT_DELIMITER}{}", felt_to_sql_string(&from_address), &token_id);
+            let from_balance = self
+                .local_cache
+                .erc_cache
+                .entry((ContractType::ERC1155, from_balance_id))
+                .or_default();
+            *from_balance -= I256::from(amount);
+        }
+
+        if to_address != Felt::ZERO {
+            let to_balance_id =
+                format!("{}{FELT_DELIMITER}{}", felt_to_sql_string(&to_address), &token_id);
+            let to_balance = self
+                .local_cache
+                .erc_cache
+                .entry((ContractType::ERC1155, to_balance_id))
+                .or_default();
+            *to_balance += I256::from(amount);
+        }
+
+        if self.local_cache.erc_cache.len() >= 100000 {
+            self.apply_cache_diff().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Registers the ERC1155 token whose URI has been set if it isn't known yet, or refreshes
+    /// its metadata otherwise.
+    pub async fn handle_erc1155_uri<P: Provider + Sync>(
+        &mut self,
+        contract_address: Felt,
+        token_id: U256,
+        uri: &str,
+        provider: &P,
+    ) -> Result<()> {
+        // contract_address:id
+        let id = token_id;
+        let token_id = felt_and_u256_to_sql_string(&contract_address, &id);
+
+        if !self.local_cache.contains_token_id(&token_id) {
+            self.register_erc1155_token_metadata(contract_address, &token_id, id, provider).await?;
+            self.execute().await?;
+        } else {
+            self.queue_token_uri(&token_id, id, uri)?;
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes the metadata of a known ERC721 token, after an ERC4906 `MetadataUpdate` event.
+    pub async fn update_erc721_metadata<P: Provider + Sync>(
+        &mut self,
+        contract_address: Felt,
+        token_id: U256,
+        provider: &P,
+    ) -> Result<()> {
+        // contract_address:id
+        let id = token_id;
+        let token_id = felt_and_u256_to_sql_string(&contract_address, &id);
+
+        // Unknown tokens have their metadata resolved when they're first transferred.
+        if self.local_cache.contains_token_id(&token_id) {
+            self.queue_token_metadata(
+                ContractType::ERC721,
+                contract_address,
+                &token_id,
+                id,
+                provider,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes the metadata of the known ERC721 tokens in the inclusive range
+    /// `[from_token_id, to_token_id]`, after an ERC4906 `BatchMetadataUpdate` event.
+    pub async fn update_erc721_metadata_batch<P: Provider + Sync>(
+        &mut self,
+        contract_address: Felt,
+        from_token_id: U256,
+        to_token_id: U256,
+        provider: &P,
+    ) -> Result<()> {
+        if self.token_metadata_resolver.is_none() {
+            return Ok(());
+        }
+
+        // The ids are zero padded, so they're ordered as the token ids.
+        let token_ids: Vec<(String,)> = sqlx::query_as(
+            "SELECT id FROM tokens WHERE contract_address = ? AND id BETWEEN ? AND ?",
+        )
+        .bind(felt_to_sql_string(&contract_address))
+        .bind(felt_and_u256_to_sql_string(&contract_address, &from_token_id))
+        .bind(felt_and_u256_to_sql_string(&contract_address, &to_token_id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (token_id,) in token_ids {
+            // contract_address:id
+            let Some((_, id)) = token_id.split_once(':') else {
+                continue;
+            };
+            let id = sql_string_to_u256(id);
+
+            self.queue_token_metadata(
+                ContractType::ERC721,
+                contract_address,
+                &token_id,
+                id,
+                provider,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Queues the ERC721 and ERC1155 tokens whose metadata hasn't been resolved yet, as the
+    /// resolutions still queued when torii stopped are lost.
+    pub async fn queue_unresolved_token_metadata<P: Provider + Sync>(
+        &self,
+        provider: &P,
+    ) -> Result<()> {
+        if self.token_metadata_resolver.is_none() {
+            return Ok(());
+        }
+
+        let tokens: Vec<(String, String)> = sqlx::query_as(
+            "SELECT tokens.id, contracts.contract_type FROM tokens JOIN contracts ON contracts.id \
+             = tokens.contract_address WHERE tokens.metadata IS NULL AND contracts.contract_type \
+             IN ('ERC721', 'ERC1155')",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        debug!(count = tokens.len(), "Queuing unresolved token metadata");
+
+        for (token_id, contract_type) in tokens {
+            // contract_address:id
+            let Some((contract_address, id)) = token_id.split_once(':') else {
+                continue;
+            };
+            let contract_address = Felt::from_str(contract_address)?;
+            let id = sql_string_to_u256(id);
+
+            self.queue_token_metadata(
+                ContractType::from_str(&contract_type)?,
+                contract_address,
+                &token_id,
+                id,
+                provider,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     async fn register_erc20_token_metadata<P: Provider + Sync>(
         &mut self,
         contract_address: Felt,
@@ -216,6 +414,7 @@ impl Sql {
         &mut self,
         contract_address: Felt,
         token_id: &str,
+        id: U256,
         provider: &P,
     ) -> Result<()> {
         let res = sqlx::query_as::<_, (String, String, u8)>(
@@ -245,6 +444,14 @@ impl Sql {
                 ],
             ))?;
             self.local_cache.register_token_id(token_id.to_string());
+            self.queue_token_metadata(
+                ContractType::ERC721,
+                contract_address,
+                token_id,
+                id,
+                provider,
+            )
+            .await?;
             return Ok(());
         }
 
@@ -307,6 +514,95 @@ impl Sql {
         ))?;
 
         self.local_cache.register_token_id(token_id.to_string());
+        self.queue_token_metadata(ContractType::ERC721, contract_address, token_id, id, provider)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn register_erc1155_token_metadata<P: Provider + Sync>(
+        &mut self,
+        contract_address: Felt,
+        token_id: &str,
+        id: U256,
+        provider: &P,
+    ) -> Result<()> {
+        let res = sqlx::query_as::<_, (String, String)>(
+            "SELECT name, symbol FROM tokens WHERE contract_address = ?",
+        )
+        .bind(felt_to_sql_string(&contract_address))
+        .fetch_one(&self.pool)
+        .await;
+
+        // Name and symbol are the same for all the tokens of a contract. They aren't part of the
+        // ERC1155 standard, so they're left empty if the contract doesn't implement them.
+        let (name, symbol) = match res {
+            Ok((name, symbol)) => (name, symbol),
+            Err(_) => (
+                fetch_optional_string(provider, contract_address, "name").await,
+                fetch_optional_string(provider, contract_address, "symbol").await,
+            ),
+        };
+
+        let decimals = 0;
+
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO tokens (id, contract_address, name, symbol, decimals) VALUES (?, ?, ?, \
+             ?, ?)"
+                .to_string(),
+            vec![
+                Argument::String(token_id.to_string()),
+                Argument::FieldElement(contract_address),
+                Argument::String(name),
+                Argument::String(symbol),
+                Argument::Int(decimals.into()),
+            ],
+        ))?;
+
+        self.local_cache.register_token_id(token_id.to_string());
+        self.queue_token_metadata(ContractType::ERC1155, contract_address, token_id, id, provider)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetches the URI of an ERC721 or ERC1155 token and queues the resolution of its metadata,
+    /// if a token metadata resolver is set.
+    async fn queue_token_metadata<P: Provider + Sync>(
+        &self,
+        contract_type: ContractType,
+        contract_address: Felt,
+        token_id: &str,
+        id: U256,
+        provider: &P,
+    ) -> Result<()> {
+        if self.token_metadata_resolver.is_none() {
+            return Ok(());
+        }
+
+        let entry_points: &[&str] = match contract_type {
+            ContractType::ERC1155 => &["uri"],
+            _ => &["token_uri", "tokenURI"],
+        };
+
+        for entry_point in entry_points {
+            if let Some(uri) = fetch_token_uri(provider, contract_address, entry_point, id).await {
+                return self.queue_token_uri(token_id, id, &uri);
+            }
+        }
+
+        debug!(token_id = %token_id, "Token doesn't have a URI, skipping metadata resolution");
+
+        Ok(())
+    }
+
+    fn queue_token_uri(&self, token_id: &str, id: U256, uri: &str) -> Result<()> {
+        if let Some(resolver) = &self.token_metadata_resolver {
+            // ERC1155 URIs can contain an `{id}` placeholder, substituted by the lowercase hex id
+            // padded to 64 characters.
+            let uri = uri.replace("{id}", &format!("{:064x}", id));
+            resolver.send(TokenMetadataRequest { token_id: token_id.to_string(), uri })?;
+        }
 
         Ok(())
     }
@@ -324,9 +620,9 @@ impl Sql {
     ) -> Result<()> {
         let insert_query = "INSERT INTO erc_transfers (id, contract_address, from_address, \
                             to_address, amount, token_id, executed_at) VALUES (?, ?, ?, ?, ?, ?, \
-                            ?)";
+                            ?) RETURNING *";
 
-        self.executor.send(QueryMessage::other(
+        self.executor.send(QueryMessage::new(
             insert_query.to_string(),
             vec![
                 Argument::String(event_id.to_string()),
@@ -337,6 +633,7 @@ impl Sql {
                 Argument::String(token_id.to_string()),
                 Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
             ],
+            QueryType::StoreErcTransfer,
         ))?;
 
         Ok(())
@@ -358,3 +655,75 @@ impl Sql {
         Ok(())
     }
 }
+
+/// Calls a getter returning a short string or a `ByteArray`, returning an empty string if the
+/// contract doesn't implement it.
+async fn fetch_optional_string<P: Provider + Sync>(
+    provider: &P,
+    contract_address: Felt,
+    entry_point: &str,
+) -> String {
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address,
+                entry_point_selector: get_selector_from_name(entry_point).unwrap(),
+                calldata: vec![],
+            },
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await;
+
+    match result {
+        Ok(value) if value.len() == 1 => parse_cairo_short_string(&value[0]).unwrap_or_default(),
+        Ok(value) => ByteArray::cairo_deserialize(&value, 0)
+            .ok()
+            .and_then(|value| value.to_string().ok())
+            .unwrap_or_default(),
+        Err(_) => {
+            debug!(
+                contract_address = %felt_to_sql_string(&contract_address),
+                entry_point,
+                "Token doesn't implement getter",
+            );
+            String::new()
+        }
+    }
+}
+
+/// Calls a token URI getter, returning `None` if the contract doesn't implement it.
+async fn fetch_token_uri<P: Provider + Sync>(
+    provider: &P,
+    contract_address: Felt,
+    entry_point: &str,
+    id: U256,
+) -> Option<String> {
+    let uri = provider
+        .call(
+            FunctionCall {
+                contract_address,
+                entry_point_selector: get_selector_from_name(entry_point).unwrap(),
+                calldata: vec![Felt::from(id.low()), Felt::from(id.high())],
+            },
+            BlockId::Tag(BlockTag::Pending),
+        )
+        .await
+        .ok()?;
+
+    // len = 1 => return value felt (i.e. legacy token)
+    // len > 1 => return value ByteArray, or an array of short strings for some legacy tokens
+    if uri.len() == 1 {
+        return parse_cairo_short_string(&uri[0]).ok();
+    }
+
+    if let Ok(uri) = ByteArray::cairo_deserialize(&uri, 0) {
+        return uri.to_string().ok();
+    }
+
+    Vec::<Felt>::cairo_deserialize(&uri, 0)
+        .ok()?
+        .iter()
+        .map(parse_cairo_short_string)
+        .collect::<Result<String, _>>()
+        .ok()
+}
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use dojo_types::schema::Ty;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use super::Sql;
+use crate::executor::{EntityHistoryQuery, QueryMessage, QueryType};
+use crate::utils::utc_dt_string_from_timestamp;
+
+/// Models whose entities are versioned in the `entities_historical` table, to be queried at a
+/// past block.
+#[derive(Debug, Clone, Default)]
+pub struct EntityHistoryConfig {
+    /// Selectors of the models indexed with history.
+    pub models: HashSet<Felt>,
+    /// Number of blocks for which the versions of an entity are kept, all of them if `None`.
+    pub retention: Option<u64>,
+}
+
+/// Bounds of the versions of the historical models the entities are queried at: their state at
+/// `at_block` and `to_timestamp`, only for the models updated since `from_timestamp`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryBounds {
+    pub at_block: Option<u64>,
+    pub from_timestamp: Option<u64>,
+    pub to_timestamp: Option<u64>,
+}
+
+impl HistoryBounds {
+    /// Whether the bounds select the latest state of the entities, which is that of the model
+    /// tables.
+    pub fn is_latest(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Condition on the versions `h` of the `entities_historical` table selecting the latest
+    /// version of each entity and model within the bounds, unless the entity was deleted from the
+    /// model, along with its arguments.
+    pub fn latest_version_condition(&self) -> Result<(String, Vec<String>)> {
+        let mut upper_bounds = Vec::new();
+        let mut upper_arguments = Vec::new();
+        if let Some(block_number) = self.at_block {
+            upper_bounds.push("block_number <= CAST(? AS INTEGER)");
+            upper_arguments.push(block_number.to_string());
+        }
+        if let Some(timestamp) = self.to_timestamp {
+            upper_bounds.push("executed_at <= ?");
+            upper_arguments.push(datetime_string(timestamp)?);
+        }
+
+        let mut condition = String::from("NOT h.deleted");
+        let mut arguments = Vec::new();
+        for upper_bound in &upper_bounds {
+            condition.push_str(&format!(" AND h.{upper_bound}"));
+        }
+        arguments.extend(upper_arguments.iter().cloned());
+        if let Some(timestamp) = self.from_timestamp {
+            condition.push_str(" AND h.executed_at >= ?");
+            arguments.push(datetime_string(timestamp)?);
+        }
+
+        condition.push_str(
+            " AND h.event_id = (SELECT MAX(event_id) FROM entities_historical WHERE id = h.id AND \
+             world_address = h.world_address AND model_id = h.model_id",
+        );
+        for upper_bound in &upper_bounds {
+            condition.push_str(&format!(" AND {upper_bound}"));
+        }
+        condition.push(')');
+        arguments.extend(upper_arguments);
+
+        Ok((condition, arguments))
+    }
+}
+
+/// Formats a timestamp as the `executed_at` column of the versions.
+fn datetime_string(timestamp: u64) -> Result<String> {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|datetime| datetime.to_rfc3339())
+        .ok_or_else(|| anyhow!("Invalid timestamp: {}", timestamp))
+}
+
+impl Sql {
+    /// Enables the history of the entities of the given models.
+    pub fn set_entity_history(&mut self, config: EntityHistoryConfig) {
+        self.entity_history = config;
+    }
+
+    pub fn is_historical_model(&self, model_id: &Felt) -> bool {
+        self.entity_history.models.contains(model_id)
+    }
+
+    /// Records a new version of an entity of `world_address` of a model indexed with history.
+    /// `entity` is the possibly partial update of the model, or `None` if the entity was deleted
+    /// from it.
+    pub(crate) async fn record_entity_history(
+        &mut self,
+        world_address: Felt,
+        entity_id: Felt,
+        model_id: Felt,
+        event_id: &str,
+        block_timestamp: u64,
+        entity: Option<Ty>,
+    ) -> Result<()> {
+        let (block_number, transaction_hash) = parse_event_id(event_id)?;
+        let schema = self.model(model_id).await?.schema;
+
+        self.executor.send(QueryMessage::new(
+            "".to_string(),
+            vec![],
+            QueryType::EntityHistory(EntityHistoryQuery {
+                entity_id: format!("{:#x}", entity_id),
+                world_address: format!("{:#x}", world_address),
+                model_id: format!("{:#x}", model_id),
+                event_id: event_id.to_string(),
+                block_number,
+                transaction_hash,
+                block_timestamp: utc_dt_string_from_timestamp(block_timestamp),
+                schema,
+                entity,
+                retention: self.entity_history.retention,
+            }),
+        ))?;
+
+        Ok(())
+    }
+}
+
+/// Extracts the block number and the transaction hash of an event id,
+/// `block_number:transaction_hash:event_idx`, or of a transaction id.
+pub(crate) fn parse_event_id(event_id: &str) -> Result<(u64, String)> {
+    let mut parts = event_id.split(':');
+    let (Some(block_number), Some(transaction_hash)) = (parts.next(), parts.next()) else {
+        return Err(anyhow!("Malformed event id: {}", event_id));
+    };
+
+    let block_number = u64::from_str_radix(block_number.trim_start_matches("0x"), 16)
+        .map_err(|e| anyhow!("Malformed event id {}: {}", event_id, e))?;
+
+    Ok((block_number, transaction_hash.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_id() {
+        let event_id = format!("{:#064x}:{:#x}:{:#04x}", 42, Felt::from(0x1234), 3);
+        assert_eq!(parse_event_id(&event_id).unwrap(), (42, "0x1234".to_string()));
+
+        assert!(parse_event_id("0x2a").is_err());
+    }
+
+    #[test]
+    fn test_latest_version_condition() {
+        assert!(HistoryBounds::default().is_latest());
+
+        let (condition, arguments) = HistoryBounds { at_block: Some(10), ..Default::default() }
+            .latest_version_condition()
+            .unwrap();
+        assert_eq!(
+            condition,
+            "NOT h.deleted AND h.block_number <= CAST(? AS INTEGER) AND h.event_id = (SELECT \
+             MAX(event_id) FROM entities_historical WHERE id = h.id AND world_address = \
+             h.world_address AND model_id = h.model_id AND block_number <= CAST(? AS INTEGER))"
+        );
+        assert_eq!(arguments, vec!["10", "10"]);
+
+        let (_, arguments) =
+            HistoryBounds { at_block: None, from_timestamp: Some(0), to_timestamp: Some(60) }
+                .latest_version_condition()
+                .unwrap();
+        assert_eq!(
+            arguments,
+            vec![
+                "1970-01-01T00:01:00+00:00",
+                "1970-01-01T00:00:00+00:00",
+                "1970-01-01T00:01:00+00:00"
+            ]
+        );
+
+        let bounds = HistoryBounds { to_timestamp: Some(u64::MAX), ..Default::default() };
+        assert!(bounds.latest_version_condition().is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use dojo_types::schema::Ty;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use serde::Deserialize;
+use tracing::warn;
+
+use super::Sql;
+use crate::executor::{Argument, QueryMessage};
+
+pub(crate) const LOG_TARGET: &str = "torii_core::sql::indexes";
+
+/// An index of a model table declared in the config file, on top of the indexes of the single
+/// members Torii creates.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModelIndexConfig {
+    /// Model of the indexed members, as `namespace-name`.
+    pub model: String,
+    /// Paths of the indexed members, in the order of the index. The members of a composite index
+    /// must be in the same struct, nested members being separated by dots.
+    pub members: Vec<String>,
+    /// Name of the index, unique for the model. Defaults to the names of the members.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// SQL condition of a partial index, on the columns `external_<member>` of the table of the
+    /// members.
+    #[serde(default)]
+    pub condition: Option<String>,
+}
+
+impl ModelIndexConfig {
+    /// Name of the index in the database.
+    pub fn index_name(&self) -> String {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => self.members.join("_").replace('.', "_"),
+        };
+        format!("{}.{}", self.model, name)
+    }
+
+    // The table of the members and the indexed columns, if all the members are stored in the
+    // same table.
+    fn table_columns(&self, schema: &Ty) -> Result<(String, Vec<String>)> {
+        let mut table = None;
+        let mut columns = Vec::with_capacity(self.members.len());
+
+        for member in &self.members {
+            let mut path = member.split('.').collect::<Vec<_>>();
+            let ty = path.iter().try_fold(schema, |ty, name| {
+                ty.as_struct()?.children.iter().find(|m| m.name == *name).map(|m| &m.ty)
+            });
+            if !matches!(ty, Some(Ty::Primitive(_) | Ty::Enum(_) | Ty::ByteArray(_))) {
+                return Err(anyhow!("Member {member} of model {} can't be indexed", self.model));
+            }
+
+            let column = path.pop().unwrap();
+            let member_table =
+                std::iter::once(self.model.as_str()).chain(path).collect::<Vec<_>>().join("$");
+            if table.get_or_insert_with(|| member_table.clone()) != &member_table {
+                return Err(anyhow!(
+                    "Members of index {} aren't in the same struct",
+                    self.index_name()
+                ));
+            }
+            columns.push(format!("external_{column}"));
+        }
+
+        let table = table.ok_or_else(|| anyhow!("Index {} has no member", self.index_name()))?;
+        Ok((table, columns))
+    }
+}
+
+impl Sql {
+    /// Sets the indexes declared in the config file, creating the ones of the models already
+    /// registered and dropping the ones no longer declared.
+    pub async fn set_model_indexes(&mut self, indexes: Vec<ModelIndexConfig>) -> Result<()> {
+        self.model_indexes = HashMap::new();
+        for index in indexes {
+            self.model_indexes.entry(index.model.clone()).or_default().push(index);
+        }
+
+        let declared = self
+            .model_indexes
+            .values()
+            .flatten()
+            .map(|index| index.index_name())
+            .collect::<HashSet<_>>();
+        let created: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM model_indexes").fetch_all(&self.pool).await?;
+        for name in created.into_iter().filter(|name| !declared.contains(name)) {
+            self.drop_model_index(&name)?;
+        }
+
+        for (model, indexes) in self.model_indexes.clone() {
+            // the indexes of models registered later are created along with their tables
+            let Ok(schema) = self.model(compute_selector_from_tag(&model)).await.map(|m| m.schema)
+            else {
+                continue;
+            };
+
+            for index in &indexes {
+                self.create_model_index(index, &schema).await?;
+            }
+        }
+
+        self.execute().await
+    }
+
+    /// Creates the indexes of a model being registered or upgraded. Invalid indexes are skipped,
+    /// since they can't prevent the model from being indexed.
+    pub(crate) async fn create_model_indexes(&mut self, model: &str, schema: &Ty) -> Result<()> {
+        let Some(indexes) = self.model_indexes.get(model).cloned() else {
+            return Ok(());
+        };
+
+        for index in &indexes {
+            if let Err(error) = self.create_model_index(index, schema).await {
+                warn!(target: LOG_TARGET, index = %index.index_name(), %error, "Skipping index.");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates an index, replacing the one of the same name if its definition changed. It is only
+    /// recorded once created, the condition of a partial index being checked by SQLite.
+    async fn create_model_index(&mut self, index: &ModelIndexConfig, schema: &Ty) -> Result<()> {
+        let (table, columns) = index.table_columns(schema)?;
+        let name = index.index_name();
+        let members = index.members.join(",");
+
+        let previous: Option<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT table_name, members, condition FROM model_indexes WHERE name = ?",
+        )
+        .bind(&name)
+        .fetch_optional(&self.pool)
+        .await?;
+        if previous.is_some_and(|previous| {
+            previous != (table.clone(), members.clone(), index.condition.clone())
+        }) {
+            self.drop_model_index(&name)?;
+        }
+
+        let mut statement =
+            format!("CREATE INDEX IF NOT EXISTS [{name}] ON [{table}] ({})", columns.join(", "));
+        if let Some(condition) = &index.condition {
+            statement.push_str(&format!(" WHERE {condition}"));
+        }
+        let (query, created) = QueryMessage::other_recv(statement, vec![]);
+        self.executor.send(query)?;
+        created.await??;
+
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO model_indexes (name, model_id, table_name, members, condition) VALUES \
+             (?, ?, ?, ?, ?) ON CONFLICT(name) DO UPDATE SET table_name=EXCLUDED.table_name, \
+             members=EXCLUDED.members, condition=EXCLUDED.condition"
+                .to_string(),
+            vec![
+                Argument::String(name),
+                Argument::String(format!("{:#x}", compute_selector_from_tag(&index.model))),
+                Argument::String(table),
+                Argument::String(members),
+                index.condition.clone().map_or(Argument::Null, Argument::String),
+            ],
+        ))?;
+
+        Ok(())
+    }
+
+    fn drop_model_index(&mut self, name: &str) -> Result<()> {
+        self.executor
+            .send(QueryMessage::other(format!("DROP INDEX IF EXISTS [{name}]"), vec![]))?;
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM model_indexes WHERE name = ?".to_string(),
+            vec![Argument::String(name.to_string())],
+        ))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Struct};
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty) -> Member {
+        Member { name: name.to_string(), ty, key: false }
+    }
+
+    fn index(members: &[&str]) -> ModelIndexConfig {
+        ModelIndexConfig {
+            model: "ns-Position".to_string(),
+            members: members.iter().map(|m| m.to_string()).collect(),
+            name: None,
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn test_index_table_columns() {
+        let vec2 = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(None))),
+                member("y", Ty::Primitive(Primitive::U32(None))),
+            ],
+        });
+        let schema = Ty::Struct(Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                member("owner", Ty::Primitive(Primitive::ContractAddress(None))),
+                member("vec", vec2),
+            ],
+        });
+
+        let (table, columns) = index(&["vec.x", "vec.y"]).table_columns(&schema).unwrap();
+        assert_eq!(table, "ns-Position$vec");
+        assert_eq!(columns, vec!["external_x", "external_y"]);
+        assert_eq!(index(&["vec.x", "vec.y"]).index_name(), "ns-Position.vec_x_vec_y");
+
+        assert!(index(&["owner", "vec.x"]).table_columns(&schema).is_err());
+        assert!(index(&["vec"]).table_columns(&schema).is_err());
+        assert!(index(&["missing"]).table_columns(&schema).is_err());
+        assert!(index(&[]).table_columns(&schema).is_err());
+    }
+}
@@ -20,6 +20,7 @@ use crate::executor::{
     Argument, DeleteEntityQuery, EventMessageQuery, QueryMessage, QueryType, ResetCursorsQuery,
     SetHeadQuery, UpdateCursorsQuery,
 };
+use crate::token_metadata::TokenMetadataRequest;
 use crate::types::ContractType;
 use crate::utils::utc_dt_string_from_timestamp;
 
@@ -30,14 +31,24 @@ pub const WORLD_CONTRACT_TYPE: &str = "WORLD";
 pub const FELT_DELIMITER: &str = "/";
 
 pub mod cache;
+pub mod custom;
 pub mod erc;
+pub mod history;
+pub mod indexes;
 pub mod query_queue;
+pub mod reorg;
+pub mod search;
 #[cfg(test)]
 #[path = "test.rs"]
 mod test;
 pub mod utils;
+pub mod world;
+pub mod world_scope;
 
 use cache::{LocalCache, Model, ModelCache};
+use history::EntityHistoryConfig;
+use indexes::ModelIndexConfig;
+use search::SearchConfig;
 
 #[derive(Debug, Clone)]
 pub struct Sql {
@@ -46,6 +57,10 @@ pub struct Sql {
     model_cache: Arc<ModelCache>,
     // when SQL struct is cloned a empty local_cache is created
     local_cache: LocalCache,
+    token_metadata_resolver: Option<UnboundedSender<TokenMetadataRequest>>,
+    entity_history: EntityHistoryConfig,
+    search: SearchConfig,
+    model_indexes: HashMap<String, Vec<ModelIndexConfig>>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,6 +95,10 @@ impl Sql {
             executor,
             model_cache: Arc::new(ModelCache::new(pool.clone())),
             local_cache,
+            token_metadata_resolver: None,
+            entity_history: EntityHistoryConfig::default(),
+            search: SearchConfig::default(),
+            model_indexes: HashMap::new(),
         };
 
         db.execute().await?;
@@ -87,6 +106,11 @@ impl Sql {
         Ok(db)
     }
 
+    /// Queues the ERC721 and ERC1155 tokens to the given resolver to fetch their metadata.
+    pub fn set_token_metadata_resolver(&mut self, resolver: UnboundedSender<TokenMetadataRequest>) {
+        self.token_metadata_resolver = Some(resolver);
+    }
+
     pub async fn head(&self, contract: Felt) -> Result<(u64, Option<Felt>, Option<Felt>)> {
         let indexer_query =
             sqlx::query_as::<_, (Option<i64>, Option<String>, Option<String>, String)>(
@@ -253,6 +277,78 @@ impl Sql {
     #[allow(clippy::too_many_arguments)]
     pub async fn register_model(
         &mut self,
+        world_address: Felt,
+        namespace: &str,
+        model: Ty,
+        layout: Layout,
+        class_hash: Felt,
+        contract_address: Felt,
+        packed_size: u32,
+        unpacked_size: u32,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        self.store_model(
+            world_address,
+            namespace,
+            model,
+            layout,
+            class_hash,
+            contract_address,
+            packed_size,
+            unpacked_size,
+            block_timestamp,
+            None,
+        )
+        .await
+    }
+
+    /// Upgrades an already registered model to its new schema.
+    ///
+    /// The new schema is diffed against the registered one: columns are added to the existing
+    /// tables for the new members, tables are created for the new nested members and
+    /// `model_members` is updated accordingly. Since the world only accepts backward compatible
+    /// upgrades, existing columns are left untouched.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upgrade_model(
+        &mut self,
+        world_address: Felt,
+        namespace: &str,
+        model: Ty,
+        layout: Layout,
+        class_hash: Felt,
+        contract_address: Felt,
+        packed_size: u32,
+        unpacked_size: u32,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let selector = compute_selector_from_names(namespace, &model.name());
+        let prev_schema = self.model(selector).await?.schema;
+
+        self.store_model(
+            world_address,
+            namespace,
+            model,
+            layout,
+            class_hash,
+            contract_address,
+            packed_size,
+            unpacked_size,
+            block_timestamp,
+            Some(&prev_schema),
+        )
+        .await
+    }
+
+    /// Rolls back a model upgraded after the fork point of a reorganization to `model`, the model
+    /// registered at the fork point.
+    ///
+    /// The columns and tables of the members added by the upgrade are dropped and `model_members`
+    /// is rebuilt from the schema of `model`. The enum columns keep the constraint of their
+    /// upgraded options, which SQLite can't alter.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn downgrade_model(
+        &mut self,
+        world_address: Felt,
         namespace: &str,
         model: Ty,
         layout: Layout,
@@ -261,9 +357,110 @@ impl Sql {
         packed_size: u32,
         unpacked_size: u32,
         block_timestamp: u64,
+    ) -> Result<()> {
+        let selector = compute_selector_from_names(namespace, &model.name());
+        let upgraded = self.model(selector).await?.schema;
+
+        let mut added = Vec::new();
+        added_members(vec![upgraded.name()], &upgraded, &model, &mut added);
+        for (table_id, name, ty) in added {
+            if matches!(ty, Ty::Primitive(_) | Ty::Enum(_) | Ty::ByteArray(_)) {
+                for statement in [
+                    format!("DROP INDEX IF EXISTS [idx_{table_id}_{name}]"),
+                    format!("ALTER TABLE [{table_id}] DROP COLUMN external_{name}"),
+                ] {
+                    self.executor.send(QueryMessage::other(statement, vec![]))?;
+                }
+            }
+
+            // the tables of the nested members, children first as they reference their parent
+            let prefix = format!("{table_id}${name}");
+            let mut tables: Vec<String> = sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND (name = ?1 OR \
+                 substr(name, 1, length(?1) + 1) = ?1 || '$')",
+            )
+            .bind(&prefix)
+            .fetch_all(&self.pool)
+            .await?;
+            tables.sort_by_key(|table| std::cmp::Reverse(table.len()));
+            for table in tables {
+                self.executor
+                    .send(QueryMessage::other(format!("DROP TABLE IF EXISTS [{table}]"), vec![]))?;
+            }
+        }
+
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM model_members WHERE model_id = ?".to_string(),
+            vec![Argument::FieldElement(selector)],
+        ))?;
+
+        self.store_model(
+            world_address,
+            namespace,
+            model,
+            layout,
+            class_hash,
+            contract_address,
+            packed_size,
+            unpacked_size,
+            block_timestamp,
+            None,
+        )
+        .await
+    }
+
+    /// Registers the model, or upgrades it if the schema of the previously registered model is
+    /// given.
+    ///
+    /// Models are identified by their selector, so the worlds registering a model in the same
+    /// namespace share its tables, their rows being keyed by world. Since they also share its
+    /// layout, a world registering or upgrading a model with another schema than the one of the
+    /// other worlds is rejected, and its records of the model aren't indexed.
+    #[allow(clippy::too_many_arguments)]
+    async fn store_model(
+        &mut self,
+        world_address: Felt,
+        namespace: &str,
+        model: Ty,
+        layout: Layout,
+        class_hash: Felt,
+        contract_address: Felt,
+        packed_size: u32,
+        unpacked_size: u32,
+        block_timestamp: u64,
+        prev_schema: Option<&Ty>,
     ) -> Result<()> {
         let selector = compute_selector_from_names(namespace, &model.name());
         let namespaced_name = format!("{}-{}", namespace, model.name());
+        // we need to update the name of the struct to include the namespace
+        let schema = Ty::Struct(Struct {
+            name: namespaced_name.clone(),
+            children: model.as_struct().unwrap().children.clone(),
+        });
+
+        let worlds = self.model_cache.worlds(&selector).await?;
+        if worlds.iter().any(|world| *world != world_address) {
+            let registered = self.model(selector).await?;
+            if registered.schema != schema || registered.layout != layout {
+                if worlds.contains(&world_address) {
+                    // the world upgraded the model, its records can't be decoded anymore
+                    self.executor.send(QueryMessage::other(
+                        "DELETE FROM world_models WHERE world_address = ? AND model_id = ?"
+                            .to_string(),
+                        vec![
+                            Argument::FieldElement(world_address),
+                            Argument::FieldElement(selector),
+                        ],
+                    ))?;
+                    self.model_cache.set_world(selector, world_address, false).await?;
+                }
+
+                return Err(anyhow!(
+                    "Model {namespaced_name} is registered by another world with a different \
+                     schema, its registration by world {world_address:#x} is rejected."
+                ));
+            }
+        }
 
         let insert_models =
             "INSERT INTO models (id, namespace, name, class_hash, contract_address, layout, \
@@ -271,7 +468,7 @@ impl Sql {
              CONFLICT(id) DO UPDATE SET contract_address=EXCLUDED.contract_address, \
              class_hash=EXCLUDED.class_hash, layout=EXCLUDED.layout, \
              packed_size=EXCLUDED.packed_size, unpacked_size=EXCLUDED.unpacked_size, \
-             executed_at=EXCLUDED.executed_at RETURNING *";
+             executed_at=EXCLUDED.executed_at";
         let arguments = vec![
             Argument::String(format!("{:#x}", selector)),
             Argument::String(namespace.to_string()),
@@ -283,9 +480,31 @@ impl Sql {
             Argument::Int(unpacked_size as i64),
             Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
         ];
+        self.executor.send(QueryMessage::other(insert_models.to_string(), arguments))?;
+
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO world_models (world_address, model_id, class_hash, contract_address, \
+             executed_at) VALUES (?, ?, ?, ?, ?) ON CONFLICT(world_address, model_id) DO UPDATE \
+             SET class_hash=EXCLUDED.class_hash, contract_address=EXCLUDED.contract_address, \
+             executed_at=EXCLUDED.executed_at"
+                .to_string(),
+            vec![
+                Argument::FieldElement(world_address),
+                Argument::FieldElement(selector),
+                Argument::FieldElement(class_hash),
+                Argument::FieldElement(contract_address),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+        ))?;
+        self.model_cache.set_world(selector, world_address, true).await?;
+
+        // the model is published as registered by this world
         self.executor.send(QueryMessage::new(
-            insert_models.to_string(),
-            arguments,
+            "SELECT m.id, m.namespace, m.name, wm.class_hash, wm.contract_address, \
+             m.transaction_hash, wm.world_address, m.executed_at, m.created_at FROM models m JOIN \
+             world_models wm ON wm.model_id = m.id WHERE m.id = ? AND wm.world_address = ?"
+                .to_string(),
+            vec![Argument::FieldElement(selector), Argument::FieldElement(world_address)],
             QueryType::RegisterModel,
         ))?;
 
@@ -298,7 +517,9 @@ impl Sql {
             block_timestamp,
             &mut 0,
             &mut 0,
+            prev_schema,
         )?;
+        self.create_model_indexes(&namespaced_name, &model).await?;
 
         // we set the model in the cache directly
         // because entities might be using it before the query queue is processed
@@ -314,11 +535,7 @@ impl Sql {
                     packed_size,
                     unpacked_size,
                     layout,
-                    // we need to update the name of the struct to include the namespace
-                    schema: Ty::Struct(Struct {
-                        name: namespaced_name,
-                        children: model.as_struct().unwrap().children.clone(),
-                    }),
+                    schema,
                 },
             )
             .await;
@@ -326,8 +543,12 @@ impl Sql {
         Ok(())
     }
 
+    /// Sets the model of an entity of `world_address`, `entity` being a possibly partial update
+    /// of the model.
+    #[allow(clippy::too_many_arguments)]
     pub async fn set_entity(
         &mut self,
+        world_address: Felt,
         entity: Ty,
         event_id: &str,
         block_timestamp: u64,
@@ -336,23 +557,26 @@ impl Sql {
         keys_str: Option<&str>,
     ) -> Result<()> {
         let namespaced_name = entity.name();
+        let history = self.is_historical_model(&model_id).then_some((entity_id, model_id));
 
+        let world = format!("{:#x}", world_address);
         let entity_id = format!("{:#x}", entity_id);
         let model_id = format!("{:#x}", model_id);
 
         let insert_entities = if keys_str.is_some() {
-            "INSERT INTO entities (id, event_id, executed_at, keys) VALUES (?, ?, ?, ?) ON \
-             CONFLICT(id) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
+            "INSERT INTO entities (id, world_address, event_id, executed_at, keys) VALUES (?, ?, \
+             ?, ?, ?) ON CONFLICT(id, world_address) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
              executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id, keys=EXCLUDED.keys \
              RETURNING *"
         } else {
-            "INSERT INTO entities (id, event_id, executed_at) VALUES (?, ?, ?) ON CONFLICT(id) DO \
-             UPDATE SET updated_at=CURRENT_TIMESTAMP, executed_at=EXCLUDED.executed_at, \
-             event_id=EXCLUDED.event_id RETURNING *"
+            "INSERT INTO entities (id, world_address, event_id, executed_at) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(id, world_address) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
+             executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id RETURNING *"
         };
 
         let mut arguments = vec![
             Argument::String(entity_id.clone()),
+            Argument::String(world.clone()),
             Argument::String(event_id.to_string()),
             Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
         ];
@@ -368,27 +592,46 @@ impl Sql {
         ))?;
 
         self.executor.send(QueryMessage::other(
-            "INSERT INTO entity_model (entity_id, model_id) VALUES (?, ?) ON CONFLICT(entity_id, \
-             model_id) DO NOTHING"
+            "INSERT INTO entity_model (entity_id, world_address, model_id) VALUES (?, ?, ?) ON \
+             CONFLICT(entity_id, world_address, model_id) DO NOTHING"
                 .to_string(),
-            vec![Argument::String(entity_id.clone()), Argument::String(model_id.clone())],
+            vec![
+                Argument::String(entity_id.clone()),
+                Argument::String(world.clone()),
+                Argument::String(model_id.clone()),
+            ],
         ))?;
 
         let path = vec![namespaced_name];
         self.build_set_entity_queries_recursive(
             path,
+            &world,
             event_id,
             (&entity_id, false),
             (&entity, keys_str.is_none()),
             block_timestamp,
             &vec![],
         )?;
+        self.index_search_members(&entity_id, &world, &entity)?;
+
+        if let Some((entity_id, model_id)) = history {
+            self.record_entity_history(
+                world_address,
+                entity_id,
+                model_id,
+                event_id,
+                block_timestamp,
+                Some(entity),
+            )
+            .await?;
+        }
 
         Ok(())
     }
 
     pub async fn set_event_message(
         &mut self,
+        world_address: Felt,
         entity: Ty,
         event_id: &str,
         block_timestamp: u64,
@@ -407,26 +650,30 @@ impl Sql {
         let namespaced_name = entity.name();
         let (model_namespace, model_name) = namespaced_name.split_once('-').unwrap();
 
+        let world = format!("{:#x}", world_address);
         let entity_id = format!("{:#x}", poseidon_hash_many(&keys));
         let model_id = format!("{:#x}", compute_selector_from_names(model_namespace, model_name));
 
         let keys_str = felts_to_sql_string(&keys);
         let block_timestamp_str = utc_dt_string_from_timestamp(block_timestamp);
 
-        let insert_entities = "INSERT INTO event_messages (id, keys, event_id, executed_at) \
-                               VALUES (?, ?, ?, ?) ON CONFLICT(id) DO UPDATE SET \
-                               updated_at=CURRENT_TIMESTAMP, executed_at=EXCLUDED.executed_at, \
-                               event_id=EXCLUDED.event_id RETURNING *";
+        let insert_entities = "INSERT INTO event_messages (id, world_address, keys, event_id, \
+                               executed_at) VALUES (?, ?, ?, ?, ?) ON CONFLICT(id, world_address) \
+                               DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
+                               executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id \
+                               RETURNING *";
         self.executor.send(QueryMessage::new(
             insert_entities.to_string(),
             vec![
                 Argument::String(entity_id.clone()),
+                Argument::String(world.clone()),
                 Argument::String(keys_str.clone()),
                 Argument::String(event_id.to_string()),
                 Argument::String(block_timestamp_str.clone()),
             ],
             QueryType::EventMessage(EventMessageQuery {
                 entity_id: entity_id.clone(),
+                world_address: world.clone(),
                 model_id: model_id.clone(),
                 keys_str: keys_str.clone(),
                 event_id: event_id.to_string(),
@@ -439,40 +686,66 @@ impl Sql {
         let path = vec![namespaced_name];
         self.build_set_entity_queries_recursive(
             path,
+            &world,
             event_id,
             (&entity_id, true),
             (&entity, false),
             block_timestamp,
             &vec![],
         )?;
+        self.index_search_members(&format!("event:{entity_id}"), &world, &entity)?;
 
         Ok(())
     }
 
     pub async fn delete_entity(
         &mut self,
+        world_address: Felt,
         entity_id: Felt,
         model_id: Felt,
         entity: Ty,
         event_id: &str,
         block_timestamp: u64,
     ) -> Result<()> {
+        let history = self.is_historical_model(&model_id).then_some(entity_id);
+        let world = format!("{:#x}", world_address);
         let entity_id = format!("{:#x}", entity_id);
         let path = vec![entity.name()];
         // delete entity models data
-        self.build_delete_entity_queries_recursive(path, &entity_id, &entity)?;
+        self.build_delete_entity_queries_recursive(path, &world, &entity_id, &entity)?;
+        self.remove_search_members(&entity_id, &world, &entity.name())?;
 
+        let model = format!("{:#x}", model_id);
         self.executor.send(QueryMessage::new(
-            "DELETE FROM entity_model WHERE entity_id = ? AND model_id = ?".to_string(),
-            vec![Argument::String(entity_id.clone()), Argument::String(format!("{:#x}", model_id))],
+            "DELETE FROM entity_model WHERE entity_id = ? AND world_address = ? AND model_id = ?"
+                .to_string(),
+            vec![
+                Argument::String(entity_id.clone()),
+                Argument::String(world.clone()),
+                Argument::String(model.clone()),
+            ],
             QueryType::DeleteEntity(DeleteEntityQuery {
                 entity_id: entity_id.clone(),
+                world_address: world.clone(),
+                model_id: model,
                 event_id: event_id.to_string(),
                 block_timestamp: utc_dt_string_from_timestamp(block_timestamp),
                 ty: entity.clone(),
             }),
         ))?;
 
+        if let Some(entity_id) = history {
+            self.record_entity_history(
+                world_address,
+                entity_id,
+                model_id,
+                event_id,
+                block_timestamp,
+                None,
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -527,11 +800,36 @@ impl Sql {
         self.model_cache.model(&selector).await.map_err(|e| e.into())
     }
 
-    pub async fn does_entity_exist(&self, model: String, key: Felt) -> Result<bool> {
-        let sql = format!("SELECT COUNT(*) FROM [{model}] WHERE id = ?");
+    /// Returns the model as registered by the world, failing if the world didn't register it or
+    /// if its registration was rejected.
+    pub async fn world_model(&self, world_address: Felt, selector: Felt) -> Result<Model> {
+        if !self.model_cache.worlds(&selector).await?.contains(&world_address) {
+            return Err(anyhow!(
+                "Model {selector:#x} is not registered by world {world_address:#x}."
+            ));
+        }
+
+        self.model(selector).await
+    }
+
+    /// Returns the worlds which registered the model.
+    pub async fn model_worlds(&self, selector: Felt) -> Result<Vec<Felt>> {
+        Ok(self.model_cache.worlds(&selector).await?.into_iter().collect())
+    }
 
-        let count: i64 =
-            sqlx::query_scalar(&sql).bind(format!("{:#x}", key)).fetch_one(&self.pool).await?;
+    pub async fn does_entity_exist(
+        &self,
+        world_address: Felt,
+        model: String,
+        key: Felt,
+    ) -> Result<bool> {
+        let sql = format!("SELECT COUNT(*) FROM [{model}] WHERE id = ? AND world_address = ?");
+
+        let count: i64 = sqlx::query_scalar(&sql)
+            .bind(format!("{:#x}", key))
+            .bind(format!("{:#x}", world_address))
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(count > 0)
     }
@@ -578,10 +876,10 @@ impl Sql {
                 _ => return Ok(()),
             };
 
-        self.executor.send(QueryMessage::other(
+        self.executor.send(QueryMessage::new(
             "INSERT OR IGNORE INTO transactions (id, transaction_hash, sender_address, calldata, \
              max_fee, signature, nonce, transaction_type, executed_at) VALUES (?, ?, ?, ?, ?, ?, \
-             ?, ?, ?)"
+             ?, ?, ?) RETURNING *"
                 .to_string(),
             vec![
                 id,
@@ -594,6 +892,7 @@ impl Sql {
                 Argument::String(transaction_type.to_string()),
                 Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
             ],
+            QueryType::StoreTransaction,
         ))?;
 
         Ok(())
@@ -610,13 +909,14 @@ impl Sql {
         let keys = Argument::String(felts_to_sql_string(&event.keys));
         let data = Argument::String(felts_to_sql_string(&event.data));
         let hash = Argument::FieldElement(transaction_hash);
+        let world_address = Argument::FieldElement(event.from_address);
         let executed_at = Argument::String(utc_dt_string_from_timestamp(block_timestamp));
 
         self.executor.send(QueryMessage::new(
-            "INSERT OR IGNORE INTO events (id, keys, data, transaction_hash, executed_at) VALUES \
-             (?, ?, ?, ?, ?) RETURNING *"
+            "INSERT OR IGNORE INTO events (id, keys, data, transaction_hash, world_address, \
+             executed_at) VALUES (?, ?, ?, ?, ?, ?) RETURNING *"
                 .to_string(),
-            vec![id, keys, data, hash, executed_at],
+            vec![id, keys, data, hash, world_address, executed_at],
             QueryType::StoreEvent,
         ))?;
 
@@ -633,6 +933,7 @@ impl Sql {
         block_timestamp: u64,
         array_idx: &mut usize,
         parent_array_idx: &mut usize,
+        prev_model: Option<&Ty>,
     ) -> Result<()> {
         if let Ty::Enum(e) = model {
             if e.options.iter().all(|o| if let Ty::Tuple(t) = &o.ty { t.is_empty() } else { false })
@@ -649,6 +950,7 @@ impl Sql {
             block_timestamp,
             *array_idx,
             *parent_array_idx,
+            prev_model,
         )?;
 
         let mut build_member = |pathname: &str, member: &Ty| -> Result<()> {
@@ -669,6 +971,7 @@ impl Sql {
                 block_timestamp,
                 &mut (*array_idx + if let Ty::Array(_) = member { 1 } else { 0 }),
                 &mut (*parent_array_idx + if let Ty::Array(_) = model { 1 } else { 0 }),
+                prev_model.and_then(|prev| member_ty(prev, pathname)),
             )?;
 
             Ok(())
@@ -701,9 +1004,11 @@ impl Sql {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_set_entity_queries_recursive(
         &mut self,
         path: Vec<String>,
+        world_address: &str,
         event_id: &str,
         // The id of the entity and if the entity is an event message
         entity_id: (&str, IsEventMessage),
@@ -721,6 +1026,7 @@ impl Sql {
             let table_id = path.join("$");
             let mut columns = vec![
                 "id".to_string(),
+                "world_address".to_string(),
                 "event_id".to_string(),
                 "executed_at".to_string(),
                 "updated_at".to_string(),
@@ -737,6 +1043,7 @@ impl Sql {
                 } else {
                     entity_id.to_string()
                 }),
+                Argument::String(world_address.to_string()),
                 Argument::String(event_id.to_string()),
                 Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
                 Argument::String(chrono::Utc::now().to_rfc3339()),
@@ -783,10 +1090,11 @@ impl Sql {
                 } else {
                     entity_id.to_string()
                 }));
+                arguments.push(Argument::String(world_address.to_string()));
 
                 // row has to exist. update it directly
                 format!(
-                    "UPDATE [{table_id}] SET {updates} WHERE id = ?",
+                    "UPDATE [{table_id}] SET {updates} WHERE id = ? AND world_address = ?",
                     table_id = table_id,
                     updates = columns
                         .iter()
@@ -817,6 +1125,7 @@ impl Sql {
                     path_clone.push(member.name.clone());
                     self.build_set_entity_queries_recursive(
                         path_clone,
+                        world_address,
                         event_id,
                         (entity_id, is_event_message),
                         (&member.ty, is_store_update_member),
@@ -853,6 +1162,7 @@ impl Sql {
                         path_clone.push(option.name.clone());
                         self.build_set_entity_queries_recursive(
                             path_clone,
+                            world_address,
                             event_id,
                             (entity_id, is_event_message),
                             (&option.ty, is_store_update_member),
@@ -882,6 +1192,7 @@ impl Sql {
                     path_clone.push(format!("_{}", idx));
                     self.build_set_entity_queries_recursive(
                         path_clone,
+                        world_address,
                         event_id,
                         (entity_id, is_event_message),
                         (member, is_store_update_member),
@@ -893,14 +1204,19 @@ impl Sql {
             Ty::Array(array) => {
                 // delete all previous array elements with the array indexes
                 let table_id = path.join("$");
-                let mut query =
-                    format!("DELETE FROM [{table_id}] WHERE entity_id = ? ", table_id = table_id);
+                let mut query = format!(
+                    "DELETE FROM [{table_id}] WHERE entity_id = ? AND world_address = ? ",
+                    table_id = table_id
+                );
                 for idx in 0..indexes.len() {
                     query.push_str(&format!("AND idx_{} = ? ", idx));
                 }
 
                 // flatten indexes with entity id
-                let mut arguments = vec![Argument::String(entity_id.to_string())];
+                let mut arguments = vec![
+                    Argument::String(entity_id.to_string()),
+                    Argument::String(world_address.to_string()),
+                ];
                 arguments.extend(indexes.iter().map(|idx| Argument::Int(*idx)));
 
                 self.executor.send(QueryMessage::other(query, arguments))?;
@@ -920,6 +1236,7 @@ impl Sql {
                     path_clone.push("data".to_string());
                     self.build_set_entity_queries_recursive(
                         path_clone,
+                        world_address,
                         event_id,
                         (entity_id, is_event_message),
                         (member, is_store_update_member),
@@ -937,21 +1254,31 @@ impl Sql {
     fn build_delete_entity_queries_recursive(
         &mut self,
         path: Vec<String>,
+        world_address: &str,
         entity_id: &str,
         entity: &Ty,
     ) -> Result<()> {
         match entity {
             Ty::Struct(s) => {
                 let table_id = path.join("$");
-                let statement = format!("DELETE FROM [{table_id}] WHERE entity_id = ?");
+                let statement =
+                    format!("DELETE FROM [{table_id}] WHERE entity_id = ? AND world_address = ?");
                 self.executor.send(QueryMessage::other(
                     statement,
-                    vec![Argument::String(entity_id.to_string())],
+                    vec![
+                        Argument::String(entity_id.to_string()),
+                        Argument::String(world_address.to_string()),
+                    ],
                 ))?;
                 for member in s.children.iter() {
                     let mut path_clone = path.clone();
                     path_clone.push(member.name.clone());
-                    self.build_delete_entity_queries_recursive(path_clone, entity_id, &member.ty)?;
+                    self.build_delete_entity_queries_recursive(
+                        path_clone,
+                        world_address,
+                        entity_id,
+                        &member.ty,
+                    )?;
                 }
             }
             Ty::Enum(e) => {
@@ -963,10 +1290,14 @@ impl Sql {
                 }
 
                 let table_id = path.join("$");
-                let statement = format!("DELETE FROM [{table_id}] WHERE entity_id = ?");
+                let statement =
+                    format!("DELETE FROM [{table_id}] WHERE entity_id = ? AND world_address = ?");
                 self.executor.send(QueryMessage::other(
                     statement,
-                    vec![Argument::String(entity_id.to_string())],
+                    vec![
+                        Argument::String(entity_id.to_string()),
+                        Argument::String(world_address.to_string()),
+                    ],
                 ))?;
 
                 for child in e.options.iter() {
@@ -978,35 +1309,58 @@ impl Sql {
 
                     let mut path_clone = path.clone();
                     path_clone.push(child.name.clone());
-                    self.build_delete_entity_queries_recursive(path_clone, entity_id, &child.ty)?;
+                    self.build_delete_entity_queries_recursive(
+                        path_clone,
+                        world_address,
+                        entity_id,
+                        &child.ty,
+                    )?;
                 }
             }
             Ty::Array(array) => {
                 let table_id = path.join("$");
-                let statement = format!("DELETE FROM [{table_id}] WHERE entity_id = ?");
+                let statement =
+                    format!("DELETE FROM [{table_id}] WHERE entity_id = ? AND world_address = ?");
                 self.executor.send(QueryMessage::other(
                     statement,
-                    vec![Argument::String(entity_id.to_string())],
+                    vec![
+                        Argument::String(entity_id.to_string()),
+                        Argument::String(world_address.to_string()),
+                    ],
                 ))?;
 
                 for member in array.iter() {
                     let mut path_clone = path.clone();
                     path_clone.push("data".to_string());
-                    self.build_delete_entity_queries_recursive(path_clone, entity_id, member)?;
+                    self.build_delete_entity_queries_recursive(
+                        path_clone,
+                        world_address,
+                        entity_id,
+                        member,
+                    )?;
                 }
             }
             Ty::Tuple(t) => {
                 let table_id = path.join("$");
-                let statement = format!("DELETE FROM [{table_id}] WHERE entity_id = ?");
+                let statement =
+                    format!("DELETE FROM [{table_id}] WHERE entity_id = ? AND world_address = ?");
                 self.executor.send(QueryMessage::other(
                     statement,
-                    vec![Argument::String(entity_id.to_string())],
+                    vec![
+                        Argument::String(entity_id.to_string()),
+                        Argument::String(world_address.to_string()),
+                    ],
                 ))?;
 
                 for (idx, member) in t.iter().enumerate() {
                     let mut path_clone = path.clone();
                     path_clone.push(format!("_{}", idx));
-                    self.build_delete_entity_queries_recursive(path_clone, entity_id, member)?;
+                    self.build_delete_entity_queries_recursive(
+                        path_clone,
+                        world_address,
+                        entity_id,
+                        member,
+                    )?;
                 }
             }
             _ => {}
@@ -1025,13 +1379,33 @@ impl Sql {
         block_timestamp: u64,
         array_idx: usize,
         parent_array_idx: usize,
+        prev_model: Option<&Ty>,
     ) -> Result<()> {
         let table_id = path.join("$");
         let mut indices = Vec::new();
+        // columns added to an already existing table, when upgrading a model
+        let mut alter_table_queries = Vec::new();
+        // whether the existing table has to be rebuilt, to change the constraint of a column
+        let mut rebuild_table = false;
+        let prev_members = prev_model.map(member_names);
+
+        // when upgrading, the members of the existing tables are updated to their new type
+        let insert_member_statement = if prev_model.is_some() {
+            "INSERT INTO model_members (id, model_id, model_idx, member_idx, name, type, \
+             type_enum, enum_options, key, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON \
+             CONFLICT(id, member_idx) DO UPDATE SET name=EXCLUDED.name, type=EXCLUDED.type, \
+             type_enum=EXCLUDED.type_enum, enum_options=EXCLUDED.enum_options, key=EXCLUDED.key, \
+             executed_at=EXCLUDED.executed_at"
+        } else {
+            // NOTE: this might cause some errors to fail silently
+            // due to the ignore clause. check migrations for type_enum check
+            "INSERT OR IGNORE INTO model_members (id, model_id, model_idx, member_idx, name, type, \
+             type_enum, enum_options, key, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        };
 
         let mut create_table_query = format!(
-            "CREATE TABLE IF NOT EXISTS [{table_id}] (id TEXT NOT NULL, event_id TEXT NOT NULL, \
-             entity_id TEXT, event_message_id TEXT, "
+            "CREATE TABLE IF NOT EXISTS [{table_id}] (id TEXT NOT NULL, world_address TEXT NOT \
+             NULL, event_id TEXT NOT NULL, entity_id TEXT, event_message_id TEXT, "
         );
 
         if array_idx > 0 {
@@ -1040,14 +1414,26 @@ impl Sql {
                 create_table_query.push_str(&format!("idx_{i} INTEGER NOT NULL, ", i = i));
             }
 
-            // full array id column
-            create_table_query.push_str("full_array_id TEXT NOT NULL UNIQUE, ");
+            // full array id column, unique along with the world
+            create_table_query.push_str("full_array_id TEXT NOT NULL, ");
         }
 
         let mut build_member = |name: &str, ty: &Ty, options: &mut Option<Argument>| {
+            let is_new_member = prev_members
+                .as_ref()
+                .is_some_and(|members| !members.iter().any(|member| member == name));
+            // new columns of an existing table can't be NOT NULL as the existing rows have no value
+            let mut add_column = |column: String| {
+                if is_new_member {
+                    alter_table_queries
+                        .push(format!("ALTER TABLE [{table_id}] ADD COLUMN {column};"));
+                }
+            };
+
             if let Ok(cairo_type) = Primitive::from_str(&ty.name()) {
                 create_table_query
                     .push_str(&format!("external_{name} {}, ", cairo_type.to_sql_type()));
+                add_column(format!("external_{name} {}", cairo_type.to_sql_type()));
                 indices.push(format!(
                     "CREATE INDEX IF NOT EXISTS [idx_{table_id}_{name}] ON [{table_id}] \
                      (external_{name});"
@@ -1066,6 +1452,16 @@ impl Sql {
 
                 // if we're an array, we could have multiple enum options
                 create_table_query.push_str(if array_idx > 0 { ", " } else { "NOT NULL, " });
+                add_column(format!(
+                    "external_{name} TEXT CHECK(external_{name} IN ({all_options}))"
+                ));
+
+                // SQLite can't alter the constraint of an existing column
+                if let Some(Ty::Enum(prev)) = prev_model.and_then(|prev| member_ty(prev, name)) {
+                    if prev.options.iter().map(|o| &o.name).ne(e.options.iter().map(|o| &o.name)) {
+                        rebuild_table = true;
+                    }
+                }
 
                 indices.push(format!(
                     "CREATE INDEX IF NOT EXISTS [idx_{table_id}_{name}] ON [{table_id}] \
@@ -1082,6 +1478,7 @@ impl Sql {
                 ));
             } else if let Ty::ByteArray(_) = &ty {
                 create_table_query.push_str(&format!("external_{name} TEXT, "));
+                add_column(format!("external_{name} TEXT"));
                 indices.push(format!(
                     "CREATE INDEX IF NOT EXISTS [idx_{table_id}_{name}] ON [{table_id}] \
                      (external_{name});"
@@ -1097,12 +1494,6 @@ impl Sql {
 
                     build_member(&name, &member.ty, &mut options);
 
-                    // NOTE: this might cause some errors to fail silently
-                    // due to the ignore clause. check migrations for type_enum check
-                    let statement = "INSERT OR IGNORE INTO model_members (id, model_id, \
-                                     model_idx, member_idx, name, type, type_enum, enum_options, \
-                                     key, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
-
                     let arguments = vec![
                         Argument::String(table_id.clone()),
                         // TEMP: this is temporary until the model hash is precomputed
@@ -1117,7 +1508,10 @@ impl Sql {
                         Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
                     ];
 
-                    self.executor.send(QueryMessage::other(statement.to_string(), arguments))?;
+                    self.executor.send(QueryMessage::other(
+                        insert_member_statement.to_string(),
+                        arguments,
+                    ))?;
                 }
             }
             Ty::Tuple(tuple) => {
@@ -1126,9 +1520,6 @@ impl Sql {
 
                     build_member(&format!("_{}", idx), member, &mut options);
 
-                    let statement = "INSERT OR IGNORE INTO model_members (id, model_id, \
-                                     model_idx, member_idx, name, type, type_enum, enum_options, \
-                                     key, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
                     let arguments = vec![
                         Argument::String(table_id.clone()),
                         // TEMP: this is temporary until the model hash is precomputed
@@ -1145,7 +1536,10 @@ impl Sql {
                         Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
                     ];
 
-                    self.executor.send(QueryMessage::other(statement.to_string(), arguments))?;
+                    self.executor.send(QueryMessage::other(
+                        insert_member_statement.to_string(),
+                        arguments,
+                    ))?;
                 }
             }
             Ty::Array(array) => {
@@ -1153,9 +1547,6 @@ impl Sql {
                 let ty = &array[0];
                 build_member("data", ty, &mut options);
 
-                let statement = "INSERT OR IGNORE INTO model_members (id, model_id, model_idx, \
-                                 member_idx, name, type, type_enum, enum_options, key, \
-                                 executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
                 let arguments = vec![
                     Argument::String(table_id.clone()),
                     // TEMP: this is temporary until the model hash is precomputed
@@ -1170,7 +1561,8 @@ impl Sql {
                     Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
                 ];
 
-                self.executor.send(QueryMessage::other(statement.to_string(), arguments))?;
+                self.executor
+                    .send(QueryMessage::other(insert_member_statement.to_string(), arguments))?;
             }
             Ty::Enum(e) => {
                 for (idx, child) in e
@@ -1192,9 +1584,6 @@ impl Sql {
                     let mut options = None; // TEMP: doesnt support complex enums yet
                     build_member(&child.name, &child.ty, &mut options);
 
-                    let statement = "INSERT OR IGNORE INTO model_members (id, model_id, \
-                                     model_idx, member_idx, name, type, type_enum, enum_options, \
-                                     key, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
                     let arguments = vec![
                         Argument::String(table_id.clone()),
                         // TEMP: this is temporary until the model hash is precomputed
@@ -1209,7 +1598,10 @@ impl Sql {
                         Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
                     ];
 
-                    self.executor.send(QueryMessage::other(statement.to_string(), arguments))?;
+                    self.executor.send(QueryMessage::other(
+                        insert_member_statement.to_string(),
+                        arguments,
+                    ))?;
                 }
             }
             _ => {}
@@ -1223,12 +1615,12 @@ impl Sql {
         if path.len() > 1 {
             let parent_table_id = path[..path.len() - 1].join("$");
 
-            create_table_query.push_str("FOREIGN KEY (id");
+            create_table_query.push_str("FOREIGN KEY (id, world_address");
             for i in 0..parent_array_idx {
                 create_table_query.push_str(&format!(", idx_{i}", i = i));
             }
             create_table_query.push_str(&format!(
-                ") REFERENCES [{parent_table_id}] (id",
+                ") REFERENCES [{parent_table_id}] (id, world_address",
                 parent_table_id = parent_table_id
             ));
             for i in 0..parent_array_idx {
@@ -1237,18 +1629,39 @@ impl Sql {
             create_table_query.push_str(") ON DELETE CASCADE, ");
         };
 
-        create_table_query.push_str("PRIMARY KEY (id");
+        if array_idx > 0 {
+            create_table_query.push_str("UNIQUE (full_array_id, world_address), ");
+        }
+
+        create_table_query.push_str("PRIMARY KEY (id, world_address");
         for i in 0..array_idx {
             create_table_query.push_str(&format!(", idx_{i}", i = i));
         }
         create_table_query.push_str("), ");
 
-        create_table_query.push_str("FOREIGN KEY (entity_id) REFERENCES entities(id), ");
+        create_table_query.push_str(
+            "FOREIGN KEY (entity_id, world_address) REFERENCES entities(id, world_address), ",
+        );
         // create_table_query.push_str("FOREIGN KEY (event_id) REFERENCES events(id), ");
-        create_table_query
-            .push_str("FOREIGN KEY (event_message_id) REFERENCES event_messages(id));");
+        create_table_query.push_str(
+            "FOREIGN KEY (event_message_id, world_address) REFERENCES event_messages(id, \
+             world_address));",
+        );
 
-        self.executor.send(QueryMessage::other(create_table_query, vec![]))?;
+        if rebuild_table {
+            // the new table already has the columns of the new members
+            self.executor.send(QueryMessage::new(
+                create_table_query,
+                vec![],
+                QueryType::RebuildTable(table_id),
+            ))?;
+        } else {
+            self.executor.send(QueryMessage::other(create_table_query, vec![]))?;
+
+            for s in alter_table_queries.iter() {
+                self.executor.send(QueryMessage::other(s.to_string(), vec![]))?;
+            }
+        }
 
         for s in indices.iter() {
             self.executor.send(QueryMessage::other(s.to_string(), vec![]))?;
@@ -1263,3 +1676,54 @@ impl Sql {
         recv.await?
     }
 }
+
+/// Returns the names of the members stored as columns in the table of the given type.
+fn member_names(ty: &Ty) -> Vec<String> {
+    match ty {
+        Ty::Struct(s) => s.children.iter().map(|m| m.name.clone()).collect(),
+        Ty::Tuple(t) => (0..t.len()).map(|idx| format!("_{idx}")).collect(),
+        Ty::Array(_) => vec!["data".to_string()],
+        Ty::Enum(e) => {
+            e.options.iter().map(|o| o.name.clone()).chain(["option".to_string()]).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Collects the members of `ty` missing from `prev`, with the table storing them, recursing into
+/// the members both have.
+fn added_members<'a>(
+    path: Vec<String>,
+    ty: &'a Ty,
+    prev: &Ty,
+    added: &mut Vec<(String, String, &'a Ty)>,
+) {
+    for name in member_names(ty) {
+        // the option of an enum is the enum itself
+        let Some(member) = member_ty(ty, &name).filter(|_| name != "option") else {
+            continue;
+        };
+
+        match member_ty(prev, &name) {
+            Some(_) if matches!(member, Ty::Primitive(_) | Ty::ByteArray(_)) => {}
+            Some(prev_member) => {
+                let mut path = path.clone();
+                path.push(name);
+                added_members(path, member, prev_member, added);
+            }
+            None => added.push((path.join("$"), name, member)),
+        }
+    }
+}
+
+/// Returns the type of the member with the given name, as named by [`member_names`].
+fn member_ty<'a>(ty: &'a Ty, name: &str) -> Option<&'a Ty> {
+    match ty {
+        Ty::Struct(s) => s.children.iter().find(|m| m.name == name).map(|m| &m.ty),
+        Ty::Tuple(t) => name.strip_prefix('_').and_then(|idx| t.get(idx.parse::<usize>().ok()?)),
+        Ty::Array(a) if name == "data" => a.first(),
+        Ty::Enum(_) if name == "option" => Some(ty),
+        Ty::Enum(e) => e.options.iter().find(|o| o.name == name).map(|o| &o.ty),
+        _ => None,
+    }
+}
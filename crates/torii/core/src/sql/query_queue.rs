@@ -107,11 +107,12 @@ impl QueryQueue {
 
                     let row = sqlx::query(
                         "UPDATE entities SET updated_at=CURRENT_TIMESTAMP, executed_at=?, \
-                         event_id=? WHERE id = ? RETURNING *",
+                         event_id=? WHERE id = ? AND world_address = ? RETURNING *",
                     )
                     .bind(entity.block_timestamp)
                     .bind(entity.event_id)
-                    .bind(entity.entity_id)
+                    .bind(&entity.entity_id)
+                    .bind(&entity.world_address)
                     .fetch_one(&mut *tx)
                     .await?;
                     let mut entity_updated = EntityUpdated::from_row(&row)?;
@@ -119,16 +120,19 @@ impl QueryQueue {
                         Some(Ty::Struct(Struct { name: entity.ty.name(), children: vec![] }));
 
                     let count = sqlx::query_scalar::<_, i64>(
-                        "SELECT count(*) FROM entity_model WHERE entity_id = ?",
+                        "SELECT count(*) FROM entity_model WHERE entity_id = ? AND world_address \
+                         = ?",
                     )
-                    .bind(entity_updated.id.clone())
+                    .bind(&entity.entity_id)
+                    .bind(&entity.world_address)
                     .fetch_one(&mut *tx)
                     .await?;
 
                     // Delete entity if all of its models are deleted
                     if count == 0 {
-                        sqlx::query("DELETE FROM entities WHERE id = ?")
-                            .bind(entity_updated.id.clone())
+                        sqlx::query("DELETE FROM entities WHERE id = ? AND world_address = ?")
+                            .bind(&entity.entity_id)
+                            .bind(&entity.world_address)
                             .execute(&mut *tx)
                             .await?;
                         entity_updated.deleted = true;
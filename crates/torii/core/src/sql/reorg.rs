@@ -0,0 +1,664 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use cainome::cairo_serde::{CairoSerde, ContractAddress};
+use dojo_world::contracts::abigen::model::Layout;
+use dojo_world::contracts::abigen::world::{Layout as WorldLayout, ModelIndex, Resource};
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::{BlockId, Felt};
+use starknet::providers::Provider;
+use tracing::{debug, warn};
+
+use super::utils::{
+    felt_to_sql_string, felts_to_sql_string, sql_string_to_felts, sql_string_to_u256, I256,
+};
+use super::{Sql, FELT_DELIMITER};
+use crate::executor::{Argument, QueryMessage, QueryType, RollbackQuery};
+use crate::types::{ContractType, PermissionKind};
+use crate::utils::utc_dt_string_from_timestamp;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::sql::reorg";
+
+/// Number of indexed block hashes kept to look for the fork point of a reorganization.
+pub const MAX_STORED_BLOCKS: i64 = 256;
+
+impl Sql {
+    /// Records the hash of an indexed block, only keeping the [`MAX_STORED_BLOCKS`] most recent
+    /// ones, along with the tombstones of the entities deleted in them.
+    pub fn set_block_hash(&mut self, block_number: u64, block_hash: Felt) -> Result<()> {
+        let oldest_event_id =
+            format!("{:#064x}", block_number.saturating_sub(MAX_STORED_BLOCKS as u64 - 1));
+
+        let block_number = Argument::Int(
+            block_number
+                .try_into()
+                .map_err(|_| anyhow!("Block number {} doesn't fit in i64", block_number))?,
+        );
+
+        self.executor.send(QueryMessage::other(
+            "INSERT OR REPLACE INTO blocks (number, hash) VALUES (?, ?)".to_string(),
+            vec![block_number, Argument::FieldElement(block_hash)],
+        ))?;
+
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM blocks WHERE number NOT IN (SELECT number FROM blocks ORDER BY number \
+             DESC LIMIT ?)"
+                .to_string(),
+            vec![Argument::Int(MAX_STORED_BLOCKS)],
+        ))?;
+
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM deleted_entities WHERE event_id < ?".to_string(),
+            vec![Argument::String(oldest_event_id)],
+        ))?;
+
+        Ok(())
+    }
+
+    pub async fn block_hash(&self, block_number: u64) -> Result<Option<Felt>> {
+        let block_number: i64 = block_number
+            .try_into()
+            .map_err(|_| anyhow!("Block number {} doesn't fit in i64", block_number))?;
+
+        let hash: Option<String> = sqlx::query_scalar("SELECT hash FROM blocks WHERE number = ?")
+            .bind(block_number)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(hash.map(|hash| Felt::from_str(&hash)).transpose()?)
+    }
+
+    /// Returns the recorded block hashes, most recent first.
+    pub async fn block_hashes(&self) -> Result<Vec<(u64, Felt)>> {
+        let blocks: Vec<(i64, String)> =
+            sqlx::query_as("SELECT number, hash FROM blocks ORDER BY number DESC")
+                .fetch_all(&self.pool)
+                .await?;
+
+        blocks
+            .into_iter()
+            .map(|(number, hash)| Ok((number.try_into()?, Felt::from_str(&hash)?)))
+            .collect()
+    }
+
+    /// Discards everything indexed after `block_number`, which is the last block shared with the
+    /// canonical chain, and moves the head of every contract back to it.
+    ///
+    /// Events, transactions, entity versions and ERC transfers are deleted, and the balances they
+    /// affected are restored. The models of the entities updated after the fork point, and those
+    /// they were deleted from since then, are reset to their state at `block_number`, read from
+    /// the world contract, or deleted if they didn't exist yet. The models upgraded after the fork
+    /// point are rolled back to their schema at `block_number` first. Event messages are reset
+    /// to their last version before the fork point, when their model keeps one. The world
+    /// resources (models, namespaces, contracts and permissions) changed after the fork point
+    /// are read back from the worlds.
+    pub async fn rollback<P: Provider + Sync>(
+        &mut self,
+        worlds: &HashMap<Felt, Arc<WorldContractReader<P>>>,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        // Event ids are prefixed by their zero padded block number, so everything indexed after
+        // the fork point sorts after this one.
+        let fork_event_id = format!("{:#064x}", block_number + 1);
+
+        self.rollback_erc_transfers(&fork_event_id).await?;
+        // the entities are read back with the models of the fork point
+        self.rollback_model_upgrades(worlds, block_number, block_timestamp).await?;
+        self.rollback_entities(worlds, &fork_event_id, block_number, block_timestamp).await?;
+        self.rollback_event_messages(&fork_event_id, block_timestamp).await?;
+        self.rollback_world_resources(worlds, block_number, block_timestamp).await?;
+
+        let custom_event_tables: Vec<(String,)> =
+            sqlx::query_as("SELECT name FROM custom_event_tables").fetch_all(&self.pool).await?;
+        for (table,) in custom_event_tables {
+            self.executor.send(QueryMessage::other(
+                format!("DELETE FROM [{table}] WHERE id >= ?"),
+                vec![Argument::String(fork_event_id.clone())],
+            ))?;
+        }
+
+        // Transaction ids are prefixed by their block number as well.
+        for statement in [
+            "DELETE FROM events WHERE id >= ?",
+            "DELETE FROM transactions WHERE id >= ?",
+            "DELETE FROM entities_historical WHERE event_id >= ?",
+            "DELETE FROM deleted_entities WHERE event_id >= ?",
+            "DELETE FROM event_messages_historical WHERE event_id >= ?",
+        ] {
+            self.executor.send(QueryMessage::other(
+                statement.to_string(),
+                vec![Argument::String(fork_event_id.clone())],
+            ))?;
+        }
+
+        self.executor.send(QueryMessage::new(
+            "".to_string(),
+            vec![],
+            QueryType::Rollback(RollbackQuery { block_number }),
+        ))?;
+
+        Ok(())
+    }
+
+    async fn rollback_erc_transfers(&mut self, fork_event_id: &str) -> Result<()> {
+        let transfers: Vec<(String, String, String, String, String, String)> = sqlx::query_as(
+            "SELECT et.contract_address, et.from_address, et.to_address, et.amount, et.token_id, \
+             c.contract_type FROM erc_transfers et JOIN contracts c ON et.contract_address = \
+             c.contract_address WHERE et.id >= ?",
+        )
+        .bind(fork_event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        debug!(target: LOG_TARGET, count = transfers.len(), "Reverting ERC transfers.");
+
+        let zero = felt_to_sql_string(&Felt::ZERO);
+        for (contract_address, from_address, to_address, amount, token_id, contract_type) in
+            transfers
+        {
+            let amount = I256::from(sql_string_to_u256(&amount));
+
+            // ERC721 and ERC1155 token ids are `contract_address:id`, ERC20 ones are the contract
+            // address.
+            let contract_type = ContractType::from_str(&contract_type)?;
+            let balance_id = |account: &str| {
+                if contract_type == ContractType::ERC20 {
+                    format!("{account}{FELT_DELIMITER}{contract_address}{FELT_DELIMITER}")
+                } else {
+                    format!("{account}{FELT_DELIMITER}{token_id}")
+                }
+            };
+
+            if from_address != zero {
+                let from_balance = self
+                    .local_cache
+                    .erc_cache
+                    .entry((contract_type, balance_id(&from_address)))
+                    .or_default();
+                *from_balance += amount;
+            }
+
+            if to_address != zero {
+                let to_balance = self
+                    .local_cache
+                    .erc_cache
+                    .entry((contract_type, balance_id(&to_address)))
+                    .or_default();
+                *to_balance -= amount;
+            }
+        }
+
+        self.apply_cache_diff().await?;
+
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM erc_transfers WHERE id >= ?".to_string(),
+            vec![Argument::String(fork_event_id.to_string())],
+        ))?;
+
+        Ok(())
+    }
+
+    async fn rollback_entities<P: Provider + Sync>(
+        &mut self,
+        worlds: &HashMap<Felt, Arc<WorldContractReader<P>>>,
+        fork_event_id: &str,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        // The models of the entities updated after the fork point, along with the models they
+        // were deleted from since then, read from their tombstones.
+        let rows: Vec<(String, String, String, String)> = sqlx::query_as(
+            "SELECT e.id, e.world_address, e.keys, em.model_id FROM entities e JOIN entity_model \
+             em ON em.entity_id = e.id AND em.world_address = e.world_address WHERE e.event_id >= \
+             ?1 UNION SELECT id, world_address, keys, model_id FROM deleted_entities WHERE \
+             event_id >= ?1",
+        )
+        .bind(fork_event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut entities: HashMap<(String, String), (String, Vec<String>)> = HashMap::new();
+        for (entity_id, world_address, keys, model_id) in rows {
+            entities
+                .entry((entity_id, world_address))
+                .or_insert_with(|| (keys, Vec::new()))
+                .1
+                .push(model_id);
+        }
+
+        // Models registered after the fork point can't be read at that block, their records are
+        // simply deleted.
+        let world_models: Vec<(String, String, bool)> =
+            sqlx::query_as("SELECT world_address, model_id, executed_at > ? FROM world_models")
+                .bind(utc_dt_string_from_timestamp(block_timestamp))
+                .fetch_all(&self.pool)
+                .await?;
+        let registered_after_fork: HashMap<(String, String), bool> = world_models
+            .into_iter()
+            .map(|(world_address, model_id, after_fork)| ((world_address, model_id), after_fork))
+            .collect();
+
+        debug!(target: LOG_TARGET, count = entities.len(), "Restoring entities.");
+
+        // Restored entities are attributed to the fork point, before any re-indexed event.
+        let event_id = format!("{:#064x}:{:#x}:{:#04x}", block_number, Felt::ZERO, 0);
+
+        for ((entity_id, world_address_str), (keys_str, model_ids)) in entities {
+            let world_address = Felt::from_str(&world_address_str)?;
+            // Entities of the worlds which are no longer indexed can't be restored.
+            let Some(world) = worlds.get(&world_address) else {
+                continue;
+            };
+
+            let keys = keys_str
+                .split(FELT_DELIMITER)
+                .filter(|key| !key.is_empty())
+                .map(Felt::from_str)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if keys.is_empty() {
+                warn!(target: LOG_TARGET, entity_id = %entity_id, "Entity without keys can't be restored.");
+                continue;
+            }
+
+            let entity_id = Felt::from_str(&entity_id)?;
+
+            for model_id in model_ids {
+                // The model isn't registered by the world anymore.
+                let Some(after_fork) =
+                    registered_after_fork.get(&(world_address_str.clone(), model_id.clone()))
+                else {
+                    continue;
+                };
+                let model = self.model(Felt::from_str(&model_id)?).await?;
+
+                let values = if *after_fork {
+                    vec![]
+                } else {
+                    // The layout of the model ABI and the world ABI are the same type generated
+                    // twice.
+                    let layout =
+                        WorldLayout::cairo_deserialize(&Layout::cairo_serialize(&model.layout), 0)?;
+                    world
+                        .entity(&model.selector, &ModelIndex::Keys(keys.clone()), &layout)
+                        .block_id(BlockId::Number(block_number))
+                        .call()
+                        .await?
+                };
+
+                // Deleted records are zeroed in the world storage.
+                if values.iter().all(|value| *value == Felt::ZERO) {
+                    let entity = model.schema.clone();
+                    self.delete_entity(
+                        world_address,
+                        entity_id,
+                        model.selector,
+                        entity,
+                        &event_id,
+                        block_timestamp,
+                    )
+                    .await?;
+                } else {
+                    let mut entity = model.schema.clone();
+                    entity.deserialize(&mut [keys.clone(), values].concat())?;
+                    self.set_entity(
+                        world_address,
+                        entity,
+                        &event_id,
+                        block_timestamp,
+                        entity_id,
+                        model.selector,
+                        Some(&keys_str),
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn rollback_event_messages(
+        &mut self,
+        fork_event_id: &str,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let event_messages: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT em.id, em.world_address, m.model_id FROM event_messages em JOIN event_model m \
+             ON m.entity_id = em.id AND m.world_address = em.world_address WHERE em.event_id >= ?",
+        )
+        .bind(fork_event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        debug!(target: LOG_TARGET, count = event_messages.len(), "Restoring event messages.");
+
+        for (id, world_address, model_id) in event_messages {
+            let model = self.model(Felt::from_str(&model_id)?).await?;
+
+            // The versions are only kept for the historical models.
+            let version: Option<(String, String)> = sqlx::query_as(
+                "SELECT event_id, data FROM event_messages_historical WHERE id = ? AND \
+                 world_address = ? AND model_id = ? AND event_id < ? ORDER BY event_id DESC LIMIT \
+                 1",
+            )
+            .bind(&id)
+            .bind(&world_address)
+            .bind(&model_id)
+            .bind(fork_event_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            match version {
+                Some((event_id, data)) => {
+                    let mut entity = model.schema.clone();
+                    entity.deserialize(&mut sql_string_to_felts(&data))?;
+                    self.set_event_message(
+                        Felt::from_str(&world_address)?,
+                        entity,
+                        &event_id,
+                        block_timestamp,
+                        false,
+                    )
+                    .await?;
+                }
+                None => {
+                    let table = model.schema.name();
+                    // The row is shared with the entity of the same id, if any. Otherwise the
+                    // rows of the nested members are deleted along by cascade.
+                    for statement in [
+                        format!(
+                            "DELETE FROM [{table}] WHERE event_message_id = ? AND world_address = \
+                             ? AND entity_id IS NULL"
+                        ),
+                        format!(
+                            "UPDATE [{table}] SET event_message_id = NULL WHERE event_message_id \
+                             = ? AND world_address = ?"
+                        ),
+                    ] {
+                        self.executor.send(QueryMessage::other(
+                            statement,
+                            vec![
+                                Argument::String(id.clone()),
+                                Argument::String(world_address.clone()),
+                            ],
+                        ))?;
+                    }
+                    self.remove_search_members(&format!("event:{id}"), &world_address, &table)?;
+                    self.executor.send(QueryMessage::other(
+                        "DELETE FROM event_model WHERE entity_id = ? AND world_address = ? AND \
+                         model_id = ?"
+                            .to_string(),
+                        vec![
+                            Argument::String(id),
+                            Argument::String(world_address),
+                            Argument::String(model_id),
+                        ],
+                    ))?;
+                }
+            }
+        }
+
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM event_messages WHERE event_id >= ? AND NOT EXISTS (SELECT 1 FROM \
+             event_model WHERE entity_id = event_messages.id AND world_address = \
+             event_messages.world_address)"
+                .to_string(),
+            vec![Argument::String(fork_event_id.to_string())],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Rolls back the models upgraded since the fork block to their schema and layout at the fork
+    /// block, read from the model contracts the worlds held then.
+    async fn rollback_model_upgrades<P: Provider + Sync>(
+        &mut self,
+        worlds: &HashMap<Felt, Arc<WorldContractReader<P>>>,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let block_id = BlockId::Number(block_number);
+
+        let world_models: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT world_address, model_id, contract_address FROM world_models WHERE executed_at \
+             >= ?",
+        )
+        .bind(utc_dt_string_from_timestamp(block_timestamp))
+        .fetch_all(&self.pool)
+        .await?;
+        for (world_address, model_id, contract_address) in world_models {
+            let Some(world) = worlds.get(&Felt::from_str(&world_address)?) else {
+                continue;
+            };
+            let selector = Felt::from_str(&model_id)?;
+
+            // models registered after the fork block are removed along with the other resources
+            let (Resource::Model((address, _)) | Resource::Event((address, _))) =
+                world.resource(&selector).block_id(block_id).call().await?
+            else {
+                continue;
+            };
+            if address.0 == Felt::from_str(&contract_address)? {
+                continue;
+            }
+
+            let model = self.model(selector).await?;
+            let world_at_fork =
+                WorldContractReader::new(world.address, world.provider()).with_block(block_id);
+            let reader = world_at_fork.model_reader(&model.namespace, &model.name).await?;
+            let schema = reader.schema().await?;
+            let layout = reader.layout().await?;
+            let packed_size = reader.packed_size().await?;
+            let unpacked_size = reader.unpacked_size().await?;
+            let class_hash = world.provider().get_class_hash_at(block_id, address.0).await?;
+
+            debug!(
+                target: LOG_TARGET,
+                namespace = %model.namespace,
+                name = %model.name,
+                "Rolling back model upgrade."
+            );
+
+            self.downgrade_model(
+                world.address,
+                &model.namespace,
+                schema,
+                layout,
+                class_hash,
+                address.0,
+                packed_size,
+                unpacked_size,
+                block_timestamp,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads back the world resources changed since the fork block. Since the timestamp of a
+    /// block may be shared by the next ones, the resources changed in the fork block itself are
+    /// read back as well.
+    async fn rollback_world_resources<P: Provider + Sync>(
+        &mut self,
+        worlds: &HashMap<Felt, Arc<WorldContractReader<P>>>,
+        block_number: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let block_id = BlockId::Number(block_number);
+        let fork_timestamp = utc_dt_string_from_timestamp(block_timestamp);
+
+        // Models are registered by a given world, their rows being kept as long as a world
+        // registered them.
+        let world_models: Vec<(String, String)> = sqlx::query_as(
+            "SELECT world_address, model_id FROM world_models WHERE executed_at >= ?",
+        )
+        .bind(&fork_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut unregistered_models = Vec::new();
+        for (world_address, model_id) in world_models {
+            let Some(world) = worlds.get(&Felt::from_str(&world_address)?) else {
+                continue;
+            };
+            let selector = Felt::from_str(&model_id)?;
+
+            match world.resource(&selector).block_id(block_id).call().await? {
+                Resource::Model((address, _)) | Resource::Event((address, _)) => {
+                    let class_hash =
+                        world.provider().get_class_hash_at(block_id, address.0).await?;
+                    for table in ["models", "world_models"] {
+                        let id_column = if table == "models" { "id" } else { "model_id" };
+                        self.executor.send(QueryMessage::other(
+                            format!(
+                                "UPDATE {table} SET class_hash = ?, contract_address = ? WHERE \
+                                 {id_column} = ?"
+                            ),
+                            vec![
+                                Argument::FieldElement(class_hash),
+                                Argument::FieldElement(address.0),
+                                Argument::FieldElement(selector),
+                            ],
+                        ))?;
+                    }
+                }
+                _ => {
+                    self.executor.send(QueryMessage::other(
+                        "DELETE FROM world_models WHERE world_address = ? AND model_id = ?"
+                            .to_string(),
+                        vec![Argument::String(world_address), Argument::String(model_id.clone())],
+                    ))?;
+                    unregistered_models.push(model_id);
+                }
+            }
+            self.model_cache.remove(&selector).await;
+        }
+
+        // The models no world registers anymore, and without entities left, are removed. Their
+        // tables are kept, to be reused if the model is registered again.
+        for model_id in unregistered_models {
+            for statement in [
+                "DELETE FROM model_members WHERE model_id = ?1 AND ?1 NOT IN (SELECT model_id \
+                 FROM world_models UNION SELECT model_id FROM entity_model UNION SELECT model_id \
+                 FROM event_model)",
+                "DELETE FROM models WHERE id = ?1 AND ?1 NOT IN (SELECT model_id FROM \
+                 world_models UNION SELECT model_id FROM entity_model UNION SELECT model_id FROM \
+                 event_model)",
+            ] {
+                self.executor.send(QueryMessage::other(
+                    statement.to_string(),
+                    vec![Argument::String(model_id.clone())],
+                ))?;
+            }
+        }
+
+        // The other resources are scoped by the world they were registered in, the resources of
+        // the worlds not indexed anymore being left as is.
+        let namespaces: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, world_address FROM namespaces WHERE executed_at >= ?")
+                .bind(&fork_timestamp)
+                .fetch_all(&self.pool)
+                .await?;
+        for (id, world_address) in namespaces {
+            let Some(world) = worlds.get(&Felt::from_str(&world_address)?) else {
+                continue;
+            };
+            let selector = Felt::from_str(&id)?;
+
+            if !matches!(
+                world.resource(&selector).block_id(block_id).call().await?,
+                Resource::Namespace(_)
+            ) {
+                self.executor.send(QueryMessage::other(
+                    "DELETE FROM namespaces WHERE id = ? AND world_address = ?".to_string(),
+                    vec![Argument::String(id), Argument::String(world_address)],
+                ))?;
+            }
+        }
+
+        let contracts: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT id, world_address, class_hashes FROM world_contracts WHERE executed_at >= ?",
+        )
+        .bind(&fork_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+        for (id, world_address, class_hashes) in contracts {
+            let Some(world) = worlds.get(&Felt::from_str(&world_address)?) else {
+                continue;
+            };
+            let selector = Felt::from_str(&id)?;
+
+            let Resource::Contract((address, _)) =
+                world.resource(&selector).block_id(block_id).call().await?
+            else {
+                self.executor.send(QueryMessage::other(
+                    "DELETE FROM world_contracts WHERE id = ? AND world_address = ?".to_string(),
+                    vec![Argument::String(id), Argument::String(world_address)],
+                ))?;
+                continue;
+            };
+
+            // the class hashes of the upgrades after the fork are dropped
+            let class_hash = world.provider().get_class_hash_at(block_id, address.0).await?;
+            let mut class_hashes = sql_string_to_felts(&class_hashes);
+            if let Some(idx) = class_hashes.iter().rposition(|hash| *hash == class_hash) {
+                class_hashes.truncate(idx + 1);
+            }
+
+            self.executor.send(QueryMessage::other(
+                "UPDATE world_contracts SET contract_address = ?, class_hash = ?, class_hashes = \
+                 ? WHERE id = ? AND world_address = ?"
+                    .to_string(),
+                vec![
+                    Argument::FieldElement(address.0),
+                    Argument::FieldElement(class_hash),
+                    Argument::String(felts_to_sql_string(&class_hashes)),
+                    Argument::String(id),
+                    Argument::String(world_address),
+                ],
+            ))?;
+        }
+
+        // Revoked permissions are kept, so the permissions granted after the fork are revoked.
+        let permissions: Vec<(String, String, String, String, String)> = sqlx::query_as(
+            "SELECT id, world_address, resource, contract_address, permission FROM permissions \
+             WHERE executed_at >= ?",
+        )
+        .bind(&fork_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+        for (id, world_address, resource, contract_address, permission) in permissions {
+            let Some(world) = worlds.get(&Felt::from_str(&world_address)?) else {
+                continue;
+            };
+            let resource = Felt::from_str(&resource)?;
+            let contract_address = ContractAddress(Felt::from_str(&contract_address)?);
+
+            let granted = if permission == PermissionKind::OWNER.to_string() {
+                world.is_owner(&resource, &contract_address).block_id(block_id).call().await?
+            } else {
+                world.is_writer(&resource, &contract_address).block_id(block_id).call().await?
+            };
+
+            self.executor.send(QueryMessage::other(
+                "UPDATE permissions SET granted = ? WHERE id = ? AND world_address = ?".to_string(),
+                vec![
+                    Argument::Bool(granted),
+                    Argument::String(id),
+                    Argument::String(world_address),
+                ],
+            ))?;
+        }
+
+        Ok(())
+    }
+}
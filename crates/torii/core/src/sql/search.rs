@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::Ty;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use starknet::core::types::Felt;
+use starknet::core::utils::parse_cairo_short_string;
+use tracing::debug;
+
+use super::Sql;
+use crate::executor::{Argument, QueryMessage};
+
+pub(crate) const LOG_TARGET: &str = "torii_core::sql::search";
+
+/// Model members indexed for full-text search in the `search_index` table. Only `ByteArray` and
+/// short string (`felt252`) members can be searched.
+#[derive(Debug, Clone, Default)]
+pub struct SearchConfig {
+    /// Paths of the indexed members by model tag, the members of nested structs being separated
+    /// by dots.
+    pub members: HashMap<String, Vec<String>>,
+}
+
+impl SearchConfig {
+    /// Parses the indexed members, given as `namespace-Model.member`.
+    pub fn from_members(members: &[String]) -> Result<Self> {
+        let mut config = Self::default();
+        for member in members {
+            let (model, path) = member
+                .split_once('.')
+                .filter(|(model, path)| model.contains('-') && !path.is_empty())
+                .ok_or_else(|| {
+                    anyhow!("Invalid search member {}, expected namespace-Model.member", member)
+                })?;
+            config.members.entry(model.to_string()).or_default().push(path.to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+impl Sql {
+    /// Enables the full-text search of the given members, indexing the ones already stored.
+    pub async fn set_search(&mut self, config: SearchConfig) -> Result<()> {
+        self.search = config;
+
+        for (model, paths) in self.search.members.clone() {
+            let Ok(schema) = self.model(compute_selector_from_tag(&model)).await.map(|m| m.schema)
+            else {
+                // the members of models registered later are indexed as they are stored
+                continue;
+            };
+
+            for path in paths {
+                self.index_stored_members(&model, &path, &schema).await?;
+            }
+        }
+
+        self.execute().await
+    }
+
+    /// Updates the indexed members of a model stored in the row `id` of `world_address` in its
+    /// table, `model` being a possibly partial update of the model.
+    pub(crate) fn index_search_members(
+        &mut self,
+        id: &str,
+        world_address: &str,
+        model: &Ty,
+    ) -> Result<()> {
+        let tag = model.name();
+        let Some(paths) = self.search.members.get(&tag) else {
+            return Ok(());
+        };
+
+        let mut documents = Vec::new();
+        for path in paths {
+            // members missing from a partial update are left untouched
+            let Some(member) = member_at_path(model, path) else {
+                continue;
+            };
+            documents.push((path.clone(), searchable_text(member).unwrap_or_default()));
+        }
+
+        for (path, value) in documents {
+            self.set_search_document(id, world_address, &tag, &path, &value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the indexed members of the model stored in the row `id` of `world_address` in its
+    /// table.
+    pub(crate) fn remove_search_members(
+        &mut self,
+        id: &str,
+        world_address: &str,
+        model: &str,
+    ) -> Result<()> {
+        if !self.search.members.contains_key(model) {
+            return Ok(());
+        }
+
+        let arguments = vec![
+            Argument::String(id.to_string()),
+            Argument::String(world_address.to_string()),
+            Argument::String(model.to_string()),
+        ];
+        // documents of an external content index must be removed with their indexed value
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO search_index (search_index, rowid, value) SELECT 'delete', rowid, value \
+             FROM search_documents WHERE id = ? AND world_address = ? AND model = ?"
+                .to_string(),
+            arguments.clone(),
+        ))?;
+        self.executor.send(QueryMessage::other(
+            "DELETE FROM search_documents WHERE id = ? AND world_address = ? AND model = ?"
+                .to_string(),
+            arguments,
+        ))?;
+
+        Ok(())
+    }
+
+    fn set_search_document(
+        &mut self,
+        id: &str,
+        world_address: &str,
+        model: &str,
+        member: &str,
+        value: &str,
+    ) -> Result<()> {
+        let arguments = vec![
+            Argument::String(id.to_string()),
+            Argument::String(world_address.to_string()),
+            Argument::String(model.to_string()),
+            Argument::String(member.to_string()),
+        ];
+
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO search_index (search_index, rowid, value) SELECT 'delete', rowid, value \
+             FROM search_documents WHERE id = ? AND world_address = ? AND model = ? AND member = ?"
+                .to_string(),
+            arguments.clone(),
+        ))?;
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO search_documents (id, world_address, model, member, value) VALUES (?, ?, \
+             ?, ?, ?) ON CONFLICT(id, world_address, model, member) DO UPDATE SET value = \
+             EXCLUDED.value"
+                .to_string(),
+            arguments.iter().cloned().chain([Argument::String(value.to_string())]).collect(),
+        ))?;
+        self.executor.send(QueryMessage::other(
+            "INSERT INTO search_index (rowid, value) SELECT rowid, value FROM search_documents \
+             WHERE id = ? AND world_address = ? AND model = ? AND member = ?"
+                .to_string(),
+            arguments,
+        ))?;
+
+        Ok(())
+    }
+
+    // Indexes the members stored before their search was enabled.
+    async fn index_stored_members(&mut self, model: &str, path: &str, schema: &Ty) -> Result<()> {
+        let Some(member) = member_at_path(schema, path) else {
+            return Err(anyhow!("Search member {path} not found in model {model}"));
+        };
+        if !matches!(member, Ty::ByteArray(_) | Ty::Primitive(Primitive::Felt252(_))) {
+            return Err(anyhow!("Search member {path} of model {model} isn't a string"));
+        }
+
+        let mut parts = path.split('.').collect::<Vec<_>>();
+        let column = parts.pop().unwrap();
+        let table = std::iter::once(model).chain(parts).collect::<Vec<_>>().join("$");
+
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(&format!(
+            "SELECT id, world_address, [external_{column}] FROM [{table}] WHERE (id, \
+             world_address) NOT IN (SELECT id, world_address FROM search_documents WHERE model = \
+             ? AND member = ?)"
+        ))
+        .bind(model)
+        .bind(path)
+        .fetch_all(&self.pool)
+        .await?;
+
+        debug!(target: LOG_TARGET, model, member = path, count = rows.len(), "Indexing members.");
+
+        for (id, world_address, value) in rows {
+            let value = match member {
+                Ty::ByteArray(_) => value,
+                _ => value
+                    .and_then(|felt| Felt::from_str(&felt).ok())
+                    .and_then(|felt| parse_cairo_short_string(&felt).ok()),
+            };
+            let value = value.unwrap_or_default();
+            self.set_search_document(&id, &world_address, model, path, &value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the FTS5 query matching the text searched, each of its words matching the words of the
+/// indexed values starting with it. `None` if the text has no word.
+pub fn search_query(text: &str) -> Option<String> {
+    let terms = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{term}\"*"))
+        .collect::<Vec<_>>();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Query of the rows of a model whose `member` matches the FTS5 query `query_expr` (e.g. a bind
+/// placeholder), with their `id`, `world_address` and `rank`, the best matches having the lowest
+/// rank.
+pub fn search_matches_query(model: &str, member: &str, query_expr: &str) -> String {
+    format!(
+        "SELECT search_documents.id AS id, search_documents.world_address AS world_address, \
+         search_index.rank AS rank FROM search_index JOIN search_documents ON \
+         search_documents.rowid = search_index.rowid WHERE search_index MATCH {query_expr} AND \
+         search_documents.model = '{}' AND search_documents.member = '{}'",
+        model.replace('\'', "''"),
+        member.replace('\'', "''")
+    )
+}
+
+fn member_at_path<'a>(model: &'a Ty, path: &str) -> Option<&'a Ty> {
+    path.split('.').try_fold(model, |ty, name| {
+        ty.as_struct()?.children.iter().find(|member| member.name == name).map(|member| &member.ty)
+    })
+}
+
+fn searchable_text(ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::ByteArray(value) => Some(value.clone()),
+        Ty::Primitive(Primitive::Felt252(Some(felt))) => parse_cairo_short_string(felt).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_query() {
+        assert_eq!(search_query("Ali"), Some("\"Ali\"*".to_string()));
+        assert_eq!(
+            search_query(" dark  knight\" OR "),
+            Some("\"dark\"* \"knight\"* \"OR\"*".to_string())
+        );
+        assert_eq!(search_query("\"*"), None);
+    }
+
+    #[test]
+    fn test_search_config() {
+        let config = SearchConfig::from_members(&[
+            "ns-Player.name".to_string(),
+            "ns-Player.profile.bio".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(config.members["ns-Player"], vec!["name", "profile.bio"]);
+
+        assert!(SearchConfig::from_members(&["ns-Player".to_string()]).is_err());
+        assert!(SearchConfig::from_members(&["Player.name".to_string()]).is_err());
+    }
+}
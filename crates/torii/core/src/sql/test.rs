@@ -1,30 +1,41 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cainome::cairo_serde::ContractAddress;
 use dojo_test_utils::compiler::CompilerTestSetup;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
 use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::contracts::abigen::model::Layout;
 use dojo_world::contracts::naming::{compute_bytearray_hash, compute_selector_from_names};
 use dojo_world::contracts::world::{WorldContract, WorldContractReader};
+use futures_util::StreamExt;
 use katana_runner::RunnerCtx;
 use scarb::compiler::Profile;
 use sozo_scarbext::WorkspaceExt;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use starknet::accounts::Account;
-use starknet::core::types::{Call, Felt};
+use starknet::core::types::contract::AbiEntry;
+use starknet::core::types::{Call, Event, Felt, U256};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::{JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider, Url};
 use starknet_crypto::poseidon_hash_many;
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
 
 use crate::engine::{Engine, EngineConfig, Processors};
-use crate::executor::Executor;
+use crate::executor::{Argument, Executor, QueryMessage, QueryType};
+use crate::processors::custom_event::CustomEventProcessor;
+use crate::processors::EventProcessor;
+use crate::simple_broker::SimpleBroker;
+use crate::sql::indexes::ModelIndexConfig;
+use crate::sql::utils::u256_to_sql_string;
 use crate::sql::Sql;
-use crate::types::ContractType;
+use crate::types::{ContractType, PermissionKind, WebhookDelivery};
 
 pub async fn bootstrap_engine<P>(
     world: WorldContractReader<P>,
@@ -38,7 +49,7 @@ where
     let to = provider.block_hash_and_number().await?.block_number;
     let world_address = world.address;
     let mut engine = Engine::new(
-        world,
+        vec![world],
         db.clone(),
         provider,
         Processors { ..Processors::default() },
@@ -379,6 +390,396 @@ async fn test_update_with_set_record(sequencer: &RunnerCtx) {
     let _ = bootstrap_engine(world_reader, db.clone(), Arc::clone(&provider)).await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upgrade_model() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let mut db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+
+    let member = |name: &str, ty: Ty, key: bool| Member { name: name.to_string(), ty, key };
+    let model = |children| Ty::Struct(Struct { name: "Position".to_string(), children });
+
+    let v1 = model(vec![
+        member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+        member("x", Ty::Primitive(Primitive::U32(None)), false),
+    ]);
+    db.register_model(Felt::ONE, "ns", v1, Layout::Fixed(vec![]), Felt::ONE, Felt::ONE, 0, 0, 0)
+        .await
+        .unwrap();
+    db.execute().await.unwrap();
+
+    let v2 = model(vec![
+        member("player", Ty::Primitive(Primitive::ContractAddress(None)), true),
+        member("x", Ty::Primitive(Primitive::U32(None)), false),
+        member("y", Ty::Primitive(Primitive::U32(None)), false),
+        member("history", Ty::Array(vec![Ty::Primitive(Primitive::U32(None))]), false),
+    ]);
+    db.upgrade_model(
+        Felt::ONE,
+        "ns",
+        v2.clone(),
+        Layout::Fixed(vec![]),
+        Felt::TWO,
+        Felt::TWO,
+        0,
+        0,
+        1,
+    )
+    .await
+    .unwrap();
+    db.execute().await.unwrap();
+
+    // the new member is added as a column of the existing table
+    let columns: Vec<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_table_info('ns-Position')")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert!(columns.iter().any(|(name,)| name == "external_y"));
+
+    // the new array member gets its own table
+    assert_eq!(count_table("ns-Position$history", &pool).await, 0);
+
+    let members: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM model_members WHERE id = 'ns-Position'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(members.0, 4);
+
+    let selector = compute_selector_from_names("ns", "Position");
+    let cached = db.model(selector).await.unwrap();
+    assert_eq!(cached.class_hash, Felt::TWO);
+    assert_eq!(cached.schema.as_struct().unwrap().children, v2.as_struct().unwrap().children);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_model_indexes() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let mut db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+
+    let model = Ty::Struct(Struct {
+        name: "Position".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                key: true,
+            },
+            Member { name: "x".to_string(), ty: Ty::Primitive(Primitive::U32(None)), key: false },
+        ],
+    });
+    db.register_model(Felt::ONE, "ns", model, Layout::Fixed(vec![]), Felt::ONE, Felt::ONE, 0, 0, 0)
+        .await
+        .unwrap();
+    db.execute().await.unwrap();
+
+    let index = |condition: Option<&str>| ModelIndexConfig {
+        model: "ns-Position".to_string(),
+        members: vec!["x".to_string()],
+        name: None,
+        condition: condition.map(str::to_string),
+    };
+    let pool_ref = &pool;
+    let index_sql = move || async move {
+        sqlx::query_scalar::<_, String>(
+            "SELECT sql FROM sqlite_master WHERE type = 'index' AND name = 'ns-Position.x'",
+        )
+        .fetch_optional(pool_ref)
+        .await
+        .unwrap()
+    };
+    let recorded = move || async move {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT condition FROM model_indexes WHERE name = 'ns-Position.x'",
+        )
+        .fetch_optional(pool_ref)
+        .await
+        .unwrap()
+    };
+
+    db.set_model_indexes(vec![index(None)]).await.unwrap();
+    assert!(index_sql().await.is_some());
+    assert_eq!(recorded().await, Some(None));
+
+    // the index is recreated when its definition changes
+    db.set_model_indexes(vec![index(Some("external_x > 10"))]).await.unwrap();
+    assert!(index_sql().await.unwrap().contains("external_x > 10"));
+    assert_eq!(recorded().await, Some(Some("external_x > 10".to_string())));
+
+    // an index SQLite rejects isn't recorded
+    assert!(db.set_model_indexes(vec![index(Some("missing > 10"))]).await.is_err());
+    db.execute().await.unwrap();
+    assert!(index_sql().await.is_none());
+    assert_eq!(recorded().await, None);
+
+    // the indexes removed from the config are dropped
+    db.set_model_indexes(vec![index(None)]).await.unwrap();
+    db.set_model_indexes(vec![]).await.unwrap();
+    assert!(index_sql().await.is_none());
+    assert_eq!(recorded().await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upgrade_model_enum() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let mut db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+
+    let member = |name: &str, ty: Ty, key: bool| Member { name: name.to_string(), ty, key };
+    let model = |name: &str, variants: &[&str], option: Option<u8>| {
+        let options = variants
+            .iter()
+            .map(|name| EnumOption { name: name.to_string(), ty: Ty::Tuple(vec![]) })
+            .collect();
+        let moves =
+            vec![Ty::Primitive(Primitive::U32(Some(1))), Ty::Primitive(Primitive::U32(Some(2)))];
+        Ty::Struct(Struct {
+            name: name.to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(Some(Felt::ONE))), true),
+                member(
+                    "direction",
+                    Ty::Enum(Enum { name: "Direction".to_string(), option, options }),
+                    false,
+                ),
+                member("moves", Ty::Array(moves), false),
+            ],
+        })
+    };
+
+    let selector = compute_selector_from_names("ns", "Moves");
+    let entity_id = poseidon_hash_many(&[Felt::ONE]);
+    let keys = format!("{:#x}/", Felt::ONE);
+
+    let v1 = &["Left", "Right"];
+    db.register_model(
+        Felt::ONE,
+        "ns",
+        model("Moves", v1, None),
+        Layout::Fixed(vec![]),
+        Felt::ONE,
+        Felt::ONE,
+        0,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+    let event_id = format!("{:#064x}:{:#x}:{:#04x}", 1, 0, 0);
+    db.set_entity(
+        Felt::ONE,
+        model("ns-Moves", v1, Some(1)),
+        &event_id,
+        1,
+        entity_id,
+        selector,
+        Some(&keys),
+    )
+    .await
+    .unwrap();
+    db.execute().await.unwrap();
+
+    let v2 = &["Left", "Right", "Up"];
+    db.upgrade_model(
+        Felt::ONE,
+        "ns",
+        model("Moves", v2, None),
+        Layout::Fixed(vec![]),
+        Felt::TWO,
+        Felt::TWO,
+        0,
+        0,
+        1,
+    )
+    .await
+    .unwrap();
+    db.execute().await.unwrap();
+
+    // the rows of the rebuilt table and of its nested table are kept
+    let direction: (String,) =
+        sqlx::query_as("SELECT external_direction FROM [ns-Moves]").fetch_one(&pool).await.unwrap();
+    assert_eq!(direction.0, "Right");
+    assert_eq!(count_table("ns-Moves$moves", &pool).await, 2);
+
+    // the new variant is accepted by the column
+    let event_id = format!("{:#064x}:{:#x}:{:#04x}", 2, 0, 0);
+    db.set_entity(
+        Felt::ONE,
+        model("ns-Moves", v2, Some(2)),
+        &event_id,
+        2,
+        entity_id,
+        selector,
+        Some(&keys),
+    )
+    .await
+    .unwrap();
+    db.execute().await.unwrap();
+
+    let direction: (String,) =
+        sqlx::query_as("SELECT external_direction FROM [ns-Moves]").fetch_one(&pool).await.unwrap();
+    assert_eq!(direction.0, "Up");
+    assert_eq!(count_table("ns-Moves$moves", &pool).await, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_worlds_sharing_namespace() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let (world_a, world_b) = (Felt::ONE, Felt::TWO);
+    let contracts = HashMap::from([(world_a, ContractType::WORLD), (world_b, ContractType::WORLD)]);
+    let mut db = Sql::new(pool.clone(), sender.clone(), &contracts).await.unwrap();
+
+    let member = |name: &str, ty: Ty, key: bool| Member { name: name.to_string(), ty, key };
+    let position = |name: &str, x: Option<u32>| {
+        Ty::Struct(Struct {
+            name: name.to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(Some(Felt::ONE))), true),
+                member("x", Ty::Primitive(Primitive::U32(x)), false),
+            ],
+        })
+    };
+
+    // both worlds register the same model in the same namespace
+    for world in [world_a, world_b] {
+        db.register_model(
+            world,
+            "ns",
+            position("Position", None),
+            Layout::Fixed(vec![]),
+            world,
+            world,
+            0,
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    }
+    db.execute().await.unwrap();
+
+    let selector = compute_selector_from_names("ns", "Position");
+    let entity_id = poseidon_hash_many(&[Felt::ONE]);
+    let keys = format!("{:#x}/", Felt::ONE);
+    for (world, x) in [(world_a, 1), (world_b, 2)] {
+        let event_id = format!("{:#064x}:{:#x}:{:#04x}", 1, world, 0);
+        let entity = position("ns-Position", Some(x));
+        db.set_entity(world, entity, &event_id, 1, entity_id, selector, Some(&keys)).await.unwrap();
+    }
+    db.execute().await.unwrap();
+
+    // the entities with the same id are kept apart
+    assert_eq!(count_table("world_models", &pool).await, 2);
+    assert_eq!(count_table("entities", &pool).await, 2);
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT world_address, external_x FROM [ns-Position] WHERE id = ? ORDER BY world_address",
+    )
+    .bind(format!("{:#x}", entity_id))
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows, vec![(format!("{:#x}", world_a), 1), (format!("{:#x}", world_b), 2)]);
+
+    // deleting the entity of a world leaves the other one untouched
+    let event_id = format!("{:#064x}:{:#x}:{:#04x}", 2, world_a, 0);
+    let entity = position("ns-Position", None);
+    db.delete_entity(world_a, entity_id, selector, entity, &event_id, 2).await.unwrap();
+    db.execute().await.unwrap();
+
+    let worlds: Vec<(String,)> =
+        sqlx::query_as("SELECT world_address FROM [ns-Position]").fetch_all(&pool).await.unwrap();
+    assert_eq!(worlds, vec![(format!("{:#x}", world_b),)]);
+    let worlds: Vec<(String,)> =
+        sqlx::query_as("SELECT world_address FROM entities").fetch_all(&pool).await.unwrap();
+    assert_eq!(worlds, vec![(format!("{:#x}", world_b),)]);
+
+    // the deleted entity is kept as a tombstone, to be restored if its deletion is reorged
+    let tombstones: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT world_address, keys, event_id FROM deleted_entities")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(tombstones, vec![(format!("{:#x}", world_a), keys, event_id)]);
+
+    // the model can't be registered with another schema, since its layout is shared
+    let world_c = Felt::THREE;
+    let mut children = position("Position", None).as_struct().unwrap().children.clone();
+    children.push(member("y", Ty::Primitive(Primitive::U32(None)), false));
+    let other = Ty::Struct(Struct { name: "Position".to_string(), children });
+    let registered = db
+        .register_model(
+            world_c,
+            "ns",
+            other.clone(),
+            Layout::Fixed(vec![]),
+            world_c,
+            world_c,
+            0,
+            0,
+            0,
+        )
+        .await;
+    assert!(registered.is_err());
+    assert!(db.world_model(world_c, selector).await.is_err());
+
+    // nor upgraded by one of the worlds sharing it, whose records aren't indexed anymore
+    let upgraded = db
+        .upgrade_model(world_b, "ns", other, Layout::Fixed(vec![]), world_b, world_b, 0, 0, 0)
+        .await;
+    assert!(upgraded.is_err());
+    db.execute().await.unwrap();
+    assert!(db.world_model(world_b, selector).await.is_err());
+
+    let model = db.world_model(world_a, selector).await.unwrap();
+    assert_eq!(model.schema.as_struct().unwrap().children.len(), 2);
+    let worlds: Vec<(String,)> =
+        sqlx::query_as("SELECT world_address FROM world_models").fetch_all(&pool).await.unwrap();
+    assert_eq!(worlds, vec![(format!("{:#x}", world_a),)]);
+}
+
 /// Count the number of rows in a table.
 ///
 /// # Arguments
@@ -387,9 +788,528 @@ async fn test_update_with_set_record(sequencer: &RunnerCtx) {
 ///
 /// # Returns
 /// The number of rows in the table.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_custom_event_table() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let mut db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+
+    let provider =
+        JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost:5050").unwrap()));
+    let world = WorldContractReader::new(Felt::ZERO, provider);
+
+    // the `Listed` event of a marketplace, whose data members are given
+    let processor = |data_members: &str| {
+        let abi: Vec<AbiEntry> = serde_json::from_str(&format!(
+            r#"[
+                {{
+                    "type": "event",
+                    "name": "marketplace::Listed",
+                    "kind": "struct",
+                    "members": [
+                        {{ "name": "listing_id", "type": "core::integer::u64", "kind": "key" }},
+                        {data_members}
+                    ]
+                }},
+                {{
+                    "type": "event",
+                    "name": "marketplace::Marketplace::Event",
+                    "kind": "enum",
+                    "variants": [
+                        {{ "name": "Listed", "type": "marketplace::Listed", "kind": "nested" }}
+                    ]
+                }}
+            ]"#
+        ))
+        .unwrap();
+        CustomEventProcessor::from_abi("marketplace", &abi).unwrap().remove(0)
+    };
+    let event = |data: Vec<Felt>| Event {
+        from_address: Felt::ONE,
+        keys: vec![get_selector_from_name("Listed").unwrap(), Felt::from(7)],
+        data,
+    };
+    let columns = || async {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT name, type FROM pragma_table_info('marketplace-Listed') WHERE name LIKE \
+             'external_%' ORDER BY cid",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap()
+    };
+    let column = |name: &str, ty: &str| (name.to_string(), ty.to_string());
+
+    let v1 = processor(
+        r#"{ "name": "tags", "type": "core::array::Span::<core::felt252>", "kind": "data" }"#,
+    );
+    let listed = event(vec![Felt::ONE, Felt::from(0xab)]);
+    assert!(EventProcessor::<JsonRpcClient<HttpTransport>>::validate(&v1, &listed));
+    assert!(!EventProcessor::<JsonRpcClient<HttpTransport>>::validate(
+        &v1,
+        &event(vec![Felt::TWO, Felt::from(0xab)])
+    ));
+    assert!(!EventProcessor::<JsonRpcClient<HttpTransport>>::validate(
+        &v1,
+        &event(vec![Felt::ONE, Felt::from(0xab), Felt::ONE])
+    ));
+
+    v1.process(&world, &mut db, 1, 1000, "0x1:0x1:0x0", &listed).await.unwrap();
+    db.execute().await.unwrap();
+    assert_eq!(
+        columns().await,
+        vec![column("external_listing_id", "TEXT"), column("external_tags", "TEXT")]
+    );
+
+    // a new member is added as a column
+    let v2 = processor(
+        r#"{ "name": "tags", "type": "core::array::Span::<core::felt252>", "kind": "data" },
+           { "name": "seller", "type": "core::felt252", "kind": "data" }"#,
+    );
+    v2.process(&world, &mut db, 2, 2000, "0x2:0x1:0x0", &event(vec![Felt::ZERO, Felt::TWO]))
+        .await
+        .unwrap();
+    db.execute().await.unwrap();
+    assert_eq!(
+        columns().await,
+        vec![
+            column("external_listing_id", "TEXT"),
+            column("external_tags", "TEXT"),
+            column("external_seller", "TEXT"),
+        ]
+    );
+
+    // the table is rebuilt when the type of a member changes, keeping the removed members
+    let v3 = processor(r#"{ "name": "tags", "type": "core::integer::u32", "kind": "data" }"#);
+    v3.process(&world, &mut db, 3, 3000, "0x3:0x1:0x0", &event(vec![Felt::THREE])).await.unwrap();
+    db.execute().await.unwrap();
+    assert_eq!(
+        columns().await,
+        vec![
+            column("external_listing_id", "TEXT"),
+            column("external_tags", "INTEGER"),
+            column("external_seller", "TEXT"),
+        ]
+    );
+
+    let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT id, CAST(external_tags AS TEXT), external_seller FROM [marketplace-Listed] ORDER \
+         BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            ("0x1:0x1:0x0".to_string(), r#"["0xab"]"#.to_string(), None),
+            ("0x2:0x1:0x0".to_string(), "[]".to_string(), Some(format!("{:#064x}", 2))),
+            ("0x3:0x1:0x0".to_string(), "3".to_string(), None),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_webhook_delivery_published_after_commit() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+    let mut deliveries = SimpleBroker::<WebhookDelivery>::subscribe();
+
+    // stored twice, e.g. when the same block is indexed again
+    for _ in 0..2 {
+        sender
+            .send(QueryMessage::new(
+                "INSERT OR IGNORE INTO webhook_deliveries (id, webhook, payload) VALUES (?, ?, ?) \
+                 RETURNING id, webhook, payload"
+                    .to_string(),
+                vec![
+                    Argument::String("hook:0x1".to_string()),
+                    Argument::String("hook".to_string()),
+                    Argument::String("{}".to_string()),
+                ],
+                QueryType::StoreWebhookDelivery,
+            ))
+            .unwrap();
+    }
+
+    let next = tokio::time::timeout(Duration::from_millis(200), deliveries.next());
+    assert!(next.await.is_err(), "the delivery must not be published before being committed");
+
+    db.execute().await.unwrap();
+    let delivery = deliveries.next().await.unwrap();
+    assert_eq!(delivery.id, "hook:0x1");
+    assert_eq!(delivery.webhook, "hook");
+
+    let next = tokio::time::timeout(Duration::from_millis(200), deliveries.next());
+    assert!(next.await.is_err(), "a delivery already stored must not be published again");
+}
+
 async fn count_table(table_name: &str, pool: &sqlx::Pool<sqlx::Sqlite>) -> i64 {
     let count_query = format!("SELECT COUNT(*) FROM [{}]", table_name);
     let count: (i64,) = sqlx::query_as(&count_query).fetch_one(pool).await.unwrap();
 
     count.0
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_world_resources() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let mut db = Sql::new(pool.clone(), sender.clone(), &HashMap::new()).await.unwrap();
+
+    let selector = compute_selector_from_names("ns", "actions");
+    let contract = Felt::from(0x1234);
+    let namespace = compute_bytearray_hash("ns");
+    let (world_a, world_b) = (Felt::from(0xa), Felt::from(0xb));
+
+    db.register_namespace(world_a, "ns", namespace, 0).unwrap();
+    db.register_contract(world_a, "ns", "actions", contract, Felt::ONE, Felt::ZERO, 0).unwrap();
+    db.upgrade_contract(world_a, selector, Felt::TWO, 1).unwrap();
+    db.initialize_contract(world_a, selector, &[Felt::THREE], 2).unwrap();
+    // a contract registered before the indexed blocks has no row to update
+    let unknown = compute_selector_from_names("ns", "unknown");
+    db.upgrade_contract(world_a, unknown, Felt::TWO, 1).unwrap();
+    db.initialize_contract(world_a, unknown, &[], 2).unwrap();
+    db.set_permission(world_a, namespace, contract, PermissionKind::WRITER, true, 3).unwrap();
+    db.set_permission(world_a, namespace, contract, PermissionKind::WRITER, false, 4).unwrap();
+    // another world registering the same resources gets its own rows
+    db.register_namespace(world_b, "ns", namespace, 5).unwrap();
+    db.register_contract(world_b, "ns", "actions", contract, Felt::ONE, Felt::ZERO, 5).unwrap();
+    db.set_permission(world_b, namespace, contract, PermissionKind::WRITER, true, 6).unwrap();
+    db.execute().await.unwrap();
+
+    assert_eq!(count_table("namespaces", &pool).await, 2);
+    assert_eq!(count_table("world_contracts", &pool).await, 2);
+    assert_eq!(count_table("permissions", &pool).await, 2);
+
+    let (class_hash, class_hashes, initialized, init_calldata): (String, String, bool, String) =
+        sqlx::query_as(
+            "SELECT class_hash, class_hashes, initialized, init_calldata FROM world_contracts \
+             WHERE id = ? AND world_address = ?",
+        )
+        .bind(format!("{:#x}", selector))
+        .bind(format!("{:#x}", world_a))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(class_hash, "0x2");
+    assert_eq!(class_hashes, "0x1/0x2/");
+    assert!(initialized);
+    assert_eq!(init_calldata, "0x3/");
+
+    let (class_hash, initialized): (String, bool) = sqlx::query_as(
+        "SELECT class_hash, initialized FROM world_contracts WHERE id = ? AND world_address = ?",
+    )
+    .bind(format!("{:#x}", selector))
+    .bind(format!("{:#x}", world_b))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(class_hash, "0x1");
+    assert!(!initialized);
+
+    // revoking a permission keeps its row around
+    let (permission, granted): (String, bool) = sqlx::query_as(
+        "SELECT permission, granted FROM permissions WHERE contract_address = ? AND world_address \
+         = ?",
+    )
+    .bind(format!("{:#x}", contract))
+    .bind(format!("{:#x}", world_a))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(permission, "WRITER");
+    assert!(!granted);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rollback() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let world_address = Felt::ONE;
+    let erc20 = Felt::from(0x20u8);
+    let (alice, bob) = (Felt::from(0xa11ceu32), Felt::from(0xb0bu32));
+    let contracts =
+        HashMap::from([(world_address, ContractType::WORLD), (erc20, ContractType::ERC20)]);
+    let mut db = Sql::new(pool.clone(), sender.clone(), &contracts).await.unwrap();
+
+    // alice sends 10 tokens to bob at block 3, after the fork point
+    let balance_id = |account: Felt| format!("{:#x}/{:#x}/", account, erc20);
+    sqlx::query(
+        "INSERT INTO tokens (id, contract_address, name, symbol, decimals) VALUES (?, ?, 'Token', \
+         'TKN', 18)",
+    )
+    .bind(format!("{:#x}", erc20))
+    .bind(format!("{:#x}", erc20))
+    .execute(&pool)
+    .await
+    .unwrap();
+    for (account, balance) in [(alice, 90u8), (bob, 10u8)] {
+        sqlx::query(
+            "INSERT INTO balances (id, balance, account_address, contract_address, token_id) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(balance_id(account))
+        .bind(u256_to_sql_string(&U256::from(balance)))
+        .bind(format!("{:#x}", account))
+        .bind(format!("{:#x}", erc20))
+        .bind(format!("{:#x}", erc20))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO erc_transfers (id, contract_address, from_address, to_address, amount, \
+         token_id, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(format!("{:#064x}:{:#x}:{:#04x}", 3, Felt::TWO, 0))
+    .bind(format!("{:#x}", erc20))
+    .bind(format!("{:#x}", alice))
+    .bind(format!("{:#x}", bob))
+    .bind(u256_to_sql_string(&U256::from(10u8)))
+    .bind(format!("{:#x}", erc20))
+    .bind("2024-01-01T00:00:00+00:00")
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let event = Event { from_address: world_address, keys: vec![Felt::ONE], data: vec![] };
+    for block_number in [1u64, 3] {
+        let event_id = format!("{:#064x}:{:#x}:{:#04x}", block_number, Felt::ONE, 0);
+        db.store_event(&event_id, &event, Felt::ONE, block_number).unwrap();
+        db.set_block_hash(block_number, Felt::from(block_number)).unwrap();
+    }
+    db.execute().await.unwrap();
+
+    assert_eq!(db.block_hashes().await.unwrap(), vec![(3, Felt::THREE), (1, Felt::ONE)]);
+
+    // no entity has been indexed, so the world is never queried
+    let provider =
+        JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost:5050").unwrap()));
+    let world = WorldContractReader::new(world_address, provider);
+    db.rollback(&HashMap::from([(world_address, Arc::new(world))]), 2, 2).await.unwrap();
+    db.execute().await.unwrap();
+
+    assert_eq!(count_table("events", &pool).await, 1);
+    assert_eq!(count_table("erc_transfers", &pool).await, 0);
+    assert_eq!(db.block_hashes().await.unwrap(), vec![(1, Felt::ONE)]);
+
+    for (account, expected) in [(alice, 100u8), (bob, 0u8)] {
+        let (balance,): (String,) = sqlx::query_as("SELECT balance FROM balances WHERE id = ?")
+            .bind(balance_id(account))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, u256_to_sql_string(&U256::from(expected)));
+    }
+
+    let heads: Vec<(i64,)> =
+        sqlx::query_as("SELECT head FROM contracts").fetch_all(&pool).await.unwrap();
+    assert!(heads.iter().all(|(head,)| *head == 2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rollback_mid_chunk() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let world_address = Felt::ONE;
+    let contracts = HashMap::from([(world_address, ContractType::WORLD)]);
+    let mut db = Sql::new(pool.clone(), sender.clone(), &contracts).await.unwrap();
+
+    let member = |name: &str, ty: Ty, key: bool| Member { name: name.to_string(), ty, key };
+    let message = |name: &str, score: Option<u32>| {
+        Ty::Struct(Struct {
+            name: name.to_string(),
+            children: vec![
+                member("player", Ty::Primitive(Primitive::ContractAddress(Some(Felt::ONE))), true),
+                member("score", Ty::Primitive(Primitive::U32(score)), false),
+            ],
+        })
+    };
+
+    // the model is registered before the fork point, so the world is never queried
+    db.register_model(
+        world_address,
+        "ns",
+        message("Score", None),
+        Layout::Fixed(vec![]),
+        Felt::ONE,
+        Felt::ONE,
+        0,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+    db.execute().await.unwrap();
+
+    // blocks 1 to 3 are indexed as a single chunk, each one emitting an event message
+    for block_number in 1u64..=3 {
+        let transaction_hash = Felt::from(block_number);
+        let event_id = format!("{:#064x}:{:#x}:{:#04x}", block_number, transaction_hash, 0);
+        db.set_event_message(
+            world_address,
+            message("ns-Score", Some(block_number as u32)),
+            &event_id,
+            block_number,
+            true,
+        )
+        .await
+        .unwrap();
+        db.set_block_hash(block_number, Felt::from(block_number)).unwrap();
+
+        sqlx::query(
+            "INSERT INTO transactions (id, transaction_hash, sender_address, calldata, max_fee, \
+             signature, nonce, executed_at) VALUES (?, ?, '0x1', '', '0x0', '', '0x0', ?)",
+        )
+        .bind(format!("{:#064x}:{:#x}", block_number, transaction_hash))
+        .bind(format!("{:#x}", transaction_hash))
+        .bind("2024-01-01T00:00:00+00:00")
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    db.execute().await.unwrap();
+
+    let provider =
+        JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost:5050").unwrap()));
+    let world = WorldContractReader::new(world_address, provider);
+    db.rollback(&HashMap::from([(world_address, Arc::new(world))]), 2, 2).await.unwrap();
+    db.execute().await.unwrap();
+
+    assert_eq!(db.block_hashes().await.unwrap(), vec![(2, Felt::TWO), (1, Felt::ONE)]);
+    assert_eq!(count_table("transactions", &pool).await, 2);
+    assert_eq!(count_table("event_messages_historical", &pool).await, 2);
+
+    // the event message is back to its version of the fork block
+    let (score,): (i64,) =
+        sqlx::query_as("SELECT score FROM [ns-Score]").fetch_one(&pool).await.unwrap();
+    assert_eq!(score, 2);
+    let (event_id,): (String,) =
+        sqlx::query_as("SELECT event_id FROM event_messages").fetch_one(&pool).await.unwrap();
+    assert_eq!(event_id, format!("{:#064x}:{:#x}:{:#04x}", 2, Felt::TWO, 0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_erc1155_transfer() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let erc1155 = Felt::from(0x1155u32);
+    let (alice, bob) = (Felt::from(0xa11ceu32), Felt::from(0xb0bu32));
+    let token_id = format!("{:#x}:{}", erc1155, u256_to_sql_string(&U256::from(7u8)));
+    let balance_id = |account: Felt| format!("{:#x}/{}", account, token_id);
+
+    // the token is already known, so its metadata isn't fetched
+    sqlx::query(
+        "INSERT INTO tokens (id, contract_address, name, symbol, decimals) VALUES (?, ?, 'Items', \
+         'ITM', 0)",
+    )
+    .bind(&token_id)
+    .bind(format!("{:#x}", erc1155))
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO balances (id, balance, account_address, contract_address, token_id) VALUES \
+         (?, ?, ?, ?, ?)",
+    )
+    .bind(balance_id(alice))
+    .bind(u256_to_sql_string(&U256::from(5u8)))
+    .bind(format!("{:#x}", alice))
+    .bind(format!("{:#x}", erc1155))
+    .bind(&token_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let contracts = HashMap::from([(erc1155, ContractType::ERC1155)]);
+    let mut db = Sql::new(pool.clone(), sender.clone(), &contracts).await.unwrap();
+
+    let provider =
+        JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost:5050").unwrap()));
+    db.handle_erc1155_transfer(
+        erc1155,
+        alice,
+        bob,
+        U256::from(7u8),
+        U256::from(2u8),
+        &provider,
+        1,
+        &format!("{:#064x}:{:#x}:{:#04x}", 1, Felt::ONE, 0),
+    )
+    .await
+    .unwrap();
+    db.apply_cache_diff().await.unwrap();
+    db.execute().await.unwrap();
+
+    assert_eq!(count_table("erc_transfers", &pool).await, 1);
+    for (account, expected) in [(alice, 3u8), (bob, 2u8)] {
+        let (balance,): (String,) = sqlx::query_as("SELECT balance FROM balances WHERE id = ?")
+            .bind(balance_id(account))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, u256_to_sql_string(&U256::from(expected)));
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use dojo_world::contracts::naming::compute_selector_from_names;
+use starknet::core::types::Felt;
+
+use super::Sql;
+use crate::executor::{Argument, QueryMessage, QueryType};
+use crate::sql::utils::{felt_to_sql_string, felts_to_sql_string};
+use crate::types::PermissionKind;
+use crate::utils::utc_dt_string_from_timestamp;
+
+impl Sql {
+    pub fn register_namespace(
+        &mut self,
+        world_address: Felt,
+        namespace: &str,
+        hash: Felt,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let statement = "INSERT INTO namespaces (id, world_address, namespace, executed_at) \
+                         VALUES (?, ?, ?, ?) ON CONFLICT(id, world_address) DO UPDATE SET \
+                         namespace=EXCLUDED.namespace RETURNING *";
+
+        self.executor.send(QueryMessage::new(
+            statement.to_string(),
+            vec![
+                Argument::FieldElement(hash),
+                Argument::FieldElement(world_address),
+                Argument::String(namespace.to_string()),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+            QueryType::RegisterNamespace,
+        ))?;
+
+        Ok(())
+    }
+
+    pub fn register_contract(
+        &mut self,
+        world_address: Felt,
+        namespace: &str,
+        name: &str,
+        contract_address: Felt,
+        class_hash: Felt,
+        salt: Felt,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let selector = compute_selector_from_names(namespace, name);
+
+        let statement = "INSERT INTO world_contracts (id, world_address, namespace, name, \
+                         contract_address, class_hash, class_hashes, salt, executed_at) VALUES \
+                         (?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT(id, world_address) DO UPDATE SET \
+                         contract_address=EXCLUDED.contract_address, \
+                         class_hash=EXCLUDED.class_hash, class_hashes=EXCLUDED.class_hashes, \
+                         salt=EXCLUDED.salt, executed_at=EXCLUDED.executed_at, \
+                         updated_at=CURRENT_TIMESTAMP RETURNING *";
+
+        self.executor.send(QueryMessage::new(
+            statement.to_string(),
+            vec![
+                Argument::FieldElement(selector),
+                Argument::FieldElement(world_address),
+                Argument::String(namespace.to_string()),
+                Argument::String(name.to_string()),
+                Argument::FieldElement(contract_address),
+                Argument::FieldElement(class_hash),
+                Argument::String(felts_to_sql_string(&[class_hash])),
+                Argument::FieldElement(salt),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+            QueryType::SetWorldContract,
+        ))?;
+
+        Ok(())
+    }
+
+    /// Sets the new class hash of a contract, appending it to its class hash history.
+    pub fn upgrade_contract(
+        &mut self,
+        world_address: Felt,
+        selector: Felt,
+        class_hash: Felt,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let statement = "UPDATE world_contracts SET class_hash=?, class_hashes=class_hashes || ?, \
+                         executed_at=?, updated_at=CURRENT_TIMESTAMP WHERE id=? AND \
+                         world_address=? RETURNING *";
+
+        self.executor.send(QueryMessage::new(
+            statement.to_string(),
+            vec![
+                Argument::FieldElement(class_hash),
+                Argument::String(felts_to_sql_string(&[class_hash])),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+                Argument::FieldElement(selector),
+                Argument::FieldElement(world_address),
+            ],
+            QueryType::SetWorldContract,
+        ))?;
+
+        Ok(())
+    }
+
+    pub fn initialize_contract(
+        &mut self,
+        world_address: Felt,
+        selector: Felt,
+        init_calldata: &[Felt],
+        block_timestamp: u64,
+    ) -> Result<()> {
+        // an empty calldata is stored as an empty string, not as a single delimiter
+        let init_calldata = if init_calldata.is_empty() {
+            String::new()
+        } else {
+            felts_to_sql_string(init_calldata)
+        };
+
+        let statement = "UPDATE world_contracts SET initialized=TRUE, init_calldata=?, \
+                         executed_at=?, updated_at=CURRENT_TIMESTAMP WHERE id=? AND \
+                         world_address=? RETURNING *";
+
+        self.executor.send(QueryMessage::new(
+            statement.to_string(),
+            vec![
+                Argument::String(init_calldata),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+                Argument::FieldElement(selector),
+                Argument::FieldElement(world_address),
+            ],
+            QueryType::SetWorldContract,
+        ))?;
+
+        Ok(())
+    }
+
+    /// Grants or revokes the given permission of `contract_address` on `resource` of the world.
+    pub fn set_permission(
+        &mut self,
+        world_address: Felt,
+        resource: Felt,
+        contract_address: Felt,
+        permission: PermissionKind,
+        granted: bool,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let id = format!(
+            "{}:{}:{permission}",
+            felt_to_sql_string(&resource),
+            felt_to_sql_string(&contract_address)
+        );
+
+        let statement = "INSERT INTO permissions (id, world_address, resource, contract_address, \
+                         permission, granted, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?) ON \
+                         CONFLICT(id, world_address) DO UPDATE SET granted=EXCLUDED.granted, \
+                         executed_at=EXCLUDED.executed_at, updated_at=CURRENT_TIMESTAMP RETURNING \
+                         *";
+
+        self.executor.send(QueryMessage::new(
+            statement.to_string(),
+            vec![
+                Argument::String(id),
+                Argument::FieldElement(world_address),
+                Argument::FieldElement(resource),
+                Argument::FieldElement(contract_address),
+                Argument::String(permission.to_string()),
+                Argument::Bool(granted),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+            QueryType::SetPermission,
+        ))?;
+
+        Ok(())
+    }
+}
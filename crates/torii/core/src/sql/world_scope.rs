@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use sqlx::{Connection, Pool, Sqlite, SqliteConnection};
+use tracing::info;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::sql::world_scope";
+
+/// Adds the world address to the keys of the model tables created before several worlds could be
+/// indexed together, which the `world_scope` migration can't rebuild since they are named after
+/// their models. Their rows are attributed to the world the migration found indexed.
+///
+/// Must be called once the migrations have been run, before anything is written to the model
+/// tables.
+pub async fn scope_model_tables(pool: &Pool<Sqlite>) -> Result<()> {
+    let pending: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'world_scope_model_tables'",
+    )
+    .fetch_optional(pool)
+    .await?;
+    if pending.is_none() {
+        return Ok(());
+    }
+
+    // The connection isn't given back to the pool, which expects the foreign keys to be enforced.
+    let mut conn = pool.acquire().await?.detach();
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut conn).await?;
+
+    let mut tx = conn.begin().await?;
+
+    let tables: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT t.name, t.world_address, m.sql FROM world_scope_model_tables t JOIN sqlite_master \
+         m ON m.type = 'table' AND m.name = t.name",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (table, world_address, sql) in tables {
+        let create_table = scoped_model_table(&table, &sql)?;
+        rebuild_table(&mut tx, &table, &create_table, &world_address).await?;
+    }
+
+    sqlx::query("DROP TABLE world_scope_model_tables").execute(&mut *tx).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Replaces `table` with the one created by `create_table`, named `{table}_new`, copying its rows
+/// attributed to `world_address`. The indexes of the table are created again.
+async fn rebuild_table(
+    conn: &mut SqliteConnection,
+    table: &str,
+    create_table: &str,
+    world_address: &str,
+) -> Result<()> {
+    info!(target: LOG_TARGET, table, "Adding the world address to the model table keys.");
+
+    let indexes: Vec<String> = sqlx::query_scalar(
+        "SELECT sql FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND sql IS NOT NULL",
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+    let columns = columns.iter().map(|column| format!("[{column}]")).collect::<Vec<_>>().join(", ");
+
+    sqlx::query(create_table).execute(&mut *conn).await?;
+    sqlx::query(&format!(
+        "INSERT INTO [{table}_new] ({columns}, world_address) SELECT {columns}, ? FROM [{table}]"
+    ))
+    .bind(world_address)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(&format!("DROP TABLE [{table}]")).execute(&mut *conn).await?;
+    sqlx::query(&format!("ALTER TABLE [{table}_new] RENAME TO [{table}]"))
+        .execute(&mut *conn)
+        .await?;
+
+    for index in indexes {
+        sqlx::query(&index).execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+/// Adds the world address to the definition of a model table, as created by
+/// [`Sql::build_model_query`](super::Sql) before the tables were keyed by world.
+fn scoped_model_table(table: &str, sql: &str) -> Result<String> {
+    let invalid = || anyhow!("Unexpected definition of the model table {}: {}", table, sql);
+
+    let body = sql.find('(').map(|start| &sql[start..]).ok_or_else(invalid)?;
+    if !body.starts_with("(id TEXT NOT NULL, ") || !body.contains("PRIMARY KEY (id") {
+        return Err(invalid());
+    }
+
+    let mut body = body
+        .replacen("(id TEXT NOT NULL, ", "(id TEXT NOT NULL, world_address TEXT NOT NULL, ", 1)
+        .replace("PRIMARY KEY (id", "PRIMARY KEY (id, world_address")
+        .replace("FOREIGN KEY (id", "FOREIGN KEY (id, world_address")
+        // the parent table referenced by the rows of a nested member
+        .replace("] (id", "] (id, world_address")
+        .replace(
+            "FOREIGN KEY (entity_id) REFERENCES entities(id)",
+            "FOREIGN KEY (entity_id, world_address) REFERENCES entities(id, world_address)",
+        )
+        .replace(
+            "FOREIGN KEY (event_message_id) REFERENCES event_messages(id)",
+            "FOREIGN KEY (event_message_id, world_address) REFERENCES event_messages(id, \
+             world_address)",
+        );
+
+    if body.contains("full_array_id TEXT NOT NULL UNIQUE") {
+        body = body.replace("full_array_id TEXT NOT NULL UNIQUE", "full_array_id TEXT NOT NULL");
+        body =
+            body.replacen("PRIMARY KEY", "UNIQUE (full_array_id, world_address), PRIMARY KEY", 1);
+    }
+
+    Ok(format!("CREATE TABLE [{table}_new] {body}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_model_table() {
+        let sql = "CREATE TABLE [ns-Moves$vec] (id TEXT NOT NULL, event_id TEXT NOT NULL, \
+                   entity_id TEXT, event_message_id TEXT, idx_0 INTEGER NOT NULL, full_array_id \
+                   TEXT NOT NULL UNIQUE, external_x INTEGER, executed_at DATETIME NOT NULL, \
+                   FOREIGN KEY (id) REFERENCES [ns-Moves] (id) ON DELETE CASCADE, PRIMARY KEY \
+                   (id, idx_0), FOREIGN KEY (entity_id) REFERENCES entities(id), FOREIGN KEY \
+                   (event_message_id) REFERENCES event_messages(id))";
+
+        assert_eq!(
+            scoped_model_table("ns-Moves$vec", sql).unwrap(),
+            "CREATE TABLE [ns-Moves$vec_new] (id TEXT NOT NULL, world_address TEXT NOT NULL, \
+             event_id TEXT NOT NULL, entity_id TEXT, event_message_id TEXT, idx_0 INTEGER NOT \
+             NULL, full_array_id TEXT NOT NULL, external_x INTEGER, executed_at DATETIME NOT \
+             NULL, FOREIGN KEY (id, world_address) REFERENCES [ns-Moves] (id, world_address) ON \
+             DELETE CASCADE, UNIQUE (full_array_id, world_address), PRIMARY KEY (id, \
+             world_address, idx_0), FOREIGN KEY (entity_id, world_address) REFERENCES \
+             entities(id, world_address), FOREIGN KEY (event_message_id, world_address) \
+             REFERENCES event_messages(id, world_address))"
+        );
+
+        assert!(scoped_model_table("ns-Moves", "CREATE TABLE [ns-Moves] (x TEXT)").is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use reqwest::{Client, Response};
+use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Semaphore;
+use tokio_util::bytes::{Bytes, BytesMut};
+use tracing::{debug, error, info};
+
+use crate::executor::{Argument, QueryMessage};
+
+pub(crate) const LOG_TARGET: &str = "torii_core::token_metadata";
+
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://cartridge.infura-ipfs.io/ipfs/";
+
+/// Maximum number of token metadata resolved at the same time.
+const MAX_CONCURRENT_RESOLUTIONS: usize = 10;
+
+/// Timeout to connect to the server of a URI.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout of a whole request, including the download of its body.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct TokenMetadataConfig {
+    /// Gateway used to fetch `ipfs://` URIs.
+    pub ipfs_gateway: String,
+    /// Directory where the token images are cached, if any.
+    pub artifacts_dir: Option<PathBuf>,
+    /// Number of attempts made to fetch a URI.
+    pub max_retries: u8,
+    /// Delay before the first retry, doubled after each failed attempt.
+    pub retry_delay: Duration,
+    /// Maximum size, in bytes, of a fetched metadata document or image.
+    pub max_size: usize,
+}
+
+impl Default for TokenMetadataConfig {
+    fn default() -> Self {
+        Self {
+            ipfs_gateway: DEFAULT_IPFS_GATEWAY.to_string(),
+            artifacts_dir: None,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(3),
+            max_size: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// The URI of a token whose metadata has to be resolved.
+#[derive(Debug, Clone)]
+pub struct TokenMetadataRequest {
+    /// The token id as stored in the `tokens` table: `contract_address:id`.
+    pub token_id: String,
+    pub uri: String,
+}
+
+/// Resolves the off-chain metadata of ERC721 and ERC1155 tokens in the background, and stores it
+/// in the `tokens.metadata` column through the executor.
+#[derive(Debug)]
+pub struct TokenMetadataResolver {
+    config: Arc<TokenMetadataConfig>,
+    client: Client,
+    executor: UnboundedSender<QueryMessage>,
+    rx: UnboundedReceiver<TokenMetadataRequest>,
+    shutdown_rx: Receiver<()>,
+    semaphore: Arc<Semaphore>,
+}
+
+impl TokenMetadataResolver {
+    pub fn new(
+        config: TokenMetadataConfig,
+        executor: UnboundedSender<QueryMessage>,
+        shutdown_tx: Sender<()>,
+    ) -> Result<(Self, UnboundedSender<TokenMetadataRequest>)> {
+        let (tx, rx) = unbounded_channel();
+        let shutdown_rx = shutdown_tx.subscribe();
+        let client =
+            Client::builder().connect_timeout(CONNECT_TIMEOUT).timeout(REQUEST_TIMEOUT).build()?;
+
+        Ok((
+            TokenMetadataResolver {
+                config: Arc::new(config),
+                client,
+                executor,
+                rx,
+                shutdown_rx,
+                semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_RESOLUTIONS)),
+            },
+            tx,
+        ))
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        loop {
+            tokio::select! {
+                _ = self.shutdown_rx.recv() => {
+                    debug!(target: LOG_TARGET, "Shutting down token metadata resolver");
+                    break Ok(());
+                }
+                Some(request) = self.rx.recv() => {
+                    let permit = Arc::clone(&self.semaphore).acquire_owned().await?;
+                    let config = Arc::clone(&self.config);
+                    let client = self.client.clone();
+                    let executor = self.executor.clone();
+
+                    tokio::spawn(async move {
+                        let _permit = permit;
+                        if let Err(e) = resolve(&config, &client, &executor, &request).await {
+                            error!(
+                                target: LOG_TARGET,
+                                token_id = %request.token_id,
+                                uri = %request.uri,
+                                error = %e,
+                                "Resolving token metadata."
+                            );
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+async fn resolve(
+    config: &TokenMetadataConfig,
+    client: &Client,
+    executor: &UnboundedSender<QueryMessage>,
+    request: &TokenMetadataRequest,
+) -> Result<()> {
+    let bytes = fetch_uri(config, client, &request.uri).await?;
+    let metadata: serde_json::Value = serde_json::from_slice(&bytes)?;
+
+    if let (Some(artifacts_dir), Some(image)) =
+        (&config.artifacts_dir, metadata.get("image").and_then(|image| image.as_str()))
+    {
+        // A missing image doesn't prevent the metadata from being stored.
+        if let Err(e) = cache_image(config, client, artifacts_dir, &request.token_id, image).await {
+            error!(
+                target: LOG_TARGET,
+                token_id = %request.token_id,
+                image = %image,
+                error = %e,
+                "Caching token image."
+            );
+        }
+    }
+
+    executor.send(QueryMessage::other(
+        "UPDATE tokens SET metadata = ? WHERE id = ?".to_string(),
+        vec![Argument::String(metadata.to_string()), Argument::String(request.token_id.clone())],
+    ))?;
+
+    info!(target: LOG_TARGET, token_id = %request.token_id, "Resolved token metadata.");
+
+    Ok(())
+}
+
+/// Stores the image of a token at `<artifacts_dir>/<contract_address>/<id>/image.<extension>`.
+async fn cache_image(
+    config: &TokenMetadataConfig,
+    client: &Client,
+    artifacts_dir: &Path,
+    token_id: &str,
+    image_uri: &str,
+) -> Result<()> {
+    let bytes = fetch_uri(config, client, image_uri).await?;
+
+    let dir = token_id.split(':').fold(artifacts_dir.to_path_buf(), |dir, part| dir.join(part));
+    tokio::fs::create_dir_all(&dir).await?;
+
+    // The image may have changed format since it was last cached, and the previous one would
+    // otherwise still be served.
+    let mut entries = tokio::fs::read_dir(&dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if Path::new(&entry.file_name()).file_stem().is_some_and(|stem| stem == "image") {
+            tokio::fs::remove_file(entry.path()).await?;
+        }
+    }
+
+    tokio::fs::write(dir.join(format!("image.{}", image_extension(&bytes))), bytes).await?;
+
+    Ok(())
+}
+
+async fn fetch_uri(config: &TokenMetadataConfig, client: &Client, uri: &str) -> Result<Bytes> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let bytes = decode_data_uri(data)?;
+        if bytes.len() > config.max_size {
+            return Err(anyhow!("Data uri exceeds the maximum size of {} bytes", config.max_size));
+        }
+        return Ok(bytes);
+    }
+
+    let url = if let Some(path) = uri.strip_prefix("ipfs://") {
+        format!("{}{}", config.ipfs_gateway, path.trim_start_matches("ipfs/"))
+    } else if uri.starts_with("http://") || uri.starts_with("https://") {
+        uri.to_string()
+    } else {
+        return Err(anyhow!("Unsupported uri: {}", uri));
+    };
+
+    let mut delay = config.retry_delay;
+    let mut attempt = 1;
+    loop {
+        let response =
+            client.get(&url).send().await.and_then(|response| response.error_for_status());
+
+        let error = match response {
+            Ok(response) => match read_body(response, config.max_size).await {
+                Ok(Some(bytes)) => return Ok(bytes),
+                // a larger body won't get any smaller on a retry
+                Ok(None) => {
+                    return Err(anyhow!(
+                        "{} exceeds the maximum size of {} bytes",
+                        url,
+                        config.max_size
+                    ));
+                }
+                Err(e) => e,
+            },
+            Err(e) => e,
+        };
+
+        if attempt >= config.max_retries {
+            return Err(anyhow!(
+                "Failed to fetch {} after {} attempts: {}",
+                url,
+                config.max_retries,
+                error
+            ));
+        }
+
+        debug!(target: LOG_TARGET, url = %url, attempt, error = %error, "Fetching uri.");
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// Reads the body of a response, `None` if it's larger than `max_size` bytes. The body is read in
+/// chunks so that a larger one is never held in memory.
+async fn read_body(mut response: Response, max_size: usize) -> reqwest::Result<Option<Bytes>> {
+    if response.content_length().is_some_and(|length| length > max_size as u64) {
+        return Ok(None);
+    }
+
+    let mut body = BytesMut::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(body.freeze()))
+}
+
+/// Decodes the content of a `data:[<media type>][;base64],<data>` URI, without its scheme.
+fn decode_data_uri(data: &str) -> Result<Bytes> {
+    let (media_type, content) =
+        data.split_once(',').ok_or_else(|| anyhow!("Malformed data uri: data:{}", data))?;
+
+    if media_type.ends_with(";base64") {
+        Ok(general_purpose::STANDARD.decode(content)?.into())
+    } else {
+        percent_decode(content)
+    }
+}
+
+/// Decodes the `%XX` escapes of the content of a data uri, as defined by RFC 3986.
+fn percent_decode(content: &str) -> Result<Bytes> {
+    let mut bytes = content.as_bytes();
+    let mut decoded = BytesMut::with_capacity(bytes.len());
+
+    while let Some((&byte, rest)) = bytes.split_first() {
+        if byte == b'%' {
+            let escape = rest
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| anyhow!("Malformed percent encoding in data uri: {}", content))?;
+            decoded.extend_from_slice(&[escape]);
+            bytes = &rest[2..];
+        } else {
+            decoded.extend_from_slice(&[byte]);
+            bytes = rest;
+        }
+    }
+
+    Ok(decoded.freeze())
+}
+
+fn image_extension(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xff, 0xd8, 0xff, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        _ if bytes.starts_with(b"<svg") || bytes.starts_with(b"<?xml") => "svg",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_data_uri() {
+        let json = r#"{"name":"Sword"}"#;
+
+        let encoded = format!("application/json;base64,{}", general_purpose::STANDARD.encode(json));
+        assert_eq!(decode_data_uri(&encoded).unwrap(), json.as_bytes());
+
+        let plain = format!("application/json,{}", json);
+        assert_eq!(decode_data_uri(&plain).unwrap(), json.as_bytes());
+
+        let escaped = "application/json,%7B%22name%22%3A%22Sword%22%7D";
+        assert_eq!(decode_data_uri(escaped).unwrap(), json.as_bytes());
+        assert!(decode_data_uri("text/plain,100%").is_err());
+        assert!(decode_data_uri("text/plain,%zz").is_err());
+
+        assert!(decode_data_uri("application/json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_uri_max_size() {
+        let config = TokenMetadataConfig { max_size: 4, ..Default::default() };
+        let client = Client::new();
+
+        assert_eq!(fetch_uri(&config, &client, "data:text/plain,1234").await.unwrap(), "1234");
+        assert!(fetch_uri(&config, &client, "data:text/plain,12345").await.is_err());
+    }
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension(&[0x89, b'P', b'N', b'G', 0x0d]), "png");
+        assert_eq!(image_extension(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), "svg");
+        assert_eq!(image_extension(b"unknown"), "bin");
+    }
+}
@@ -9,6 +9,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use starknet::core::types::Felt;
 
+use crate::sql::indexes::ModelIndexConfig;
+use crate::webhooks::WebhookConfig;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SQLFelt(pub Felt);
 
@@ -36,6 +39,8 @@ impl fmt::LowerHex for SQLFelt {
 #[serde(rename_all = "camelCase")]
 pub struct Entity {
     pub id: String,
+    /// The world which set the entity.
+    pub world_address: String,
     pub keys: String,
     pub event_id: String,
     pub executed_at: DateTime<Utc>,
@@ -53,6 +58,8 @@ pub struct Entity {
 #[serde(rename_all = "camelCase")]
 pub struct OptimisticEntity {
     pub id: String,
+    /// The world which set the entity.
+    pub world_address: String,
     pub keys: String,
     pub event_id: String,
     pub executed_at: DateTime<Utc>,
@@ -70,6 +77,8 @@ pub struct OptimisticEntity {
 #[serde(rename_all = "camelCase")]
 pub struct EventMessage {
     pub id: String,
+    /// The world which set the entity.
+    pub world_address: String,
     pub keys: String,
     pub event_id: String,
     pub executed_at: DateTime<Utc>,
@@ -87,6 +96,8 @@ pub struct EventMessage {
 #[serde(rename_all = "camelCase")]
 pub struct OptimisticEventMessage {
     pub id: String,
+    /// The world which set the entity.
+    pub world_address: String,
     pub keys: String,
     pub event_id: String,
     pub executed_at: DateTime<Utc>,
@@ -109,8 +120,67 @@ pub struct Model {
     pub class_hash: String,
     pub contract_address: String,
     pub transaction_hash: String,
+    /// The world which registered the model.
+    pub world_address: String,
+    pub executed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Namespace {
+    pub id: String,
+    pub world_address: String,
+    pub namespace: String,
+    pub executed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldContract {
+    pub id: String,
+    pub world_address: String,
+    pub namespace: String,
+    pub name: String,
+    pub contract_address: String,
+    pub class_hash: String,
+    pub class_hashes: String,
+    pub salt: String,
+    pub initialized: bool,
+    pub init_calldata: String,
     pub executed_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub id: String,
+    pub world_address: String,
+    pub resource: String,
+    pub contract_address: String,
+    pub permission: String,
+    pub granted: bool,
+    pub executed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermissionKind {
+    OWNER,
+    WRITER,
+}
+
+impl std::fmt::Display for PermissionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PermissionKind::OWNER => write!(f, "OWNER"),
+            PermissionKind::WRITER => write!(f, "WRITER"),
+        }
+    }
 }
 
 #[derive(FromRow, Deserialize, Debug, Clone)]
@@ -120,14 +190,81 @@ pub struct Event {
     pub keys: String,
     pub data: String,
     pub transaction_hash: String,
+    /// The world which emitted the event.
+    pub world_address: String,
     pub executed_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub id: String,
+    pub transaction_hash: String,
+    pub sender_address: String,
+    pub calldata: String,
+    pub max_fee: String,
+    pub signature: String,
+    pub nonce: String,
+    pub transaction_type: String,
+    pub executed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A payload to deliver to a webhook, published once stored so that it is only attempted after
+/// being committed.
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    /// Name of the webhook.
+    pub webhook: String,
+    pub payload: String,
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErcTransfer {
+    pub id: String,
+    pub contract_address: String,
+    pub from_address: String,
+    pub to_address: String,
+    pub amount: String,
+    /// `contract_address:token_id`
+    pub token_id: String,
+    pub executed_at: DateTime<Utc>,
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErcBalance {
+    pub id: String,
+    pub balance: String,
+    pub account_address: String,
+    pub contract_address: String,
+    /// `contract_address:token_id`
+    pub token_id: String,
+}
+
 #[derive(Default, Deserialize, Debug, Clone)]
 pub struct ToriiConfig {
     /// contract addresses to index
     pub contracts: VecDeque<Contract>,
+    /// models whose entities are versioned, as `namespace-name`
+    #[serde(default)]
+    pub historical_models: Vec<String>,
+    /// number of blocks for which entity versions are kept, all of them if not set
+    #[serde(default)]
+    pub history_retention: Option<u64>,
+    /// model members searchable with full-text search, as `namespace-name.member`
+    #[serde(default)]
+    pub search_members: Vec<String>,
+    /// indexes of the model tables, on top of the ones of the single members
+    #[serde(default)]
+    pub indexes: Vec<ModelIndexConfig>,
+    /// endpoints the entity, event message and event updates are posted to
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
 }
 
 impl ToriiConfig {
@@ -138,10 +275,16 @@ impl ToriiConfig {
     }
 }
 
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Contract {
     pub address: Felt,
     pub r#type: ContractType,
+    /// ABI of a custom contract, or the Sierra class containing it
+    #[serde(default)]
+    pub abi: Option<PathBuf>,
+    /// name prefixing the tables of a custom contract, its address if not set
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -149,6 +292,8 @@ pub enum ContractType {
     WORLD,
     ERC20,
     ERC721,
+    ERC1155,
+    CUSTOM,
 }
 
 impl FromStr for ContractType {
@@ -159,6 +304,8 @@ impl FromStr for ContractType {
             "world" => Ok(ContractType::WORLD),
             "erc20" => Ok(ContractType::ERC20),
             "erc721" => Ok(ContractType::ERC721),
+            "erc1155" => Ok(ContractType::ERC1155),
+            "custom" => Ok(ContractType::CUSTOM),
             _ => Err(anyhow::anyhow!("Invalid ERC type: {}", input)),
         }
     }
@@ -170,6 +317,8 @@ impl std::fmt::Display for ContractType {
             ContractType::WORLD => write!(f, "WORLD"),
             ContractType::ERC20 => write!(f, "ERC20"),
             ContractType::ERC721 => write!(f, "ERC721"),
+            ContractType::ERC1155 => write!(f, "ERC1155"),
+            ContractType::CUSTOM => write!(f, "CUSTOM"),
         }
     }
 }
@@ -184,3 +333,12 @@ pub struct ContractCursor {
     pub last_pending_block_tx: Option<String>,
     pub last_pending_block_contract_tx: Option<String>,
 }
+
+/// Published once the indexed state has been rolled back to `block_number` after a chain
+/// reorganization. Everything indexed after that block has been discarded and will be
+/// re-indexed from the canonical chain.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollback {
+    pub block_number: u64,
+}
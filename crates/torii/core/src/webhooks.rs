@@ -0,0 +1,650 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use dojo_types::primitive::{Primitive, SqlType};
+use dojo_types::schema::Ty;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::{Pool, Sqlite};
+use starknet::core::types::Felt;
+use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Semaphore;
+use tracing::{debug, error, info, warn};
+
+use crate::executor::{Argument, QueryMessage, QueryType};
+use crate::model::ty_to_json;
+use crate::simple_broker::SimpleBroker;
+use crate::sql::FELT_DELIMITER;
+use crate::types::{Entity, Event, EventMessage, WebhookDelivery};
+
+pub(crate) const LOG_TARGET: &str = "torii_core::webhooks";
+
+/// Header holding the id of a delivery, the same for all of its attempts.
+pub const DELIVERY_HEADER: &str = "X-Torii-Delivery";
+/// Header holding the `sha256=<hex>` HMAC of the payload, keyed with the secret of the webhook.
+pub const SIGNATURE_HEADER: &str = "X-Torii-Signature";
+
+/// Maximum number of deliveries attempted at the same time.
+const MAX_CONCURRENT_DELIVERIES: usize = 10;
+
+/// A webhook of the config file, receiving the updates of a model or the events of a selector.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookConfig {
+    /// Unique name of the webhook, identifying its pending deliveries across restarts.
+    pub name: String,
+    /// Endpoint the updates are POSTed to.
+    pub url: String,
+    /// Secret the payloads are signed with.
+    pub secret: String,
+    /// World whose updates and events are delivered, those of all the indexed worlds if unset.
+    #[serde(default)]
+    pub world_address: Option<Felt>,
+    /// Model whose entity and event message updates are delivered, as `namespace-name`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Selector of the raw events delivered, their first key.
+    #[serde(default)]
+    pub event: Option<Felt>,
+    /// Keys of the entities, or of the events following their selector.
+    #[serde(default)]
+    pub keys: Option<KeysFilter>,
+    /// Conditions on the members of the model, all of which have to match.
+    #[serde(default)]
+    pub members: Vec<MemberFilter>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct KeysFilter {
+    /// The keys to match, `*` matching any key.
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub pattern_matching: PatternMatching,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatternMatching {
+    /// The keys have exactly as many elements as the filter.
+    #[default]
+    FixedLen,
+    /// The keys start with the elements of the filter.
+    VariableLen,
+}
+
+/// Compares a member of the model, `.` separating the members of nested structs, to a value.
+/// Integers are given in decimal or hex, enums by the name of their option.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MemberFilter {
+    pub member: String,
+    pub operator: ComparisonOperator,
+    pub value: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RetryPolicy {
+    /// Number of attempts made to deliver a payload before giving up on it.
+    pub max_attempts: u32,
+    /// Delay in seconds before the first retry, doubled after each failed attempt.
+    pub initial_delay: u64,
+    /// Maximum delay in seconds between two attempts.
+    pub max_delay: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, initial_delay: 1, max_delay: 300 }
+    }
+}
+
+impl WebhookConfig {
+    fn matches_entity(&self, world_address: &str, model: &Ty, keys: &[Felt]) -> bool {
+        self.matches_world(world_address)
+            && self.model.as_ref().is_some_and(|name| *name == model.name())
+            && self.keys.as_ref().map_or(true, |filter| filter.matches(keys))
+            && self.members.iter().all(|filter| filter.matches(model))
+    }
+
+    fn matches_event(&self, world_address: &str, keys: &[Felt]) -> bool {
+        if !self.matches_world(world_address) {
+            return false;
+        }
+
+        match (self.event, keys.split_first()) {
+            (Some(selector), Some((first, keys))) => {
+                selector == *first && self.keys.as_ref().map_or(true, |filter| filter.matches(keys))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_world(&self, world_address: &str) -> bool {
+        self.world_address.map_or(true, |world| {
+            Felt::from_str(world_address).is_ok_and(|address| address == world)
+        })
+    }
+}
+
+impl KeysFilter {
+    fn matches(&self, keys: &[Felt]) -> bool {
+        if self.pattern_matching == PatternMatching::FixedLen && keys.len() != self.keys.len() {
+            return false;
+        }
+
+        keys.len() >= self.keys.len()
+            && self.keys.iter().zip(keys).all(|(expected, key)| {
+                expected == "*" || Felt::from_str(expected).is_ok_and(|expected| expected == *key)
+            })
+    }
+}
+
+impl MemberFilter {
+    fn matches(&self, model: &Ty) -> bool {
+        self.compare(model).is_some_and(|ordering| match self.operator {
+            ComparisonOperator::Eq => ordering == Ordering::Equal,
+            ComparisonOperator::Neq => ordering != Ordering::Equal,
+            ComparisonOperator::Gt => ordering == Ordering::Greater,
+            ComparisonOperator::Gte => ordering != Ordering::Less,
+            ComparisonOperator::Lt => ordering == Ordering::Less,
+            ComparisonOperator::Lte => ordering != Ordering::Greater,
+        })
+    }
+
+    /// Orders the member and the value like their sql values are in the model tables.
+    fn compare(&self, model: &Ty) -> Option<Ordering> {
+        let member =
+            self.member.split('.').try_fold(model, |ty, name| ty.as_struct()?.get(name))?;
+
+        match member {
+            Ty::Primitive(primitive) => {
+                let mut value = *primitive;
+                value.deserialize(&mut primitive_felts(primitive, &self.value)?).ok()?;
+
+                let (member, value) = (primitive.to_sql_value().ok()?, value.to_sql_value().ok()?);
+                match primitive.to_sql_type() {
+                    SqlType::Integer => {
+                        Some(member.parse::<i64>().ok()?.cmp(&value.parse::<i64>().ok()?))
+                    }
+                    SqlType::Text => Some(member.cmp(&value)),
+                }
+            }
+            Ty::Enum(enum_) => Some(enum_.to_sql_value().ok()?.cmp(&self.value)),
+            Ty::ByteArray(bytes) => Some(bytes.cmp(&self.value)),
+            _ => None,
+        }
+    }
+}
+
+/// The felts a primitive is deserialized from, for a value given as a (signed) integer.
+fn primitive_felts(primitive: &Primitive, value: &str) -> Option<Vec<Felt>> {
+    let felt = match value.strip_prefix('-') {
+        Some(value) => -Felt::from_str(value).ok()?,
+        None => Felt::from_str(value).ok()?,
+    };
+
+    match primitive {
+        Primitive::U256(_) => {
+            let bytes = felt.to_bytes_be();
+            Some(vec![
+                Felt::from_bytes_be_slice(&bytes[16..]),
+                Felt::from_bytes_be_slice(&bytes[..16]),
+            ])
+        }
+        _ => Some(vec![felt]),
+    }
+}
+
+/// A payload waiting to be delivered to a webhook.
+#[derive(Debug, Clone)]
+struct Delivery {
+    id: String,
+    webhook: Arc<WebhookConfig>,
+    payload: String,
+    attempts: u32,
+}
+
+/// Delivers the entity, event message and event updates published on the [`SimpleBroker`] to the
+/// webhooks they match. Pending deliveries are stored in the `webhook_deliveries` table through
+/// the executor, attempted once committed and resumed on restart.
+pub struct Webhooks {
+    webhooks: Vec<Arc<WebhookConfig>>,
+    pool: Pool<Sqlite>,
+    client: Client,
+    executor: UnboundedSender<QueryMessage>,
+    shutdown_rx: Receiver<()>,
+    semaphore: Arc<Semaphore>,
+    entities: BoxStream<'static, Entity>,
+    event_messages: BoxStream<'static, EventMessage>,
+    events: BoxStream<'static, Event>,
+    deliveries: BoxStream<'static, WebhookDelivery>,
+}
+
+impl std::fmt::Debug for Webhooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Webhooks").field("webhooks", &self.webhooks).finish_non_exhaustive()
+    }
+}
+
+impl Webhooks {
+    pub fn new(
+        webhooks: Vec<WebhookConfig>,
+        pool: Pool<Sqlite>,
+        executor: UnboundedSender<QueryMessage>,
+        shutdown_tx: Sender<()>,
+    ) -> Result<Self> {
+        for webhook in &webhooks {
+            if webhook.model.is_some() == webhook.event.is_some() {
+                return Err(anyhow!(
+                    "Webhook {} must have either a model or an event selector",
+                    webhook.name
+                ));
+            }
+        }
+
+        Ok(Self {
+            webhooks: webhooks.into_iter().map(Arc::new).collect(),
+            pool,
+            client: Client::builder().timeout(Duration::from_secs(10)).build()?,
+            executor,
+            shutdown_rx: shutdown_tx.subscribe(),
+            semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_DELIVERIES)),
+            // subscribe right away so that no update is missed before running
+            entities: SimpleBroker::<Entity>::subscribe().boxed(),
+            event_messages: SimpleBroker::<EventMessage>::subscribe().boxed(),
+            events: SimpleBroker::<Event>::subscribe().boxed(),
+            deliveries: SimpleBroker::<WebhookDelivery>::subscribe().boxed(),
+        })
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        self.resume_deliveries().await?;
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown_rx.recv() => {
+                    debug!(target: LOG_TARGET, "Shutting down webhooks");
+                    break Ok(());
+                }
+                Some(entity) = self.entities.next() => {
+                    if let Some(model) = &entity.updated_model {
+                        let payload = json!({
+                            "type": "entity",
+                            "id": entity.id,
+                            "worldAddress": entity.world_address,
+                            "keys": felts_value(&entity.keys),
+                            "eventId": entity.event_id,
+                            "model": model.name(),
+                            "deleted": entity.deleted,
+                            "data": if entity.deleted { Value::Null } else { ty_to_json(model) },
+                            "executedAt": entity.executed_at.to_rfc3339(),
+                        });
+                        self.dispatch_model_update(
+                            &entity.world_address,
+                            &entity.id,
+                            &entity.event_id,
+                            &entity.keys,
+                            model,
+                            payload,
+                        )?;
+                    }
+                }
+                Some(event_message) = self.event_messages.next() => {
+                    if let Some(model) = &event_message.updated_model {
+                        let payload = json!({
+                            "type": "eventMessage",
+                            "id": event_message.id,
+                            "worldAddress": event_message.world_address,
+                            "keys": felts_value(&event_message.keys),
+                            "eventId": event_message.event_id,
+                            "model": model.name(),
+                            "data": ty_to_json(model),
+                            "executedAt": event_message.executed_at.to_rfc3339(),
+                        });
+                        self.dispatch_model_update(
+                            &event_message.world_address,
+                            &event_message.id,
+                            &event_message.event_id,
+                            &event_message.keys,
+                            model,
+                            payload,
+                        )?;
+                    }
+                }
+                Some(event) = self.events.next() => {
+                    let keys = parse_keys(&event.keys);
+                    let payload = json!({
+                        "type": "event",
+                        "id": event.id,
+                        "worldAddress": event.world_address,
+                        "keys": felts_value(&event.keys),
+                        "data": felts_value(&event.data),
+                        "transactionHash": event.transaction_hash,
+                        "executedAt": event.executed_at.to_rfc3339(),
+                    });
+                    let webhooks = self
+                        .webhooks
+                        .iter()
+                        .filter(|webhook| webhook.matches_event(&event.world_address, &keys));
+                    for webhook in webhooks {
+                        self.enqueue(webhook, format!("{}:{}", webhook.name, event.id), &payload)?;
+                    }
+                }
+                Some(delivery) = self.deliveries.next() => {
+                    // the config of the webhook is the one it was stored with
+                    if let Some(webhook) =
+                        self.webhooks.iter().find(|webhook| webhook.name == delivery.webhook)
+                    {
+                        self.spawn_delivery(Delivery {
+                            id: delivery.id,
+                            webhook: Arc::clone(webhook),
+                            payload: delivery.payload,
+                            attempts: 0,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn dispatch_model_update(
+        &self,
+        world_address: &str,
+        id: &str,
+        event_id: &str,
+        keys: &str,
+        model: &Ty,
+        payload: Value,
+    ) -> Result<()> {
+        let keys = parse_keys(keys);
+        let webhooks = self
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.matches_entity(world_address, model, &keys));
+        for webhook in webhooks {
+            self.enqueue(webhook, format!("{}:{}:{}", webhook.name, event_id, id), &payload)?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the delivery of the payload, which is attempted once the executor published it
+    /// after committing it. Deliveries already stored, e.g. when indexing again the same blocks,
+    /// aren't attempted twice.
+    fn enqueue(&self, webhook: &WebhookConfig, id: String, payload: &Value) -> Result<()> {
+        self.executor.send(QueryMessage::new(
+            "INSERT OR IGNORE INTO webhook_deliveries (id, webhook, payload) VALUES (?, ?, ?) \
+             RETURNING id, webhook, payload"
+                .to_string(),
+            vec![
+                Argument::String(id),
+                Argument::String(webhook.name.clone()),
+                Argument::String(payload.to_string()),
+            ],
+            QueryType::StoreWebhookDelivery,
+        ))?;
+
+        Ok(())
+    }
+
+    /// Attempts again the deliveries that were pending when Torii stopped. The ones of webhooks
+    /// that were removed from the config are dropped.
+    async fn resume_deliveries(&self) -> Result<()> {
+        let rows: Vec<(String, String, String, i64)> = sqlx::query_as(
+            "SELECT id, webhook, payload, attempts FROM webhook_deliveries WHERE NOT failed ORDER \
+             BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (id, name, payload, attempts) in rows {
+            match self.webhooks.iter().find(|webhook| webhook.name == name) {
+                Some(webhook) => self.spawn_delivery(Delivery {
+                    id,
+                    webhook: Arc::clone(webhook),
+                    payload,
+                    attempts: attempts as u32,
+                }),
+                None => {
+                    warn!(
+                        target: LOG_TARGET,
+                        webhook = %name,
+                        id = %id,
+                        "Dropping delivery of unknown webhook."
+                    );
+                    self.executor.send(QueryMessage::other(
+                        "DELETE FROM webhook_deliveries WHERE id = ?".to_string(),
+                        vec![Argument::String(id)],
+                    ))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn spawn_delivery(&self, delivery: Delivery) {
+        let client = self.client.clone();
+        let executor = self.executor.clone();
+        let semaphore = Arc::clone(&self.semaphore);
+
+        tokio::spawn(async move {
+            if let Err(e) = deliver(&client, &executor, &semaphore, delivery).await {
+                error!(target: LOG_TARGET, error = %e, "Delivering webhook.");
+            }
+        });
+    }
+}
+
+/// Attempts to POST the payload until it succeeds or the retry policy of the webhook gives up,
+/// recording each failed attempt. Failed deliveries are kept but not attempted again.
+async fn deliver(
+    client: &Client,
+    executor: &UnboundedSender<QueryMessage>,
+    semaphore: &Semaphore,
+    mut delivery: Delivery,
+) -> Result<()> {
+    let webhook = Arc::clone(&delivery.webhook);
+    let signature = sign(&webhook.secret, delivery.payload.as_bytes())?;
+    let retry = &webhook.retry;
+
+    loop {
+        let response = {
+            let _permit = semaphore.acquire().await?;
+            client
+                .post(&webhook.url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(DELIVERY_HEADER, &delivery.id)
+                .header(SIGNATURE_HEADER, &signature)
+                .body(delivery.payload.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+        };
+
+        let error = match response {
+            Ok(_) => {
+                executor.send(QueryMessage::other(
+                    "DELETE FROM webhook_deliveries WHERE id = ?".to_string(),
+                    vec![Argument::String(delivery.id.clone())],
+                ))?;
+                info!(
+                    target: LOG_TARGET,
+                    webhook = %webhook.name,
+                    id = %delivery.id,
+                    "Delivered webhook."
+                );
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        delivery.attempts += 1;
+        let failed = delivery.attempts >= retry.max_attempts;
+        executor.send(QueryMessage::other(
+            "UPDATE webhook_deliveries SET attempts = ?, last_error = ?, failed = ? WHERE id = ?"
+                .to_string(),
+            vec![
+                Argument::Int(delivery.attempts as i64),
+                Argument::String(error.to_string()),
+                Argument::Bool(failed),
+                Argument::String(delivery.id.clone()),
+            ],
+        ))?;
+
+        if failed {
+            return Err(anyhow!(
+                "Failed to deliver {} to webhook {} after {} attempts: {}",
+                delivery.id,
+                webhook.name,
+                delivery.attempts,
+                error
+            ));
+        }
+
+        let delay = retry
+            .initial_delay
+            .saturating_mul(1 << (delivery.attempts - 1).min(32))
+            .min(retry.max_delay);
+        debug!(
+            target: LOG_TARGET,
+            webhook = %webhook.name,
+            id = %delivery.id,
+            attempt = delivery.attempts,
+            error = %error,
+            "Delivering webhook."
+        );
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+    }
+}
+
+/// `sha256=<hex>` HMAC of the payload.
+fn sign(secret: &str, payload: &[u8]) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow!("Invalid webhook secret: {}", e))?;
+    mac.update(payload);
+    Ok(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+}
+
+fn parse_keys(keys: &str) -> Vec<Felt> {
+    keys.split(FELT_DELIMITER).filter_map(|key| Felt::from_str(key).ok()).collect()
+}
+
+fn felts_value(felts: &str) -> Value {
+    Value::from(felts.split(FELT_DELIMITER).filter(|felt| !felt.is_empty()).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct};
+
+    use super::*;
+
+    fn position() -> Ty {
+        Ty::Struct(Struct {
+            name: "ns-Position".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(Felt::from(0x1234)))),
+                    key: true,
+                },
+                Member {
+                    name: "x".to_string(),
+                    ty: Ty::Primitive(Primitive::U32(Some(10))),
+                    key: false,
+                },
+                Member {
+                    name: "direction".to_string(),
+                    ty: Ty::Enum(Enum {
+                        name: "Direction".to_string(),
+                        option: Some(1),
+                        options: vec![
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                    key: false,
+                },
+            ],
+        })
+    }
+
+    fn member_filter(member: &str, operator: ComparisonOperator, value: &str) -> MemberFilter {
+        MemberFilter { member: member.to_string(), operator, value: value.to_string() }
+    }
+
+    #[test]
+    fn test_keys_filter() {
+        let keys = [Felt::from(1), Felt::from(2)];
+        let filter = |keys: &[&str], pattern_matching| KeysFilter {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            pattern_matching,
+        };
+
+        assert!(filter(&["0x1", "*"], PatternMatching::FixedLen).matches(&keys));
+        assert!(!filter(&["0x1"], PatternMatching::FixedLen).matches(&keys));
+        assert!(filter(&["0x1"], PatternMatching::VariableLen).matches(&keys));
+        assert!(!filter(&["0x2", "*"], PatternMatching::FixedLen).matches(&keys));
+    }
+
+    #[test]
+    fn test_member_filter() {
+        let model = position();
+
+        assert!(member_filter("x", ComparisonOperator::Gte, "10").matches(&model));
+        assert!(member_filter("x", ComparisonOperator::Lt, "0xb").matches(&model));
+        assert!(!member_filter("x", ComparisonOperator::Gt, "10").matches(&model));
+        assert!(member_filter("player", ComparisonOperator::Eq, "0x1234").matches(&model));
+        assert!(member_filter("direction", ComparisonOperator::Eq, "Right").matches(&model));
+        assert!(!member_filter("missing", ComparisonOperator::Eq, "0").matches(&model));
+    }
+
+    #[test]
+    fn test_world_filter() {
+        let webhook: WebhookConfig = serde_json::from_value(json!({
+            "name": "hook",
+            "url": "http://localhost:8080",
+            "secret": "secret",
+            "world_address": "0x1",
+            "model": "ns-Position",
+        }))
+        .unwrap();
+        let keys = [Felt::from(0x1234)];
+
+        assert!(webhook.matches_entity("0x1", &position(), &keys));
+        assert!(!webhook.matches_entity("0x2", &position(), &keys));
+
+        let all_worlds = WebhookConfig { world_address: None, ..webhook };
+        assert!(all_worlds.matches_entity("0x2", &position(), &keys));
+    }
+
+    #[test]
+    fn test_sign() {
+        // HMAC-SHA256 test vector of RFC 4231
+        let signature = sign("Jefe", b"what do ya want for nothing?").unwrap();
+        assert_eq!(
+            signature,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -4,11 +4,15 @@ pub const DEFAULT_LIMIT: u64 = 10;
 pub const BOOLEAN_TRUE: i64 = 1;
 
 pub const ENTITY_TABLE: &str = "entities";
+pub const ENTITY_HISTORICAL_TABLE: &str = "entities_historical";
 pub const EVENT_TABLE: &str = "events";
 pub const EVENT_MESSAGE_TABLE: &str = "event_messages";
 pub const MODEL_TABLE: &str = "models";
 pub const TRANSACTION_TABLE: &str = "transactions";
 pub const METADATA_TABLE: &str = "metadata";
+pub const NAMESPACE_TABLE: &str = "namespaces";
+pub const WORLD_CONTRACT_TABLE: &str = "world_contracts";
+pub const PERMISSION_TABLE: &str = "permissions";
 
 pub const ID_COLUMN: &str = "id";
 pub const EVENT_ID_COLUMN: &str = "event_id";
@@ -16,8 +20,12 @@ pub const ENTITY_ID_COLUMN: &str = "entity_id";
 pub const EVENT_MESSAGE_ID_COLUMN: &str = "event_message_id";
 pub const JSON_COLUMN: &str = "json";
 pub const TRANSACTION_HASH_COLUMN: &str = "transaction_hash";
+pub const WORLD_ADDRESS_COLUMN: &str = "world_address";
 
 pub const INTERNAL_ENTITY_ID_KEY: &str = "$entity_id$";
+pub const INTERNAL_WORLD_ADDRESS_KEY: &str = "$world_address$";
+pub const INTERNAL_HISTORY_BOUNDS_KEY: &str = "$history_bounds$";
+pub const INTERNAL_MODELS_DIFF_KEY: &str = "$models_diff$";
 
 // objects namespaced to avoid conflicts with user models
 pub const ENTITY_TYPE_NAME: &str = "World__Entity";
@@ -29,6 +37,10 @@ pub const CONTENT_TYPE_NAME: &str = "World__Content";
 pub const METADATA_TYPE_NAME: &str = "World__Metadata";
 pub const PAGE_INFO_TYPE_NAME: &str = "World__PageInfo";
 pub const TRANSACTION_TYPE_NAME: &str = "World__Transaction";
+pub const NAMESPACE_TYPE_NAME: &str = "World__Namespace";
+pub const WORLD_CONTRACT_TYPE_NAME: &str = "World__Contract";
+pub const PERMISSION_TYPE_NAME: &str = "World__Permission";
+pub const ROLLBACK_TYPE_NAME: &str = "World__Rollback";
 pub const QUERY_TYPE_NAME: &str = "World__Query";
 pub const SUBSCRIPTION_TYPE_NAME: &str = "World__Subscription";
 pub const MODEL_ORDER_TYPE_NAME: &str = "World__ModelOrder";
@@ -46,6 +58,10 @@ pub const SOCIAL_NAMES: (&str, &str) = ("social", "socials");
 pub const CONTENT_NAMES: (&str, &str) = ("content", "contents");
 pub const METADATA_NAMES: (&str, &str) = ("metadata", "metadatas");
 pub const TRANSACTION_NAMES: (&str, &str) = ("transaction", "transactions");
+pub const NAMESPACE_NAMES: (&str, &str) = ("namespace", "namespaces");
+pub const WORLD_CONTRACT_NAMES: (&str, &str) = ("worldContract", "worldContracts");
+pub const PERMISSION_NAMES: (&str, &str) = ("permission", "permissions");
+pub const ROLLBACK_NAMES: (&str, &str) = ("rollback", "");
 pub const PAGE_INFO_NAMES: (&str, &str) = ("pageInfo", "");
 
 pub const ERC_BALANCE_NAME: (&str, &str) = ("ercBalance", "");
@@ -10,6 +10,7 @@ use crate::types::{GraphqlType, TypeData, TypeMapping};
 lazy_static! {
     pub static ref ENTITY_TYPE_MAPPING: TypeMapping = IndexMap::from([
         (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("worldAddress"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
         (Name::new("keys"), TypeData::Simple(TypeRef::named_list(TypeRef::STRING))),
         (Name::new("eventId"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
         (
@@ -38,6 +39,7 @@ lazy_static! {
             TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
         ),
         (Name::new("transactionHash"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("worldAddress"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
     ]);
     pub static ref MODEL_TYPE_MAPPING: TypeMapping = IndexMap::from([
         (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
@@ -145,6 +147,89 @@ lazy_static! {
         ),
     ]);
 
+    pub static ref NAMESPACE_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("worldAddress"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("namespace"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("createdAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
+    pub static ref WORLD_CONTRACT_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("worldAddress"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("namespace"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("name"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("contractAddress"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (
+            Name::new("classHash"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (
+            Name::new("classHashes"),
+            TypeData::Simple(TypeRef::named_list(Primitive::Felt252(None).to_string())),
+        ),
+        (Name::new("salt"), TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string()))),
+        (
+            Name::new("initialized"),
+            TypeData::Simple(TypeRef::named(Primitive::Bool(None).to_string())),
+        ),
+        (
+            Name::new("initCalldata"),
+            TypeData::Simple(TypeRef::named_list(Primitive::Felt252(None).to_string())),
+        ),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("createdAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("updatedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
+    pub static ref PERMISSION_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("worldAddress"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("resource"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (
+            Name::new("contractAddress"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (Name::new("permission"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("granted"), TypeData::Simple(TypeRef::named(Primitive::Bool(None).to_string()))),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("createdAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("updatedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
+
+    pub static ref ROLLBACK_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("blockNumber"), TypeData::Simple(TypeRef::named_nn(TypeRef::INT))),
+    ]);
+
     pub static ref ERC_BALANCE_TYPE_MAPPING: TypeMapping = IndexMap::from([
         (Name::new("balance"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
         (Name::new("type"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
@@ -167,5 +252,6 @@ lazy_static! {
         (Name::new("tokenId"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
         (Name::new("decimals"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
         (Name::new("contractAddress"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
+        (Name::new("metadata"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
     ]);
 }
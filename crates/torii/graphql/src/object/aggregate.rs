@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use async_graphql::dynamic::{Enum, Field, FieldFuture, InputValue, Object, TypeRef};
+use async_graphql::{Name, Value};
+use crypto_bigint::U512;
+use dojo_types::primitive::{Primitive, SqlType};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite};
+use tokio_stream::StreamExt;
+
+use super::inputs::where_input::{parse_where_argument, where_argument};
+use super::inputs::world_input::{parse_world_argument, world_argument, world_filter};
+use super::{TypeMapping, ValueMapping};
+use crate::query::{
+    build_aggregate_query, build_hex_totals_query, remove_hex_leading_zeros,
+    value_mapping_from_row, AGGREGATE_FUNCTIONS, BOUND_FUNCTIONS,
+};
+use crate::types::TypeData;
+
+/// Sqlite can't parse the hex strings the larger unsigned members are stored as, so they are
+/// summed over every filtered record rather than by sqlite.
+const TOTALS_DESCRIPTION: &str = "The i8 to i64, u8 to u32 and usize members are summed and \
+                                  averaged as floats. The u64, u128, u256 and felt252 members are \
+                                  summed and averaged as hex strings, the averages being rounded \
+                                  down.";
+
+/// Unsigned members are at most 256 bits, so their sum can't overflow.
+type HexTotal = U512;
+
+/// Sums and number of values of the hex members of the records of a group, keyed by the values of
+/// the group.
+type HexTotals = HashMap<String, Vec<(HexTotal, u64)>>;
+
+/// Aggregations of a model: the number of records and the sum, average, minimum and maximum of
+/// its integer and unsigned members, optionally grouped by its key and enum members. The members
+/// of nested structs, enums and arrays, stored in their own tables, aren't aggregated.
+#[derive(Debug)]
+pub struct AggregateObject {
+    pub type_name: String,
+    /// Members stored as sqlite integers.
+    pub integer_members: TypeMapping,
+    /// Unsigned members stored as zero padded hex strings, which sqlite can order but not sum.
+    pub hex_members: TypeMapping,
+    pub group_members: TypeMapping,
+}
+
+impl AggregateObject {
+    pub fn new(type_name: &str, type_mapping: &TypeMapping, key_members: &[String]) -> Self {
+        let simple_members = type_mapping.iter().filter(|(_, type_data)| type_data.is_simple());
+        let primitive_members = simple_members.clone().filter_map(|(name, type_data)| {
+            let primitive = Primitive::from_str(&type_data.type_ref().to_string()).ok()?;
+            Some((name, type_data, primitive))
+        });
+
+        let integer_members = primitive_members
+            .clone()
+            .filter(|(_, _, primitive)| {
+                !matches!(primitive, Primitive::Bool(_))
+                    && primitive.to_sql_type() == SqlType::Integer
+            })
+            .map(|(name, type_data, _)| (name.clone(), type_data.clone()))
+            .collect();
+
+        // signed integers are stored in two's complement, and addresses and class hashes aren't
+        // numbers
+        let hex_members = primitive_members
+            .filter(|(_, _, primitive)| {
+                matches!(
+                    primitive,
+                    Primitive::U64(_)
+                        | Primitive::U128(_)
+                        | Primitive::U256(_)
+                        | Primitive::Felt252(_)
+                )
+            })
+            .map(|(name, type_data, _)| (name.clone(), type_data.clone()))
+            .collect();
+
+        let group_members = simple_members
+            .filter(|(name, type_data)| {
+                key_members.iter().any(|key| key == name.as_str())
+                    || type_data.type_ref() == TypeRef::named("Enum")
+            })
+            .map(|(name, type_data)| (name.clone(), type_data.clone()))
+            .collect();
+
+        Self {
+            type_name: format!("{}Aggregate", type_name),
+            integer_members,
+            hex_members,
+            group_members,
+        }
+    }
+
+    pub fn objects(&self) -> Vec<Object> {
+        let mut aggregate = Object::new(&self.type_name)
+            .field(value_field(Name::new("countBy"), TypeRef::named_nn(TypeRef::INT)));
+        let mut objects = Vec::new();
+
+        if !self.group_members.is_empty() {
+            let group_type_name = format!("{}Group", self.type_name);
+            aggregate =
+                aggregate.field(value_field(Name::new("group"), TypeRef::named(&group_type_name)));
+            objects.push(simple_object(&group_type_name, &self.group_members));
+        }
+
+        if !self.integer_members.is_empty() || !self.hex_members.is_empty() {
+            // sums and averages don't fit the type of the members
+            let total = |name: &Name, type_name: &str| {
+                (name.clone(), TypeData::Simple(TypeRef::named(type_name)))
+            };
+            let totals = self
+                .integer_members
+                .keys()
+                .map(|name| total(name, TypeRef::FLOAT))
+                .chain(self.hex_members.keys().map(|name| total(name, TypeRef::STRING)))
+                .collect();
+            let totals_type_name = format!("{}Totals", self.type_name);
+
+            for function in ["sum", "avg"] {
+                let field = value_field(Name::new(function), TypeRef::named(&totals_type_name));
+                aggregate = aggregate.field(field.description(TOTALS_DESCRIPTION));
+            }
+            objects.push(simple_object(&totals_type_name, &totals).description(TOTALS_DESCRIPTION));
+        }
+
+        if !self.integer_members.is_empty() || !self.hex_members.is_empty() {
+            let bounds_type_name = format!("{}Bounds", self.type_name);
+            let bounds = self
+                .integer_members
+                .iter()
+                .chain(self.hex_members.iter())
+                .map(|(name, type_data)| (name.clone(), type_data.clone()))
+                .collect();
+
+            for function in BOUND_FUNCTIONS {
+                aggregate = aggregate
+                    .field(value_field(Name::new(function), TypeRef::named(&bounds_type_name)));
+            }
+            objects.push(simple_object(&bounds_type_name, &bounds));
+        }
+
+        objects.push(aggregate);
+        objects
+    }
+
+    pub fn enum_objects(&self) -> Option<Vec<Enum>> {
+        if self.group_members.is_empty() {
+            return None;
+        }
+
+        let group_by = self
+            .group_members
+            .keys()
+            .fold(Enum::new(self.group_by_type_name()), |acc, name| acc.item(name.to_uppercase()));
+        Some(vec![group_by])
+    }
+
+    /// Field resolving the aggregations of the model table, filtered by the `where` input of the
+    /// model and grouped by the `groupBy` argument.
+    pub fn field(
+        &self,
+        field_name: &str,
+        model_type_name: &str,
+        table_name: String,
+        where_mapping: TypeMapping,
+    ) -> Field {
+        let integer_members = self.integer_members.clone();
+        let hex_members = self.hex_members.clone();
+        let group_members = self.group_members.clone();
+
+        let mut field =
+            Field::new(field_name, TypeRef::named_nn_list_nn(&self.type_name), move |ctx| {
+                let integer_members = integer_members.clone();
+                let hex_members = hex_members.clone();
+                let group_members = group_members.clone();
+                let where_mapping = where_mapping.clone();
+                let table_name = table_name.clone();
+
+                FieldFuture::new(async move {
+                    let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                    let mut filters = parse_where_argument(&ctx, &where_mapping, &table_name)?;
+                    if let Some(world) = parse_world_argument(&ctx)? {
+                        filters
+                            .get_or_insert_with(Vec::new)
+                            .push(world_filter(&table_name, &world));
+                    }
+
+                    // enum items are the upper cased member names
+                    let group_by: TypeMapping = match ctx.args.get("groupBy") {
+                        Some(group_by) => group_by
+                            .list()?
+                            .iter()
+                            .map(|item| {
+                                let item = item.enum_name()?;
+                                group_members
+                                    .iter()
+                                    .find(|(name, _)| name.to_uppercase() == item)
+                                    .map(|(name, type_data)| (name.clone(), type_data.clone()))
+                                    .ok_or_else(|| {
+                                        format!("Unknown group by member {}", item).into()
+                                    })
+                            })
+                            .collect::<async_graphql::Result<_>>()?,
+                        None => TypeMapping::new(),
+                    };
+
+                    let names = |members: &TypeMapping| {
+                        members.keys().map(|name| name.to_string()).collect::<Vec<_>>()
+                    };
+
+                    let mut hex_totals = HexTotals::new();
+                    if !hex_members.is_empty() {
+                        let query = build_hex_totals_query(
+                            &table_name,
+                            &names(&hex_members),
+                            &names(&group_by),
+                            &filters,
+                        );
+                        let mut rows = sqlx::query(&query).fetch(&mut *conn);
+                        while let Some(row) = rows.next().await {
+                            add_hex_totals(&mut hex_totals, &row?, &hex_members, &group_by)?;
+                        }
+                    }
+
+                    let query = build_aggregate_query(
+                        &table_name,
+                        &names(&integer_members),
+                        &names(&hex_members),
+                        &names(&group_by),
+                        &filters,
+                    );
+                    let rows = sqlx::query(&query).fetch_all(&mut *conn).await?;
+
+                    let aggregates = rows
+                        .iter()
+                        .map(|row| {
+                            aggregate_value(
+                                row,
+                                &integer_members,
+                                &hex_members,
+                                &group_by,
+                                &hex_totals,
+                            )
+                        })
+                        .collect::<sqlx::Result<Vec<_>>>()?;
+
+                    Ok(Some(Value::List(aggregates)))
+                })
+            });
+
+        field = where_argument(field, model_type_name);
+        field = world_argument(field);
+        if !self.group_members.is_empty() {
+            field = field.argument(InputValue::new(
+                "groupBy",
+                TypeRef::named_nn_list(self.group_by_type_name()),
+            ));
+        }
+
+        field
+    }
+
+    fn group_by_type_name(&self) -> String {
+        format!("{}GroupBy", self.type_name)
+    }
+}
+
+/// Adds the hex members of a record to the totals of its group.
+fn add_hex_totals(
+    hex_totals: &mut HexTotals,
+    row: &SqliteRow,
+    hex_members: &TypeMapping,
+    group_by: &TypeMapping,
+) -> sqlx::Result<()> {
+    let totals = hex_totals
+        .entry(group_key(row, group_by)?)
+        .or_insert_with(|| vec![(HexTotal::ZERO, 0); hex_members.len()]);
+
+    for ((sum, count), name) in totals.iter_mut().zip(hex_members.keys()) {
+        let column = format!("external_{name}");
+        let Some(value) = row.try_get::<Option<String>, &str>(&column)? else {
+            continue;
+        };
+
+        let digits = value.trim_start_matches("0x");
+        if digits.len() > 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(sqlx::Error::ColumnDecode {
+                index: column,
+                source: format!("invalid hex value {value}").into(),
+            });
+        }
+
+        *sum = sum.wrapping_add(&HexTotal::from_be_hex(&format!("{digits:0>128}")));
+        *count += 1;
+    }
+
+    Ok(())
+}
+
+/// Identifies the group of a row by the values of its `group_by` members.
+fn group_key(row: &SqliteRow, group_by: &TypeMapping) -> sqlx::Result<String> {
+    Ok(Value::Object(value_mapping_from_row(row, group_by, true)?).to_string())
+}
+
+fn aggregate_value(
+    row: &SqliteRow,
+    integer_members: &TypeMapping,
+    hex_members: &TypeMapping,
+    group_by: &TypeMapping,
+    hex_totals: &HexTotals,
+) -> sqlx::Result<Value> {
+    let mut aggregate = ValueMapping::new();
+    aggregate.insert(Name::new("countBy"), Value::from(row.try_get::<i64, &str>("count_by")?));
+
+    if !group_by.is_empty() {
+        aggregate.insert(
+            Name::new("group"),
+            Value::Object(value_mapping_from_row(row, group_by, true)?),
+        );
+    }
+
+    if integer_members.is_empty() && hex_members.is_empty() {
+        return Ok(Value::Object(aggregate));
+    }
+
+    // without grouping, there are no totals if no record is filtered
+    let totals = hex_totals.get(&group_key(row, group_by)?);
+
+    for function in AGGREGATE_FUNCTIONS {
+        let is_bound = BOUND_FUNCTIONS.contains(&function);
+
+        let mut values = integer_members
+            .keys()
+            .map(|name| {
+                let column = format!("{function}_{name}");
+                let value = if is_bound {
+                    row.try_get::<Option<i64>, &str>(&column)?.map(Value::from)
+                } else {
+                    row.try_get::<Option<f64>, &str>(&column)?.map(Value::from)
+                };
+                Ok((name.clone(), value.unwrap_or(Value::Null)))
+            })
+            .collect::<sqlx::Result<ValueMapping>>()?;
+        for (i, name) in hex_members.keys().enumerate() {
+            let value = if is_bound {
+                row.try_get::<Option<String>, &str>(format!("{function}_{name}").as_str())?
+            } else {
+                let (sum, count) = totals.map_or((HexTotal::ZERO, 0), |totals| totals[i]);
+                match function {
+                    // like `TOTAL`, the sum of no values is zero
+                    "sum" => Some(format!("0x{:x}", sum)),
+                    _ => (count > 0)
+                        .then(|| format!("0x{:x}", sum.wrapping_div(&HexTotal::from_u64(count)))),
+                }
+            };
+            values.insert(
+                name.clone(),
+                value.map_or(Value::Null, |v| remove_hex_leading_zeros(Value::from(v))),
+            );
+        }
+
+        aggregate.insert(Name::new(function), Value::Object(values));
+    }
+
+    Ok(Value::Object(aggregate))
+}
+
+fn simple_object(type_name: &str, type_mapping: &TypeMapping) -> Object {
+    type_mapping.iter().fold(Object::new(type_name), |object, (name, type_data)| {
+        object.field(value_field(name.clone(), type_data.type_ref()))
+    })
+}
+
+fn value_field(field_name: Name, type_ref: TypeRef) -> Field {
+    Field::new(field_name.to_string(), type_ref, move |ctx| {
+        let field_name = field_name.clone();
+
+        FieldFuture::new(async move {
+            match ctx.parent_value.try_to_value()? {
+                Value::Object(values) => Ok(values.get(&field_name).cloned()),
+                _ => Err("incorrect value, requires Value::Object".into()),
+            }
+        })
+    })
+}
@@ -1,27 +1,46 @@
+use std::collections::HashMap;
+
 use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::{
-    Field, FieldFuture, FieldValue, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+    Field, FieldFuture, FieldValue, InputValue, ResolverContext, SubscriptionField,
+    SubscriptionFieldFuture, TypeRef,
 };
 use async_graphql::{Name, Value};
 use async_recursion::async_recursion;
+use chrono::DateTime;
+use dojo_types::schema::{Member, Struct, Ty};
 use sqlx::pool::PoolConnection;
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use starknet_crypto::Felt;
 use tokio_stream::StreamExt;
+use torii_core::model::ty_to_json;
 use torii_core::simple_broker::SimpleBroker;
+use torii_core::sql::cache::ModelCache;
+use torii_core::sql::history::HistoryBounds;
+use torii_core::sql::utils::sql_string_to_felts;
 use torii_core::types::Entity;
 
-use super::inputs::keys_input::keys_argument;
+use super::connection::{connection_arguments, connection_output, parse_connection_arguments};
+use super::inputs::keys_input::{keys_argument, parse_keys_argument};
+use super::inputs::order_input::parse_order_argument;
+use super::inputs::world_input::{parse_world_argument, world_argument, world_filter};
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
-    DATETIME_FORMAT, ENTITY_ID_COLUMN, ENTITY_NAMES, ENTITY_TABLE, ENTITY_TYPE_NAME,
-    EVENT_ID_COLUMN, ID_COLUMN,
+    DATETIME_FORMAT, ENTITY_HISTORICAL_TABLE, ENTITY_ID_COLUMN, ENTITY_NAMES, ENTITY_TABLE,
+    ENTITY_TYPE_NAME, EVENT_ID_COLUMN, ID_COLUMN, INTERNAL_ENTITY_ID_KEY,
+    INTERNAL_HISTORY_BOUNDS_KEY, INTERNAL_MODELS_DIFF_KEY, INTERNAL_WORLD_ADDRESS_KEY,
 };
 use crate::mapping::ENTITY_TYPE_MAPPING;
-use crate::object::{resolve_many, resolve_one};
+use crate::object::resolve_one;
+use crate::query::data::{count_rows, fetch_multiple_rows};
 use crate::query::{type_mapping_query, value_mapping_from_row};
-use crate::types::TypeData;
+use crate::types::{GraphqlType, TypeData};
 use crate::utils;
 
+/// Temporary table holding the entities within the bounds requested by the `atBlock`, `fromTime`
+/// and `toTime` arguments.
+const ENTITY_AT_BOUNDS_TABLE: &str = "entities_at_bounds";
+
 #[derive(Debug)]
 pub struct EntityObject;
 
@@ -45,48 +64,70 @@ impl BasicObject for EntityObject {
 
 impl ResolvableObject for EntityObject {
     fn resolvers(&self) -> Vec<Field> {
-        let resolve_one = resolve_one(
+        let resolve_one = world_argument(resolve_one(
             ENTITY_TABLE,
             ID_COLUMN,
             self.name().0,
             self.type_name(),
             self.type_mapping(),
-        );
+        ));
 
-        let mut resolve_many = resolve_many(
-            ENTITY_TABLE,
-            EVENT_ID_COLUMN,
-            self.name().1,
-            self.type_name(),
-            self.type_mapping(),
-        );
-        resolve_many = keys_argument(resolve_many);
+        let resolve_many =
+            resolve_many_at_block(self.name().1, self.type_name(), self.type_mapping());
 
         vec![resolve_one, resolve_many]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new("entityUpdated", TypeRef::named_nn(self.type_name()), |ctx| {
+        Some(vec![SubscriptionField::new(
+            "entityUpdated",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
                 SubscriptionFieldFuture::new(async move {
                     let id = match ctx.args.get("id") {
                         Some(id) => Some(id.string()?.to_string()),
                         None => None,
                     };
+                    let world = parse_world_argument(&ctx)?;
+                    let mut diffs = parse_diff_argument(&ctx)?;
                     // if id is None, then subscribe to all entities
                     // if id is Some, then subscribe to only the entity with that id
                     Ok(SimpleBroker::<Entity>::subscribe().filter_map(move |entity: Entity| {
-                        if id.is_none() || id == Some(entity.id.clone()) {
-                            Some(Ok(Value::Object(EntityObject::value_mapping(entity))))
+                        if world.as_ref().is_some_and(|world| *world != entity.world_address) {
+                            // set by another world, still listening
+                            None
+                        } else if id.is_none() || id == Some(entity.id.clone()) {
+                            let diff = match &mut diffs {
+                                Some(diffs) => match diffs.update(
+                                    &entity.world_address,
+                                    &entity.id,
+                                    entity.updated_model.as_ref(),
+                                    entity.deleted,
+                                ) {
+                                    // none of the members sent to the subscriber changed
+                                    Ok(None) => return None,
+                                    Ok(diff) => diff,
+                                    Err(e) => return Some(Err(e)),
+                                },
+                                None => None,
+                            };
+
+                            let mut value = EntityObject::value_mapping(entity);
+                            if let Some(diff) = diff {
+                                value.insert(Name::new(INTERNAL_MODELS_DIFF_KEY), diff);
+                            }
+                            Some(Ok(Value::Object(value)))
                         } else {
                             // id != entity.id , then don't send anything, still listening
                             None
                         }
                     }))
                 })
-            })
-            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
-        ])
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))
+        .argument(InputValue::new("diff", TypeRef::named(TypeRef::BOOLEAN)))])
     }
 }
 
@@ -95,6 +136,7 @@ impl EntityObject {
         let keys: Vec<&str> = entity.keys.split('/').filter(|&k| !k.is_empty()).collect();
         IndexMap::from([
             (Name::new("id"), Value::from(entity.id)),
+            (Name::new("worldAddress"), Value::from(entity.world_address)),
             (Name::new("keys"), Value::from(keys)),
             (Name::new("eventId"), Value::from(entity.event_id)),
             (
@@ -113,6 +155,234 @@ impl EntityObject {
     }
 }
 
+/// The models sent to a subscription with the `diff` argument, whose updates only resolve the
+/// members which changed since the previous update of their model. The first update of a model
+/// holds all of its members, unless it only updates some of them.
+#[derive(Debug, Default)]
+pub(crate) struct EntityDiffs {
+    /// The members of the models by world and entity id.
+    entities: HashMap<(String, String), HashMap<String, Vec<Member>>>,
+}
+
+impl EntityDiffs {
+    /// The models of the update resolved by the subscription, `None` if none of the members sent
+    /// changed. When a model or the entity is deleted, the remaining models are resolved with all
+    /// of their members, the diff being null.
+    pub(crate) fn update(
+        &mut self,
+        world_address: &str,
+        id: &str,
+        updated_model: Option<&Ty>,
+        deleted: bool,
+    ) -> async_graphql::Result<Option<Value>> {
+        let entity = (world_address.to_string(), id.to_string());
+        let model = match updated_model.and_then(|model| model.as_struct()) {
+            Some(model) if !deleted && !model.children.is_empty() => model,
+            _ => {
+                self.entities.remove(&entity);
+                return Ok(Some(Value::Null));
+            }
+        };
+
+        let known = self.entities.entry(entity).or_default().entry(model.name.clone()).or_default();
+        let changed = model
+            .children
+            .iter()
+            .filter(|member| !known.contains(member))
+            .cloned()
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return Ok(None);
+        }
+
+        for member in &changed {
+            match known.iter_mut().find(|known| known.name == member.name) {
+                Some(known) => *known = member.clone(),
+                None => known.push(member.clone()),
+            }
+        }
+
+        // the models are named after their tag
+        let (namespace, name) = model.name.split_once('-').unwrap_or(("", &model.name));
+        let data = Value::from_json(ty_to_json(&Ty::Struct(Struct {
+            name: model.name.clone(),
+            children: changed,
+        })))?;
+
+        Ok(Some(Value::Object(IndexMap::from([(
+            Name::new(utils::type_name_from_names(namespace, name)),
+            data,
+        )]))))
+    }
+}
+
+/// The diffs of the entities sent to the subscription, if it has the `diff` argument.
+pub(crate) fn parse_diff_argument(
+    ctx: &ResolverContext<'_>,
+) -> async_graphql::Result<Option<EntityDiffs>> {
+    let diff = match ctx.args.get("diff") {
+        Some(diff) => diff.boolean()?,
+        None => false,
+    };
+
+    Ok(diff.then(EntityDiffs::default))
+}
+
+/// The models of an entity resolved from the diff of its update, if it was sent to a subscription
+/// receiving diffs.
+pub(crate) fn models_from_diff(
+    entity: &ValueMapping,
+    entity_id: &str,
+    world_address: &str,
+) -> Option<Vec<FieldValue<'static>>> {
+    let Some(Value::Object(models)) = entity.get(INTERNAL_MODELS_DIFF_KEY) else {
+        return None;
+    };
+
+    let results = models
+        .iter()
+        .filter_map(|(type_name, data)| {
+            let Value::Object(data) = data else {
+                return None;
+            };
+
+            let mut data = data.clone();
+            data.insert(Name::new(INTERNAL_ENTITY_ID_KEY), Value::from(entity_id));
+            data.insert(Name::new(INTERNAL_WORLD_ADDRESS_KEY), Value::from(world_address));
+            Some(FieldValue::with_type(FieldValue::owned_any(data), type_name.to_string()))
+        })
+        .collect();
+
+    Some(results)
+}
+
+/// Resolves the entities at their latest state, or from the versions of the historical models as
+/// they were at the block given by the `atBlock` argument and at the end of the `fromTime` and
+/// `toTime` range, only with the models updated within it.
+fn resolve_many_at_block(field_name: &str, type_name: &str, type_mapping: &TypeMapping) -> Field {
+    let type_mapping = type_mapping.clone();
+
+    let mut field =
+        Field::new(field_name, TypeRef::named(format!("{}Connection", type_name)), move |ctx| {
+            let type_mapping = type_mapping.clone();
+
+            FieldFuture::new(async move {
+                let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                let connection = parse_connection_arguments(&ctx)?;
+                let keys = parse_keys_argument(&ctx)?;
+                let order = parse_order_argument(&ctx);
+                let bounds = parse_history_bounds(&ctx)?;
+                let filters = parse_world_argument(&ctx)?
+                    .map(|world| vec![world_filter(ENTITY_TABLE, &world)]);
+
+                let table_name = if bounds.is_latest() {
+                    ENTITY_TABLE
+                } else {
+                    create_entities_at_bounds_table(&mut conn, &bounds).await?;
+                    ENTITY_AT_BOUNDS_TABLE
+                };
+
+                let total_count = count_rows(&mut conn, table_name, &keys, &filters).await?;
+                let (data, page_info) = fetch_multiple_rows(
+                    &mut conn,
+                    table_name,
+                    EVENT_ID_COLUMN,
+                    &keys,
+                    &order,
+                    &filters,
+                    &connection,
+                    total_count,
+                )
+                .await?;
+                let mut results = connection_output(
+                    &data,
+                    &type_mapping,
+                    &order,
+                    EVENT_ID_COLUMN,
+                    total_count,
+                    false,
+                    page_info,
+                )?;
+
+                if !bounds.is_latest() {
+                    sqlx::query(&format!("DROP TABLE {ENTITY_AT_BOUNDS_TABLE}"))
+                        .execute(&mut *conn)
+                        .await?;
+
+                    // the models of the entities are then resolved within the same bounds
+                    let bounds = async_graphql::to_value(bounds)?;
+                    if let Some(Value::List(edges)) = results.get_mut(&Name::new("edges")) {
+                        for edge in edges {
+                            if let Value::Object(edge) = edge {
+                                if let Some(Value::Object(node)) = edge.get_mut(&Name::new("node"))
+                                {
+                                    node.insert(
+                                        Name::new(INTERNAL_HISTORY_BOUNDS_KEY),
+                                        bounds.clone(),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Ok(Some(Value::Object(results)))
+            })
+        });
+
+    field = connection_arguments(field);
+    field = keys_argument(field);
+    field = world_argument(field);
+    field
+        .argument(InputValue::new("atBlock", TypeRef::named(TypeRef::INT)))
+        .argument(InputValue::new("fromTime", TypeRef::named(GraphqlType::DateTime.to_string())))
+        .argument(InputValue::new("toTime", TypeRef::named(GraphqlType::DateTime.to_string())))
+}
+
+fn parse_history_bounds(ctx: &ResolverContext<'_>) -> async_graphql::Result<HistoryBounds> {
+    let time = |name: &str| -> async_graphql::Result<Option<u64>> {
+        let Some(time) = ctx.args.get(name) else {
+            return Ok(None);
+        };
+        let timestamp = DateTime::parse_from_rfc3339(time.string()?)?.timestamp();
+        Ok(Some(u64::try_from(timestamp).map_err(|_| format!("{name} is before the epoch"))?))
+    };
+
+    let at_block = match ctx.args.get("atBlock") {
+        Some(block) => Some(u64::try_from(block.i64()?).map_err(|_| "atBlock is negative")?),
+        None => None,
+    };
+
+    Ok(HistoryBounds { at_block, from_timestamp: time("fromTime")?, to_timestamp: time("toTime")? })
+}
+
+/// Fills a temporary table, only visible to this connection, with the entities that had at least
+/// one model within `bounds`.
+async fn create_entities_at_bounds_table(
+    conn: &mut SqliteConnection,
+    bounds: &HistoryBounds,
+) -> async_graphql::Result<()> {
+    // left over by a previous request that failed before dropping it
+    sqlx::query(&format!("DROP TABLE IF EXISTS {ENTITY_AT_BOUNDS_TABLE}"))
+        .execute(&mut *conn)
+        .await?;
+
+    let (latest_version, arguments) = bounds.latest_version_condition()?;
+    let statement = format!(
+        "CREATE TEMPORARY TABLE {ENTITY_AT_BOUNDS_TABLE} AS SELECT h.id, h.world_address, h.keys, \
+         MAX(h.event_id) AS event_id, MAX(h.executed_at) AS executed_at, MIN(h.created_at) AS \
+         created_at, MAX(h.created_at) AS updated_at FROM {ENTITY_HISTORICAL_TABLE} h WHERE \
+         {latest_version} GROUP BY h.id, h.world_address"
+    );
+    let mut query = sqlx::query(&statement);
+    for argument in arguments {
+        query = query.bind(argument);
+    }
+    query.execute(&mut *conn).await?;
+
+    Ok(())
+}
+
 fn model_union_field() -> Field {
     Field::new("models", TypeRef::named_list("ModelUnion"), move |ctx| {
         FieldFuture::new(async move {
@@ -121,6 +391,21 @@ fn model_union_field() -> Field {
                     let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
 
                     let entity_id = utils::extract::<String>(indexmap, "id")?;
+                    let world_address = utils::extract::<String>(indexmap, "worldAddress")?;
+
+                    if let Some(results) = models_from_diff(indexmap, &entity_id, &world_address) {
+                        return Ok(Some(FieldValue::list(results)));
+                    }
+
+                    if let Some(bounds) = indexmap.get(INTERNAL_HISTORY_BOUNDS_KEY) {
+                        let bounds: HistoryBounds = async_graphql::from_value(bounds.clone())?;
+                        let pool = ctx.data::<Pool<Sqlite>>()?.clone();
+                        let results =
+                            models_at_bounds(&mut conn, pool, &entity_id, &world_address, &bounds)
+                                .await?;
+                        return Ok(Some(FieldValue::list(results)));
+                    }
+
                     // fetch name from the models table
                     // using the model id (hashed model name)
                     let model_ids: Vec<(String, String, String)> = sqlx::query_as(
@@ -129,10 +414,11 @@ fn model_union_field() -> Field {
                         WHERE id IN (    
                             SELECT model_id
                             FROM entity_model
-                            WHERE entity_id = ?
+                            WHERE entity_id = ? AND world_address = ?
                         )",
                     )
                     .bind(&entity_id)
+                    .bind(&world_address)
                     .fetch_all(&mut *conn)
                     .await?;
 
@@ -147,6 +433,7 @@ fn model_union_field() -> Field {
                             ENTITY_ID_COLUMN,
                             vec![format!("{namespace}-{name}")],
                             &entity_id,
+                            &world_address,
                             &[],
                             &type_mapping,
                             false,
@@ -171,6 +458,47 @@ fn model_union_field() -> Field {
     })
 }
 
+/// Resolves the models of an entity from their latest version within `bounds`.
+async fn models_at_bounds(
+    conn: &mut PoolConnection<Sqlite>,
+    pool: Pool<Sqlite>,
+    entity_id: &str,
+    world_address: &str,
+    bounds: &HistoryBounds,
+) -> async_graphql::Result<Vec<FieldValue<'static>>> {
+    let (latest_version, arguments) = bounds.latest_version_condition()?;
+    let statement = format!(
+        "SELECT h.model_id, m.namespace, m.name, h.data FROM {ENTITY_HISTORICAL_TABLE} h JOIN \
+         models m ON h.model_id = m.id WHERE h.id = ? AND h.world_address = ? AND {latest_version}"
+    );
+    let mut query = sqlx::query_as(&statement).bind(entity_id).bind(world_address);
+    for argument in arguments {
+        query = query.bind(argument);
+    }
+    let versions: Vec<(String, String, String, String)> = query.fetch_all(conn.as_mut()).await?;
+
+    let model_cache = ModelCache::new(pool);
+    let mut results = Vec::with_capacity(versions.len());
+    for (model_id, namespace, name, data) in versions {
+        let mut schema = model_cache.model(&Felt::from_hex(&model_id)?).await?.schema;
+        schema.deserialize(&mut sql_string_to_felts(&data))?;
+
+        let mut data: ValueMapping = match Value::from_json(ty_to_json(&schema))? {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        data.insert(Name::new(INTERNAL_ENTITY_ID_KEY), Value::from(entity_id));
+        data.insert(Name::new(INTERNAL_WORLD_ADDRESS_KEY), Value::from(world_address));
+
+        results.push(FieldValue::with_type(
+            FieldValue::owned_any(data),
+            utils::type_name_from_names(&namespace, &name),
+        ));
+    }
+
+    Ok(results)
+}
+
 // TODO: flatten query
 #[async_recursion]
 pub async fn model_data_recursive_query(
@@ -178,6 +506,7 @@ pub async fn model_data_recursive_query(
     entity_id_column: &str,
     path_array: Vec<String>,
     entity_id: &str,
+    world_address: &str,
     indexes: &[i64],
     type_mapping: &TypeMapping,
     is_list: bool,
@@ -185,8 +514,10 @@ pub async fn model_data_recursive_query(
     // For nested types, we need to remove prefix in path array
     let namespace = format!("{}_", path_array[0]);
     let table_name = &path_array.join("$").replace(&namespace, "");
-    let mut query =
-        format!("SELECT * FROM [{}] WHERE {entity_id_column} = '{}' ", table_name, entity_id);
+    let mut query = format!(
+        "SELECT * FROM [{}] WHERE {entity_id_column} = '{}' AND world_address = '{}' ",
+        table_name, entity_id, world_address
+    );
     for (column_idx, index) in indexes.iter().enumerate() {
         query.push_str(&format!("AND idx_{} = {} ", column_idx, index));
     }
@@ -212,6 +543,7 @@ pub async fn model_data_recursive_query(
                     entity_id_column,
                     nested_path,
                     entity_id,
+                    world_address,
                     &if is_list {
                         let mut indexes = indexes.to_vec();
                         indexes.push(idx as i64);
@@ -234,6 +566,7 @@ pub async fn model_data_recursive_query(
                     entity_id_column,
                     nested_path,
                     entity_id,
+                    world_address,
                     // this might need to be changed to support 2d+ arrays
                     &if is_list {
                         let mut indexes = indexes.to_vec();
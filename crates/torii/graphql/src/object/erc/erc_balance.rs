@@ -1,10 +1,15 @@
-use async_graphql::dynamic::{Field, FieldFuture, InputValue, TypeRef};
-use async_graphql::{Name, Value};
+use async_graphql::dynamic::{
+    Field, FieldFuture, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+};
+use async_graphql::{Error, Name, Value};
 use convert_case::{Case, Casing};
 use serde::Deserialize;
 use sqlx::{FromRow, Pool, Sqlite, SqliteConnection};
 use starknet_crypto::Felt;
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::utils::felt_to_sql_string;
+use torii_core::types::ErcBalance;
 use tracing::warn;
 
 use crate::constants::{ERC_BALANCE_NAME, ERC_BALANCE_TYPE_NAME};
@@ -54,81 +59,123 @@ impl ResolvableObject for ErcBalanceObject {
         .argument(argument);
         vec![field]
     }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        let account_address = "account_address";
+        Some(vec![SubscriptionField::new(
+            "ercBalanceUpdated",
+            TypeRef::named_nn(self.type_name()),
+            move |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let address = extract::<Felt>(
+                        ctx.args.as_index_map(),
+                        &account_address.to_case(Case::Camel),
+                    )?;
+                    let address = felt_to_sql_string(&address);
+                    let pool = ctx.data::<Pool<Sqlite>>()?.clone();
+
+                    let balances = SimpleBroker::<ErcBalance>::subscribe()
+                        .filter(move |balance| balance.account_address == address)
+                        .then(move |balance: ErcBalance| {
+                            let pool = pool.clone();
+                            async move {
+                                let mut conn = pool.acquire().await?;
+                                fetch_erc_balance(&mut conn, &balance.id).await
+                            }
+                        });
+                    Ok(balances.filter_map(|balance| balance.map_err(Error::from).transpose()))
+                })
+            },
+        )
+        .argument(InputValue::new(
+            account_address.to_case(Case::Camel),
+            TypeRef::named_nn(TypeRef::STRING),
+        ))])
+    }
 }
 
+const BALANCE_QUERY: &str = "SELECT t.contract_address, t.name, t.symbol, t.decimals, t.metadata, \
+                             b.balance, b.token_id, c.contract_type FROM balances b JOIN tokens t \
+                             ON b.token_id = t.id JOIN contracts c ON t.contract_address = \
+                             c.contract_address";
+
 async fn fetch_erc_balances(
     conn: &mut SqliteConnection,
     address: Felt,
 ) -> sqlx::Result<Vec<Value>> {
-    let query = "SELECT t.contract_address, t.name, t.symbol, t.decimals, b.balance, b.token_id, \
-                 c.contract_type
-         FROM balances b
-         JOIN tokens t ON b.token_id = t.id
-         JOIN contracts c ON t.contract_address = c.contract_address
-         WHERE b.account_address = ?";
+    let query = format!("{BALANCE_QUERY} WHERE b.account_address = ?");
 
-    let rows = sqlx::query(query).bind(felt_to_sql_string(&address)).fetch_all(conn).await?;
+    let rows = sqlx::query(&query).bind(felt_to_sql_string(&address)).fetch_all(conn).await?;
 
     let mut erc_balances = Vec::new();
 
     for row in rows {
         let row = BalanceQueryResultRaw::from_row(&row)?;
-
-        let balance_value = match row.contract_type.to_lowercase().as_str() {
-            "erc20" => {
-                let token_metadata = Value::Object(ValueMapping::from([
-                    (Name::new("name"), Value::String(row.name)),
-                    (Name::new("symbol"), Value::String(row.symbol)),
-                    // for erc20 there is no token_id
-                    (Name::new("tokenId"), Value::Null),
-                    (Name::new("decimals"), Value::String(row.decimals.to_string())),
-                    (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
-                ]));
-
-                Value::Object(ValueMapping::from([
-                    (Name::new("balance"), Value::String(row.balance)),
-                    (Name::new("type"), Value::String(row.contract_type)),
-                    (Name::new("tokenMetadata"), token_metadata),
-                ]))
-            }
-            "erc721" => {
-                // contract_address:token_id
-                let token_id = row.token_id.split(':').collect::<Vec<&str>>();
-                assert!(token_id.len() == 2);
-
-                let token_metadata = Value::Object(ValueMapping::from([
-                    (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
-                    (Name::new("name"), Value::String(row.name)),
-                    (Name::new("symbol"), Value::String(row.symbol)),
-                    (Name::new("tokenId"), Value::String(token_id[1].to_string())),
-                    (Name::new("decimals"), Value::String(row.decimals.to_string())),
-                ]));
-
-                Value::Object(ValueMapping::from([
-                    (Name::new("balance"), Value::String(row.balance)),
-                    (Name::new("type"), Value::String(row.contract_type)),
-                    (Name::new("tokenMetadata"), token_metadata),
-                ]))
-            }
-            _ => {
-                warn!("Unknown contract type: {}", row.contract_type);
-                continue;
-            }
-        };
-
-        erc_balances.push(balance_value);
+        if let Some(balance_value) = balance_value(row) {
+            erc_balances.push(balance_value);
+        }
     }
 
     Ok(erc_balances)
 }
 
-// TODO: This would be required when subscriptions are needed
-// impl ErcBalanceObject {
-//     pub fn value_mapping(entity: ErcBalance) -> ValueMapping {
-//         IndexMap::from([
-//         ])
-//     }
-// }
+async fn fetch_erc_balance(conn: &mut SqliteConnection, id: &str) -> sqlx::Result<Option<Value>> {
+    let query = format!("{BALANCE_QUERY} WHERE b.id = ?");
+    let row = sqlx::query(&query).bind(id).fetch_optional(conn).await?;
+
+    match row {
+        Some(row) => Ok(balance_value(BalanceQueryResultRaw::from_row(&row)?)),
+        None => Ok(None),
+    }
+}
+
+fn balance_value(row: BalanceQueryResultRaw) -> Option<Value> {
+    let balance_value = match row.contract_type.to_lowercase().as_str() {
+        "erc20" => {
+            let token_metadata = Value::Object(ValueMapping::from([
+                (Name::new("name"), Value::String(row.name)),
+                (Name::new("symbol"), Value::String(row.symbol)),
+                // for erc20 there is no token_id
+                (Name::new("tokenId"), Value::Null),
+                (Name::new("decimals"), Value::String(row.decimals.to_string())),
+                (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
+                (Name::new("metadata"), Value::Null),
+            ]));
+
+            Value::Object(ValueMapping::from([
+                (Name::new("balance"), Value::String(row.balance)),
+                (Name::new("type"), Value::String(row.contract_type)),
+                (Name::new("tokenMetadata"), token_metadata),
+            ]))
+        }
+        "erc721" | "erc1155" => {
+            // contract_address:token_id
+            let token_id = row.token_id.split(':').collect::<Vec<&str>>();
+            assert!(token_id.len() == 2);
+
+            let token_metadata = Value::Object(ValueMapping::from([
+                (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
+                (Name::new("name"), Value::String(row.name)),
+                (Name::new("symbol"), Value::String(row.symbol)),
+                (Name::new("tokenId"), Value::String(token_id[1].to_string())),
+                (Name::new("decimals"), Value::String(row.decimals.to_string())),
+                (Name::new("metadata"), row.metadata.map(Value::String).unwrap_or(Value::Null)),
+            ]));
+
+            Value::Object(ValueMapping::from([
+                (Name::new("balance"), Value::String(row.balance)),
+                (Name::new("type"), Value::String(row.contract_type)),
+                (Name::new("tokenMetadata"), token_metadata),
+            ]))
+        }
+        _ => {
+            warn!("Unknown contract type: {}", row.contract_type);
+            return None;
+        }
+    };
+
+    Some(balance_value)
+}
 
 #[derive(FromRow, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -137,6 +184,7 @@ struct BalanceQueryResultRaw {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
+    pub metadata: Option<String>,
     pub token_id: String,
     pub balance: String,
     pub contract_type: String,
@@ -1,11 +1,16 @@
-use async_graphql::dynamic::{Field, FieldFuture, InputValue, TypeRef};
-use async_graphql::{Name, Value};
+use async_graphql::dynamic::{
+    Field, FieldFuture, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+};
+use async_graphql::{Error, Name, Value};
 use convert_case::{Case, Casing};
 use serde::Deserialize;
 use sqlx::{FromRow, Pool, Sqlite, SqliteConnection};
 use starknet_crypto::Felt;
+use tokio_stream::StreamExt;
 use torii_core::engine::get_transaction_hash_from_event_id;
+use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::utils::felt_to_sql_string;
+use torii_core::types::ErcTransfer;
 use tracing::warn;
 
 use crate::constants::{ERC_TRANSFER_NAME, ERC_TRANSFER_TYPE_NAME};
@@ -61,15 +66,45 @@ impl ResolvableObject for ErcTransferObject {
         .argument(arg_limit);
         vec![field]
     }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        let account_address = "account_address";
+        Some(vec![SubscriptionField::new(
+            "ercTransfer",
+            TypeRef::named_nn(self.type_name()),
+            move |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let address = extract::<Felt>(
+                        ctx.args.as_index_map(),
+                        &account_address.to_case(Case::Camel),
+                    )?;
+                    let address = felt_to_sql_string(&address);
+                    let pool = ctx.data::<Pool<Sqlite>>()?.clone();
+
+                    // only transfers from or to the account, joined with their token once stored
+                    let transfers = SimpleBroker::<ErcTransfer>::subscribe()
+                        .filter(move |transfer| {
+                            transfer.from_address == address || transfer.to_address == address
+                        })
+                        .then(move |transfer: ErcTransfer| {
+                            let pool = pool.clone();
+                            async move {
+                                let mut conn = pool.acquire().await?;
+                                fetch_erc_transfer(&mut conn, &transfer.id).await
+                            }
+                        });
+                    Ok(transfers.filter_map(|transfer| transfer.map_err(Error::from).transpose()))
+                })
+            },
+        )
+        .argument(InputValue::new(
+            account_address.to_case(Case::Camel),
+            TypeRef::named_nn(TypeRef::STRING),
+        ))])
+    }
 }
 
-async fn fetch_erc_transfers(
-    conn: &mut SqliteConnection,
-    address: Felt,
-    limit: u32,
-) -> sqlx::Result<Vec<Value>> {
-    let query = format!(
-        r#"
+const TRANSFER_QUERY: &str = r#"
 SELECT
     et.id,
     et.contract_address,
@@ -81,6 +116,7 @@ SELECT
     t.name,
     t.symbol,
     t.decimals,
+    t.metadata,
     c.contract_type
 FROM
     erc_transfers et
@@ -88,87 +124,105 @@ JOIN
     tokens t ON et.token_id = t.id
 JOIN
     contracts c ON t.contract_address = c.contract_address
+"#;
+
+async fn fetch_erc_transfers(
+    conn: &mut SqliteConnection,
+    address: Felt,
+    limit: u32,
+) -> sqlx::Result<Vec<Value>> {
+    let query = format!(
+        r#"{TRANSFER_QUERY}
 WHERE
     et.from_address = ? OR et.to_address = ?
 ORDER BY
     et.executed_at DESC
-LIMIT {};
-"#,
-        limit
+LIMIT {limit};
+"#
     );
 
     let address = felt_to_sql_string(&address);
     let rows = sqlx::query(&query).bind(&address).bind(&address).fetch_all(conn).await?;
 
-    let mut erc_balances = Vec::new();
+    let mut erc_transfers = Vec::new();
 
     for row in rows {
         let row = TransferQueryResultRaw::from_row(&row)?;
-        let transaction_hash = get_transaction_hash_from_event_id(&row.id);
-
-        let transfer_value = match row.contract_type.to_lowercase().as_str() {
-            "erc20" => {
-                let token_metadata = Value::Object(ValueMapping::from([
-                    (Name::new("name"), Value::String(row.name)),
-                    (Name::new("symbol"), Value::String(row.symbol)),
-                    // for erc20 there is no token_id
-                    (Name::new("tokenId"), Value::Null),
-                    (Name::new("decimals"), Value::String(row.decimals.to_string())),
-                    (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
-                ]));
-
-                Value::Object(ValueMapping::from([
-                    (Name::new("from"), Value::String(row.from_address)),
-                    (Name::new("to"), Value::String(row.to_address)),
-                    (Name::new("amount"), Value::String(row.amount)),
-                    (Name::new("type"), Value::String(row.contract_type)),
-                    (Name::new("executedAt"), Value::String(row.executed_at)),
-                    (Name::new("tokenMetadata"), token_metadata),
-                    (Name::new("transactionHash"), Value::String(transaction_hash)),
-                ]))
-            }
-            "erc721" => {
-                // contract_address:token_id
-                let token_id = row.token_id.split(':').collect::<Vec<&str>>();
-                assert!(token_id.len() == 2);
-
-                let token_metadata = Value::Object(ValueMapping::from([
-                    (Name::new("name"), Value::String(row.name)),
-                    (Name::new("symbol"), Value::String(row.symbol)),
-                    (Name::new("tokenId"), Value::String(token_id[1].to_string())),
-                    (Name::new("decimals"), Value::String(row.decimals.to_string())),
-                    (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
-                ]));
-
-                Value::Object(ValueMapping::from([
-                    (Name::new("from"), Value::String(row.from_address)),
-                    (Name::new("to"), Value::String(row.to_address)),
-                    (Name::new("amount"), Value::String(row.amount)),
-                    (Name::new("type"), Value::String(row.contract_type)),
-                    (Name::new("executedAt"), Value::String(row.executed_at)),
-                    (Name::new("tokenMetadata"), token_metadata),
-                    (Name::new("transactionHash"), Value::String(transaction_hash)),
-                ]))
-            }
-            _ => {
-                warn!("Unknown contract type: {}", row.contract_type);
-                continue;
-            }
-        };
-
-        erc_balances.push(transfer_value);
+        if let Some(transfer_value) = transfer_value(row) {
+            erc_transfers.push(transfer_value);
+        }
     }
 
-    Ok(erc_balances)
+    Ok(erc_transfers)
 }
 
-// TODO: This would be required when subscriptions are needed
-// impl ErcTransferObject {
-//     pub fn value_mapping(entity: ErcBalance) -> ValueMapping {
-//         IndexMap::from([
-//         ])
-//     }
-// }
+async fn fetch_erc_transfer(conn: &mut SqliteConnection, id: &str) -> sqlx::Result<Option<Value>> {
+    let query = format!("{TRANSFER_QUERY} WHERE et.id = ?");
+    let row = sqlx::query(&query).bind(id).fetch_optional(conn).await?;
+
+    match row {
+        Some(row) => Ok(transfer_value(TransferQueryResultRaw::from_row(&row)?)),
+        None => Ok(None),
+    }
+}
+
+fn transfer_value(row: TransferQueryResultRaw) -> Option<Value> {
+    let transaction_hash = get_transaction_hash_from_event_id(&row.id);
+
+    let transfer_value = match row.contract_type.to_lowercase().as_str() {
+        "erc20" => {
+            let token_metadata = Value::Object(ValueMapping::from([
+                (Name::new("name"), Value::String(row.name)),
+                (Name::new("symbol"), Value::String(row.symbol)),
+                // for erc20 there is no token_id
+                (Name::new("tokenId"), Value::Null),
+                (Name::new("decimals"), Value::String(row.decimals.to_string())),
+                (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
+                (Name::new("metadata"), Value::Null),
+            ]));
+
+            Value::Object(ValueMapping::from([
+                (Name::new("from"), Value::String(row.from_address)),
+                (Name::new("to"), Value::String(row.to_address)),
+                (Name::new("amount"), Value::String(row.amount)),
+                (Name::new("type"), Value::String(row.contract_type)),
+                (Name::new("executedAt"), Value::String(row.executed_at)),
+                (Name::new("tokenMetadata"), token_metadata),
+                (Name::new("transactionHash"), Value::String(transaction_hash)),
+            ]))
+        }
+        "erc721" | "erc1155" => {
+            // contract_address:token_id
+            let token_id = row.token_id.split(':').collect::<Vec<&str>>();
+            assert!(token_id.len() == 2);
+
+            let token_metadata = Value::Object(ValueMapping::from([
+                (Name::new("name"), Value::String(row.name)),
+                (Name::new("symbol"), Value::String(row.symbol)),
+                (Name::new("tokenId"), Value::String(token_id[1].to_string())),
+                (Name::new("decimals"), Value::String(row.decimals.to_string())),
+                (Name::new("contractAddress"), Value::String(row.contract_address.clone())),
+                (Name::new("metadata"), row.metadata.map(Value::String).unwrap_or(Value::Null)),
+            ]));
+
+            Value::Object(ValueMapping::from([
+                (Name::new("from"), Value::String(row.from_address)),
+                (Name::new("to"), Value::String(row.to_address)),
+                (Name::new("amount"), Value::String(row.amount)),
+                (Name::new("type"), Value::String(row.contract_type)),
+                (Name::new("executedAt"), Value::String(row.executed_at)),
+                (Name::new("tokenMetadata"), token_metadata),
+                (Name::new("transactionHash"), Value::String(transaction_hash)),
+            ]))
+        }
+        _ => {
+            warn!("Unknown contract type: {}", row.contract_type);
+            return None;
+        }
+    };
+
+    Some(transfer_value)
+}
 
 #[derive(FromRow, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -183,5 +237,6 @@ struct TransferQueryResultRaw {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
+    pub metadata: Option<String>,
     pub contract_type: String,
 }
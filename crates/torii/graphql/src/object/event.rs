@@ -8,6 +8,7 @@ use torii_core::sql::FELT_DELIMITER;
 use torii_core::types::Event;
 
 use super::inputs::keys_input::{keys_argument, parse_keys_argument};
+use super::inputs::world_input::{parse_world_argument, world_argument};
 use super::{resolve_many, BasicObject, ResolvableObject, TypeMapping};
 use crate::constants::{DATETIME_FORMAT, EVENT_NAMES, EVENT_TABLE, EVENT_TYPE_NAME, ID_COLUMN};
 use crate::mapping::EVENT_TYPE_MAPPING;
@@ -40,20 +41,25 @@ impl ResolvableObject for EventObject {
             self.type_mapping(),
         );
         resolve_many = keys_argument(resolve_many);
+        resolve_many = world_argument(resolve_many);
 
         vec![resolve_many]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new("eventEmitted", TypeRef::named_nn(self.type_name()), |ctx| {
+        Some(vec![SubscriptionField::new(
+            "eventEmitted",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
                 SubscriptionFieldFuture::new(async move {
                     let input_keys = parse_keys_argument(&ctx)?;
-                    Ok(EventObject::subscription_stream(input_keys))
+                    let world = parse_world_argument(&ctx)?;
+                    Ok(EventObject::subscription_stream(input_keys, world))
                 })
-            })
-            .argument(InputValue::new("keys", TypeRef::named_list(TypeRef::STRING))),
-        ])
+            },
+        )
+        .argument(InputValue::new("keys", TypeRef::named_list(TypeRef::STRING)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))])
     }
 }
 
@@ -66,6 +72,7 @@ impl EventObject {
             (Name::new("keys"), Value::from(keys)),
             (Name::new("data"), Value::from(data)),
             (Name::new("transactionHash"), Value::from(event.transaction_hash)),
+            (Name::new("worldAddress"), Value::from(event.world_address)),
             (
                 Name::new("createdAt"),
                 Value::from(event.created_at.format(DATETIME_FORMAT).to_string()),
@@ -77,8 +84,16 @@ impl EventObject {
         ])
     }
 
-    fn subscription_stream(input_keys: Option<Vec<String>>) -> impl Stream<Item = Result<Value>> {
+    fn subscription_stream(
+        input_keys: Option<Vec<String>>,
+        world: Option<String>,
+    ) -> impl Stream<Item = Result<Value>> {
         SimpleBroker::<Event>::subscribe().filter_map(move |event| {
+            if world.as_ref().is_some_and(|world| *world != event.world_address) {
+                // emitted by another world, keep listening
+                return None;
+            }
+
             EventObject::match_and_map_event(&input_keys, event)
                 .map(|value_mapping| Ok(Value::Object(value_mapping)))
         })
@@ -8,12 +8,13 @@ use tokio_stream::StreamExt;
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::types::EventMessage;
 
-use super::entity::model_data_recursive_query;
+use super::entity::{model_data_recursive_query, models_from_diff, parse_diff_argument};
 use super::inputs::keys_input::keys_argument;
+use super::inputs::world_input::{parse_world_argument, world_argument};
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
     DATETIME_FORMAT, EVENT_ID_COLUMN, EVENT_MESSAGE_ID_COLUMN, EVENT_MESSAGE_NAMES,
-    EVENT_MESSAGE_TABLE, EVENT_MESSAGE_TYPE_NAME, ID_COLUMN,
+    EVENT_MESSAGE_TABLE, EVENT_MESSAGE_TYPE_NAME, ID_COLUMN, INTERNAL_MODELS_DIFF_KEY,
 };
 use crate::mapping::ENTITY_TYPE_MAPPING;
 use crate::object::{resolve_many, resolve_one};
@@ -43,13 +44,13 @@ impl BasicObject for EventMessageObject {
 
 impl ResolvableObject for EventMessageObject {
     fn resolvers(&self) -> Vec<Field> {
-        let resolve_one = resolve_one(
+        let resolve_one = world_argument(resolve_one(
             EVENT_MESSAGE_TABLE,
             ID_COLUMN,
             self.name().0,
             self.type_name(),
             self.type_mapping(),
-        );
+        ));
 
         let mut resolve_many = resolve_many(
             EVENT_MESSAGE_TABLE,
@@ -59,40 +60,63 @@ impl ResolvableObject for EventMessageObject {
             self.type_mapping(),
         );
         resolve_many = keys_argument(resolve_many);
+        resolve_many = world_argument(resolve_many);
 
         vec![resolve_one, resolve_many]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new(
-                "eventMessageUpdated",
-                TypeRef::named_nn(self.type_name()),
-                |ctx| {
-                    SubscriptionFieldFuture::new(async move {
-                        let id = match ctx.args.get("id") {
-                            Some(id) => Some(id.string()?.to_string()),
-                            None => None,
-                        };
-                        // if id is None, then subscribe to all entities
-                        // if id is Some, then subscribe to only the entity with that id
-                        Ok(SimpleBroker::<EventMessage>::subscribe().filter_map(
-                            move |entity: EventMessage| {
-                                if id.is_none() || id == Some(entity.id.clone()) {
-                                    Some(Ok(Value::Object(EventMessageObject::value_mapping(
-                                        entity,
-                                    ))))
-                                } else {
-                                    // id != entity.id , then don't send anything, still listening
-                                    None
+        Some(vec![SubscriptionField::new(
+            "eventMessageUpdated",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let id = match ctx.args.get("id") {
+                        Some(id) => Some(id.string()?.to_string()),
+                        None => None,
+                    };
+                    let world = parse_world_argument(&ctx)?;
+                    let mut diffs = parse_diff_argument(&ctx)?;
+                    // if id is None, then subscribe to all entities
+                    // if id is Some, then subscribe to only the entity with that id
+                    Ok(SimpleBroker::<EventMessage>::subscribe().filter_map(
+                        move |entity: EventMessage| {
+                            if world.as_ref().is_some_and(|world| *world != entity.world_address) {
+                                // set by another world, still listening
+                                None
+                            } else if id.is_none() || id == Some(entity.id.clone()) {
+                                let diff = match &mut diffs {
+                                    Some(diffs) => match diffs.update(
+                                        &entity.world_address,
+                                        &entity.id,
+                                        entity.updated_model.as_ref(),
+                                        false,
+                                    ) {
+                                        // none of the members sent to the subscriber changed
+                                        Ok(None) => return None,
+                                        Ok(diff) => diff,
+                                        Err(e) => return Some(Err(e)),
+                                    },
+                                    None => None,
+                                };
+
+                                let mut value = EventMessageObject::value_mapping(entity);
+                                if let Some(diff) = diff {
+                                    value.insert(Name::new(INTERNAL_MODELS_DIFF_KEY), diff);
                                 }
-                            },
-                        ))
-                    })
-                },
-            )
-            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
-        ])
+                                Some(Ok(Value::Object(value)))
+                            } else {
+                                // id != entity.id , then don't send anything, still listening
+                                None
+                            }
+                        },
+                    ))
+                })
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))
+        .argument(InputValue::new("diff", TypeRef::named(TypeRef::BOOLEAN)))])
     }
 }
 
@@ -101,6 +125,7 @@ impl EventMessageObject {
         let keys: Vec<&str> = entity.keys.split('/').filter(|&k| !k.is_empty()).collect();
         IndexMap::from([
             (Name::new("id"), Value::from(entity.id)),
+            (Name::new("worldAddress"), Value::from(entity.world_address)),
             (Name::new("keys"), Value::from(keys)),
             (Name::new("eventId"), Value::from(entity.event_id)),
             (
@@ -127,6 +152,12 @@ fn model_union_field() -> Field {
                     let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
 
                     let entity_id = utils::extract::<String>(indexmap, "id")?;
+                    let world_address = utils::extract::<String>(indexmap, "worldAddress")?;
+
+                    if let Some(results) = models_from_diff(indexmap, &entity_id, &world_address) {
+                        return Ok(Some(FieldValue::list(results)));
+                    }
+
                     // fetch name from the models table
                     // using the model id (hashed model name)
                     let model_ids: Vec<(String, String, String)> = sqlx::query_as(
@@ -135,10 +166,11 @@ fn model_union_field() -> Field {
                         WHERE id IN (    
                             SELECT model_id
                             FROM event_model
-                            WHERE entity_id = ?
+                            WHERE entity_id = ? AND world_address = ?
                         )",
                     )
                     .bind(&entity_id)
+                    .bind(&world_address)
                     .fetch_all(&mut *conn)
                     .await?;
 
@@ -153,6 +185,7 @@ fn model_union_field() -> Field {
                             EVENT_MESSAGE_ID_COLUMN,
                             vec![format!("{namespace}-{name}")],
                             &entity_id,
+                            &world_address,
                             &[],
                             &type_mapping,
                             false,
@@ -5,6 +5,7 @@ use super::TypeMapping;
 pub mod keys_input;
 pub mod order_input;
 pub mod where_input;
+pub mod world_input;
 
 pub trait InputObjectTrait {
     // Type name of the input graphql object, we don't need a name as this will always be an input
@@ -6,6 +6,7 @@ use async_graphql::dynamic::{
 use async_graphql::{Error as GqlError, Name, Result};
 use dojo_types::primitive::{Primitive, SqlType};
 use strum::IntoEnumIterator;
+use torii_core::sql::search::{search_matches_query, search_query};
 
 use super::InputObjectTrait;
 use crate::object::TypeMapping;
@@ -44,6 +45,17 @@ impl WhereInputObject {
                                 Name::new(name),
                                 TypeData::List(Box::new(type_data.clone())),
                             )),
+                            // only strings can be searched
+                            Comparator::Search => {
+                                if type_data.type_ref() == TypeRef::named("ByteArray")
+                                    || type_data.type_ref() == TypeRef::named("felt252")
+                                {
+                                    acc.push((
+                                        Name::new(name),
+                                        TypeData::Simple(TypeRef::named(TypeRef::STRING)),
+                                    ));
+                                }
+                            }
                             _ => {
                                 acc.push((Name::new(name), type_data.clone()));
                             }
@@ -82,6 +94,7 @@ pub fn where_argument(field: Field, type_name: &str) -> Field {
 pub fn parse_where_argument(
     ctx: &ResolverContext<'_>,
     where_mapping: &TypeMapping,
+    table_name: &str,
 ) -> Result<Option<Vec<Filter>>> {
     ctx.args.get("where").map_or(Ok(None), |where_input| {
         let input_object = where_input.object()?;
@@ -89,6 +102,9 @@ pub fn parse_where_argument(
             .iter()
             .filter_map(|(type_name, type_data)| {
                 input_object.get(type_name).map(|input| match type_data {
+                    TypeData::Simple(_) if type_name.ends_with(Comparator::Search.as_ref()) => {
+                        Ok(Some(parse_search(input, type_name, table_name)?))
+                    }
                     TypeData::Simple(_) => {
                         if type_data.type_ref() == TypeRef::named("Enum") {
                             let value = input.string().unwrap();
@@ -128,6 +144,24 @@ pub fn parse_where_argument(
     })
 }
 
+fn parse_search(input: ValueAccessor<'_>, type_name: &str, table_name: &str) -> Result<Filter> {
+    let text = input
+        .string()
+        .map_err(|_| GqlError::new(format!("Expected string on field {}", type_name)))?;
+    let query = search_query(text)
+        .ok_or_else(|| GqlError::new(format!("No word to search on field {}", type_name)))?;
+    let member = type_name.strip_suffix(Comparator::Search.as_ref()).unwrap();
+
+    // the query is inlined like the other filter values
+    let matches =
+        search_matches_query(table_name, member, &format!("'{}'", query.replace('\'', "''")));
+    Ok(Filter {
+        field: "id".to_string(),
+        comparator: Comparator::Search,
+        value: FilterValue::Query(matches),
+    })
+}
+
 fn parse_integer(
     input: ValueAccessor<'_>,
     type_name: &str,
@@ -0,0 +1,42 @@
+use async_graphql::dynamic::{Field, InputValue, ResolverContext, TypeRef};
+use async_graphql::Error;
+use starknet_crypto::Felt;
+
+use crate::constants::MODEL_TABLE;
+use crate::query::filter::{Comparator, Filter, FilterValue};
+use crate::utils::extract;
+
+pub fn world_argument(field: Field) -> Field {
+    field.argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))
+}
+
+/// Parses the `world` argument, returned in the format world addresses are stored with.
+pub fn parse_world_argument(ctx: &ResolverContext<'_>) -> Result<Option<String>, Error> {
+    match extract::<String>(ctx.args.as_index_map(), "world") {
+        Ok(world) => match Felt::from_hex(&world) {
+            Ok(world) => Ok(Some(format!("{:#x}", world))),
+            Err(_) => Err("World address must be a hex string".into()),
+        },
+        Err(_) => Ok(None),
+    }
+}
+
+/// Filter selecting the rows of the table that belong to the world. Models belong to the worlds
+/// which registered them.
+pub fn world_filter(table_name: &str, world: &str) -> Filter {
+    if table_name == MODEL_TABLE {
+        return Filter {
+            field: "id".to_string(),
+            comparator: Comparator::In,
+            value: FilterValue::Query(format!(
+                "SELECT model_id FROM world_models WHERE world_address = '{world}'"
+            )),
+        };
+    }
+
+    Filter {
+        field: "world_address".to_string(),
+        comparator: Comparator::Eq,
+        value: FilterValue::String(world.to_string()),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod aggregate;
 pub mod connection;
 pub mod entity;
 pub mod erc;
@@ -7,7 +8,11 @@ pub mod inputs;
 pub mod metadata;
 pub mod model;
 pub mod model_data;
+pub mod namespace;
+pub mod permission;
+pub mod rollback;
 pub mod transaction;
+pub mod world_contract;
 
 use async_graphql::dynamic::{
     Enum, Field, FieldFuture, InputObject, InputValue, Object, SubscriptionField, TypeRef,
@@ -22,6 +27,7 @@ use self::connection::{
 };
 use self::inputs::keys_input::parse_keys_argument;
 use self::inputs::order_input::parse_order_argument;
+use self::inputs::world_input::{parse_world_argument, world_filter};
 use crate::query::data::{count_rows, fetch_multiple_rows, fetch_single_row};
 use crate::query::value_mapping_from_row;
 use crate::types::{TypeMapping, ValueMapping};
@@ -135,7 +141,9 @@ pub fn resolve_one(
             let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
             let id: String =
                 extract::<String>(ctx.args.as_index_map(), &id_column.to_case(Case::Camel))?;
-            let data = fetch_single_row(&mut conn, &table_name, &id_column, &id).await?;
+            let filters =
+                parse_world_argument(&ctx)?.map(|world| vec![world_filter(&table_name, &world)]);
+            let data = fetch_single_row(&mut conn, &table_name, &id_column, &id, &filters).await?;
             let model = value_mapping_from_row(&data, &type_mapping, false)?;
             Ok(Some(Value::Object(model)))
         })
@@ -166,7 +174,9 @@ pub fn resolve_many(
                 let connection = parse_connection_arguments(&ctx)?;
                 let keys = parse_keys_argument(&ctx)?;
                 let order = parse_order_argument(&ctx);
-                let total_count = count_rows(&mut conn, &table_name, &keys, &None).await?;
+                let filters = parse_world_argument(&ctx)?
+                    .map(|world| vec![world_filter(&table_name, &world)]);
+                let total_count = count_rows(&mut conn, &table_name, &keys, &filters).await?;
 
                 let (data, page_info) = fetch_multiple_rows(
                     &mut conn,
@@ -174,7 +184,7 @@ pub fn resolve_many(
                     &id_column,
                     &keys,
                     &order,
-                    &None,
+                    &filters,
                     &connection,
                     total_count,
                 )
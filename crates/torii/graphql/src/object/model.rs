@@ -7,6 +7,7 @@ use tokio_stream::StreamExt;
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::types::Model;
 
+use super::inputs::world_input::{parse_world_argument, world_argument};
 use super::{resolve_many, BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
     DATETIME_FORMAT, ID_COLUMN, MODEL_NAMES, MODEL_ORDER_FIELD_TYPE_NAME, MODEL_ORDER_TYPE_NAME,
@@ -53,13 +54,13 @@ impl ResolvableObject for ModelObject {
     }
 
     fn resolvers(&self) -> Vec<Field> {
-        let resolve_one = resolve_one(
+        let resolve_one = world_argument(resolve_one(
             MODEL_TABLE,
             ID_COLUMN,
             self.name().0,
             self.type_name(),
             self.type_mapping(),
-        );
+        ));
 
         let mut resolve_many = resolve_many(
             MODEL_TABLE,
@@ -70,23 +71,30 @@ impl ResolvableObject for ModelObject {
         );
         resolve_many =
             resolve_many.argument(InputValue::new("order", TypeRef::named(MODEL_ORDER_TYPE_NAME)));
+        resolve_many = world_argument(resolve_many);
 
         vec![resolve_one, resolve_many]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new("modelRegistered", TypeRef::named_nn(self.type_name()), |ctx| {
+        Some(vec![SubscriptionField::new(
+            "modelRegistered",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
                 {
                     SubscriptionFieldFuture::new(async move {
                         let id = match ctx.args.get("id") {
                             Some(id) => Some(id.string()?.to_string()),
                             None => None,
                         };
+                        let world = parse_world_argument(&ctx)?;
                         // if id is None, then subscribe to all models
                         // if id is Some, then subscribe to only the model with that id
                         Ok(SimpleBroker::<Model>::subscribe().filter_map(move |model: Model| {
-                            if id.is_none() || id == Some(model.id.clone()) {
+                            if world.as_ref().is_some_and(|world| *world != model.world_address) {
+                                // registered by another world, still listening
+                                None
+                            } else if id.is_none() || id == Some(model.id.clone()) {
                                 Some(Ok(Value::Object(ModelObject::value_mapping(model))))
                             } else {
                                 // id != model.id, so don't send anything, still listening
@@ -95,9 +103,10 @@ impl ResolvableObject for ModelObject {
                         }))
                     })
                 }
-            })
-            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
-        ])
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))])
     }
 }
 
@@ -4,17 +4,19 @@ use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use sqlx::{FromRow, Pool, Sqlite};
 
+use super::aggregate::AggregateObject;
 use super::connection::{connection_arguments, connection_output, parse_connection_arguments};
 use super::inputs::order_input::{order_argument, parse_order_argument, OrderInputObject};
 use super::inputs::where_input::{parse_where_argument, where_argument, WhereInputObject};
+use super::inputs::world_input::{parse_world_argument, world_argument, world_filter};
 use super::inputs::InputObjectTrait;
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
     ENTITY_ID_COLUMN, ENTITY_TABLE, ENTITY_TYPE_NAME, EVENT_ID_COLUMN, EVENT_MESSAGE_TABLE,
-    EVENT_MESSAGE_TYPE_NAME, ID_COLUMN, INTERNAL_ENTITY_ID_KEY,
+    EVENT_MESSAGE_TYPE_NAME, ID_COLUMN, INTERNAL_ENTITY_ID_KEY, INTERNAL_WORLD_ADDRESS_KEY,
 };
 use crate::mapping::ENTITY_TYPE_MAPPING;
-use crate::query::data::{count_rows, fetch_multiple_rows, fetch_single_row};
+use crate::query::data::{count_rows, fetch_multiple_rows, fetch_single_row_of_world};
 use crate::query::value_mapping_from_row;
 use crate::types::TypeData;
 use crate::utils;
@@ -38,17 +40,38 @@ pub struct ModelDataObject {
     pub name: String,
     pub plural_name: String,
     pub type_name: String,
+    /// Table of the model, named after its tag.
+    pub table_name: String,
     pub type_mapping: TypeMapping,
     pub where_input: WhereInputObject,
     pub order_input: OrderInputObject,
+    pub aggregate: AggregateObject,
 }
 
 impl ModelDataObject {
-    pub fn new(name: String, type_name: String, type_mapping: TypeMapping) -> Self {
+    pub fn new(
+        namespace: &str,
+        model: &str,
+        type_mapping: TypeMapping,
+        key_members: &[String],
+    ) -> Self {
+        let name = utils::field_name_from_names(namespace, model);
+        let type_name = utils::type_name_from_names(namespace, model);
+        let table_name = utils::struct_name_from_names(namespace, model);
         let where_input = WhereInputObject::new(type_name.as_str(), &type_mapping);
         let order_input = OrderInputObject::new(type_name.as_str(), &type_mapping);
+        let aggregate = AggregateObject::new(type_name.as_str(), &type_mapping, key_members);
         let plural_name = format!("{}Models", name);
-        Self { name, plural_name, type_name, type_mapping, where_input, order_input }
+        Self {
+            name,
+            plural_name,
+            type_name,
+            table_name,
+            type_mapping,
+            where_input,
+            order_input,
+            aggregate,
+        }
     }
 }
 
@@ -66,13 +89,9 @@ impl BasicObject for ModelDataObject {
     }
 
     fn objects(&self) -> Vec<Object> {
-        let mut parts = self.type_name().split('_').collect::<Vec<&str>>();
-        let model = parts.pop().unwrap();
-        let namespace = parts.join("_");
-        let type_name = utils::struct_name_from_names(&namespace, model);
         let mut objects = data_objects_recursion(
             &TypeData::Nested((TypeRef::named(self.type_name()), self.type_mapping.clone())),
-            &vec![type_name],
+            &vec![self.table_name.clone()],
         );
 
         // root object requires entity_field association
@@ -81,6 +100,7 @@ impl BasicObject for ModelDataObject {
         root = root.field(event_message_field());
 
         objects.push(root);
+        objects.extend(self.aggregate.objects());
         objects
     }
 }
@@ -91,11 +111,13 @@ impl ResolvableObject for ModelDataObject {
     }
 
     fn enum_objects(&self) -> Option<Vec<Enum>> {
-        self.order_input.enum_objects()
+        let mut enums = self.order_input.enum_objects().unwrap_or_default();
+        enums.extend(self.aggregate.enum_objects().unwrap_or_default());
+        Some(enums)
     }
 
     fn resolvers(&self) -> Vec<Field> {
-        let type_name = self.type_name.clone();
+        let table_name = self.table_name.clone();
         let type_mapping = self.type_mapping.clone();
         let where_mapping = self.where_input.type_mapping.clone();
         let field_type = format!("{}Connection", self.type_name());
@@ -103,21 +125,21 @@ impl ResolvableObject for ModelDataObject {
         let mut field = Field::new(self.name().1, TypeRef::named(field_type), move |ctx| {
             let type_mapping = type_mapping.clone();
             let where_mapping = where_mapping.clone();
-            let mut parts = type_name.split('_').collect::<Vec<&str>>();
-            let model = parts.pop().unwrap();
-            let namespace = parts.join("_");
-            let type_name = utils::struct_name_from_names(&namespace, model);
+            let table_name = table_name.clone();
 
             FieldFuture::new(async move {
                 let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                 let order = parse_order_argument(&ctx);
-                let filters = parse_where_argument(&ctx, &where_mapping)?;
+                let mut filters = parse_where_argument(&ctx, &where_mapping, &table_name)?;
+                if let Some(world) = parse_world_argument(&ctx)? {
+                    filters.get_or_insert_with(Vec::new).push(world_filter(&table_name, &world));
+                }
                 let connection = parse_connection_arguments(&ctx)?;
 
-                let total_count = count_rows(&mut conn, &type_name, &None, &filters).await?;
+                let total_count = count_rows(&mut conn, &table_name, &None, &filters).await?;
                 let (data, page_info) = fetch_multiple_rows(
                     &mut conn,
-                    &type_name,
+                    &table_name,
                     EVENT_ID_COLUMN,
                     &None,
                     &order,
@@ -144,8 +166,16 @@ impl ResolvableObject for ModelDataObject {
         field = connection_arguments(field);
         field = where_argument(field, self.type_name());
         field = order_argument(field, self.type_name());
+        field = world_argument(field);
+
+        let aggregate = self.aggregate.field(
+            &format!("{}Aggregate", self.name),
+            self.type_name(),
+            self.table_name.clone(),
+            self.where_input.type_mapping.clone(),
+        );
 
-        vec![field]
+        vec![field, aggregate]
     }
 }
 
@@ -199,6 +229,8 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
                                 let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                                 let entity_id =
                                     utils::extract::<String>(indexmap, INTERNAL_ENTITY_ID_KEY)?;
+                                let world_address =
+                                    utils::extract::<String>(indexmap, INTERNAL_WORLD_ADDRESS_KEY)?;
 
                                 // if we already fetched our model data, return it
                                 if let Some(data) = indexmap.get(&field_name) {
@@ -206,11 +238,12 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
                                 }
 
                                 // TODO: remove subqueries and use JOIN in parent query
-                                let data = fetch_single_row(
+                                let data = fetch_single_row_of_world(
                                     &mut conn,
                                     &table_name,
                                     ENTITY_ID_COLUMN,
                                     &entity_id,
+                                    &world_address,
                                 )
                                 .await?;
                                 let result = value_mapping_from_row(&data, &nested_mapping, true)?;
@@ -232,8 +265,9 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
 
                 // Catch model union resolutions, async-graphql sends union types as IndexMap<Name,
                 // ConstValue>
+                // the members which didn't change are missing from the diffs of the subscriptions
                 if let Some(value_mapping) = ctx.parent_value.downcast_ref::<ValueMapping>() {
-                    return Ok(Some(value_mapping.get(&field_name).unwrap().clone()));
+                    return Ok(value_mapping.get(&field_name).cloned());
                 }
 
                 Err("Field resolver only accepts Value or IndexMap".into())
@@ -253,8 +287,16 @@ fn entity_field() -> Field {
                 Value::Object(indexmap) => {
                     let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                     let entity_id = utils::extract::<String>(indexmap, INTERNAL_ENTITY_ID_KEY)?;
-                    let data =
-                        fetch_single_row(&mut conn, ENTITY_TABLE, ID_COLUMN, &entity_id).await?;
+                    let world_address =
+                        utils::extract::<String>(indexmap, INTERNAL_WORLD_ADDRESS_KEY)?;
+                    let data = fetch_single_row_of_world(
+                        &mut conn,
+                        ENTITY_TABLE,
+                        ID_COLUMN,
+                        &entity_id,
+                        &world_address,
+                    )
+                    .await?;
                     let entity = value_mapping_from_row(&data, &ENTITY_TYPE_MAPPING, false)?;
 
                     Ok(Some(Value::Object(entity)))
@@ -272,9 +314,16 @@ fn event_message_field() -> Field {
                 Value::Object(indexmap) => {
                     let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                     let entity_id = utils::extract::<String>(indexmap, INTERNAL_ENTITY_ID_KEY)?;
-                    let data =
-                        fetch_single_row(&mut conn, EVENT_MESSAGE_TABLE, ID_COLUMN, &entity_id)
-                            .await?;
+                    let world_address =
+                        utils::extract::<String>(indexmap, INTERNAL_WORLD_ADDRESS_KEY)?;
+                    let data = fetch_single_row_of_world(
+                        &mut conn,
+                        EVENT_MESSAGE_TABLE,
+                        ID_COLUMN,
+                        &entity_id,
+                        &world_address,
+                    )
+                    .await?;
                     let event_message = value_mapping_from_row(&data, &ENTITY_TYPE_MAPPING, false)?;
 
                     Ok(Some(Value::Object(event_message)))
@@ -0,0 +1,98 @@
+use async_graphql::dynamic::indexmap::IndexMap;
+use async_graphql::dynamic::{
+    Field, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+};
+use async_graphql::{Name, Value};
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::Namespace;
+
+use super::inputs::world_input::{parse_world_argument, world_argument};
+use super::{resolve_many, resolve_one, BasicObject, ResolvableObject, TypeMapping, ValueMapping};
+use crate::constants::{
+    DATETIME_FORMAT, ID_COLUMN, NAMESPACE_NAMES, NAMESPACE_TABLE, NAMESPACE_TYPE_NAME,
+};
+use crate::mapping::NAMESPACE_TYPE_MAPPING;
+
+#[derive(Debug)]
+pub struct NamespaceObject;
+
+impl BasicObject for NamespaceObject {
+    fn name(&self) -> (&str, &str) {
+        NAMESPACE_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        NAMESPACE_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &NAMESPACE_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for NamespaceObject {
+    fn resolvers(&self) -> Vec<Field> {
+        let mut resolve_one = resolve_one(
+            NAMESPACE_TABLE,
+            ID_COLUMN,
+            self.name().0,
+            self.type_name(),
+            self.type_mapping(),
+        );
+
+        resolve_one = world_argument(resolve_one);
+
+        let mut resolve_many = resolve_many(
+            NAMESPACE_TABLE,
+            ID_COLUMN,
+            self.name().1,
+            self.type_name(),
+            self.type_mapping(),
+        );
+        resolve_many = world_argument(resolve_many);
+
+        vec![resolve_one, resolve_many]
+    }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        Some(vec![SubscriptionField::new(
+            "namespaceRegistered",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let world = parse_world_argument(&ctx)?;
+                    // only the namespaces of the given world are sent
+                    Ok(SimpleBroker::<Namespace>::subscribe().filter_map(
+                        move |namespace: Namespace| {
+                            if world.as_ref().is_some_and(|w| *w != namespace.world_address) {
+                                return None;
+                            }
+
+                            Some(Ok(Value::Object(NamespaceObject::value_mapping(namespace))))
+                        },
+                    ))
+                })
+            },
+        )
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))])
+    }
+}
+
+impl NamespaceObject {
+    pub fn value_mapping(namespace: Namespace) -> ValueMapping {
+        IndexMap::from([
+            (Name::new("id"), Value::from(namespace.id)),
+            (Name::new("worldAddress"), Value::from(namespace.world_address)),
+            (Name::new("namespace"), Value::from(namespace.namespace)),
+            (
+                Name::new("executedAt"),
+                Value::from(namespace.executed_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("createdAt"),
+                Value::from(namespace.created_at.format(DATETIME_FORMAT).to_string()),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,121 @@
+use async_graphql::dynamic::indexmap::IndexMap;
+use async_graphql::dynamic::{
+    Field, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+};
+use async_graphql::{Name, Value};
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::Permission;
+
+use super::inputs::world_input::{parse_world_argument, world_argument};
+use super::{resolve_many, resolve_one, BasicObject, ResolvableObject, TypeMapping, ValueMapping};
+use crate::constants::{
+    DATETIME_FORMAT, ID_COLUMN, PERMISSION_NAMES, PERMISSION_TABLE, PERMISSION_TYPE_NAME,
+};
+use crate::mapping::PERMISSION_TYPE_MAPPING;
+
+#[derive(Debug)]
+pub struct PermissionObject;
+
+impl BasicObject for PermissionObject {
+    fn name(&self) -> (&str, &str) {
+        PERMISSION_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        PERMISSION_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &PERMISSION_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for PermissionObject {
+    fn resolvers(&self) -> Vec<Field> {
+        let mut resolve_one = resolve_one(
+            PERMISSION_TABLE,
+            ID_COLUMN,
+            self.name().0,
+            self.type_name(),
+            self.type_mapping(),
+        );
+
+        resolve_one = world_argument(resolve_one);
+
+        let mut resolve_many = resolve_many(
+            PERMISSION_TABLE,
+            ID_COLUMN,
+            self.name().1,
+            self.type_name(),
+            self.type_mapping(),
+        );
+        resolve_many = world_argument(resolve_many);
+
+        vec![resolve_one, resolve_many]
+    }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        Some(vec![SubscriptionField::new(
+            "permissionUpdated",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let resource = match ctx.args.get("resource") {
+                        Some(resource) => Some(resource.string()?.to_string()),
+                        None => None,
+                    };
+                    let contract_address = match ctx.args.get("contractAddress") {
+                        Some(address) => Some(address.string()?.to_string()),
+                        None => None,
+                    };
+                    let world = parse_world_argument(&ctx)?;
+                    // only the permissions matching the given resource, contract and world are
+                    // sent
+                    Ok(SimpleBroker::<Permission>::subscribe().filter_map(
+                        move |permission: Permission| {
+                            if world.as_ref().is_some_and(|w| *w != permission.world_address)
+                                || resource.as_ref().is_some_and(|r| *r != permission.resource)
+                                || contract_address
+                                    .as_ref()
+                                    .is_some_and(|a| *a != permission.contract_address)
+                            {
+                                return None;
+                            }
+
+                            Some(Ok(Value::Object(PermissionObject::value_mapping(permission))))
+                        },
+                    ))
+                })
+            },
+        )
+        .argument(InputValue::new("resource", TypeRef::named(TypeRef::STRING)))
+        .argument(InputValue::new("contractAddress", TypeRef::named(TypeRef::STRING)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))])
+    }
+}
+
+impl PermissionObject {
+    pub fn value_mapping(permission: Permission) -> ValueMapping {
+        IndexMap::from([
+            (Name::new("id"), Value::from(permission.id)),
+            (Name::new("worldAddress"), Value::from(permission.world_address)),
+            (Name::new("resource"), Value::from(permission.resource)),
+            (Name::new("contractAddress"), Value::from(permission.contract_address)),
+            (Name::new("permission"), Value::from(permission.permission)),
+            (Name::new("granted"), Value::from(permission.granted)),
+            (
+                Name::new("executedAt"),
+                Value::from(permission.executed_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("createdAt"),
+                Value::from(permission.created_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("updatedAt"),
+                Value::from(permission.updated_at.format(DATETIME_FORMAT).to_string()),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,52 @@
+use async_graphql::dynamic::{Field, Object, SubscriptionField, SubscriptionFieldFuture, TypeRef};
+use async_graphql::{Name, Value};
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::Rollback;
+
+use super::{BasicObject, ResolvableObject, TypeMapping};
+use crate::constants::{ROLLBACK_NAMES, ROLLBACK_TYPE_NAME};
+use crate::mapping::ROLLBACK_TYPE_MAPPING;
+use crate::types::ValueMapping;
+
+/// Notifies the subscribers that the indexed data after a block has been discarded because of a
+/// chain reorganization. It is only a subscription, since rollbacks aren't stored.
+#[derive(Debug)]
+pub struct RollbackObject;
+
+impl BasicObject for RollbackObject {
+    fn name(&self) -> (&str, &str) {
+        ROLLBACK_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        ROLLBACK_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &ROLLBACK_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for RollbackObject {
+    fn resolvers(&self) -> Vec<Field> {
+        vec![]
+    }
+
+    fn connection_objects(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        Some(vec![SubscriptionField::new("rollback", TypeRef::named_nn(self.type_name()), |_| {
+            SubscriptionFieldFuture::new(async move {
+                Ok(SimpleBroker::<Rollback>::subscribe().map(|rollback| {
+                    Ok(Value::Object(ValueMapping::from([(
+                        Name::new("blockNumber"),
+                        Value::from(rollback.block_number),
+                    )])))
+                }))
+            })
+        })])
+    }
+}
@@ -1,11 +1,17 @@
-use async_graphql::dynamic::Field;
+use async_graphql::dynamic::{Field, SubscriptionField, SubscriptionFieldFuture, TypeRef};
+use async_graphql::{Name, Value};
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::Transaction;
 
 use super::{BasicObject, ResolvableObject, TypeMapping};
 use crate::constants::{
-    ID_COLUMN, TRANSACTION_HASH_COLUMN, TRANSACTION_NAMES, TRANSACTION_TABLE, TRANSACTION_TYPE_NAME,
+    DATETIME_FORMAT, ID_COLUMN, TRANSACTION_HASH_COLUMN, TRANSACTION_NAMES, TRANSACTION_TABLE,
+    TRANSACTION_TYPE_NAME,
 };
 use crate::mapping::TRANSACTION_MAPPING;
 use crate::object::{resolve_many, resolve_one};
+use crate::types::ValueMapping;
 
 #[derive(Debug)]
 pub struct TransactionObject;
@@ -44,4 +50,43 @@ impl ResolvableObject for TransactionObject {
 
         vec![resolve_one, resolve_many]
     }
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        Some(vec![SubscriptionField::new(
+            "transactionIndexed",
+            TypeRef::named_nn(self.type_name()),
+            |_| {
+                SubscriptionFieldFuture::new(async move {
+                    Ok(SimpleBroker::<Transaction>::subscribe().map(|transaction| {
+                        Ok(Value::Object(TransactionObject::value_mapping(transaction)))
+                    }))
+                })
+            },
+        )])
+    }
+}
+
+impl TransactionObject {
+    fn value_mapping(transaction: Transaction) -> ValueMapping {
+        let calldata: Vec<&str> =
+            transaction.calldata.split('/').filter(|&c| !c.is_empty()).collect();
+        let signature: Vec<&str> =
+            transaction.signature.split('/').filter(|&s| !s.is_empty()).collect();
+        ValueMapping::from([
+            (Name::new("id"), Value::from(transaction.id)),
+            (Name::new("transactionHash"), Value::from(transaction.transaction_hash)),
+            (Name::new("senderAddress"), Value::from(transaction.sender_address)),
+            (Name::new("calldata"), Value::from(calldata)),
+            (Name::new("maxFee"), Value::from(transaction.max_fee)),
+            (Name::new("signature"), Value::from(signature)),
+            (Name::new("nonce"), Value::from(transaction.nonce)),
+            (
+                Name::new("executedAt"),
+                Value::from(transaction.executed_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("createdAt"),
+                Value::from(transaction.created_at.format(DATETIME_FORMAT).to_string()),
+            ),
+        ])
+    }
 }
@@ -0,0 +1,129 @@
+use async_graphql::dynamic::indexmap::IndexMap;
+use async_graphql::dynamic::{
+    Field, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+};
+use async_graphql::{Name, Value};
+use tokio_stream::StreamExt;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::sql::FELT_DELIMITER;
+use torii_core::types::WorldContract;
+
+use super::inputs::world_input::{parse_world_argument, world_argument};
+use super::{resolve_many, resolve_one, BasicObject, ResolvableObject, TypeMapping, ValueMapping};
+use crate::constants::{
+    DATETIME_FORMAT, ID_COLUMN, WORLD_CONTRACT_NAMES, WORLD_CONTRACT_TABLE,
+    WORLD_CONTRACT_TYPE_NAME,
+};
+use crate::mapping::WORLD_CONTRACT_TYPE_MAPPING;
+
+#[derive(Debug)]
+pub struct WorldContractObject;
+
+impl BasicObject for WorldContractObject {
+    fn name(&self) -> (&str, &str) {
+        WORLD_CONTRACT_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        WORLD_CONTRACT_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &WORLD_CONTRACT_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for WorldContractObject {
+    fn resolvers(&self) -> Vec<Field> {
+        let mut resolve_one = resolve_one(
+            WORLD_CONTRACT_TABLE,
+            ID_COLUMN,
+            self.name().0,
+            self.type_name(),
+            self.type_mapping(),
+        );
+
+        resolve_one = world_argument(resolve_one);
+
+        let mut resolve_many = resolve_many(
+            WORLD_CONTRACT_TABLE,
+            ID_COLUMN,
+            self.name().1,
+            self.type_name(),
+            self.type_mapping(),
+        );
+        resolve_many = world_argument(resolve_many);
+
+        vec![resolve_one, resolve_many]
+    }
+
+    fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
+        Some(vec![SubscriptionField::new(
+            "worldContractUpdated",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let id = match ctx.args.get("id") {
+                        Some(id) => Some(id.string()?.to_string()),
+                        None => None,
+                    };
+                    let world = parse_world_argument(&ctx)?;
+                    // if id is None, then subscribe to all contracts
+                    // if id is Some, then subscribe to only the contract with that id
+                    Ok(SimpleBroker::<WorldContract>::subscribe().filter_map(
+                        move |contract: WorldContract| {
+                            if world.as_ref().is_some_and(|w| *w != contract.world_address) {
+                                // registered in another world, still listening
+                                None
+                            } else if id.is_none() || id == Some(contract.id.clone()) {
+                                Some(Ok(Value::Object(WorldContractObject::value_mapping(
+                                    contract,
+                                ))))
+                            } else {
+                                None
+                            }
+                        },
+                    ))
+                })
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+        .argument(InputValue::new("world", TypeRef::named(TypeRef::STRING)))])
+    }
+}
+
+impl WorldContractObject {
+    pub fn value_mapping(contract: WorldContract) -> ValueMapping {
+        IndexMap::from([
+            (Name::new("id"), Value::from(contract.id)),
+            (Name::new("worldAddress"), Value::from(contract.world_address)),
+            (Name::new("namespace"), Value::from(contract.namespace)),
+            (Name::new("name"), Value::from(contract.name)),
+            (Name::new("contractAddress"), Value::from(contract.contract_address)),
+            (Name::new("classHash"), Value::from(contract.class_hash)),
+            (Name::new("classHashes"), felts_value(&contract.class_hashes)),
+            (Name::new("salt"), Value::from(contract.salt)),
+            (Name::new("initialized"), Value::from(contract.initialized)),
+            (Name::new("initCalldata"), felts_value(&contract.init_calldata)),
+            (
+                Name::new("executedAt"),
+                Value::from(contract.executed_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("createdAt"),
+                Value::from(contract.created_at.format(DATETIME_FORMAT).to_string()),
+            ),
+            (
+                Name::new("updatedAt"),
+                Value::from(contract.updated_at.format(DATETIME_FORMAT).to_string()),
+            ),
+        ])
+    }
+}
+
+// felt arrays are stored as a delimited string, with a trailing delimiter
+fn felts_value(felts: &str) -> Value {
+    Value::List(
+        felts.split(FELT_DELIMITER).filter(|felt| !felt.is_empty()).map(Value::from).collect(),
+    )
+}
@@ -3,7 +3,7 @@ use sqlx::sqlite::SqliteRow;
 use sqlx::{Result, Row, SqliteConnection};
 use torii_core::sql::WORLD_CONTRACT_TYPE;
 
-use super::filter::{Filter, FilterValue};
+use super::filter::{Comparator, Filter, FilterValue};
 use super::order::{CursorDirection, Direction, Order};
 use crate::constants::{DEFAULT_LIMIT, MODEL_TABLE};
 use crate::object::connection::{cursor, ConnectionArguments};
@@ -37,8 +37,27 @@ pub async fn fetch_single_row(
     table_name: &str,
     id_column: &str,
     id: &str,
+    filters: &Option<Vec<Filter>>,
+) -> sqlx::Result<SqliteRow> {
+    let mut query = format!("SELECT * FROM [{}] WHERE {} = '{}'", table_name, id_column, id);
+    for condition in build_conditions(&None, filters) {
+        query.push_str(&format!(" AND {}", condition));
+    }
+    sqlx::query(&query).fetch_one(conn).await
+}
+
+/// Fetches the row of `id` set by the world, ids being only unique within a world.
+pub async fn fetch_single_row_of_world(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    id_column: &str,
+    id: &str,
+    world_address: &str,
 ) -> sqlx::Result<SqliteRow> {
-    let query = format!("SELECT * FROM [{}] WHERE {} = '{}'", table_name, id_column, id);
+    let query = format!(
+        "SELECT * FROM [{}] WHERE {} = '{}' AND world_address = '{}'",
+        table_name, id_column, id, world_address
+    );
     sqlx::query(&query).fetch_one(conn).await
 }
 
@@ -65,13 +84,28 @@ pub async fn fetch_multiple_rows(
         conditions.push(handle_cursor(before_cursor, order, CursorDirection::Before, id_column)?);
     }
 
-    let mut query = format!("SELECT * FROM [{}]", table_name);
+    let is_cursor_based = connection.first.or(connection.last).is_some() || cursor_param.is_some();
+
+    // rows searched without an order are ordered from the best match, pages being offset based
+    let search = match (order, is_cursor_based, filters) {
+        (None, false, Some(filters)) => filters.iter().find_map(|filter| match &filter.value {
+            FilterValue::Query(query) if filter.comparator == Comparator::Search => Some(query),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    let mut query = match search {
+        Some(search) => format!(
+            "SELECT [{table_name}].* FROM [{table_name}] JOIN (SELECT id AS search_id, MIN(rank) \
+             AS search_rank FROM ({search}) GROUP BY id) ON search_id = [{table_name}].id"
+        ),
+        None => format!("SELECT * FROM [{}]", table_name),
+    };
     if !conditions.is_empty() {
         query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
     }
 
-    let is_cursor_based = connection.first.or(connection.last).is_some() || cursor_param.is_some();
-
     let data_limit =
         connection.first.or(connection.last).or(connection.limit).unwrap_or(DEFAULT_LIMIT);
     let limit = if is_cursor_based {
@@ -86,6 +120,9 @@ pub async fn fetch_multiple_rows(
     // NOTE: Order is determined by the `order` param if provided, otherwise it's inferred from the
     // `first` or `last` param. Explicit ordering take precedence
     match order {
+        None if search.is_some() => {
+            query.push_str(&format!(" ORDER BY search_rank ASC, {id_column} DESC LIMIT {limit}"));
+        }
         Some(order) => {
             let mut column_name = order.field.clone();
             if table_name != MODEL_TABLE {
@@ -210,7 +247,7 @@ fn handle_cursor(
     }
 }
 
-fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -> Vec<String> {
+pub fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -> Vec<String> {
     let mut conditions = Vec::new();
 
     if let Some(keys) = keys {
@@ -235,12 +272,18 @@ fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -
                     .map(|value| match value {
                         FilterValue::Int(i) => i.to_string(),
                         FilterValue::String(s) => format!("'{}'", s),
-                        FilterValue::List(_) => unreachable!(),
+                        FilterValue::List(_) | FilterValue::Query(_) => unreachable!(),
                     })
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!("{} {} ({})", filter.field, filter.comparator, values)
             }
+            FilterValue::Query(query) if filter.comparator == Comparator::Search => {
+                format!("{} IN (SELECT id FROM ({}))", filter.field, query)
+            }
+            FilterValue::Query(query) => {
+                format!("{} {} ({})", filter.field, filter.comparator, query)
+            }
         }));
     }
 
@@ -20,6 +20,8 @@ pub enum Comparator {
     In,
     NotLike,
     Like,
+    // Full-text search of string members, its value is the query of the matching rows
+    Search,
 }
 
 impl fmt::Display for Comparator {
@@ -35,6 +37,7 @@ impl fmt::Display for Comparator {
             Comparator::NotIn => write!(f, "NOT IN"),
             Comparator::Like => write!(f, "LIKE"),
             Comparator::NotLike => write!(f, "NOT LIKE"),
+            Comparator::Search => write!(f, "MATCH"),
         }
     }
 }
@@ -44,6 +47,8 @@ pub enum FilterValue {
     Int(i64),
     String(String),
     List(Vec<FilterValue>),
+    // Subquery whose rows are compared against, only used internally
+    Query(String),
 }
 
 #[derive(Debug)]
@@ -5,6 +5,7 @@ use async_graphql::{Name, Value};
 use chrono::{DateTime, Utc};
 use convert_case::{Case, Casing};
 use dojo_types::primitive::{Primitive, SqlType};
+use dojo_types::schema::Ty;
 use regex::Regex;
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Row, SqliteConnection};
@@ -12,14 +13,26 @@ use torii_core::sql::FELT_DELIMITER;
 
 use crate::constants::{
     BOOLEAN_TRUE, ENTITY_ID_COLUMN, EVENT_MESSAGE_ID_COLUMN, INTERNAL_ENTITY_ID_KEY,
+    INTERNAL_WORLD_ADDRESS_KEY, WORLD_ADDRESS_COLUMN,
 };
 use crate::object::model_data::ModelMember;
+use crate::query::data::build_conditions;
+use crate::query::filter::Filter;
 use crate::types::{TypeData, TypeMapping, ValueMapping};
 
 pub mod data;
 pub mod filter;
 pub mod order;
 
+/// Aggregations computed on the integer members of a model, the aliases of their columns being
+/// prefixed by their name.
+pub const AGGREGATE_FUNCTIONS: [&str; 4] = ["sum", "avg", "min", "max"];
+
+/// Aggregations computed by sqlite on the unsigned members stored as zero padded hex strings,
+/// which are ordered as their values but can't be summed. Their sums and averages are computed
+/// over the rows of [`build_hex_totals_query`].
+pub const BOUND_FUNCTIONS: [&str; 2] = ["min", "max"];
+
 pub async fn type_mapping_query(
     conn: &mut SqliteConnection,
     model_id: &str,
@@ -31,6 +44,84 @@ pub async fn type_mapping_query(
     build_type_mapping(&root_members, &nested_members)
 }
 
+/// Names of the key members of a model.
+pub async fn key_members_query(
+    conn: &mut SqliteConnection,
+    model_id: &str,
+) -> sqlx::Result<Vec<String>> {
+    let model_members = fetch_model_members(conn, model_id).await?;
+    Ok(model_members
+        .into_iter()
+        .filter(|member| member.model_idx == 0 && member.key)
+        .map(|member| member.name)
+        .collect())
+}
+
+/// Builds the query aggregating the integer and hex members of a model table, one row per group
+/// of `group_by` members. Sums are computed with `TOTAL` to be returned as floats rather than
+/// overflowing.
+pub fn build_aggregate_query(
+    table_name: &str,
+    integer_members: &[String],
+    hex_members: &[String],
+    group_by: &[String],
+    filters: &Option<Vec<Filter>>,
+) -> String {
+    let group_columns =
+        group_by.iter().map(|member| format!("external_{member}")).collect::<Vec<_>>();
+
+    let mut columns = group_columns.clone();
+    columns.push("COUNT(*) AS count_by".to_string());
+    for member in integer_members {
+        for function in AGGREGATE_FUNCTIONS {
+            let sql_function = if function == "sum" { "TOTAL" } else { function };
+            columns.push(format!("{sql_function}(external_{member}) AS [{function}_{member}]"));
+        }
+    }
+    for member in hex_members {
+        for function in BOUND_FUNCTIONS {
+            columns.push(format!("{function}(external_{member}) AS [{function}_{member}]"));
+        }
+    }
+
+    let mut query = format!("SELECT {} FROM [{}]", columns.join(", "), table_name);
+
+    let conditions = build_conditions(&None, filters);
+    if !conditions.is_empty() {
+        query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    }
+
+    if !group_columns.is_empty() {
+        query.push_str(&format!(" GROUP BY {} ORDER BY count_by DESC", group_columns.join(", ")));
+    }
+
+    query
+}
+
+/// Builds the query selecting the `group_by` and hex members of the records aggregated by
+/// [`build_aggregate_query`], for their hex members to be summed as sqlite can't parse them.
+pub fn build_hex_totals_query(
+    table_name: &str,
+    hex_members: &[String],
+    group_by: &[String],
+    filters: &Option<Vec<Filter>>,
+) -> String {
+    let columns = group_by
+        .iter()
+        .chain(hex_members)
+        .map(|member| format!("external_{member}"))
+        .collect::<Vec<_>>();
+
+    let mut query = format!("SELECT {} FROM [{}]", columns.join(", "), table_name);
+
+    let conditions = build_conditions(&None, filters);
+    if !conditions.is_empty() {
+        query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    }
+
+    query
+}
+
 async fn fetch_model_members(
     conn: &mut SqliteConnection,
     model_id: &str,
@@ -154,7 +245,7 @@ fn parse_nested_type(member: &ModelMember, nested_members: &[&ModelMember]) -> T
     TypeData::Nested((TypeRef::named(namespaced), nested_mapping))
 }
 
-fn remove_hex_leading_zeros(value: Value) -> Value {
+pub(crate) fn remove_hex_leading_zeros(value: Value) -> Value {
     if let Value::String(str_val) = &value {
         if !str_val.starts_with("0x") {
             return value;
@@ -201,6 +292,11 @@ pub fn value_mapping_from_row(
         value_mapping.insert(Name::new(INTERNAL_ENTITY_ID_KEY), Value::from(event_message_id));
     }
 
+    // the rows of different worlds can share the same entity id
+    if let Ok(world_address) = row.try_get::<String, &str>(WORLD_ADDRESS_COLUMN) {
+        value_mapping.insert(Name::new(INTERNAL_WORLD_ADDRESS_KEY), Value::from(world_address));
+    }
+
     Ok(value_mapping)
 }
 
@@ -1,7 +1,6 @@
 use anyhow::Result;
 use async_graphql::dynamic::{Object, Scalar, Schema, Subscription, Union};
 use sqlx::SqlitePool;
-use torii_core::types::Model;
 
 use super::object::connection::page_info::PageInfoObject;
 use super::object::entity::EntityObject;
@@ -18,9 +17,13 @@ use crate::object::metadata::content::ContentObject;
 use crate::object::metadata::social::SocialObject;
 use crate::object::metadata::MetadataObject;
 use crate::object::model::ModelObject;
+use crate::object::namespace::NamespaceObject;
+use crate::object::permission::PermissionObject;
+use crate::object::rollback::RollbackObject;
 use crate::object::transaction::TransactionObject;
+use crate::object::world_contract::WorldContractObject;
 use crate::object::ObjectVariant;
-use crate::query::type_mapping_query;
+use crate::query::{key_members_query, type_mapping_query};
 
 // The graphql schema is built dynamically at runtime, this is because we won't know the schema of
 // the models until runtime. There are however, predefined objects such as entities and
@@ -106,7 +109,8 @@ pub async fn build_schema(pool: &SqlitePool) -> Result<Schema> {
 
 async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Union>)> {
     let mut conn = pool.acquire().await?;
-    let models: Vec<Model> = sqlx::query_as("SELECT * FROM models").fetch_all(&mut *conn).await?;
+    let models: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT id, namespace, name FROM models").fetch_all(&mut *conn).await?;
 
     // predefined objects
     let mut objects: Vec<ObjectVariant> = vec![
@@ -116,6 +120,10 @@ async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Uni
         ObjectVariant::Resolvable(Box::new(MetadataObject)),
         ObjectVariant::Resolvable(Box::new(ModelObject)),
         ObjectVariant::Resolvable(Box::new(TransactionObject)),
+        ObjectVariant::Resolvable(Box::new(NamespaceObject)),
+        ObjectVariant::Resolvable(Box::new(WorldContractObject)),
+        ObjectVariant::Resolvable(Box::new(PermissionObject)),
+        ObjectVariant::Resolvable(Box::new(RollbackObject)),
         ObjectVariant::Resolvable(Box::new(ErcBalanceObject)),
         ObjectVariant::Resolvable(Box::new(ErcTransferObject)),
         ObjectVariant::Basic(Box::new(SocialObject)),
@@ -129,21 +137,20 @@ async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Uni
     let mut model_union = Union::new("ModelUnion");
 
     // model data objects
-    for model in models {
-        let type_mapping = type_mapping_query(&mut conn, &model.id).await?;
+    for (id, namespace, name) in models {
+        let type_mapping = type_mapping_query(&mut conn, &id).await?;
 
         if !type_mapping.is_empty() {
             // add models objects & unions
-            let field_name = utils::field_name_from_names(&model.namespace, &model.name);
+            let key_members = key_members_query(&mut conn, &id).await?;
 
-            let type_name = utils::type_name_from_names(&model.namespace, &model.name);
-
-            model_union = model_union.possible_type(&type_name);
+            model_union = model_union.possible_type(utils::type_name_from_names(&namespace, &name));
 
             objects.push(ObjectVariant::Resolvable(Box::new(ModelDataObject::new(
-                field_name,
-                type_name,
+                &namespace,
+                &name,
                 type_mapping.clone(),
+                &key_members,
             ))));
         }
     }
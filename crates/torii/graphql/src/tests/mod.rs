@@ -208,8 +208,23 @@ pub async fn run_graphql_subscription(
     // fn subscribe() is called from inside dynamic subscription
 }
 
+pub async fn run_graphql_subscription_updates(
+    pool: &SqlitePool,
+    subscription: &str,
+    count: usize,
+) -> Vec<async_graphql::Value> {
+    let schema = build_schema(pool).await.unwrap();
+    schema
+        .execute_stream(subscription)
+        .take(count)
+        .map(|response| response.into_result().unwrap().data)
+        .collect()
+        .await
+}
+
 pub async fn model_fixtures(db: &mut Sql) {
     db.register_model(
+        Felt::ZERO,
         "types_test",
         Ty::Struct(Struct {
             name: "Record".to_string(),
@@ -351,7 +366,7 @@ pub async fn spinup_types_test(path: &str) -> Result<SqlitePool> {
 
     let (shutdown_tx, _) = broadcast::channel(1);
     let mut engine = Engine::new(
-        world,
+        vec![world],
         db.clone(),
         Arc::clone(&provider),
         Processors { ..Processors::default() },
@@ -483,6 +483,51 @@ mod tests {
         let connection: Connection<Record> = serde_json::from_value(records).unwrap();
         assert_eq!(connection.edges.len(), 0);
 
+        // *** AGGREGATION TESTING ***
+
+        let aggregates = run_graphql_query(
+            &schema,
+            r#"
+            {
+                typesTestRecordAggregate(where: { type_u8GTE: 5 }) {
+                    countBy
+                    sum { type_u8 type_u128 }
+                    avg { type_u128 }
+                    min { type_u8 type_u128 }
+                    max { type_u8 type_u128 }
+                }
+            }
+            "#,
+        )
+        .await;
+        let aggregate = &aggregates["typesTestRecordAggregate"][0];
+        assert_eq!(aggregate["countBy"], 5);
+        assert_eq!(aggregate["sum"]["type_u8"], 35.0);
+        assert_eq!(aggregate["min"]["type_u8"], 5);
+        assert_eq!(aggregate["max"]["type_u8"], 9);
+        // unsigned members stored as hex are summed outside of sqlite
+        assert_eq!(aggregate["sum"]["type_u128"], "0x23");
+        assert_eq!(aggregate["avg"]["type_u128"], "0x7");
+        assert_eq!(aggregate["min"]["type_u128"], "0x5");
+        assert_eq!(aggregate["max"]["type_u128"], "0x9");
+
+        let aggregates = run_graphql_query(
+            &schema,
+            r#"
+            {
+                typesTestRecordAggregate(groupBy: [DEPTH]) {
+                    group { depth }
+                    countBy
+                }
+            }
+            "#,
+        )
+        .await;
+        let groups = aggregates["typesTestRecordAggregate"].as_array().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0]["group"]["depth"], "Zero");
+        assert_eq!(groups[0]["countBy"], 10);
+
         // *** SIBLING TESTING ***
         let sibling = record_sibling_query(&schema, "").await;
         let connection: Connection<RecordSibling> = serde_json::from_value(sibling).unwrap();
@@ -2,6 +2,7 @@
 mod tests {
     use std::collections::HashMap;
     use std::str::FromStr;
+    use std::sync::Arc;
     use std::time::Duration;
 
     use async_graphql::value;
@@ -9,17 +10,24 @@ mod tests {
     use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
     use dojo_world::contracts::abigen::model::Layout;
     use dojo_world::contracts::naming::{compute_selector_from_names, compute_selector_from_tag};
+    use dojo_world::contracts::world::WorldContractReader;
     use serial_test::serial;
     use sqlx::SqlitePool;
-    use starknet::core::types::Event;
+    use starknet::core::types::{Event, InvokeTransaction, InvokeTransactionV1, Transaction, U256};
+    use starknet::providers::jsonrpc::HttpTransport;
+    use starknet::providers::JsonRpcClient;
     use starknet_crypto::{poseidon_hash_many, Felt};
     use tokio::sync::{broadcast, mpsc};
     use torii_core::executor::Executor;
-    use torii_core::sql::utils::felts_to_sql_string;
+    use torii_core::sql::utils::{felts_to_sql_string, u256_to_sql_string};
     use torii_core::sql::Sql;
     use torii_core::types::ContractType;
+    use torii_core::utils::utc_dt_string_from_timestamp;
+    use url::Url;
 
-    use crate::tests::{model_fixtures, run_graphql_subscription};
+    use crate::tests::{
+        model_fixtures, run_graphql_subscription, run_graphql_subscription_updates,
+    };
     use crate::utils;
 
     #[sqlx::test(migrations = "../migrations")]
@@ -123,6 +131,7 @@ mod tests {
 
             // Set entity with one Record model
             db.set_entity(
+                Felt::ZERO,
                 ty,
                 &format!("0x{:064x}:0x{:04x}:0x{:04x}", 0, 0, 0),
                 block_timestamp,
@@ -250,6 +259,7 @@ mod tests {
 
             // Set entity with one Record model
             db.set_entity(
+                Felt::ZERO,
                 ty,
                 &format!("0x{:064x}:0x{:04x}:0x{:04x}", 0, 0, 0),
                 block_timestamp,
@@ -290,6 +300,116 @@ mod tests {
         rx.recv().await.unwrap();
     }
 
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_entity_subscription_with_diff(pool: SqlitePool) {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let (mut executor, sender) =
+            Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+        let mut db =
+            Sql::new(pool.clone(), sender, &HashMap::from([(Felt::ZERO, ContractType::WORLD)]))
+                .await
+                .unwrap();
+
+        model_fixtures(&mut db).await;
+        let namespace = "types_test".to_string();
+        let model_name = "Record".to_string();
+        let type_name = utils::type_name_from_names(&namespace, &model_name);
+
+        let (tx, mut rx) = mpsc::channel(10);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let record = |type_u64: u64| {
+                Ty::Struct(Struct {
+                    name: utils::struct_name_from_names(&namespace, &model_name),
+                    children: vec![
+                        Member {
+                            name: "record_id".to_string(),
+                            key: false,
+                            ty: Ty::Primitive(Primitive::U8(Some(0))),
+                        },
+                        Member {
+                            name: "type_u64".to_string(),
+                            key: false,
+                            ty: Ty::Primitive(Primitive::U64(Some(type_u64))),
+                        },
+                        Member {
+                            name: "typeContractAddress".to_string(),
+                            key: true,
+                            ty: Ty::Primitive(Primitive::ContractAddress(Some(Felt::ONE))),
+                        },
+                    ],
+                })
+            };
+
+            // the second update only changes `type_u64`
+            for (i, type_u64) in [1, 2].into_iter().enumerate() {
+                let ty = record(type_u64);
+                let keys = keys_from_ty(&ty).unwrap();
+                let keys_str = felts_to_sql_string(&keys);
+                let entity_id = poseidon_hash_many(&keys);
+                let model_id = model_id_from_ty(&ty);
+
+                db.set_entity(
+                    Felt::ZERO,
+                    ty,
+                    &format!("0x{:064x}:0x{:04x}:0x{:04x}", i, 0, 0),
+                    1710754478_u64,
+                    entity_id,
+                    model_id,
+                    Some(&keys_str),
+                )
+                .await
+                .unwrap();
+                db.execute().await.unwrap();
+            }
+
+            tx.send(()).await.unwrap();
+        });
+
+        let updates = run_graphql_subscription_updates(
+            &pool,
+            r#"subscription {
+                entityUpdated(diff: true) {
+                    models {
+                        __typename
+                        ... on types_test_Record {
+                            record_id
+                            type_u64
+                        }
+                    }
+                }
+            }"#,
+            2,
+        )
+        .await;
+
+        assert_eq!(
+            updates,
+            vec![
+                value!({
+                    "entityUpdated": {
+                        "models": [{ "__typename": type_name, "record_id": 0, "type_u64": "0x1" }]
+                    }
+                }),
+                value!({
+                    "entityUpdated": {
+                        "models": [{
+                            "__typename": type_name,
+                            "record_id": null,
+                            "type_u64": "0x2"
+                        }]
+                    }
+                }),
+            ]
+        );
+        rx.recv().await.unwrap();
+    }
+
     #[sqlx::test(migrations = "../migrations")]
     #[serial]
     async fn test_model_subscription(pool: SqlitePool) {
@@ -329,6 +449,7 @@ mod tests {
                 }],
             });
             db.register_model(
+                Felt::ZERO,
                 &namespace,
                 model,
                 Layout::Fixed(vec![]),
@@ -402,6 +523,7 @@ mod tests {
                 }],
             });
             db.register_model(
+                Felt::ZERO,
                 &namespace,
                 model,
                 Layout::Fixed(vec![]),
@@ -510,6 +632,305 @@ mod tests {
         rx.recv().await.unwrap();
     }
 
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_transaction_indexed(pool: SqlitePool) {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let (mut executor, sender) =
+            Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+        let mut db =
+            Sql::new(pool.clone(), sender, &HashMap::from([(Felt::ZERO, ContractType::WORLD)]))
+                .await
+                .unwrap();
+        let block_timestamp: u64 = 1710754478_u64;
+        let (tx, mut rx) = mpsc::channel(7);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+                transaction_hash: Felt::from_str("0xdead").unwrap(),
+                sender_address: Felt::from_str("0xbeef").unwrap(),
+                calldata: vec![
+                    Felt::from_str("0xc0de").unwrap(),
+                    Felt::from_str("0xface").unwrap(),
+                ],
+                max_fee: Felt::ONE,
+                signature: vec![Felt::TWO],
+                nonce: Felt::THREE,
+            }));
+            db.store_transaction(&transaction, "0x0:0xdead", block_timestamp).unwrap();
+            db.execute().await.unwrap();
+
+            tx.send(()).await.unwrap();
+        });
+
+        let response_value = run_graphql_subscription(
+            &pool,
+            r#"
+                subscription {
+                    transactionIndexed {
+                        transactionHash
+                        senderAddress
+                        calldata
+                        signature
+                    }
+                }
+            "#,
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+         "transactionIndexed": {
+            "transactionHash": format!("{:#x}", Felt::from_str("0xdead").unwrap()),
+            "senderAddress": format!("{:#x}", Felt::from_str("0xbeef").unwrap()),
+            "calldata": vec![
+                format!("{:#x}", Felt::from_str("0xc0de").unwrap()),
+                format!("{:#x}", Felt::from_str("0xface").unwrap())
+            ],
+            "signature": vec![format!("{:#x}", Felt::TWO)]
+         }
+        });
+
+        assert_eq!(response_value, expected_value);
+        rx.recv().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_rollback(pool: SqlitePool) {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let (mut executor, sender) =
+            Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+        let mut db =
+            Sql::new(pool.clone(), sender, &HashMap::from([(Felt::ZERO, ContractType::WORLD)]))
+                .await
+                .unwrap();
+        let (tx, mut rx) = mpsc::channel(7);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            // nothing has been indexed, so the world is never queried
+            let worlds: HashMap<Felt, Arc<WorldContractReader<JsonRpcClient<HttpTransport>>>> =
+                HashMap::new();
+            db.rollback(&worlds, 2, 2).await.unwrap();
+            db.execute().await.unwrap();
+
+            tx.send(()).await.unwrap();
+        });
+
+        let response_value = run_graphql_subscription(
+            &pool,
+            r#"
+                subscription {
+                    rollback {
+                        blockNumber
+                    }
+                }
+            "#,
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+            "rollback": { "blockNumber": 2 }
+        });
+
+        assert_eq!(response_value, expected_value);
+        rx.recv().await.unwrap();
+    }
+
+    /// Returns a database indexing the ERC20 `contract`, whose token is already stored so that its
+    /// metadata isn't fetched from the chain.
+    async fn erc20_db(pool: &SqlitePool, contract: Felt) -> Sql {
+        let address = format!("{contract:#x}");
+        sqlx::query(
+            "INSERT INTO contracts (id, contract_address, contract_type) VALUES (?, ?, 'ERC20')",
+        )
+        .bind(&address)
+        .bind(&address)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO tokens (id, contract_address, name, symbol, decimals) VALUES (?, ?, \
+             'Ether', 'ETH', 18)",
+        )
+        .bind(&address)
+        .bind(&address)
+        .execute(pool)
+        .await
+        .unwrap();
+        // the known token ids are loaded from the balances
+        sqlx::query(
+            "INSERT INTO balances (id, balance, account_address, contract_address, token_id) \
+             VALUES (?, ?, '0x0', ?, ?)",
+        )
+        .bind(felts_to_sql_string(&[Felt::ZERO, contract]))
+        .bind(u256_to_sql_string(&U256::from(0u8)))
+        .bind(&address)
+        .bind(&address)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let (mut executor, sender) =
+            Executor::new(pool.clone(), shutdown_tx.clone()).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+        Sql::new(pool.clone(), sender, &HashMap::from([(contract, ContractType::ERC20)]))
+            .await
+            .unwrap()
+    }
+
+    /// Mints `1` token to `0xcafe` and `100` tokens to `account` in the same block.
+    async fn erc20_mints(db: &mut Sql, contract: Felt, account: Felt, block_timestamp: u64) {
+        let provider =
+            JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost").unwrap()));
+        let mints = [
+            (Felt::from_str("0xcafe").unwrap(), 1u64, "0x0:0xc0de:0x0"),
+            (account, 100u64, "0x0:0xdead:0x1"),
+        ];
+        for (to, amount, event_id) in mints {
+            db.handle_erc20_transfer(
+                contract,
+                Felt::ZERO,
+                to,
+                U256::from(amount),
+                &provider,
+                block_timestamp,
+                event_id,
+            )
+            .await
+            .unwrap();
+        }
+        db.apply_cache_diff().await.unwrap();
+        db.execute().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_erc_transfer_subscription(pool: SqlitePool) {
+        let contract = Felt::from_str("0xe7c").unwrap();
+        let account = Felt::from_str("0xbeef").unwrap();
+        let mut db = erc20_db(&pool, contract).await;
+        let block_timestamp: u64 = 1710754478_u64;
+        let (tx, mut rx) = mpsc::channel(7);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            erc20_mints(&mut db, contract, account, block_timestamp).await;
+
+            tx.send(()).await.unwrap();
+        });
+
+        // the mint to `0xcafe` is filtered out
+        let response_value = run_graphql_subscription(
+            &pool,
+            &format!(
+                r#"
+                subscription {{
+                    ercTransfer(accountAddress: "{account:#x}") {{
+                        from
+                        to
+                        amount
+                        type
+                        executedAt
+                        tokenMetadata {{
+                            name
+                            symbol
+                            decimals
+                            contractAddress
+                        }}
+                        transactionHash
+                    }}
+                }}
+            "#
+            ),
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+            "ercTransfer": {
+                "from": format!("{:#x}", Felt::ZERO),
+                "to": format!("{account:#x}"),
+                "amount": u256_to_sql_string(&U256::from(100u64)),
+                "type": "ERC20",
+                "executedAt": utc_dt_string_from_timestamp(block_timestamp),
+                "tokenMetadata": {
+                    "name": "Ether",
+                    "symbol": "ETH",
+                    "decimals": "18",
+                    "contractAddress": format!("{contract:#x}")
+                },
+                "transactionHash": "0xdead"
+            }
+        });
+
+        assert_eq!(response_value, expected_value);
+        rx.recv().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_erc_balance_subscription(pool: SqlitePool) {
+        let contract = Felt::from_str("0xe7c").unwrap();
+        let account = Felt::from_str("0xbeef").unwrap();
+        let mut db = erc20_db(&pool, contract).await;
+        let (tx, mut rx) = mpsc::channel(7);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            erc20_mints(&mut db, contract, account, 1710754478_u64).await;
+
+            tx.send(()).await.unwrap();
+        });
+
+        // the balance of `0xcafe` is filtered out
+        let response_value = run_graphql_subscription(
+            &pool,
+            &format!(
+                r#"
+                subscription {{
+                    ercBalanceUpdated(accountAddress: "{account:#x}") {{
+                        balance
+                        type
+                        tokenMetadata {{
+                            name
+                            symbol
+                            decimals
+                            contractAddress
+                        }}
+                    }}
+                }}
+            "#
+            ),
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+            "ercBalanceUpdated": {
+                "balance": u256_to_sql_string(&U256::from(100u64)),
+                "type": "ERC20",
+                "tokenMetadata": {
+                    "name": "Ether",
+                    "symbol": "ETH",
+                    "decimals": "18",
+                    "contractAddress": format!("{contract:#x}")
+                }
+            }
+        });
+
+        assert_eq!(response_value, expected_value);
+        rx.recv().await.unwrap();
+    }
+
     fn keys_from_ty(ty: &Ty) -> anyhow::Result<Vec<Felt>> {
         if let Ty::Struct(s) = &ty {
             let mut keys = Vec::new();
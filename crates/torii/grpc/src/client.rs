@@ -16,7 +16,7 @@ use crate::proto::world::{
     SubscribeModelsRequest, SubscribeModelsResponse, UpdateEntitiesSubscriptionRequest,
     UpdateEventMessagesSubscriptionRequest, WorldMetadataRequest,
 };
-use crate::types::schema::{Entity, SchemaError};
+use crate::types::schema::{Entity, EntityDiffUpdate, SchemaError};
 use crate::types::{EntityKeysClause, Event, EventQuery, IndexerUpdate, ModelKeysClause, Query};
 
 #[derive(Debug, thiserror::Error)]
@@ -38,9 +38,9 @@ pub enum Error {
 }
 
 #[derive(Debug)]
-/// A lightweight wrapper around the grpc client.
+/// A lightweight wrapper around the grpc client, scoped to a world of the indexer.
 pub struct WorldClient {
-    _world_address: Felt,
+    world_address: Felt,
     #[cfg(not(target_arch = "wasm32"))]
     inner: world_client::WorldClient<tonic::transport::Channel>,
     #[cfg(target_arch = "wasm32")]
@@ -54,7 +54,7 @@ impl WorldClient {
             Endpoint::from_shared(dst.clone()).map_err(|e| Error::Endpoint(e.to_string()))?;
         let channel = endpoint.connect().await.map_err(Error::Transport)?;
         Ok(Self {
-            _world_address: world_address,
+            world_address,
             inner: world_client::WorldClient::with_origin(channel, endpoint.uri().clone())
                 .accept_compressed(CompressionEncoding::Gzip)
                 .send_compressed(CompressionEncoding::Gzip),
@@ -63,9 +63,9 @@ impl WorldClient {
 
     // we make this function async so that we can keep the function signature similar
     #[cfg(target_arch = "wasm32")]
-    pub async fn new(endpoint: String, _world_address: Felt) -> Result<Self, Error> {
+    pub async fn new(endpoint: String, world_address: Felt) -> Result<Self, Error> {
         Ok(Self {
-            _world_address,
+            world_address,
             inner: world_client::WorldClient::new(tonic_web_wasm_client::Client::new(endpoint))
                 .accept_compressed(CompressionEncoding::Gzip)
                 .send_compressed(CompressionEncoding::Gzip),
@@ -75,7 +75,7 @@ impl WorldClient {
     /// Retrieve the metadata of the World.
     pub async fn metadata(&mut self) -> Result<dojo_types::WorldMetadata, Error> {
         self.inner
-            .world_metadata(WorldMetadataRequest {})
+            .world_metadata(WorldMetadataRequest { world_address: self.world_address_bytes() })
             .await
             .map_err(Error::Grpc)
             .and_then(|res| {
@@ -86,19 +86,26 @@ impl WorldClient {
             .and_then(|metadata| metadata.try_into().map_err(Error::ParseStr))
     }
 
+    /// Retrieves the entities of the world of the client, unless the query selects another one.
     pub async fn retrieve_entities(
         &mut self,
-        query: Query,
+        mut query: Query,
     ) -> Result<RetrieveEntitiesResponse, Error> {
+        query.world_address.get_or_insert(self.world_address);
         let request = RetrieveEntitiesRequest { query: Some(query.into()) };
         self.inner.retrieve_entities(request).await.map_err(Error::Grpc).map(|res| res.into_inner())
     }
 
+    /// Retrieves the event messages of the world of the client, unless the query selects another
+    /// one. The historical event messages can't be filtered by world.
     pub async fn retrieve_event_messages(
         &mut self,
-        query: Query,
+        mut query: Query,
         historical: bool,
     ) -> Result<RetrieveEntitiesResponse, Error> {
+        if !historical {
+            query.world_address.get_or_insert(self.world_address);
+        }
         let request = RetrieveEventMessagesRequest { query: Some(query.into()), historical };
         self.inner
             .retrieve_event_messages(request)
@@ -107,10 +114,12 @@ impl WorldClient {
             .map(|res| res.into_inner())
     }
 
+    /// Retrieves the events of the world of the client, unless the query selects another one.
     pub async fn retrieve_events(
         &mut self,
-        query: EventQuery,
+        mut query: EventQuery,
     ) -> Result<RetrieveEventsResponse, Error> {
+        query.world_address.get_or_insert(self.world_address);
         let request = RetrieveEventsRequest { query: Some(query.into()) };
         self.inner.retrieve_events(request).await.map_err(Error::Grpc).map(|res| res.into_inner())
     }
@@ -139,19 +148,48 @@ impl WorldClient {
         let clauses = clauses.into_iter().map(|c| c.into()).collect();
         let stream = self
             .inner
-            .subscribe_entities(SubscribeEntitiesRequest { clauses })
+            .subscribe_entities(SubscribeEntitiesRequest {
+                clauses,
+                world_address: self.world_address_bytes(),
+                diff: false,
+            })
             .await
             .map_err(Error::Grpc)
             .map(|res| res.into_inner())?;
 
         Ok(EntityUpdateStreaming(stream.map_ok(Box::new(|res| {
             res.entity.map_or(
-                (res.subscription_id, Entity { hashed_keys: Felt::ZERO, models: vec![] }),
+                (
+                    res.subscription_id,
+                    Entity { hashed_keys: Felt::ZERO, models: vec![], world_address: Felt::ZERO },
+                ),
                 |entity| (res.subscription_id, entity.try_into().expect("must able to serialize")),
             )
         }))))
     }
 
+    /// Subscribe to entities updates of a World, only receiving the changed models and members.
+    pub async fn subscribe_entity_diffs(
+        &mut self,
+        clauses: Vec<EntityKeysClause>,
+    ) -> Result<EntityDiffStreaming, Error> {
+        let clauses = clauses.into_iter().map(|c| c.into()).collect();
+        let stream = self
+            .inner
+            .subscribe_entities(SubscribeEntitiesRequest {
+                clauses,
+                world_address: self.world_address_bytes(),
+                diff: true,
+            })
+            .await
+            .map_err(Error::Grpc)
+            .map(|res| res.into_inner())?;
+
+        Ok(EntityDiffStreaming(stream.map_ok(Box::new(|res| {
+            (res.subscription_id, res.try_into().expect("must able to serialize"))
+        }))))
+    }
+
     /// Update an entities subscription.
     pub async fn update_entities_subscription(
         &mut self,
@@ -164,6 +202,7 @@ impl WorldClient {
             .update_entities_subscription(UpdateEntitiesSubscriptionRequest {
                 subscription_id,
                 clauses,
+                world_address: self.world_address_bytes(),
             })
             .await
             .map_err(Error::Grpc)
@@ -179,19 +218,51 @@ impl WorldClient {
         let clauses = clauses.into_iter().map(|c| c.into()).collect();
         let stream = self
             .inner
-            .subscribe_event_messages(SubscribeEventMessagesRequest { clauses, historical })
+            .subscribe_event_messages(SubscribeEventMessagesRequest {
+                clauses,
+                historical,
+                world_address: self.world_address_bytes(),
+                diff: false,
+            })
             .await
             .map_err(Error::Grpc)
             .map(|res| res.into_inner())?;
 
         Ok(EntityUpdateStreaming(stream.map_ok(Box::new(|res| {
             res.entity.map_or(
-                (res.subscription_id, Entity { hashed_keys: Felt::ZERO, models: vec![] }),
+                (
+                    res.subscription_id,
+                    Entity { hashed_keys: Felt::ZERO, models: vec![], world_address: Felt::ZERO },
+                ),
                 |entity| (res.subscription_id, entity.try_into().expect("must able to serialize")),
             )
         }))))
     }
 
+    /// Subscribe to event messages of a World, only receiving the changed models and members.
+    pub async fn subscribe_event_message_diffs(
+        &mut self,
+        clauses: Vec<EntityKeysClause>,
+        historical: bool,
+    ) -> Result<EntityDiffStreaming, Error> {
+        let clauses = clauses.into_iter().map(|c| c.into()).collect();
+        let stream = self
+            .inner
+            .subscribe_event_messages(SubscribeEventMessagesRequest {
+                clauses,
+                historical,
+                world_address: self.world_address_bytes(),
+                diff: true,
+            })
+            .await
+            .map_err(Error::Grpc)
+            .map(|res| res.into_inner())?;
+
+        Ok(EntityDiffStreaming(stream.map_ok(Box::new(|res| {
+            (res.subscription_id, res.try_into().expect("must able to serialize"))
+        }))))
+    }
+
     /// Update an event messages subscription.
     pub async fn update_event_messages_subscription(
         &mut self,
@@ -205,6 +276,7 @@ impl WorldClient {
                 subscription_id,
                 clauses,
                 historical,
+                world_address: self.world_address_bytes(),
             })
             .await
             .map_err(Error::Grpc)
@@ -220,7 +292,10 @@ impl WorldClient {
 
         let stream = self
             .inner
-            .subscribe_events(SubscribeEventsRequest { keys })
+            .subscribe_events(SubscribeEventsRequest {
+                keys,
+                world_address: self.world_address_bytes(),
+            })
             .await
             .map_err(Error::Grpc)
             .map(|res| res.into_inner())?;
@@ -240,6 +315,7 @@ impl WorldClient {
             .inner
             .subscribe_models(SubscribeModelsRequest {
                 models_keys: models_keys.into_iter().map(|e| e.into()).collect(),
+                world_address: self.world_address_bytes(),
             })
             .await
             .map_err(Error::Grpc)
@@ -252,6 +328,10 @@ impl WorldClient {
             None => empty_state_update(),
         }))))
     }
+
+    fn world_address_bytes(&self) -> Vec<u8> {
+        self.world_address.to_bytes_be().to_vec()
+    }
 }
 
 type ModelDiffMappedStream = MapOk<
@@ -291,6 +371,24 @@ impl Stream for EntityUpdateStreaming {
     }
 }
 
+type EntityDiffMappedStream = MapOk<
+    tonic::Streaming<SubscribeEntityResponse>,
+    Box<dyn Fn(SubscribeEntityResponse) -> (SubscriptionId, EntityDiffUpdate) + Send>,
+>;
+
+#[derive(Debug)]
+pub struct EntityDiffStreaming(EntityDiffMappedStream);
+
+impl Stream for EntityDiffStreaming {
+    type Item = <EntityDiffMappedStream as Stream>::Item;
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
 type EventMappedStream = MapOk<
     tonic::Streaming<SubscribeEventsResponse>,
     Box<dyn Fn(SubscribeEventsResponse) -> Event + Send>,
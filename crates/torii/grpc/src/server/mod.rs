@@ -1,4 +1,5 @@
 pub mod logger;
+pub mod rest;
 pub mod subscriptions;
 
 #[cfg(test)]
@@ -13,14 +14,18 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
 use dojo_types::primitive::{Primitive, PrimitiveError};
 use dojo_types::schema::Ty;
 use dojo_world::contracts::naming::compute_selector_from_names;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use http::HeaderName;
 use proto::world::{
     RetrieveEntitiesRequest, RetrieveEntitiesResponse, RetrieveEventsRequest,
-    RetrieveEventsResponse, SubscribeModelsRequest, SubscribeModelsResponse,
+    RetrieveEventsResponse, RetrieveTokenBalancesRequest, RetrieveTokenBalancesResponse,
+    RetrieveTokensRequest, RetrieveTokensResponse, RetrieveWorldResourcesRequest,
+    RetrieveWorldResourcesResponse, SubscribeModelsRequest, SubscribeModelsResponse,
     UpdateEntitiesSubscriptionRequest,
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
@@ -32,6 +37,7 @@ use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 use subscriptions::event::EventManager;
 use subscriptions::indexer::IndexerManager;
+use subscriptions::world_resources::WorldResourcesManager;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::{channel, Receiver};
 use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
@@ -41,22 +47,32 @@ use tonic::{Request, Response, Status};
 use tonic_web::GrpcWebLayer;
 use torii_core::error::{Error, ParseError, QueryError};
 use torii_core::model::{build_sql_query, map_row_to_ty};
+use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::cache::ModelCache;
+use torii_core::sql::history::HistoryBounds;
+use torii_core::sql::search::{search_matches_query, search_query};
 use torii_core::sql::utils::sql_string_to_felts;
+use torii_core::types::{Model as ModelRegistered, Namespace, Permission, WorldContract};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
+use self::rest::RestApi;
 use self::subscriptions::entity::EntityManager;
 use self::subscriptions::event_message::EventMessageManager;
 use self::subscriptions::model_diff::{ModelDiffRequest, StateDiffManager};
+use self::subscriptions::token::TokenManager;
+use self::subscriptions::transaction::TransactionManager;
 use crate::proto::types::clause::ClauseType;
 use crate::proto::types::member_value::ValueType;
-use crate::proto::types::LogicalOperator;
+use crate::proto::types::{LogicalOperator, OrderDirection};
 use crate::proto::world::world_server::WorldServer;
 use crate::proto::world::{
     RetrieveEntitiesStreamingResponse, RetrieveEventMessagesRequest, SubscribeEntitiesRequest,
     SubscribeEntityResponse, SubscribeEventMessagesRequest, SubscribeEventsResponse,
-    SubscribeIndexerRequest, SubscribeIndexerResponse, UpdateEventMessagesSubscriptionRequest,
-    WorldMetadataRequest, WorldMetadataResponse,
+    SubscribeIndexerRequest, SubscribeIndexerResponse, SubscribeTokenBalancesRequest,
+    SubscribeTokenBalancesResponse, SubscribeTokenTransfersRequest,
+    SubscribeTokenTransfersResponse, SubscribeTransactionsRequest, SubscribeTransactionsResponse,
+    SubscribeWorldResourcesRequest, SubscribeWorldResourcesResponse,
+    UpdateEventMessagesSubscriptionRequest, WorldMetadataRequest, WorldMetadataResponse,
 };
 use crate::proto::{self};
 use crate::types::schema::SchemaError;
@@ -97,6 +113,9 @@ pub struct DojoWorld {
     event_manager: Arc<EventManager>,
     state_diff_manager: Arc<StateDiffManager>,
     indexer_manager: Arc<IndexerManager>,
+    world_resources_manager: Arc<WorldResourcesManager>,
+    token_manager: Arc<TokenManager>,
+    transaction_manager: Arc<TransactionManager>,
 }
 
 impl DojoWorld {
@@ -112,10 +131,12 @@ impl DojoWorld {
         let event_manager = Arc::new(EventManager::default());
         let state_diff_manager = Arc::new(StateDiffManager::default());
         let indexer_manager = Arc::new(IndexerManager::default());
+        let world_resources_manager = Arc::new(WorldResourcesManager::default());
+        let token_manager = Arc::new(TokenManager::default());
+        let transaction_manager = Arc::new(TransactionManager::default());
 
         tokio::task::spawn(subscriptions::model_diff::Service::new_with_block_rcv(
             block_rx,
-            world_address,
             provider,
             Arc::clone(&state_diff_manager),
         ));
@@ -130,6 +151,34 @@ impl DojoWorld {
 
         tokio::task::spawn(subscriptions::indexer::Service::new(Arc::clone(&indexer_manager)));
 
+        tokio::task::spawn(subscriptions::world_resources::Service::new(Arc::clone(
+            &world_resources_manager,
+        )));
+
+        tokio::task::spawn(subscriptions::token::Service::new(
+            Arc::clone(&token_manager),
+            pool.clone(),
+        ));
+
+        tokio::task::spawn(subscriptions::transaction::Service::new(Arc::clone(
+            &transaction_manager,
+        )));
+
+        // Models can be upgraded at any time, so cached schemas are dropped whenever a model is
+        // (re)registered to be reloaded from the database on next use.
+        tokio::task::spawn({
+            let model_cache = Arc::clone(&model_cache);
+            async move {
+                let mut models = SimpleBroker::<ModelRegistered>::subscribe();
+                while let Some(model) = models.next().await {
+                    match Felt::from_str(&model.id) {
+                        Ok(selector) => model_cache.remove(&selector).await,
+                        Err(_) => model_cache.clear().await,
+                    }
+                }
+            }
+        });
+
         Self {
             pool,
             world_address,
@@ -139,16 +188,24 @@ impl DojoWorld {
             event_manager,
             state_diff_manager,
             indexer_manager,
+            world_resources_manager,
+            token_manager,
+            transaction_manager,
         }
     }
 }
 
 impl DojoWorld {
-    pub async fn world(&self) -> Result<proto::types::WorldMetadata, Error> {
-        let world_address = sqlx::query_scalar(&format!(
-            "SELECT contract_address FROM contracts WHERE id = '{:#x}'",
-            self.world_address
-        ))
+    /// Retrieves the metadata of the given world, the first indexed world if `None`.
+    pub async fn world(
+        &self,
+        world_address: Option<Felt>,
+    ) -> Result<proto::types::WorldMetadata, Error> {
+        let world_address = format!("{:#x}", world_address.unwrap_or(self.world_address));
+        let world_address = sqlx::query_scalar(
+            "SELECT contract_address FROM contracts WHERE id = ? AND contract_type = 'WORLD'",
+        )
+        .bind(&world_address)
         .fetch_one(&self.pool)
         .await?;
 
@@ -165,9 +222,11 @@ impl DojoWorld {
         }
 
         let models: Vec<ModelDb> = sqlx::query_as(
-            "SELECT id, namespace, name, class_hash, contract_address, packed_size, \
-             unpacked_size, layout FROM models",
+            "SELECT m.id, m.namespace, m.name, wm.class_hash, wm.contract_address, m.packed_size, \
+             m.unpacked_size, m.layout FROM models m JOIN world_models wm ON wm.model_id = m.id \
+             WHERE wm.world_address = ?",
         )
+        .bind(&world_address)
         .fetch_all(&self.pool)
         .await?;
 
@@ -187,6 +246,7 @@ impl DojoWorld {
                 unpacked_size: model.unpacked_size,
                 layout: model.layout.as_bytes().to_vec(),
                 schema: serde_json::to_vec(&schema).unwrap(),
+                indexes: self.model_indexes(&model.id).await?,
             });
         }
 
@@ -214,31 +274,17 @@ impl DojoWorld {
         .await
     }
 
-    async fn events_all(&self, limit: u32, offset: u32) -> Result<Vec<proto::types::Event>, Error> {
-        let query = r#"
-            SELECT keys, data, transaction_hash
-            FROM events
-            ORDER BY id DESC
-            LIMIT ? OFFSET ?
-         "#
-        .to_string();
-
-        let row_events: Vec<(String, String, String)> =
-            sqlx::query_as(&query).bind(limit).bind(offset).fetch_all(&self.pool).await?;
-        row_events.iter().map(map_row_to_event).collect()
-    }
-
     async fn fetch_entities(
         &self,
         table: &str,
         entity_relation_column: &str,
-        entities: Vec<(String, String)>,
+        entities: Vec<(String, String, String)>,
         dont_include_hashed_keys: bool,
     ) -> Result<Vec<proto::types::Entity>, Error> {
         // Group entities by their model combinations
-        let mut model_groups: HashMap<String, Vec<String>> = HashMap::new();
-        for (entity_id, models_str) in entities {
-            model_groups.entry(models_str).or_default().push(entity_id);
+        let mut model_groups: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for (entity_id, world_address, models_str) in entities {
+            model_groups.entry(models_str).or_default().push((entity_id, world_address));
         }
 
         let mut all_entities = Vec::new();
@@ -248,22 +294,23 @@ impl DojoWorld {
         // Create a temporary table to store entity IDs due to them potentially exceeding
         // SQLite's parameters limit which is 999
         sqlx::query(
-            "CREATE TEMPORARY TABLE temp_entity_ids (id TEXT PRIMARY KEY, model_group TEXT)",
+            "CREATE TEMPORARY TABLE temp_entity_ids (id TEXT NOT NULL, world_address TEXT NOT \
+             NULL, model_group TEXT, PRIMARY KEY (id, world_address))",
         )
         .execute(&mut *tx)
         .await?;
 
         // Insert all entity IDs into the temporary table
         for (model_ids, entity_ids) in &model_groups {
-            for chunk in entity_ids.chunks(999) {
-                let placeholders = chunk.iter().map(|_| "(?, ?)").collect::<Vec<_>>().join(",");
+            for chunk in entity_ids.chunks(333) {
+                let placeholders = chunk.iter().map(|_| "(?, ?, ?)").collect::<Vec<_>>().join(",");
                 let query = format!(
-                    "INSERT INTO temp_entity_ids (id, model_group) VALUES {}",
+                    "INSERT INTO temp_entity_ids (id, world_address, model_group) VALUES {}",
                     placeholders
                 );
                 let mut query = sqlx::query(&query);
-                for id in chunk {
-                    query = query.bind(id).bind(model_ids);
+                for (id, world_address) in chunk {
+                    query = query.bind(id).bind(world_address).bind(model_ids);
                 }
                 query.execute(&mut *tx).await?;
             }
@@ -280,10 +327,12 @@ impl DojoWorld {
                 table,
                 entity_relation_column,
                 Some(&format!(
-                    "[{table}].id IN (SELECT id FROM temp_entity_ids WHERE model_group = ?)"
+                    "([{table}].id, [{table}].world_address) IN (SELECT id, world_address FROM \
+                     temp_entity_ids WHERE model_group = ?)"
                 )),
                 Some(&format!(
-                    "[{table}].id IN (SELECT id FROM temp_entity_ids WHERE model_group = ?)"
+                    "([{table}].id, [{table}].world_address) IN (SELECT id, world_address FROM \
+                     temp_entity_ids WHERE model_group = ?)"
                 )),
                 None,
                 None,
@@ -323,7 +372,7 @@ impl DojoWorld {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<Vec<proto::types::Entity>, Error> {
-        let db_entities: Vec<(String, String, String, String)> = if keys_pattern.is_some() {
+        let db_entities: Vec<(String, String, String, String, String)> = if keys_pattern.is_some() {
             sqlx::query_as(query)
                 .bind(keys_pattern.unwrap())
                 .bind(limit)
@@ -335,9 +384,11 @@ impl DojoWorld {
         };
 
         let mut entities = HashMap::new();
-        for (id, data, model_id, _) in db_entities {
+        for (id, world_address, data, model_id, _) in db_entities {
             let hashed_keys =
                 Felt::from_str(&id).map_err(ParseError::FromStr)?.to_bytes_be().to_vec();
+            let world =
+                Felt::from_str(&world_address).map_err(ParseError::FromStr)?.to_bytes_be().to_vec();
             let model = self
                 .model_cache
                 .model(&Felt::from_str(&model_id).map_err(ParseError::FromStr)?)
@@ -345,15 +396,144 @@ impl DojoWorld {
             let mut schema = model.schema;
             schema.deserialize(&mut sql_string_to_felts(&data))?;
 
-            let entity = entities
-                .entry(id)
-                .or_insert_with(|| proto::types::Entity { hashed_keys, models: vec![] });
+            let entity = entities.entry((id, world_address)).or_insert_with(|| {
+                proto::types::Entity { hashed_keys, models: vec![], world_address: world }
+            });
             entity.models.push(schema.as_struct().unwrap().clone().into());
         }
 
         Ok(entities.into_values().collect())
     }
 
+    /// Retrieves the entities of the historical models as they were within `bounds`, from their
+    /// latest version in them. Only hashed keys and keys clauses are supported, the members of the
+    /// versions not being stored in columns.
+    pub(crate) async fn query_historical(
+        &self,
+        clause: Option<proto::types::Clause>,
+        bounds: HistoryBounds,
+        limit: u32,
+        offset: u32,
+        dont_include_hashed_keys: bool,
+        world_address: Option<Felt>,
+    ) -> Result<(Vec<proto::types::Entity>, u32), Error> {
+        let (latest_version, mut arguments) = bounds
+            .latest_version_condition()
+            .map_err(|e| QueryError::UnsupportedValue(e.to_string()))?;
+
+        let mut conditions = Vec::new();
+        let clause_type = clause
+            .map(|clause| clause.clause_type.ok_or(QueryError::MissingParam("clause_type".into())))
+            .transpose()?;
+        match clause_type {
+            None => {}
+            Some(ClauseType::HashedKeys(hashed_keys)) => {
+                if !hashed_keys.hashed_keys.is_empty() {
+                    conditions.push(format!(
+                        "h.id IN ({})",
+                        vec!["?"; hashed_keys.hashed_keys.len()].join(", ")
+                    ));
+                    arguments.extend(
+                        hashed_keys
+                            .hashed_keys
+                            .iter()
+                            .map(|id| format!("{:#x}", Felt::from_bytes_be_slice(id))),
+                    );
+                }
+            }
+            Some(ClauseType::Keys(keys)) => {
+                conditions.push("h.keys REGEXP ?".to_string());
+                arguments.push(build_keys_pattern(&keys)?);
+
+                if !keys.models.is_empty() {
+                    conditions.push(format!(
+                        "h.model_id IN ({})",
+                        vec!["?"; keys.models.len()].join(", ")
+                    ));
+                    for model in &keys.models {
+                        let (namespace, name) = model
+                            .split_once('-')
+                            .ok_or(QueryError::InvalidNamespacedModel(model.clone()))?;
+                        arguments
+                            .push(format!("{:#x}", compute_selector_from_names(namespace, name)));
+                    }
+                }
+            }
+            Some(ClauseType::Member(_) | ClauseType::Composite(_)) => {
+                return Err(QueryError::UnsupportedQuery.into());
+            }
+        }
+        if let Some(world_address) = world_address {
+            conditions.push("h.world_address = ?".into());
+            arguments.push(format!("{:#x}", world_address));
+        }
+
+        let versions = format!(
+            "FROM entities_historical h WHERE {latest_version}{}",
+            conditions.iter().map(|condition| format!(" AND {condition}")).collect::<String>()
+        );
+
+        let count_query =
+            format!("SELECT COUNT(*) FROM (SELECT DISTINCT h.id, h.world_address {versions})");
+        let mut count = sqlx::query_scalar(&count_query);
+        for argument in &arguments {
+            count = count.bind(argument);
+        }
+        let total_count: u32 = count.fetch_one(&self.pool).await?;
+        if total_count == 0 {
+            return Ok((Vec::new(), 0));
+        }
+
+        let ids_query = format!(
+            "SELECT h.id, h.world_address {versions} GROUP BY h.id, h.world_address ORDER BY \
+             MAX(h.event_id) DESC LIMIT ? OFFSET ?"
+        );
+        let mut ids = sqlx::query_as(&ids_query);
+        for argument in &arguments {
+            ids = ids.bind(argument);
+        }
+        let ids: Vec<(String, String)> = ids.bind(limit).bind(offset).fetch_all(&self.pool).await?;
+
+        let rows_query = format!(
+            "SELECT h.id, h.world_address, h.model_id, h.data {versions} AND (h.id, \
+             h.world_address) IN (VALUES {})",
+            vec!["(?, ?)"; ids.len()].join(", ")
+        );
+        let mut rows = sqlx::query_as(&rows_query);
+        for argument in &arguments {
+            rows = rows.bind(argument);
+        }
+        for (id, world_address) in &ids {
+            rows = rows.bind(id).bind(world_address);
+        }
+        let rows: Vec<(String, String, String, String)> = rows.fetch_all(&self.pool).await?;
+
+        let mut entities = HashMap::new();
+        for (id, world_address, model_id, data) in rows {
+            let model = self
+                .model_cache
+                .model(&Felt::from_str(&model_id).map_err(ParseError::FromStr)?)
+                .await?;
+            let mut schema = model.schema;
+            schema.deserialize(&mut sql_string_to_felts(&data))?;
+
+            let hashed_keys = if dont_include_hashed_keys {
+                vec![]
+            } else {
+                Felt::from_str(&id).map_err(ParseError::FromStr)?.to_bytes_be().to_vec()
+            };
+            let world =
+                Felt::from_str(&world_address).map_err(ParseError::FromStr)?.to_bytes_be().to_vec();
+            let entity = entities.entry((id, world_address)).or_insert_with(|| {
+                proto::types::Entity { hashed_keys, models: vec![], world_address: world }
+            });
+            entity.models.push(schema.as_struct().unwrap().clone().into());
+        }
+
+        let entities = ids.iter().filter_map(|id| entities.remove(id)).collect();
+        Ok((entities, total_count))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn query_by_hashed_keys(
         &self,
@@ -398,9 +578,10 @@ impl DojoWorld {
         let mut query = if table == EVENT_MESSAGES_HISTORICAL_TABLE {
             format!(
                 r#"
-            SELECT {table}.id, {table}.data, {table}.model_id, group_concat({model_relation_table}.model_id) as model_ids
+            SELECT {table}.id, {table}.world_address, {table}.data, {table}.model_id, group_concat({model_relation_table}.model_id) as model_ids
             FROM {table}
             JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
+                AND {table}.world_address = {model_relation_table}.world_address
             {filter_ids}
             GROUP BY {table}.event_id
             ORDER BY {table}.event_id DESC
@@ -409,11 +590,12 @@ impl DojoWorld {
         } else {
             format!(
                 r#"
-            SELECT {table}.id, group_concat({model_relation_table}.model_id) as model_ids
+            SELECT {table}.id, {table}.world_address, group_concat({model_relation_table}.model_id) as model_ids
             FROM {table}
             JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
+                AND {table}.world_address = {model_relation_table}.world_address
             {filter_ids}
-            GROUP BY {table}.id
+            GROUP BY {table}.id, {table}.world_address
             ORDER BY {table}.event_id DESC
          "#
             )
@@ -433,7 +615,7 @@ impl DojoWorld {
             return Ok((entities, total_count));
         }
 
-        let db_entities: Vec<(String, String)> =
+        let db_entities: Vec<(String, String, String)> =
             sqlx::query_as(&query).bind(limit).bind(offset).fetch_all(&self.pool).await?;
 
         let entities = self
@@ -484,6 +666,7 @@ impl DojoWorld {
                 format!(
                     r#"
                 JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
+                    AND {table}.world_address = {model_relation_table}.world_address
                 WHERE {model_relation_table}.model_id IN ({})
                 AND {table}.keys REGEXP ?
             "#,
@@ -510,9 +693,10 @@ impl DojoWorld {
         let mut models_query = if table == EVENT_MESSAGES_HISTORICAL_TABLE {
             format!(
                 r#"
-                SELECT {table}.id, {table}.data, {table}.model_id, group_concat({model_relation_table}.model_id) as model_ids
+                SELECT {table}.id, {table}.world_address, {table}.data, {table}.model_id, group_concat({model_relation_table}.model_id) as model_ids
                 FROM {table}
                 JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
+                    AND {table}.world_address = {model_relation_table}.world_address
                 WHERE {table}.keys REGEXP ?
                 GROUP BY {table}.event_id
             "#
@@ -520,11 +704,12 @@ impl DojoWorld {
         } else {
             format!(
                 r#"
-                SELECT {table}.id, group_concat({model_relation_table}.model_id) as model_ids
+                SELECT {table}.id, {table}.world_address, group_concat({model_relation_table}.model_id) as model_ids
                 FROM {table}
                 JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
+                    AND {table}.world_address = {model_relation_table}.world_address
                 WHERE {table}.keys REGEXP ?
-                GROUP BY {table}.id
+                GROUP BY {table}.id, {table}.world_address
             "#
             )
         };
@@ -565,7 +750,7 @@ impl DojoWorld {
             return Ok((entities, total_count));
         }
 
-        let db_entities: Vec<(String, String)> = sqlx::query_as(&models_query)
+        let db_entities: Vec<(String, String, String)> = sqlx::query_as(&models_query)
             .bind(&keys_pattern)
             .bind(limit)
             .bind(offset)
@@ -578,31 +763,72 @@ impl DojoWorld {
         Ok((entities, total_count))
     }
 
-    pub(crate) async fn events_by_keys(
+    /// Retrieves a page of events matching the keys, the most recent first. With a `cursor`, the
+    /// page starts after the last event of the page the cursor was returned with.
+    pub(crate) async fn query_events(
         &self,
-        keys_clause: &proto::types::KeysClause,
-        limit: Option<u32>,
-        offset: Option<u32>,
-    ) -> Result<Vec<proto::types::Event>, Error> {
-        let keys_pattern = build_keys_pattern(keys_clause)?;
+        keys_clause: Option<&proto::types::KeysClause>,
+        limit: u32,
+        offset: u32,
+        cursor: Option<&str>,
+        world_address: Option<Felt>,
+    ) -> Result<(Vec<proto::types::Event>, Option<String>), Error> {
+        let mut conditions = Vec::new();
+        let mut bind_values = Vec::new();
+        if let Some(keys_clause) = keys_clause {
+            conditions.push("keys REGEXP ?");
+            bind_values.push(build_keys_pattern(keys_clause)?);
+        }
+        if let Some(world_address) = world_address {
+            conditions.push("world_address = ?");
+            bind_values.push(format!("{:#x}", world_address));
+        }
+        if let Some(cursor) = cursor {
+            let id = match <[Option<String>; 1]>::try_from(decode_cursor(cursor)?) {
+                Ok([Some(id)]) => id,
+                _ => return Err(QueryError::InvalidCursor(cursor.to_string()).into()),
+            };
+            conditions.push("id < ?");
+            bind_values.push(id);
+        }
+        let where_clause = if !conditions.is_empty() {
+            format!("WHERE {}", conditions.join(" AND "))
+        } else {
+            String::new()
+        };
 
-        let events_query = r#"
-            SELECT keys, data, transaction_hash
+        let events_query = format!(
+            r#"
+            SELECT id, keys, data, transaction_hash
             FROM events
-            WHERE keys REGEXP ?
+            {where_clause}
             ORDER BY id DESC
             LIMIT ? OFFSET ?
         "#
-        .to_string();
+        );
 
-        let row_events: Vec<(String, String, String)> = sqlx::query_as(&events_query)
-            .bind(&keys_pattern)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await?;
+        let mut query = sqlx::query_as(&events_query);
+        for value in &bind_values {
+            query = query.bind(value);
+        }
+        let row_events: Vec<(String, String, String, String)> =
+            query.bind(limit).bind(offset).fetch_all(&self.pool).await?;
 
-        row_events.iter().map(map_row_to_event).collect()
+        // there may be more events only if the page is full
+        let next_cursor = match row_events.last() {
+            Some((id, ..)) if row_events.len() == limit as usize => {
+                Some(encode_cursor(&[Some(id.clone())])?)
+            }
+            _ => None,
+        };
+
+        let events = row_events
+            .into_iter()
+            .map(|(_, keys, data, transaction_hash)| {
+                map_row_to_event(&(keys, data, transaction_hash))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok((events, next_cursor))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -619,15 +845,23 @@ impl DojoWorld {
         let comparison_operator = ComparisonOperator::from_repr(member_clause.operator as usize)
             .expect("invalid comparison operator");
 
-        let comparison_value =
-            match member_clause.value.ok_or(QueryError::MissingParam("value".into()))?.value_type {
+        let comparison_value = if comparison_operator == ComparisonOperator::Search {
+            search_text(&member_clause)?
+        } else {
+            match member_clause
+                .value
+                .clone()
+                .ok_or(QueryError::MissingParam("value".into()))?
+                .value_type
+            {
                 Some(ValueType::String(value)) => value,
                 Some(ValueType::Primitive(value)) => {
                     let primitive: Primitive = value.try_into()?;
                     primitive.to_sql_value()?
                 }
                 None => return Err(QueryError::MissingParam("value_type".into()).into()),
-            };
+            }
+        };
 
         let (namespace, model) = member_clause
             .model
@@ -639,7 +873,8 @@ impl DojoWorld {
             SELECT group_concat({model_relation_table}.model_id) as model_ids
             FROM {table}
             JOIN {model_relation_table} ON {table}.id = {model_relation_table}.entity_id
-            GROUP BY {table}.id
+                AND {table}.world_address = {model_relation_table}.world_address
+            GROUP BY {table}.id, {table}.world_address
             HAVING INSTR(model_ids, '{:#x}') > 0
             LIMIT 1
         "#,
@@ -669,11 +904,16 @@ impl DojoWorld {
         } else {
             (model, format!("external_{}", member_clause.member))
         };
+        let condition = if comparison_operator == ComparisonOperator::Search {
+            search_condition(&table_name, &member_clause)
+        } else {
+            format!("[{table_name}].{column_name} {comparison_operator} ?")
+        };
         let (entity_query, arrays_queries, count_query) = build_sql_query(
             &schemas,
             table,
             entity_relation_column,
-            Some(&format!("[{table_name}].{column_name} {comparison_operator} ?")),
+            Some(&condition),
             None,
             limit,
             offset,
@@ -715,17 +955,25 @@ impl DojoWorld {
         offset: Option<u32>,
         dont_include_hashed_keys: bool,
     ) -> Result<(Vec<proto::types::Entity>, u32), Error> {
-        let (where_clause, having_clause, join_clause, bind_values) =
-            build_composite_clause(table, model_relation_table, &composite)?;
+        let (where_clause, having_clause, join_clause, bind_values) = build_composite_clause(
+            table,
+            model_relation_table,
+            entity_relation_column,
+            &composite,
+        )?;
 
         let count_query = format!(
             r#"
-            SELECT COUNT(DISTINCT [{table}].id)
-            FROM [{table}]
-            JOIN {model_relation_table} ON [{table}].id = {model_relation_table}.entity_id
-            {join_clause}
-            {where_clause}
-            {having_clause}
+            SELECT COUNT(*) FROM (
+                SELECT [{table}].id
+                FROM [{table}]
+                JOIN {model_relation_table} ON [{table}].id = {model_relation_table}.entity_id
+                    AND [{table}].world_address = {model_relation_table}.world_address
+                {join_clause}
+                {where_clause}
+                GROUP BY [{table}].id, [{table}].world_address
+                {having_clause}
+            )
             "#
         );
 
@@ -741,12 +989,13 @@ impl DojoWorld {
 
         let query = format!(
             r#"
-            SELECT [{table}].id, group_concat({model_relation_table}.model_id) as model_ids
+            SELECT [{table}].id, [{table}].world_address, group_concat({model_relation_table}.model_id) as model_ids
             FROM [{table}]
             JOIN {model_relation_table} ON [{table}].id = {model_relation_table}.entity_id
+                AND [{table}].world_address = {model_relation_table}.world_address
             {join_clause}
             {where_clause}
-            GROUP BY [{table}].id
+            GROUP BY [{table}].id, [{table}].world_address
             {having_clause}
             ORDER BY [{table}].event_id DESC
             LIMIT ? OFFSET ?
@@ -759,7 +1008,7 @@ impl DojoWorld {
         }
         db_query = db_query.bind(limit.unwrap_or(u32::MAX)).bind(offset.unwrap_or(0));
 
-        let db_entities: Vec<(String, String)> = db_query.fetch_all(&self.pool).await?;
+        let db_entities: Vec<(String, String, String)> = db_query.fetch_all(&self.pool).await?;
 
         let entities = self
             .fetch_entities(table, entity_relation_column, db_entities, dont_include_hashed_keys)
@@ -767,6 +1016,188 @@ impl DojoWorld {
         Ok((entities, total_count))
     }
 
+    /// Retrieves a page of entities in the order of `order_by`, then of their ids, which never
+    /// change as entities are updated. Rather than skipping rows, a page starts after the last
+    /// entity of the page the `cursor` was returned with, so that pages stay consistent while
+    /// entities are being indexed. Only the entities having the models of `order_by` are
+    /// retrieved, and only the models of `world_address` if set.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn query_ordered(
+        &self,
+        table: &str,
+        model_relation_table: &str,
+        entity_relation_column: &str,
+        clause: Option<proto::types::Clause>,
+        order_by: &[proto::types::OrderBy],
+        cursor: Option<&str>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        dont_include_hashed_keys: bool,
+        world_address: Option<Felt>,
+    ) -> Result<(Vec<proto::types::Entity>, u32, Option<String>), Error> {
+        // entities searched without an order are ordered from the best match
+        let search_ranking = if order_by.is_empty() {
+            build_search_ranking(table, entity_relation_column, clause.as_ref())?
+        } else {
+            None
+        };
+        let (mut where_clause, having_clause, join_clause, mut bind_values) =
+            build_clause(table, model_relation_table, entity_relation_column, clause)?;
+        if let Some(world_address) = world_address {
+            let condition = format!("[{table}].world_address = ?");
+            where_clause = if where_clause.is_empty() {
+                format!("WHERE {condition}")
+            } else {
+                format!("WHERE ({}) AND {condition}", where_clause.trim_start_matches("WHERE "))
+            };
+            bind_values.push(format!("{:#x}", world_address));
+        }
+        let (mut order_join_clause, mut order_columns) =
+            build_order_columns(table, entity_relation_column, order_by)?;
+        if let Some((join_clause, search_value)) = search_ranking {
+            // the ranks are compared as numbers to the ones of the cursor
+            order_join_clause = join_clause;
+            order_columns
+                .insert(0, ("CAST([search_rank].rank AS REAL)".into(), OrderDirection::Asc));
+            bind_values.insert(0, search_value);
+        }
+
+        let count_query = format!(
+            r#"
+            SELECT COUNT(*) FROM (
+                SELECT [{table}].id
+                FROM [{table}]
+                JOIN {model_relation_table} ON [{table}].id = {model_relation_table}.entity_id
+                    AND [{table}].world_address = {model_relation_table}.world_address
+                {join_clause}
+                {order_join_clause}
+                {where_clause}
+                GROUP BY [{table}].id, [{table}].world_address
+                {having_clause}
+            )
+            "#
+        );
+
+        let mut count_query = sqlx::query_scalar::<_, u32>(&count_query);
+        for value in &bind_values {
+            count_query = count_query.bind(value);
+        }
+
+        let total_count = count_query.fetch_optional(&self.pool).await?.unwrap_or(0);
+        if total_count == 0 {
+            return Ok((Vec::new(), 0, None));
+        }
+
+        let mut conditions = Vec::new();
+        if !where_clause.is_empty() {
+            conditions.push(format!("({})", where_clause.trim_start_matches("WHERE ")));
+        }
+        let cursor_values = match cursor {
+            Some(cursor) => {
+                let (condition, values) = build_cursor_condition(&order_columns, cursor)?;
+                conditions.push(condition);
+                values
+            }
+            None => Vec::new(),
+        };
+        let where_clause = if !conditions.is_empty() {
+            format!("WHERE {}", conditions.join(" AND "))
+        } else {
+            String::new()
+        };
+
+        let order_values = order_columns
+            .iter()
+            .enumerate()
+            .map(|(i, (column, _))| format!("CAST({column} AS TEXT) AS order_{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ordering = order_columns
+            .iter()
+            .map(|(column, direction)| format!("{column} {}", direction.as_str_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let query = format!(
+            r#"
+            SELECT [{table}].id, [{table}].world_address, group_concat({model_relation_table}.model_id) as model_ids,
+                {order_values}
+            FROM [{table}]
+            JOIN {model_relation_table} ON [{table}].id = {model_relation_table}.entity_id
+                AND [{table}].world_address = {model_relation_table}.world_address
+            {join_clause}
+            {order_join_clause}
+            {where_clause}
+            GROUP BY [{table}].id, [{table}].world_address
+            {having_clause}
+            ORDER BY {ordering}
+            LIMIT ? OFFSET ?
+            "#
+        );
+
+        let mut db_query = sqlx::query(&query);
+        for value in &bind_values {
+            db_query = db_query.bind(value);
+        }
+        for value in &cursor_values {
+            db_query = db_query.bind(value);
+        }
+        let rows = db_query
+            .bind(limit.unwrap_or(u32::MAX))
+            .bind(offset.unwrap_or(0))
+            .fetch_all(&self.pool)
+            .await?;
+
+        // there may be more entities only if the page is full
+        let next_cursor = match rows.last() {
+            Some(last) if limit.is_some_and(|limit| rows.len() == limit as usize) => {
+                // members may be NULL, which the cursor keeps to compare them as such
+                let values = (0..order_columns.len())
+                    .map(|i| last.try_get::<Option<String>, _>(format!("order_{i}").as_str()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Some(encode_cursor(&values)?)
+            }
+            _ => None,
+        };
+
+        let db_entities = rows
+            .iter()
+            .map(|row| {
+                Ok((row.try_get("id")?, row.try_get("world_address")?, row.try_get("model_ids")?))
+            })
+            .collect::<Result<Vec<(String, String, String)>, sqlx::Error>>()?;
+        let ids = db_entities
+            .iter()
+            .map(|(id, world_address, _)| (id.clone(), world_address.clone()))
+            .collect::<Vec<_>>();
+
+        // entities are fetched grouped by models, their hashed keys and world give back the page
+        // order
+        let mut entities = self
+            .fetch_entities(table, entity_relation_column, db_entities, false)
+            .await?
+            .into_iter()
+            .map(|entity| {
+                let id = format!("{:#x}", Felt::from_bytes_be_slice(&entity.hashed_keys));
+                let world_address =
+                    format!("{:#x}", Felt::from_bytes_be_slice(&entity.world_address));
+                ((id, world_address), entity)
+            })
+            .collect::<HashMap<_, _>>();
+        let entities = ids
+            .iter()
+            .filter_map(|id| entities.remove(id))
+            .map(|mut entity| {
+                if dont_include_hashed_keys {
+                    entity.hashed_keys = vec![];
+                }
+                entity
+            })
+            .collect();
+
+        Ok((entities, total_count, next_cursor))
+    }
+
     pub async fn model_metadata(
         &self,
         namespace: &str,
@@ -774,6 +1205,7 @@ impl DojoWorld {
     ) -> Result<proto::types::ModelMetadata, Error> {
         // selector
         let model = compute_selector_from_names(namespace, name);
+        let indexes = self.model_indexes(&format!("{:#x}", model)).await?;
 
         let model = self.model_cache.model(&model).await?;
 
@@ -786,9 +1218,29 @@ impl DojoWorld {
             unpacked_size: model.unpacked_size,
             layout: serde_json::to_vec(&model.layout).unwrap(),
             schema: serde_json::to_vec(&model.schema).unwrap(),
+            indexes,
         })
     }
 
+    /// The indexes of the model declared in the config, created along with its tables.
+    async fn model_indexes(&self, model_id: &str) -> Result<Vec<proto::types::ModelIndex>, Error> {
+        let indexes: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT name, members, condition FROM model_indexes WHERE model_id = ? ORDER BY name",
+        )
+        .bind(model_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(indexes
+            .into_iter()
+            .map(|(name, members, condition)| proto::types::ModelIndex {
+                name,
+                members: members.split(',').map(String::from).collect(),
+                condition: condition.unwrap_or_default(),
+            })
+            .collect())
+    }
+
     async fn subscribe_indexer(
         &self,
         contract_address: Felt,
@@ -800,6 +1252,7 @@ impl DojoWorld {
     async fn subscribe_models(
         &self,
         models_keys: Vec<proto::types::ModelKeysClause>,
+        world_address: Option<Felt>,
     ) -> Result<Receiver<Result<proto::world::SubscribeModelsResponse, tonic::Status>>, Error> {
         let mut subs = Vec::with_capacity(models_keys.len());
         for keys in models_keys {
@@ -809,17 +1262,29 @@ impl DojoWorld {
                 .ok_or(QueryError::InvalidNamespacedModel(keys.model.clone()))?;
 
             let selector = compute_selector_from_names(namespace, model);
+            let model = self.model_cache.model(&selector).await?;
+
+            // the model is stored in the storage of every world registering it
+            let worlds: Vec<String> =
+                sqlx::query_scalar("SELECT world_address FROM world_models WHERE model_id = ?")
+                    .bind(format!("{:#x}", selector))
+                    .fetch_all(&self.pool)
+                    .await?;
+            for world in worlds {
+                let world = Felt::from_str(&world).map_err(ParseError::FromStr)?;
+                if world_address.is_some_and(|address| address != world) {
+                    continue;
+                }
 
-            let proto::types::ModelMetadata { packed_size, .. } =
-                self.model_metadata(namespace, model).await?;
-
-            subs.push(ModelDiffRequest {
-                keys,
-                model: subscriptions::model_diff::ModelMetadata {
-                    selector,
-                    packed_size: packed_size as usize,
-                },
-            });
+                subs.push(ModelDiffRequest {
+                    keys: keys.clone(),
+                    model: subscriptions::model_diff::ModelMetadata {
+                        selector,
+                        packed_size: model.packed_size as usize,
+                        world_address: world,
+                    },
+                });
+            }
         }
 
         self.state_diff_manager.add_subscriber(subs).await
@@ -828,8 +1293,12 @@ impl DojoWorld {
     async fn subscribe_entities(
         &self,
         keys: Vec<proto::types::EntityKeysClause>,
+        world_address: Option<Felt>,
+        diff: bool,
     ) -> Result<Receiver<Result<proto::world::SubscribeEntityResponse, tonic::Status>>, Error> {
-        self.entity_manager.add_subscriber(keys.into_iter().map(|keys| keys.into()).collect()).await
+        self.entity_manager
+            .add_subscriber(keys.into_iter().map(|keys| keys.into()).collect(), world_address, diff)
+            .await
     }
 
     async fn retrieve_entities(
@@ -839,6 +1308,63 @@ impl DojoWorld {
         entity_relation_column: &str,
         query: proto::types::Query,
     ) -> Result<proto::world::RetrieveEntitiesResponse, Error> {
+        let world_address = world_selector(&query.world_address);
+
+        let bounds = HistoryBounds {
+            at_block: query.at_block,
+            from_timestamp: query.time_range.as_ref().map(|time_range| time_range.from_timestamp),
+            to_timestamp: query.time_range.as_ref().map(|time_range| time_range.to_timestamp),
+        };
+        if !bounds.is_latest() {
+            if table != ENTITIES_TABLE || !query.order_by.is_empty() || !query.cursor.is_empty() {
+                return Err(QueryError::UnsupportedQuery.into());
+            }
+
+            let (entities, total_count) = self
+                .query_historical(
+                    query.clause,
+                    bounds,
+                    query.limit,
+                    query.offset,
+                    query.dont_include_hashed_keys,
+                    world_address,
+                )
+                .await?;
+            return Ok(RetrieveEntitiesResponse {
+                entities,
+                total_count,
+                next_cursor: String::new(),
+            });
+        }
+
+        // the historical event messages are versions rather than entities, they can only be
+        // paginated by offset
+        if table != EVENT_MESSAGES_HISTORICAL_TABLE {
+            let (entities, total_count, next_cursor) = self
+                .query_ordered(
+                    table,
+                    model_relation_table,
+                    entity_relation_column,
+                    query.clause,
+                    &query.order_by,
+                    (!query.cursor.is_empty()).then_some(query.cursor.as_str()),
+                    Some(query.limit),
+                    Some(query.offset),
+                    query.dont_include_hashed_keys,
+                    world_address,
+                )
+                .await?;
+            return Ok(RetrieveEntitiesResponse {
+                entities,
+                total_count,
+                next_cursor: next_cursor.unwrap_or_default(),
+            });
+        }
+
+        if !query.order_by.is_empty() || !query.cursor.is_empty() || world_address.is_some() {
+            return Err(QueryError::UnsupportedQuery.into());
+        }
+
         let (entities, total_count) = match query.clause {
             None => {
                 self.entities_all(
@@ -912,16 +1438,23 @@ impl DojoWorld {
             }
         };
 
-        Ok(RetrieveEntitiesResponse { entities, total_count })
+        Ok(RetrieveEntitiesResponse { entities, total_count, next_cursor: String::new() })
     }
 
     async fn subscribe_event_messages(
         &self,
         clauses: Vec<proto::types::EntityKeysClause>,
         historical: bool,
+        world_address: Option<Felt>,
+        diff: bool,
     ) -> Result<Receiver<Result<proto::world::SubscribeEntityResponse, tonic::Status>>, Error> {
         self.event_message_manager
-            .add_subscriber(clauses.into_iter().map(|keys| keys.into()).collect(), historical)
+            .add_subscriber(
+                clauses.into_iter().map(|keys| keys.into()).collect(),
+                historical,
+                world_address,
+                diff,
+            )
             .await
     }
 
@@ -929,21 +1462,238 @@ impl DojoWorld {
         &self,
         query: &proto::types::EventQuery,
     ) -> Result<proto::world::RetrieveEventsResponse, Error> {
-        let events = match &query.keys {
-            None => self.events_all(query.limit, query.offset).await?,
-            Some(keys) => self.events_by_keys(keys, Some(query.limit), Some(query.offset)).await?,
-        };
-        Ok(RetrieveEventsResponse { events })
+        let (events, next_cursor) = self
+            .query_events(
+                query.keys.as_ref(),
+                query.limit,
+                query.offset,
+                (!query.cursor.is_empty()).then_some(query.cursor.as_str()),
+                world_selector(&query.world_address),
+            )
+            .await?;
+        Ok(RetrieveEventsResponse { events, next_cursor: next_cursor.unwrap_or_default() })
     }
 
     async fn subscribe_events(
         &self,
         clause: Vec<proto::types::EntityKeysClause>,
+        world_address: Option<Felt>,
     ) -> Result<Receiver<Result<proto::world::SubscribeEventsResponse, tonic::Status>>, Error> {
         self.event_manager
-            .add_subscriber(clause.into_iter().map(|keys| keys.into()).collect())
+            .add_subscriber(clause.into_iter().map(|keys| keys.into()).collect(), world_address)
             .await
     }
+
+    async fn retrieve_world_resources(
+        &self,
+        resource: Felt,
+        contract_address: Felt,
+        world_address: Option<Felt>,
+    ) -> Result<proto::world::RetrieveWorldResourcesResponse, Error> {
+        let world_condition =
+            if world_address.is_some() { " WHERE world_address = ?" } else { "" };
+        let world_address = world_address.map(|address| format!("{:#x}", address));
+
+        let namespaces: Vec<Namespace> = sqlx::query_as(&format!(
+            "SELECT * FROM namespaces{world_condition} ORDER BY executed_at ASC"
+        ))
+        .bind(&world_address)
+        .fetch_all(&self.pool)
+        .await?;
+        let contracts: Vec<WorldContract> = sqlx::query_as(&format!(
+            "SELECT * FROM world_contracts{world_condition} ORDER BY executed_at ASC"
+        ))
+        .bind(&world_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // zero means no filter on the given column
+        let mut conditions = Vec::new();
+        let mut bind_values = Vec::new();
+        if resource != Felt::ZERO {
+            conditions.push("resource = ?");
+            bind_values.push(format!("{:#x}", resource));
+        }
+        if contract_address != Felt::ZERO {
+            conditions.push("contract_address = ?");
+            bind_values.push(format!("{:#x}", contract_address));
+        }
+        if let Some(world_address) = world_address {
+            conditions.push("world_address = ?");
+            bind_values.push(world_address);
+        }
+
+        let mut query = "SELECT * FROM permissions".to_string();
+        if !conditions.is_empty() {
+            query += &format!(" WHERE {}", conditions.join(" AND "));
+        }
+        query += " ORDER BY executed_at ASC";
+
+        let mut permissions_query = sqlx::query_as::<_, Permission>(&query);
+        for value in bind_values {
+            permissions_query = permissions_query.bind(value);
+        }
+        let permissions = permissions_query.fetch_all(&self.pool).await?;
+
+        Ok(RetrieveWorldResourcesResponse {
+            namespaces: namespaces
+                .into_iter()
+                .map(subscriptions::world_resources::namespace_to_proto)
+                .collect::<Result<_, _>>()?,
+            contracts: contracts
+                .into_iter()
+                .map(subscriptions::world_resources::contract_to_proto)
+                .collect::<Result<_, _>>()?,
+            permissions: permissions
+                .into_iter()
+                .map(subscriptions::world_resources::permission_to_proto)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    async fn subscribe_world_resources(
+        &self,
+        resource: Felt,
+        contract_address: Felt,
+        world_address: Option<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeWorldResourcesResponse, tonic::Status>> {
+        self.world_resources_manager.add_subscriber(resource, contract_address, world_address).await
+    }
+
+    async fn retrieve_tokens(
+        &self,
+        contract_addresses: Vec<Felt>,
+    ) -> Result<proto::world::RetrieveTokensResponse, Error> {
+        let mut query = "SELECT t.id, t.contract_address, t.name, t.symbol, t.decimals, \
+                         t.metadata, c.contract_type FROM tokens t JOIN contracts c ON \
+                         t.contract_address = c.contract_address"
+            .to_string();
+        if !contract_addresses.is_empty() {
+            query += &format!(
+                " WHERE t.contract_address IN ({})",
+                vec!["?"; contract_addresses.len()].join(", ")
+            );
+        }
+
+        let mut tokens_query = sqlx::query_as::<
+            _,
+            (String, String, String, String, u32, Option<String>, String),
+        >(&query);
+        for contract_address in &contract_addresses {
+            tokens_query = tokens_query.bind(format!("{:#x}", contract_address));
+        }
+        let rows = tokens_query.fetch_all(&self.pool).await?;
+
+        let tokens = rows
+            .into_iter()
+            .map(|(id, contract_address, name, symbol, decimals, metadata, contract_type)| {
+                Ok(proto::types::Token {
+                    contract_address: Felt::from_str(&contract_address)
+                        .map_err(ParseError::FromStr)?
+                        .to_bytes_be()
+                        .to_vec(),
+                    token_id: token_id_from_sql_string(&id),
+                    name,
+                    symbol,
+                    decimals,
+                    contract_type: contract_type_to_proto(&contract_type)?,
+                    metadata: metadata.unwrap_or_default(),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(RetrieveTokensResponse { tokens })
+    }
+
+    async fn retrieve_token_balances(
+        &self,
+        account_addresses: Vec<Felt>,
+        contract_addresses: Vec<Felt>,
+    ) -> Result<proto::world::RetrieveTokenBalancesResponse, Error> {
+        let mut conditions = Vec::new();
+        if !account_addresses.is_empty() {
+            conditions.push(format!(
+                "b.account_address IN ({})",
+                vec!["?"; account_addresses.len()].join(", ")
+            ));
+        }
+        if !contract_addresses.is_empty() {
+            conditions.push(format!(
+                "b.contract_address IN ({})",
+                vec!["?"; contract_addresses.len()].join(", ")
+            ));
+        }
+
+        let mut query = "SELECT b.account_address, b.contract_address, b.token_id, b.balance, \
+                         c.contract_type FROM balances b JOIN contracts c ON b.contract_address = \
+                         c.contract_address"
+            .to_string();
+        if !conditions.is_empty() {
+            query += &format!(" WHERE {}", conditions.join(" AND "));
+        }
+
+        let mut balances_query =
+            sqlx::query_as::<_, (String, String, String, String, String)>(&query);
+        for address in account_addresses.iter().chain(&contract_addresses) {
+            balances_query = balances_query.bind(format!("{:#x}", address));
+        }
+        let rows = balances_query.fetch_all(&self.pool).await?;
+
+        let balances = rows
+            .into_iter()
+            .map(|(account_address, contract_address, token_id, balance, contract_type)| {
+                Ok(proto::types::TokenBalance {
+                    account_address: Felt::from_str(&account_address)
+                        .map_err(ParseError::FromStr)?
+                        .to_bytes_be()
+                        .to_vec(),
+                    contract_address: Felt::from_str(&contract_address)
+                        .map_err(ParseError::FromStr)?
+                        .to_bytes_be()
+                        .to_vec(),
+                    token_id: token_id_from_sql_string(&token_id),
+                    balance,
+                    contract_type: contract_type_to_proto(&contract_type)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(RetrieveTokenBalancesResponse { balances })
+    }
+
+    async fn subscribe_token_transfers(
+        &self,
+        account_addresses: Vec<Felt>,
+        contract_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTokenTransfersResponse, tonic::Status>> {
+        self.token_manager.add_transfers_subscriber(account_addresses, contract_addresses).await
+    }
+
+    async fn subscribe_token_balances(
+        &self,
+        account_addresses: Vec<Felt>,
+        contract_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTokenBalancesResponse, tonic::Status>> {
+        self.token_manager.add_balances_subscriber(account_addresses, contract_addresses).await
+    }
+
+    async fn subscribe_transactions(
+        &self,
+        sender_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTransactionsResponse, tonic::Status>> {
+        self.transaction_manager.add_subscriber(sender_addresses).await
+    }
+}
+
+// Token ids are stored as `contract_address:id`, or as the contract address for ERC20 tokens.
+fn token_id_from_sql_string(token_id: &str) -> String {
+    token_id.split_once(':').map(|(_, id)| id.to_string()).unwrap_or_default()
+}
+
+fn contract_type_to_proto(contract_type: &str) -> Result<i32, Error> {
+    proto::types::ContractType::from_str_name(contract_type)
+        .map(|contract_type| contract_type as i32)
+        .ok_or_else(|| QueryError::UnsupportedValue(contract_type.to_string()).into())
 }
 
 fn process_event_field(data: &str) -> Result<Vec<Vec<u8>>, Error> {
@@ -970,6 +1720,8 @@ fn map_row_to_entity(
     dont_include_hashed_keys: bool,
 ) -> Result<proto::types::Entity, Error> {
     let hashed_keys = Felt::from_str(&row.get::<String, _>("id")).map_err(ParseError::FromStr)?;
+    let world_address =
+        Felt::from_str(&row.get::<String, _>("world_address")).map_err(ParseError::FromStr)?;
     let models = schemas
         .iter()
         .map(|schema| {
@@ -986,6 +1738,7 @@ fn map_row_to_entity(
             vec![]
         },
         models,
+        world_address: world_address.to_bytes_be().to_vec(),
     })
 }
 
@@ -1021,6 +1774,7 @@ fn build_keys_pattern(clause: &proto::types::KeysClause) -> Result<String, Error
 fn build_composite_clause(
     table: &str,
     model_relation_table: &str,
+    entity_relation_column: &str,
     composite: &proto::types::CompositeClause,
 ) -> Result<(String, String, String, Vec<String>), Error> {
     let is_or = composite.operator == LogicalOperator::Or as i32;
@@ -1039,7 +1793,7 @@ fn build_composite_clause(
                     .hashed_keys
                     .iter()
                     .map(|id| {
-                        bind_values.push(Felt::from_bytes_be_slice(id).to_string());
+                        bind_values.push(format!("{:#x}", Felt::from_bytes_be_slice(id)));
                         "?".to_string()
                     })
                     .collect::<Vec<_>>()
@@ -1055,7 +1809,9 @@ fn build_composite_clause(
                 let comparison_operator = ComparisonOperator::from_repr(member.operator as usize)
                     .expect("invalid comparison operator");
                 let value = member.value.clone();
-                let comparison_value =
+                let comparison_value = if comparison_operator == ComparisonOperator::Search {
+                    search_text(member)?
+                } else {
                     match value.ok_or(QueryError::MissingParam("value".into()))?.value_type {
                         Some(ValueType::String(value)) => value,
                         Some(ValueType::Primitive(value)) => {
@@ -1063,7 +1819,8 @@ fn build_composite_clause(
                             primitive.to_sql_value()?
                         }
                         None => return Err(QueryError::MissingParam("value_type".into()).into()),
-                    };
+                    }
+                };
                 bind_values.push(comparison_value);
 
                 let model = member.model.clone();
@@ -1091,9 +1848,15 @@ fn build_composite_clause(
                     if *counter == 1 { model.clone() } else { format!("{model}_{}", *counter - 1) };
 
                 join_clauses.push(format!(
-                    "LEFT JOIN {table_name} AS [{alias}] ON [{table}].id = [{alias}].entity_id"
+                    "LEFT JOIN {table_name} AS [{alias}] ON [{table}].id = \
+                     [{alias}].{entity_relation_column} AND [{table}].world_address = \
+                     [{alias}].world_address"
                 ));
-                where_clauses.push(format!("[{alias}].{column_name} {comparison_operator} ?"));
+                where_clauses.push(if comparison_operator == ComparisonOperator::Search {
+                    search_condition(&alias, member)
+                } else {
+                    format!("[{alias}].{column_name} {comparison_operator} ?")
+                });
                 having_clauses.push(format!(
                     "INSTR(group_concat({model_relation_table}.model_id), '{:#x}') > 0",
                     model_id
@@ -1101,7 +1864,12 @@ fn build_composite_clause(
             }
             ClauseType::Composite(nested_composite) => {
                 let (nested_where, nested_having, nested_join, nested_values) =
-                    build_composite_clause(table, model_relation_table, nested_composite)?;
+                    build_composite_clause(
+                        table,
+                        model_relation_table,
+                        entity_relation_column,
+                        nested_composite,
+                    )?;
                 where_clauses.push(format!("({})", nested_where.trim_start_matches("WHERE ")));
                 if !nested_having.is_empty() {
                     having_clauses.push(nested_having.trim_start_matches("HAVING ").to_string());
@@ -1132,6 +1900,211 @@ fn build_composite_clause(
     Ok((where_clause, having_clause, join_clause, bind_values))
 }
 
+/// Builds the where, having and join clauses of a query clause, with their bind values.
+fn build_clause(
+    table: &str,
+    model_relation_table: &str,
+    entity_relation_column: &str,
+    clause: Option<proto::types::Clause>,
+) -> Result<(String, String, String, Vec<String>), Error> {
+    let Some(clause) = clause else {
+        return Ok(Default::default());
+    };
+
+    let (where_clause, mut having_clause, join_clause, bind_values) = match &clause.clause_type {
+        None => return Err(QueryError::MissingParam("clause_type".into()).into()),
+        Some(ClauseType::HashedKeys(hashed_keys)) if hashed_keys.hashed_keys.is_empty() => {
+            return Ok(Default::default());
+        }
+        _ => build_composite_clause(
+            table,
+            model_relation_table,
+            entity_relation_column,
+            &proto::types::CompositeClause {
+                operator: LogicalOperator::And as i32,
+                clauses: vec![clause.clone()],
+            },
+        )?,
+    };
+
+    // entities matching the keys are restricted to the ones having one of the models
+    if let Some(ClauseType::Keys(keys)) = &clause.clause_type {
+        if !keys.models.is_empty() {
+            let models = keys
+                .models
+                .iter()
+                .map(|model| {
+                    let (namespace, name) = model
+                        .split_once('-')
+                        .ok_or(QueryError::InvalidNamespacedModel(model.clone()))?;
+                    let model_id = compute_selector_from_names(namespace, name);
+                    Ok(format!(
+                        "INSTR(group_concat({model_relation_table}.model_id), '{:#x}') > 0",
+                        model_id
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            having_clause = format!("HAVING {}", models.join(" OR "));
+        }
+    }
+
+    Ok((where_clause, having_clause, join_clause, bind_values))
+}
+
+/// The FTS5 query of the text searched by a `SEARCH` member clause.
+fn search_text(member: &proto::types::MemberClause) -> Result<String, Error> {
+    match member.value.as_ref().and_then(|value| value.value_type.as_ref()) {
+        Some(ValueType::String(text)) => {
+            search_query(text).ok_or_else(|| QueryError::InvalidSearch(text.clone()).into())
+        }
+        Some(ValueType::Primitive(_)) => {
+            Err(QueryError::InvalidSearch("the searched value must be a string".into()).into())
+        }
+        None => Err(QueryError::MissingParam("value".into()).into()),
+    }
+}
+
+/// The condition selecting the rows of the model table `table_name` matching a `SEARCH` member
+/// clause, the FTS5 query being bound to it.
+fn search_condition(table_name: &str, member: &proto::types::MemberClause) -> String {
+    format!(
+        "([{table_name}].id, [{table_name}].world_address) IN (SELECT id, world_address FROM ({}))",
+        search_matches_query(&member.model, &member.member, "?")
+    )
+}
+
+/// Joins the rank of the entities matched by a `SEARCH` member clause, to order them from the best
+/// match. The FTS5 query must be bound to the join.
+fn build_search_ranking(
+    table: &str,
+    entity_relation_column: &str,
+    clause: Option<&proto::types::Clause>,
+) -> Result<Option<(String, String)>, Error> {
+    let Some(ClauseType::Member(member)) = clause.and_then(|clause| clause.clause_type.as_ref())
+    else {
+        return Ok(None);
+    };
+    if member.operator != proto::types::ComparisonOperator::Search as i32 {
+        return Ok(None);
+    }
+
+    // the model rows of event messages aren't identified by the id of the event message
+    let model = &member.model;
+    let join_clause = format!(
+        "JOIN (SELECT [{model}].{entity_relation_column} AS id, [{model}].world_address AS \
+         world_address, MIN(search.rank) AS rank FROM ({}) AS search JOIN [{model}] ON \
+         [{model}].id = search.id AND [{model}].world_address = search.world_address GROUP BY \
+         [{model}].{entity_relation_column}, [{model}].world_address) AS [search_rank] ON \
+         [search_rank].id = [{table}].id AND [search_rank].world_address = [{table}].world_address",
+        search_matches_query(model, &member.member, "?")
+    );
+
+    Ok(Some((join_clause, search_text(member)?)))
+}
+
+/// Builds the joins of the model tables of `order_by` and the columns the entities are ordered
+/// by, the most recently updated first if `order_by` is empty. The entity id and world always
+/// come last, to order the entities with equal members.
+fn build_order_columns(
+    table: &str,
+    entity_relation_column: &str,
+    order_by: &[proto::types::OrderBy],
+) -> Result<(String, Vec<(String, OrderDirection)>), Error> {
+    let mut join_clauses = Vec::new();
+    let mut columns = Vec::new();
+
+    for (i, order_by) in order_by.iter().enumerate() {
+        let model = &order_by.model;
+        model.split_once('-').ok_or(QueryError::InvalidNamespacedModel(model.clone()))?;
+
+        let parts: Vec<&str> = order_by.member.split('.').collect();
+        let (table_name, column_name) = if parts.len() > 1 {
+            let nested_table = parts[..parts.len() - 1].join("$");
+            (format!("[{model}${nested_table}]"), format!("external_{}", parts.last().unwrap()))
+        } else {
+            (format!("[{model}]"), format!("external_{}", order_by.member))
+        };
+
+        let alias = format!("order_by_{i}");
+        join_clauses.push(format!(
+            "JOIN {table_name} AS [{alias}] ON [{table}].id = [{alias}].{entity_relation_column} \
+             AND [{table}].world_address = [{alias}].world_address"
+        ));
+        let direction = OrderDirection::try_from(order_by.direction)
+            .map_err(|_| QueryError::UnsupportedValue(order_by.direction.to_string()))?;
+        columns.push((format!("[{alias}].{column_name}"), direction));
+    }
+
+    if columns.is_empty() {
+        columns.push((format!("[{table}].event_id"), OrderDirection::Desc));
+    }
+
+    // the event id changes on every update, only the entity id and world break ties consistently
+    columns.push((format!("[{table}].id"), OrderDirection::Desc));
+    columns.push((format!("[{table}].world_address"), OrderDirection::Desc));
+
+    Ok((join_clauses.join(" "), columns))
+}
+
+/// Builds the condition selecting the entities ordered after the cursor, with its bind values.
+fn build_cursor_condition(
+    columns: &[(String, OrderDirection)],
+    cursor: &str,
+) -> Result<(String, Vec<Option<String>>), Error> {
+    let values = decode_cursor(cursor)?;
+    if values.len() != columns.len() {
+        return Err(QueryError::InvalidCursor(cursor.to_string()).into());
+    }
+
+    // (a > ?) OR (a IS ? AND b > ?) OR ... for the columns in ascending order, NULL being
+    // ordered before any value as in SQLite
+    let mut conditions = Vec::new();
+    let mut bind_values = Vec::new();
+    for (i, (column, direction)) in columns.iter().enumerate() {
+        let mut condition = Vec::new();
+        for ((previous, _), value) in columns[..i].iter().zip(&values) {
+            condition.push(format!("{previous} IS ?"));
+            bind_values.push(value.clone());
+        }
+        let after = match (direction, &values[i]) {
+            (OrderDirection::Asc, Some(value)) => {
+                bind_values.push(Some(value.clone()));
+                format!("{column} > ?")
+            }
+            (OrderDirection::Asc, None) => format!("{column} IS NOT NULL"),
+            (OrderDirection::Desc, Some(value)) => {
+                bind_values.push(Some(value.clone()));
+                format!("({column} < ? OR {column} IS NULL)")
+            }
+            // nothing is ordered after NULL in descending order
+            (OrderDirection::Desc, None) => "FALSE".to_string(),
+        };
+        condition.push(after);
+        conditions.push(format!("({})", condition.join(" AND ")));
+    }
+
+    Ok((format!("({})", conditions.join(" OR ")), bind_values))
+}
+
+/// Cursors are the opaque encoding of the ordered values of the last row of a page.
+fn encode_cursor(values: &[Option<String>]) -> Result<String, Error> {
+    let json = serde_json::to_vec(values).map_err(ParseError::FromJsonStr)?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+/// The world selected by a request, none if the address is empty.
+fn world_selector(world_address: &[u8]) -> Option<Felt> {
+    (!world_address.is_empty()).then(|| Felt::from_bytes_be_slice(world_address))
+}
+
+fn decode_cursor(cursor: &str) -> Result<Vec<Option<String>>, Error> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(|| QueryError::InvalidCursor(cursor.to_string()).into())
+}
+
 type ServiceResult<T> = Result<Response<T>, Status>;
 type SubscribeModelsResponseStream =
     Pin<Box<dyn Stream<Item = Result<SubscribeModelsResponse, Status>> + Send>>;
@@ -1143,6 +2116,14 @@ type SubscribeIndexerResponseStream =
     Pin<Box<dyn Stream<Item = Result<SubscribeIndexerResponse, Status>> + Send>>;
 type RetrieveEntitiesStreamingResponseStream =
     Pin<Box<dyn Stream<Item = Result<RetrieveEntitiesStreamingResponse, Status>> + Send>>;
+type SubscribeWorldResourcesResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeWorldResourcesResponse, Status>> + Send>>;
+type SubscribeTokenTransfersResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeTokenTransfersResponse, Status>> + Send>>;
+type SubscribeTokenBalancesResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeTokenBalancesResponse, Status>> + Send>>;
+type SubscribeTransactionsResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeTransactionsResponse, Status>> + Send>>;
 
 #[tonic::async_trait]
 impl proto::world::world_server::World for DojoWorld {
@@ -1152,15 +2133,21 @@ impl proto::world::world_server::World for DojoWorld {
     type SubscribeEventsStream = SubscribeEventsResponseStream;
     type SubscribeIndexerStream = SubscribeIndexerResponseStream;
     type RetrieveEntitiesStreamingStream = RetrieveEntitiesStreamingResponseStream;
+    type SubscribeWorldResourcesStream = SubscribeWorldResourcesResponseStream;
+    type SubscribeTokenTransfersStream = SubscribeTokenTransfersResponseStream;
+    type SubscribeTokenBalancesStream = SubscribeTokenBalancesResponseStream;
+    type SubscribeTransactionsStream = SubscribeTransactionsResponseStream;
 
     async fn world_metadata(
         &self,
-        _request: Request<WorldMetadataRequest>,
+        request: Request<WorldMetadataRequest>,
     ) -> Result<Response<WorldMetadataResponse>, Status> {
-        let metadata = Some(self.world().await.map_err(|e| match e {
-            Error::Sql(sqlx::Error::RowNotFound) => Status::not_found("World not found"),
-            e => Status::internal(e.to_string()),
-        })?);
+        let WorldMetadataRequest { world_address } = request.into_inner();
+        let metadata =
+            Some(self.world(world_selector(&world_address)).await.map_err(|e| match e {
+                Error::Sql(sqlx::Error::RowNotFound) => Status::not_found("World not found"),
+                e => Status::internal(e.to_string()),
+            })?);
 
         Ok(Response::new(WorldMetadataResponse { metadata }))
     }
@@ -1181,9 +2168,9 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<SubscribeModelsRequest>,
     ) -> ServiceResult<Self::SubscribeModelsStream> {
-        let SubscribeModelsRequest { models_keys } = request.into_inner();
+        let SubscribeModelsRequest { models_keys, world_address } = request.into_inner();
         let rx = self
-            .subscribe_models(models_keys)
+            .subscribe_models(models_keys, world_selector(&world_address))
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
         Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeModelsStream))
@@ -1193,9 +2180,11 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<SubscribeEntitiesRequest>,
     ) -> ServiceResult<Self::SubscribeEntitiesStream> {
-        let SubscribeEntitiesRequest { clauses } = request.into_inner();
-        let rx =
-            self.subscribe_entities(clauses).await.map_err(|e| Status::internal(e.to_string()))?;
+        let SubscribeEntitiesRequest { clauses, world_address, diff } = request.into_inner();
+        let rx = self
+            .subscribe_entities(clauses, world_selector(&world_address), diff)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeEntitiesStream))
     }
@@ -1204,11 +2193,13 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<UpdateEntitiesSubscriptionRequest>,
     ) -> ServiceResult<()> {
-        let UpdateEntitiesSubscriptionRequest { subscription_id, clauses } = request.into_inner();
+        let UpdateEntitiesSubscriptionRequest { subscription_id, clauses, world_address } =
+            request.into_inner();
         self.entity_manager
             .update_subscriber(
                 subscription_id,
                 clauses.into_iter().map(|keys| keys.into()).collect(),
+                world_selector(&world_address),
             )
             .await;
 
@@ -1232,7 +2223,12 @@ impl proto::world::world_server::World for DojoWorld {
                 query,
             )
             .await
-            .map_err(|e| Status::internal(e.to_string()))?;
+            .map_err(|e| match e {
+                Error::QueryError(QueryError::UnsupportedQuery) => {
+                    Status::unimplemented(e.to_string())
+                }
+                e => Status::internal(e.to_string()),
+            })?;
 
         Ok(Response::new(entities))
     }
@@ -1278,9 +2274,10 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<SubscribeEventMessagesRequest>,
     ) -> ServiceResult<Self::SubscribeEntitiesStream> {
-        let SubscribeEventMessagesRequest { clauses, historical } = request.into_inner();
+        let SubscribeEventMessagesRequest { clauses, historical, world_address, diff } =
+            request.into_inner();
         let rx = self
-            .subscribe_event_messages(clauses, historical)
+            .subscribe_event_messages(clauses, historical, world_selector(&world_address), diff)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
@@ -1291,13 +2288,18 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<UpdateEventMessagesSubscriptionRequest>,
     ) -> ServiceResult<()> {
-        let UpdateEventMessagesSubscriptionRequest { subscription_id, clauses, historical } =
-            request.into_inner();
+        let UpdateEventMessagesSubscriptionRequest {
+            subscription_id,
+            clauses,
+            historical,
+            world_address,
+        } = request.into_inner();
         self.event_message_manager
             .update_subscriber(
                 subscription_id,
                 clauses.into_iter().map(|keys| keys.into()).collect(),
                 historical,
+                world_selector(&world_address),
             )
             .await;
 
@@ -1343,11 +2345,148 @@ impl proto::world::world_server::World for DojoWorld {
         &self,
         request: Request<proto::world::SubscribeEventsRequest>,
     ) -> ServiceResult<Self::SubscribeEventsStream> {
-        let keys = request.into_inner().keys;
-        let rx = self.subscribe_events(keys).await.map_err(|e| Status::internal(e.to_string()))?;
+        let proto::world::SubscribeEventsRequest { keys, world_address } = request.into_inner();
+        let rx = self
+            .subscribe_events(keys, world_selector(&world_address))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeEventsStream))
     }
+
+    async fn retrieve_world_resources(
+        &self,
+        request: Request<RetrieveWorldResourcesRequest>,
+    ) -> Result<Response<RetrieveWorldResourcesResponse>, Status> {
+        let RetrieveWorldResourcesRequest { resource, contract_address, world_address } =
+            request.into_inner();
+        let resources = self
+            .retrieve_world_resources(
+                Felt::from_bytes_be_slice(&resource),
+                Felt::from_bytes_be_slice(&contract_address),
+                world_selector(&world_address),
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(resources))
+    }
+
+    async fn subscribe_world_resources(
+        &self,
+        request: Request<SubscribeWorldResourcesRequest>,
+    ) -> ServiceResult<Self::SubscribeWorldResourcesStream> {
+        let SubscribeWorldResourcesRequest { resource, contract_address, world_address } =
+            request.into_inner();
+        let rx = self
+            .subscribe_world_resources(
+                Felt::from_bytes_be_slice(&resource),
+                Felt::from_bytes_be_slice(&contract_address),
+                world_selector(&world_address),
+            )
+            .await;
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeWorldResourcesStream))
+    }
+
+    async fn retrieve_tokens(
+        &self,
+        request: Request<RetrieveTokensRequest>,
+    ) -> Result<Response<RetrieveTokensResponse>, Status> {
+        let RetrieveTokensRequest { contract_addresses } = request.into_inner();
+        let tokens = self
+            .retrieve_tokens(
+                contract_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(tokens))
+    }
+
+    async fn retrieve_token_balances(
+        &self,
+        request: Request<RetrieveTokenBalancesRequest>,
+    ) -> Result<Response<RetrieveTokenBalancesResponse>, Status> {
+        let RetrieveTokenBalancesRequest { account_addresses, contract_addresses } =
+            request.into_inner();
+        let balances = self
+            .retrieve_token_balances(
+                account_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+                contract_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(balances))
+    }
+
+    async fn subscribe_token_transfers(
+        &self,
+        request: Request<SubscribeTokenTransfersRequest>,
+    ) -> ServiceResult<Self::SubscribeTokenTransfersStream> {
+        let SubscribeTokenTransfersRequest { account_addresses, contract_addresses } =
+            request.into_inner();
+        let rx = self
+            .subscribe_token_transfers(
+                account_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+                contract_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+            )
+            .await;
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeTokenTransfersStream))
+    }
+
+    async fn subscribe_token_balances(
+        &self,
+        request: Request<SubscribeTokenBalancesRequest>,
+    ) -> ServiceResult<Self::SubscribeTokenBalancesStream> {
+        let SubscribeTokenBalancesRequest { account_addresses, contract_addresses } =
+            request.into_inner();
+        let rx = self
+            .subscribe_token_balances(
+                account_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+                contract_addresses
+                    .iter()
+                    .map(|address| Felt::from_bytes_be_slice(address))
+                    .collect(),
+            )
+            .await;
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeTokenBalancesStream))
+    }
+
+    async fn subscribe_transactions(
+        &self,
+        request: Request<SubscribeTransactionsRequest>,
+    ) -> ServiceResult<Self::SubscribeTransactionsStream> {
+        let SubscribeTransactionsRequest { sender_addresses } = request.into_inner();
+        let rx = self
+            .subscribe_transactions(
+                sender_addresses.iter().map(|address| Felt::from_bytes_be_slice(address)).collect(),
+            )
+            .await;
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as Self::SubscribeTransactionsStream))
+    }
 }
 
 const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
@@ -1369,7 +2508,7 @@ pub async fn new(
     world_address: Felt,
     provider: Arc<JsonRpcClient<HttpTransport>>,
 ) -> Result<
-    (SocketAddr, impl Future<Output = Result<(), tonic::transport::Error>> + 'static),
+    (SocketAddr, RestApi, impl Future<Output = Result<(), tonic::transport::Error>> + 'static),
     std::io::Error,
 > {
     let listener = TcpListener::bind("127.0.0.1:0").await?;
@@ -1381,6 +2520,7 @@ pub async fn new(
         .unwrap();
 
     let world = DojoWorld::new(pool.clone(), block_rx, world_address, provider);
+    let rest_api = RestApi::new(world.clone());
     let server = WorldServer::new(world)
         .accept_compressed(CompressionEncoding::Gzip)
         .send_compressed(CompressionEncoding::Gzip);
@@ -1415,5 +2555,5 @@ pub async fn new(
             shutdown_rx.recv().await.map_or((), |_| ())
         });
 
-    Ok((addr, server_future))
+    Ok((addr, rest_api, server_future))
 }
@@ -0,0 +1,665 @@
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use hyper::{Body, Request, Response};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use starknet::core::types::{Felt, StateUpdate};
+use tonic::{Code, Status};
+
+use super::DojoWorld;
+use crate::proto::world::world_server::World;
+use crate::proto::world::{
+    subscribe_world_resources_response, RetrieveEntitiesRequest, RetrieveEntitiesResponse,
+    RetrieveEventMessagesRequest, RetrieveEventsRequest, RetrieveTokenBalancesRequest,
+    RetrieveTokensRequest, RetrieveWorldResourcesRequest, SubscribeEntitiesRequest,
+    SubscribeEntityResponse, SubscribeEventMessagesRequest, SubscribeEventsRequest,
+    SubscribeIndexerRequest, SubscribeModelsRequest, SubscribeTokenBalancesRequest,
+    SubscribeTokenTransfersRequest, SubscribeTransactionsRequest, SubscribeWorldResourcesRequest,
+    UpdateEntitiesSubscriptionRequest, UpdateEventMessagesSubscriptionRequest,
+    WorldMetadataRequest,
+};
+use crate::proto::{self};
+use crate::types::schema::{Entity, EntityDiff};
+use crate::types::{EntityKeysClause, Event, EventQuery, IndexerUpdate, ModelKeysClause, Query};
+
+type ApiResult = Result<Response<Body>, (StatusCode, String)>;
+
+/// Interval after which a comment is sent on an idle event stream, so that the proxies between the
+/// server and the client don't close the connection.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// JSON API mirroring the world service, for the clients which can't use gRPC. The requests are
+/// handled by the gRPC handlers, with the `Query` and `Clause` types encoded as JSON.
+///
+/// - `POST /entities` and `POST /event-messages` (`?historical=true`) retrieve the entities of the
+///   `Query` of the body, and `POST /events` the events of its `EventQuery`.
+/// - `GET /world`, `GET /world/resources`, `GET /tokens` and `GET /tokens/balances` take the fields
+///   of their gRPC request as parameters, the lists being comma-separated.
+/// - `GET /subscriptions/<indexer|models|entities|event-messages|events|world-resources>` and `GET
+///   /subscriptions/<token-transfers|token-balances|transactions>` stream the updates of the
+///   subscription as server-sent events, its clauses being JSON parameters.
+/// - `POST /subscriptions/<entities|event-messages>/update` updates a subscription.
+#[derive(Debug, Clone)]
+pub struct RestApi {
+    world: DojoWorld,
+}
+
+/// Body of the requests updating a subscription.
+#[derive(Debug, Deserialize)]
+struct SubscriptionUpdate {
+    subscription_id: u64,
+    clauses: Vec<EntityKeysClause>,
+    #[serde(default)]
+    historical: bool,
+    #[serde(default)]
+    world_address: Option<Felt>,
+}
+
+impl RestApi {
+    pub fn new(world: DojoWorld) -> Self {
+        Self { world }
+    }
+
+    pub async fn handle(&self, path: &str, req: Request<Body>) -> Response<Body> {
+        let method = req.method().clone();
+        let params = Params::new(&req);
+
+        let result = async {
+            match (method, path.trim_end_matches('/')) {
+                (Method::POST, "/entities") => self.entities(json_body(req).await?).await,
+                (Method::POST, "/event-messages") => {
+                    self.event_messages(json_body(req).await?, params.bool("historical")?).await
+                }
+                (Method::POST, "/events") => self.events(json_body(req).await?).await,
+                (Method::GET, "/world") => self.world_metadata(&params).await,
+                (Method::GET, "/world/resources") => self.world_resources(&params).await,
+                (Method::GET, "/tokens") => self.tokens(&params).await,
+                (Method::GET, "/tokens/balances") => self.token_balances(&params).await,
+                (Method::GET, "/subscriptions/indexer") => self.subscribe_indexer(&params).await,
+                (Method::GET, "/subscriptions/models") => self.subscribe_models(&params).await,
+                (Method::GET, "/subscriptions/entities") => self.subscribe_entities(&params).await,
+                (Method::GET, "/subscriptions/event-messages") => {
+                    self.subscribe_event_messages(&params).await
+                }
+                (Method::GET, "/subscriptions/events") => self.subscribe_events(&params).await,
+                (Method::GET, "/subscriptions/world-resources") => {
+                    self.subscribe_world_resources(&params).await
+                }
+                (Method::GET, "/subscriptions/token-transfers") => {
+                    self.subscribe_token_transfers(&params).await
+                }
+                (Method::GET, "/subscriptions/token-balances") => {
+                    self.subscribe_token_balances(&params).await
+                }
+                (Method::GET, "/subscriptions/transactions") => {
+                    self.subscribe_transactions(&params).await
+                }
+                (Method::POST, "/subscriptions/entities/update") => {
+                    self.update_entities_subscription(json_body(req).await?).await
+                }
+                (Method::POST, "/subscriptions/event-messages/update") => {
+                    self.update_event_messages_subscription(json_body(req).await?).await
+                }
+                _ => Err((StatusCode::NOT_FOUND, "Not found".to_string())),
+            }
+        }
+        .await;
+
+        result.unwrap_or_else(|(status, error)| json_response(status, &json!({ "error": error })))
+    }
+
+    async fn entities(&self, query: Query) -> ApiResult {
+        let request = RetrieveEntitiesRequest { query: Some(query.into()) };
+        let response = World::retrieve_entities(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?;
+
+        entities_response(response.into_inner())
+    }
+
+    async fn event_messages(&self, query: Query, historical: bool) -> ApiResult {
+        let request = RetrieveEventMessagesRequest { query: Some(query.into()), historical };
+        let response = World::retrieve_event_messages(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?;
+
+        entities_response(response.into_inner())
+    }
+
+    async fn events(&self, query: EventQuery) -> ApiResult {
+        let request = RetrieveEventsRequest { query: Some(query.into()) };
+        let response = World::retrieve_events(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        let events = response.events.into_iter().map(Event::from).collect::<Vec<_>>();
+        Ok(json_response(
+            StatusCode::OK,
+            &json!({ "events": events, "nextCursor": non_empty(response.next_cursor) }),
+        ))
+    }
+
+    async fn world_metadata(&self, params: &Params) -> ApiResult {
+        let request = WorldMetadataRequest { world_address: params.address("world_address")? };
+        let response = World::world_metadata(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?;
+
+        let metadata = response.into_inner().metadata.ok_or_else(|| {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Missing world metadata".to_string())
+        })?;
+        let metadata = dojo_types::WorldMetadata::try_from(metadata).map_err(internal_error)?;
+        Ok(json_response(StatusCode::OK, &serde_json::to_value(metadata).map_err(internal_error)?))
+    }
+
+    async fn world_resources(&self, params: &Params) -> ApiResult {
+        let request = RetrieveWorldResourcesRequest {
+            resource: params.address("resource")?,
+            contract_address: params.address("contract_address")?,
+            world_address: params.address("world_address")?,
+        };
+        let response = World::retrieve_world_resources(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(json_response(
+            StatusCode::OK,
+            &json!({
+                "namespaces": response.namespaces.iter().map(namespace_json).collect::<Vec<_>>(),
+                "contracts": response.contracts.iter().map(contract_json).collect::<Vec<_>>(),
+                "permissions": response.permissions.iter().map(permission_json).collect::<Vec<_>>(),
+            }),
+        ))
+    }
+
+    async fn tokens(&self, params: &Params) -> ApiResult {
+        let request =
+            RetrieveTokensRequest { contract_addresses: params.addresses("contract_addresses")? };
+        let response = World::retrieve_tokens(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        let tokens = response
+            .tokens
+            .into_iter()
+            .map(|token| {
+                json!({
+                    "contractAddress": hex(&token.contract_address),
+                    "tokenId": non_empty(token.token_id),
+                    "name": token.name,
+                    "symbol": token.symbol,
+                    "decimals": token.decimals,
+                    "contractType": contract_type(token.contract_type),
+                    "metadata": serde_json::from_str::<Value>(&token.metadata).ok(),
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json_response(StatusCode::OK, &Value::Array(tokens)))
+    }
+
+    async fn token_balances(&self, params: &Params) -> ApiResult {
+        let request = RetrieveTokenBalancesRequest {
+            account_addresses: params.addresses("account_addresses")?,
+            contract_addresses: params.addresses("contract_addresses")?,
+        };
+        let response = World::retrieve_token_balances(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        let balances = response.balances.iter().map(balance_json).collect::<Vec<_>>();
+        Ok(json_response(StatusCode::OK, &Value::Array(balances)))
+    }
+
+    async fn subscribe_indexer(&self, params: &Params) -> ApiResult {
+        let request =
+            SubscribeIndexerRequest { contract_address: params.address("contract_address")? };
+        let stream = World::subscribe_indexer(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |update| {
+            serde_json::to_value(IndexerUpdate::from(update)).map_err(|e| e.to_string())
+        }))
+    }
+
+    async fn subscribe_models(&self, params: &Params) -> ApiResult {
+        let models_keys: Vec<ModelKeysClause> = params.json("models_keys")?.unwrap_or_default();
+        let request = SubscribeModelsRequest {
+            models_keys: models_keys.into_iter().map(Into::into).collect(),
+            world_address: params.address("world_address")?,
+        };
+        let stream = World::subscribe_models(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            let update = response
+                .model_update
+                .map(StateUpdate::try_from)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            serde_json::to_value(update).map_err(|e| e.to_string())
+        }))
+    }
+
+    async fn subscribe_entities(&self, params: &Params) -> ApiResult {
+        let request = SubscribeEntitiesRequest {
+            clauses: params.clauses()?,
+            world_address: params.address("world_address")?,
+            diff: params.bool("diff")?,
+        };
+        let stream = World::subscribe_entities(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, entity_update_json))
+    }
+
+    async fn subscribe_event_messages(&self, params: &Params) -> ApiResult {
+        let request = SubscribeEventMessagesRequest {
+            clauses: params.clauses()?,
+            historical: params.bool("historical")?,
+            world_address: params.address("world_address")?,
+            diff: params.bool("diff")?,
+        };
+        let stream = World::subscribe_event_messages(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, entity_update_json))
+    }
+
+    async fn subscribe_events(&self, params: &Params) -> ApiResult {
+        let request = SubscribeEventsRequest {
+            keys: params.clauses()?,
+            world_address: params.address("world_address")?,
+        };
+        let stream = World::subscribe_events(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            serde_json::to_value(response.event.map(Event::from)).map_err(|e| e.to_string())
+        }))
+    }
+
+    async fn subscribe_world_resources(&self, params: &Params) -> ApiResult {
+        let request = SubscribeWorldResourcesRequest {
+            resource: params.address("resource")?,
+            contract_address: params.address("contract_address")?,
+            world_address: params.address("world_address")?,
+        };
+        let stream = World::subscribe_world_resources(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            Ok(match response.update {
+                Some(subscribe_world_resources_response::Update::Namespace(namespace)) => {
+                    json!({ "namespace": namespace_json(&namespace) })
+                }
+                Some(subscribe_world_resources_response::Update::Contract(contract)) => {
+                    json!({ "contract": contract_json(&contract) })
+                }
+                Some(subscribe_world_resources_response::Update::Permission(permission)) => {
+                    json!({ "permission": permission_json(&permission) })
+                }
+                None => Value::Null,
+            })
+        }))
+    }
+
+    async fn subscribe_token_transfers(&self, params: &Params) -> ApiResult {
+        let request = SubscribeTokenTransfersRequest {
+            account_addresses: params.addresses("account_addresses")?,
+            contract_addresses: params.addresses("contract_addresses")?,
+        };
+        let stream = World::subscribe_token_transfers(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            Ok(response.transfer.map(|transfer| transfer_json(&transfer)).unwrap_or(Value::Null))
+        }))
+    }
+
+    async fn subscribe_token_balances(&self, params: &Params) -> ApiResult {
+        let request = SubscribeTokenBalancesRequest {
+            account_addresses: params.addresses("account_addresses")?,
+            contract_addresses: params.addresses("contract_addresses")?,
+        };
+        let stream = World::subscribe_token_balances(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            Ok(response.balance.map(|balance| balance_json(&balance)).unwrap_or(Value::Null))
+        }))
+    }
+
+    async fn subscribe_transactions(&self, params: &Params) -> ApiResult {
+        let request = SubscribeTransactionsRequest {
+            sender_addresses: params.addresses("sender_addresses")?,
+        };
+        let stream = World::subscribe_transactions(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(event_stream(stream, |response| {
+            Ok(response
+                .transaction
+                .map(|transaction| transaction_json(&transaction))
+                .unwrap_or(Value::Null))
+        }))
+    }
+
+    async fn update_entities_subscription(&self, update: SubscriptionUpdate) -> ApiResult {
+        let request = UpdateEntitiesSubscriptionRequest {
+            subscription_id: update.subscription_id,
+            clauses: update.clauses.into_iter().map(Into::into).collect(),
+            world_address: felt_bytes(update.world_address),
+        };
+        World::update_entities_subscription(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?;
+
+        Ok(json_response(StatusCode::OK, &json!({})))
+    }
+
+    async fn update_event_messages_subscription(&self, update: SubscriptionUpdate) -> ApiResult {
+        let request = UpdateEventMessagesSubscriptionRequest {
+            subscription_id: update.subscription_id,
+            clauses: update.clauses.into_iter().map(Into::into).collect(),
+            historical: update.historical,
+            world_address: felt_bytes(update.world_address),
+        };
+        World::update_event_messages_subscription(&self.world, tonic::Request::new(request))
+            .await
+            .map_err(status_error)?;
+
+        Ok(json_response(StatusCode::OK, &json!({})))
+    }
+}
+
+/// The parameters of the query string of a request.
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn new(req: &Request<Body>) -> Self {
+        Self(
+            req.uri()
+                .query()
+                .map(|query| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+                .unwrap_or_default(),
+        )
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    fn bool(&self, name: &str) -> Result<bool, (StatusCode, String)> {
+        self.get(name).map_or(Ok(false), |value| value.parse().map_err(|_| invalid_param(name)))
+    }
+
+    /// An address as the bytes of the gRPC requests, empty if not set.
+    fn address(&self, name: &str) -> Result<Vec<u8>, (StatusCode, String)> {
+        match self.get(name).filter(|value| !value.is_empty()) {
+            Some(value) => {
+                Ok(Felt::from_str(value).map_err(|_| invalid_param(name))?.to_bytes_be().to_vec())
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn addresses(&self, name: &str) -> Result<Vec<Vec<u8>>, (StatusCode, String)> {
+        self.get(name)
+            .map(|value| value.split(',').filter(|address| !address.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|address| {
+                Felt::from_str(address)
+                    .map(|address| address.to_bytes_be().to_vec())
+                    .map_err(|_| invalid_param(name))
+            })
+            .collect()
+    }
+
+    fn json<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, (StatusCode, String)> {
+        self.get(name)
+            .map(|value| {
+                serde_json::from_str(value).map_err(|e| {
+                    (StatusCode::BAD_REQUEST, format!("Invalid parameter {name}: {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    /// The `clauses` of a subscription, all the updates being received if not set.
+    fn clauses(&self) -> Result<Vec<proto::types::EntityKeysClause>, (StatusCode, String)> {
+        let clauses: Vec<EntityKeysClause> = self.json("clauses")?.unwrap_or_default();
+        Ok(clauses.into_iter().map(Into::into).collect())
+    }
+}
+
+async fn json_body<T: DeserializeOwned>(req: Request<Body>) -> Result<T, (StatusCode, String)> {
+    let body = hyper::body::to_bytes(req.into_body())
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    serde_json::from_slice(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid request body: {e}")))
+}
+
+fn entities_response(response: RetrieveEntitiesResponse) -> ApiResult {
+    let entities = response
+        .entities
+        .into_iter()
+        .map(Entity::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(internal_error)?;
+
+    Ok(json_response(
+        StatusCode::OK,
+        &json!({
+            "entities": entities,
+            "totalCount": response.total_count,
+            "nextCursor": non_empty(response.next_cursor),
+        }),
+    ))
+}
+
+fn entity_update_json(response: SubscribeEntityResponse) -> Result<Value, String> {
+    let entity = response.entity.map(Entity::try_from).transpose().map_err(|e| e.to_string())?;
+    let diff = response.diff.map(EntityDiff::try_from).transpose().map_err(|e| e.to_string())?;
+
+    Ok(json!({
+        "subscriptionId": response.subscription_id,
+        "entity": entity,
+        "diff": diff,
+        "resync": response.resync,
+    }))
+}
+
+/// Streams the updates of a subscription as server-sent events, an error ending the stream. A
+/// `:keep-alive` comment is sent whenever no update was sent for [`KEEP_ALIVE_INTERVAL`].
+fn event_stream<S, T, F>(stream: S, to_json: F) -> Response<Body>
+where
+    S: Stream<Item = Result<T, Status>> + Send + 'static,
+    F: Fn(T) -> Result<Value, String> + Send + 'static,
+{
+    let events = stream
+        .map(move |update| update.map_err(|status| status.message().to_string()).and_then(&to_json))
+        .scan(false, |failed, update| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            let event = match update {
+                Ok(value) => format!("data: {value}\n\n"),
+                Err(error) => {
+                    *failed = true;
+                    format!("event: error\ndata: {}\n\n", json!({ "error": error }))
+                }
+            };
+            futures::future::ready(Some(event))
+        })
+        .boxed();
+
+    let keep_alive = keep_alive_interval();
+    let events =
+        futures::stream::unfold((events, keep_alive), |(mut events, mut keep_alive)| async move {
+            let event = tokio::select! {
+                event = events.next() => {
+                    keep_alive.reset();
+                    event?
+                }
+                _ = keep_alive.tick() => ":keep-alive\n\n".to_string(),
+            };
+            Some((Ok::<_, Infallible>(event), (events, keep_alive)))
+        });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "text/event-stream")
+        .header(CACHE_CONTROL, "no-cache")
+        .body(Body::wrap_stream(events))
+        .unwrap()
+}
+
+fn keep_alive_interval() -> tokio::time::Interval {
+    let start = tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL;
+    let mut interval = tokio::time::interval_at(start, KEEP_ALIVE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    interval
+}
+
+fn namespace_json(namespace: &proto::types::Namespace) -> Value {
+    json!({
+        "hash": hex(&namespace.hash),
+        "namespace": namespace.namespace,
+        "worldAddress": hex(&namespace.world_address),
+    })
+}
+
+fn contract_json(contract: &proto::types::WorldContract) -> Value {
+    json!({
+        "selector": hex(&contract.selector),
+        "namespace": contract.namespace,
+        "name": contract.name,
+        "contractAddress": hex(&contract.contract_address),
+        "classHash": hex(&contract.class_hash),
+        "classHashes": contract.class_hashes.iter().map(|hash| hex(hash)).collect::<Vec<_>>(),
+        "salt": hex(&contract.salt),
+        "initialized": contract.initialized,
+        "initCalldata": contract.init_calldata.iter().map(|felt| hex(felt)).collect::<Vec<_>>(),
+        "worldAddress": hex(&contract.world_address),
+    })
+}
+
+fn permission_json(permission: &proto::types::Permission) -> Value {
+    json!({
+        "resource": hex(&permission.resource),
+        "contractAddress": hex(&permission.contract_address),
+        "permission": proto::types::PermissionKind::try_from(permission.permission)
+            .map(|kind| kind.as_str_name())
+            .ok(),
+        "granted": permission.granted,
+        "worldAddress": hex(&permission.world_address),
+    })
+}
+
+fn balance_json(balance: &proto::types::TokenBalance) -> Value {
+    json!({
+        "accountAddress": hex(&balance.account_address),
+        "contractAddress": hex(&balance.contract_address),
+        "tokenId": non_empty(balance.token_id.clone()),
+        "balance": balance.balance,
+        "contractType": contract_type(balance.contract_type),
+    })
+}
+
+fn transfer_json(transfer: &proto::types::TokenTransfer) -> Value {
+    json!({
+        "contractAddress": hex(&transfer.contract_address),
+        "from": hex(&transfer.from_address),
+        "to": hex(&transfer.to_address),
+        "amount": transfer.amount,
+        "tokenId": non_empty(transfer.token_id.clone()),
+        "contractType": contract_type(transfer.contract_type),
+        "transactionHash": hex(&transfer.transaction_hash),
+        "executedAt": transfer.executed_at,
+    })
+}
+
+fn transaction_json(transaction: &proto::types::Transaction) -> Value {
+    json!({
+        "transactionHash": hex(&transaction.transaction_hash),
+        "senderAddress": hex(&transaction.sender_address),
+        "calldata": transaction.calldata.iter().map(|felt| hex(felt)).collect::<Vec<_>>(),
+        "maxFee": hex(&transaction.max_fee),
+        "signature": transaction.signature.iter().map(|felt| hex(felt)).collect::<Vec<_>>(),
+        "nonce": hex(&transaction.nonce),
+        "transactionType": transaction.transaction_type,
+        "executedAt": transaction.executed_at,
+    })
+}
+
+fn contract_type(contract_type: i32) -> Option<&'static str> {
+    proto::types::ContractType::try_from(contract_type).map(|r#type| r#type.as_str_name()).ok()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("{:#x}", Felt::from_bytes_be_slice(bytes))
+}
+
+fn felt_bytes(felt: Option<Felt>) -> Vec<u8> {
+    felt.map(|felt| felt.to_bytes_be().to_vec()).unwrap_or_default()
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+fn status_error(status: Status) -> (StatusCode, String) {
+    let code = match status.code() {
+        Code::InvalidArgument => StatusCode::BAD_REQUEST,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (code, status.message().to_string())
+}
+
+fn internal_error(error: impl ToString) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+fn invalid_param(name: &str) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, format!("Invalid parameter {name}"))
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
@@ -2,10 +2,10 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
-use futures::Stream;
+use futures::{stream, Stream};
 use futures_util::StreamExt;
 use rand::Rng;
 use starknet::core::types::Felt;
@@ -16,9 +16,10 @@ use tokio::sync::RwLock;
 use torii_core::error::{Error, ParseError};
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::FELT_DELIMITER;
-use torii_core::types::OptimisticEntity;
+use torii_core::types::{OptimisticEntity, Rollback};
 use tracing::{error, trace};
 
+use super::entity_diff::EntityDiffState;
 use super::match_entity_keys;
 use crate::proto;
 use crate::proto::world::SubscribeEntityResponse;
@@ -30,8 +31,12 @@ pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::entity"
 pub struct EntitiesSubscriber {
     /// Entity ids that the subscriber is interested in
     pub(crate) clauses: Vec<EntityKeysClause>,
+    /// The world of the models that the subscriber is interested in, all of them if `None`
+    pub(crate) world_address: Option<Felt>,
     /// The channel to send the response back to the subscriber.
     pub(crate) sender: Sender<Result<proto::world::SubscribeEntityResponse, tonic::Status>>,
+    /// The entities sent to the subscriber, if it receives diffs.
+    pub(crate) diff: Option<Mutex<EntityDiffState>>,
 }
 #[derive(Debug, Default)]
 pub struct EntityManager {
@@ -42,6 +47,8 @@ impl EntityManager {
     pub async fn add_subscriber(
         &self,
         clauses: Vec<EntityKeysClause>,
+        world_address: Option<Felt>,
+        diff: bool,
     ) -> Result<Receiver<Result<proto::world::SubscribeEntityResponse, tonic::Status>>, Error> {
         let subscription_id = rand::thread_rng().gen::<u64>();
         let (sender, receiver) = channel(1);
@@ -49,27 +56,46 @@ impl EntityManager {
         // NOTE: unlock issue with firefox/safari
         // initially send empty stream message to return from
         // initial subscribe call
-        let _ = sender.send(Ok(SubscribeEntityResponse { entity: None, subscription_id })).await;
+        let _ = sender
+            .send(Ok(SubscribeEntityResponse { subscription_id, ..Default::default() }))
+            .await;
 
+        let diff = diff.then(|| Mutex::new(EntityDiffState::default()));
         self.subscribers
             .write()
             .await
-            .insert(subscription_id, EntitiesSubscriber { clauses, sender });
+            .insert(subscription_id, EntitiesSubscriber { clauses, world_address, sender, diff });
 
         Ok(receiver)
     }
 
-    pub async fn update_subscriber(&self, id: u64, clauses: Vec<EntityKeysClause>) {
-        let sender = {
+    pub async fn update_subscriber(
+        &self,
+        id: u64,
+        clauses: Vec<EntityKeysClause>,
+        world_address: Option<Felt>,
+    ) {
+        let (sender, diff) = {
             let subscribers = self.subscribers.read().await;
             if let Some(subscriber) = subscribers.get(&id) {
-                subscriber.sender.clone()
+                (subscriber.sender.clone(), subscriber.diff.is_some())
             } else {
                 return; // Subscriber not found, exit early
             }
         };
 
-        self.subscribers.write().await.insert(id, EntitiesSubscriber { clauses, sender });
+        // the entities of the new clauses may not have been sent to the subscriber
+        let diff = diff.then(|| Mutex::new(EntityDiffState::default()));
+        if diff.is_some() {
+            let resync =
+                SubscribeEntityResponse { subscription_id: id, resync: true, ..Default::default() };
+            let _ = sender.send(Ok(resync)).await;
+        }
+
+        self.subscribers
+            .write()
+            .await
+            .insert(id, EntitiesSubscriber { clauses, world_address, sender, diff });
     }
 
     pub(super) async fn remove_subscriber(&self, id: u64) {
@@ -77,20 +103,27 @@ impl EntityManager {
     }
 }
 
+#[derive(Debug, Clone)]
+enum EntityUpdate {
+    Entity(OptimisticEntity),
+    Rollback(Rollback),
+}
+
 #[must_use = "Service does nothing unless polled"]
 #[allow(missing_debug_implementations)]
 pub struct Service {
-    simple_broker: Pin<Box<dyn Stream<Item = OptimisticEntity> + Send>>,
-    entity_sender: UnboundedSender<OptimisticEntity>,
+    simple_broker: Pin<Box<dyn Stream<Item = EntityUpdate> + Send>>,
+    entity_sender: UnboundedSender<EntityUpdate>,
 }
 
 impl Service {
     pub fn new(subs_manager: Arc<EntityManager>) -> Self {
         let (entity_sender, entity_receiver) = unbounded_channel();
-        let service = Self {
-            simple_broker: Box::pin(SimpleBroker::<OptimisticEntity>::subscribe()),
-            entity_sender,
-        };
+        let simple_broker = stream::select(
+            SimpleBroker::<OptimisticEntity>::subscribe().map(EntityUpdate::Entity),
+            SimpleBroker::<Rollback>::subscribe().map(EntityUpdate::Rollback),
+        );
+        let service = Self { simple_broker: Box::pin(simple_broker), entity_sender };
 
         tokio::spawn(Self::publish_updates(subs_manager, entity_receiver));
 
@@ -99,15 +132,48 @@ impl Service {
 
     async fn publish_updates(
         subs: Arc<EntityManager>,
-        mut entity_receiver: UnboundedReceiver<OptimisticEntity>,
+        mut entity_receiver: UnboundedReceiver<EntityUpdate>,
     ) {
-        while let Some(entity) = entity_receiver.recv().await {
-            if let Err(e) = Self::process_entity_update(&subs, &entity).await {
+        while let Some(update) = entity_receiver.recv().await {
+            let result = match update {
+                EntityUpdate::Entity(entity) => Self::process_entity_update(&subs, &entity).await,
+                EntityUpdate::Rollback(_) => Self::process_rollback(&subs).await,
+            };
+
+            if let Err(e) = result {
                 error!(target = LOG_TARGET, error = %e, "Processing entity update.");
             }
         }
     }
 
+    // The entities held by the subscribers receiving diffs may have been rolled back.
+    async fn process_rollback(subs: &Arc<EntityManager>) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        for (idx, sub) in subs.subscribers.read().await.iter() {
+            let Some(diff) = &sub.diff else {
+                continue;
+            };
+            diff.lock().unwrap().clear();
+
+            let resp = SubscribeEntityResponse {
+                subscription_id: *idx,
+                resync: true,
+                ..Default::default()
+            };
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing entity stream.");
+            subs.remove_subscriber(id).await
+        }
+
+        Ok(())
+    }
+
     async fn process_entity_update(
         subs: &Arc<EntityManager>,
         entity: &OptimisticEntity,
@@ -121,8 +187,13 @@ impl Service {
             .map(Felt::from_str)
             .collect::<Result<Vec<_>, _>>()
             .map_err(ParseError::FromStr)?;
+        let world_address = Felt::from_str(&entity.world_address).map_err(ParseError::FromStr)?;
 
         for (idx, sub) in subs.subscribers.read().await.iter() {
+            if sub.world_address.is_some_and(|world| world != world_address) {
+                continue;
+            }
+
             // Check if the subscriber is interested in this entity
             // If we have a clause of hashed keys, then check that the id of the entity
             // is in the list of hashed keys.
@@ -133,13 +204,42 @@ impl Service {
                 continue;
             }
 
+            // This should NEVER be None
+            let model = entity.updated_model.as_ref().unwrap().as_struct().unwrap();
+
+            if let Some(diff) = &sub.diff {
+                let diff = if entity.deleted {
+                    Some(diff.lock().unwrap().delete(world_address, hashed))
+                } else {
+                    diff.lock().unwrap().update(world_address, hashed, model)
+                };
+
+                // none of the members sent to the subscriber changed
+                let Some(diff) = diff else {
+                    continue;
+                };
+
+                let resp = proto::world::SubscribeEntityResponse {
+                    subscription_id: *idx,
+                    diff: Some(diff),
+                    ..Default::default()
+                };
+                if sub.sender.send(Ok(resp)).await.is_err() {
+                    closed_stream.push(*idx);
+                }
+
+                continue;
+            }
+
             if entity.deleted {
                 let resp = proto::world::SubscribeEntityResponse {
                     entity: Some(proto::types::Entity {
                         hashed_keys: hashed.to_bytes_be().to_vec(),
                         models: vec![],
+                        world_address: world_address.to_bytes_be().to_vec(),
                     }),
                     subscription_id: *idx,
+                    ..Default::default()
                 };
 
                 if sub.sender.send(Ok(resp)).await.is_err() {
@@ -149,14 +249,14 @@ impl Service {
                 continue;
             }
 
-            // This should NEVER be None
-            let model = entity.updated_model.as_ref().unwrap().as_struct().unwrap().clone();
             let resp = proto::world::SubscribeEntityResponse {
                 entity: Some(proto::types::Entity {
                     hashed_keys: hashed.to_bytes_be().to_vec(),
-                    models: vec![model.into()],
+                    models: vec![model.clone().into()],
+                    world_address: world_address.to_bytes_be().to_vec(),
                 }),
                 subscription_id: *idx,
+                ..Default::default()
             };
 
             if sub.sender.send(Ok(resp)).await.is_err() {
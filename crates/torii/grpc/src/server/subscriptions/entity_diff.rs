@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dojo_types::schema::Struct;
+use starknet::core::types::Felt;
+
+use crate::proto;
+
+/// The maximum number of entities held for a subscriber. Past it, the least recently updated
+/// entity is forgotten: its next diff holds all the members of its models and has no previous
+/// sequence, so that the subscriber retrieves it again.
+pub const MAX_ENTITIES: usize = 10_000;
+
+/// The models of the entities sent to a subscriber receiving diffs, to only send it the members
+/// which changed.
+#[derive(Debug, Default)]
+pub struct EntityDiffState {
+    /// Sequence number of the last diff sent to the subscriber.
+    sequence: u64,
+    /// The entities by world and hashed keys.
+    entities: HashMap<(Felt, Felt), EntityState>,
+    /// The world and hashed keys of the entities by the sequence number of their last diff.
+    by_sequence: BTreeMap<u64, (Felt, Felt)>,
+}
+
+#[derive(Debug, Default)]
+struct EntityState {
+    /// Sequence number of the last diff of the entity.
+    sequence: u64,
+    models: HashMap<String, Struct>,
+}
+
+impl EntityDiffState {
+    /// The diff of an update of a model of an entity, `None` if none of its members changed. A
+    /// model without members has been removed from the entity.
+    pub fn update(
+        &mut self,
+        world_address: Felt,
+        hashed_keys: Felt,
+        model: &Struct,
+    ) -> Option<proto::types::EntityDiff> {
+        let entity = self.entities.entry((world_address, hashed_keys)).or_default();
+
+        let mut diff = proto::types::EntityDiff {
+            hashed_keys: hashed_keys.to_bytes_be().to_vec(),
+            world_address: world_address.to_bytes_be().to_vec(),
+            previous_sequence: entity.sequence,
+            ..Default::default()
+        };
+
+        if model.children.is_empty() {
+            entity.models.remove(&model.name);
+            diff.deleted_models.push(model.name.clone());
+        } else {
+            let known = entity
+                .models
+                .entry(model.name.clone())
+                .or_insert_with(|| Struct { name: model.name.clone(), children: vec![] });
+
+            // updates of a single member only hold that member
+            let mut changed = Vec::new();
+            for member in &model.children {
+                match known.children.iter_mut().find(|m| m.name == member.name) {
+                    Some(known) if known == member => {}
+                    Some(known) => {
+                        *known = member.clone();
+                        changed.push(member.clone());
+                    }
+                    None => {
+                        known.children.push(member.clone());
+                        changed.push(member.clone());
+                    }
+                }
+            }
+
+            if changed.is_empty() {
+                return None;
+            }
+            diff.models.push(Struct { name: model.name.clone(), children: changed }.into());
+        }
+
+        self.sequence += 1;
+        self.by_sequence.remove(&entity.sequence);
+        self.by_sequence.insert(self.sequence, (world_address, hashed_keys));
+        entity.sequence = self.sequence;
+        diff.sequence = self.sequence;
+
+        if self.entities.len() > MAX_ENTITIES {
+            if let Some((_, oldest)) = self.by_sequence.pop_first() {
+                self.entities.remove(&oldest);
+            }
+        }
+
+        Some(diff)
+    }
+
+    /// The diff of the deletion of an entity, along with all of its models.
+    pub fn delete(&mut self, world_address: Felt, hashed_keys: Felt) -> proto::types::EntityDiff {
+        let previous_sequence = self
+            .entities
+            .remove(&(world_address, hashed_keys))
+            .map(|entity| entity.sequence)
+            .unwrap_or_default();
+        self.by_sequence.remove(&previous_sequence);
+        self.sequence += 1;
+
+        proto::types::EntityDiff {
+            hashed_keys: hashed_keys.to_bytes_be().to_vec(),
+            world_address: world_address.to_bytes_be().to_vec(),
+            deleted: true,
+            sequence: self.sequence,
+            previous_sequence,
+            ..Default::default()
+        }
+    }
+
+    /// Forgets the entities sent to the subscriber, the following diffs holding all the members
+    /// of the models.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.by_sequence.clear();
+    }
+
+    /// The number of entities held for the subscriber.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
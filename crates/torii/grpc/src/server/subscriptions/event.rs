@@ -30,6 +30,8 @@ pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::event";
 pub struct EventSubscriber {
     /// Event keys that the subscriber is interested in
     keys: Vec<EntityKeysClause>,
+    /// The world that the subscriber is interested in the events of, all of them if `None`
+    world_address: Option<Felt>,
     /// The channel to send the response back to the subscriber.
     sender: Sender<Result<proto::world::SubscribeEventsResponse, tonic::Status>>,
 }
@@ -43,6 +45,7 @@ impl EventManager {
     pub async fn add_subscriber(
         &self,
         keys: Vec<EntityKeysClause>,
+        world_address: Option<Felt>,
     ) -> Result<Receiver<Result<proto::world::SubscribeEventsResponse, tonic::Status>>, Error> {
         let id = rand::thread_rng().gen::<usize>();
         let (sender, receiver) = channel(1);
@@ -52,7 +55,7 @@ impl EventManager {
         // initial subscribe call
         let _ = sender.send(Ok(SubscribeEventsResponse { event: None })).await;
 
-        self.subscribers.write().await.insert(id, EventSubscriber { keys, sender });
+        self.subscribers.write().await.insert(id, EventSubscriber { keys, world_address, sender });
 
         Ok(receiver)
     }
@@ -112,6 +115,10 @@ impl Service {
             if !match_keys(&keys, &sub.keys) {
                 continue;
             }
+            if sub.world_address.is_some_and(|world| format!("{:#x}", world) != event.world_address)
+            {
+                continue;
+            }
 
             let resp = proto::world::SubscribeEventsResponse {
                 event: Some(proto::types::Event {
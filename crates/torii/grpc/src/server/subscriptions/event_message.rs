@@ -2,10 +2,10 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
-use futures::Stream;
+use futures::{stream, Stream};
 use futures_util::StreamExt;
 use rand::Rng;
 use starknet::core::types::Felt;
@@ -16,9 +16,10 @@ use tokio::sync::RwLock;
 use torii_core::error::{Error, ParseError};
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::FELT_DELIMITER;
-use torii_core::types::OptimisticEventMessage;
+use torii_core::types::{OptimisticEventMessage, Rollback};
 use tracing::{error, trace};
 
+use super::entity_diff::EntityDiffState;
 use super::match_entity_keys;
 use crate::proto;
 use crate::proto::world::SubscribeEntityResponse;
@@ -32,8 +33,12 @@ pub struct EventMessageSubscriber {
     pub(crate) clauses: Vec<EntityKeysClause>,
     /// Whether the subscriber is interested in historical event messages
     pub(crate) historical: bool,
+    /// The world of the models that the subscriber is interested in, all of them if `None`
+    pub(crate) world_address: Option<Felt>,
     /// The channel to send the response back to the subscriber.
     pub(crate) sender: Sender<Result<proto::world::SubscribeEntityResponse, tonic::Status>>,
+    /// The event messages sent to the subscriber, if it receives diffs.
+    pub(crate) diff: Option<Mutex<EntityDiffState>>,
 }
 
 #[derive(Debug, Default)]
@@ -46,6 +51,8 @@ impl EventMessageManager {
         &self,
         clauses: Vec<EntityKeysClause>,
         historical: bool,
+        world_address: Option<Felt>,
+        diff: bool,
     ) -> Result<Receiver<Result<proto::world::SubscribeEntityResponse, tonic::Status>>, Error> {
         let subscription_id = rand::thread_rng().gen::<u64>();
         let (sender, receiver) = channel(1);
@@ -53,12 +60,15 @@ impl EventMessageManager {
         // NOTE: unlock issue with firefox/safari
         // initially send empty stream message to return from
         // initial subscribe call
-        let _ = sender.send(Ok(SubscribeEntityResponse { entity: None, subscription_id })).await;
+        let _ = sender
+            .send(Ok(SubscribeEntityResponse { subscription_id, ..Default::default() }))
+            .await;
 
-        self.subscribers
-            .write()
-            .await
-            .insert(subscription_id, EventMessageSubscriber { clauses, historical, sender });
+        let diff = diff.then(|| Mutex::new(EntityDiffState::default()));
+        self.subscribers.write().await.insert(
+            subscription_id,
+            EventMessageSubscriber { clauses, historical, world_address, sender, diff },
+        );
 
         Ok(receiver)
     }
@@ -68,20 +78,29 @@ impl EventMessageManager {
         id: u64,
         clauses: Vec<EntityKeysClause>,
         historical: bool,
+        world_address: Option<Felt>,
     ) {
-        let sender = {
+        let (sender, diff) = {
             let subscribers = self.subscribers.read().await;
             if let Some(subscriber) = subscribers.get(&id) {
-                subscriber.sender.clone()
+                (subscriber.sender.clone(), subscriber.diff.is_some())
             } else {
                 return; // Subscriber not found, exit early
             }
         };
 
-        self.subscribers
-            .write()
-            .await
-            .insert(id, EventMessageSubscriber { clauses, historical, sender });
+        // the event messages of the new clauses may not have been sent to the subscriber
+        let diff = diff.then(|| Mutex::new(EntityDiffState::default()));
+        if diff.is_some() {
+            let resync =
+                SubscribeEntityResponse { subscription_id: id, resync: true, ..Default::default() };
+            let _ = sender.send(Ok(resync)).await;
+        }
+
+        self.subscribers.write().await.insert(
+            id,
+            EventMessageSubscriber { clauses, historical, world_address, sender, diff },
+        );
     }
 
     pub(super) async fn remove_subscriber(&self, id: u64) {
@@ -89,20 +108,28 @@ impl EventMessageManager {
     }
 }
 
+#[derive(Debug, Clone)]
+enum EventMessageUpdate {
+    EventMessage(OptimisticEventMessage),
+    Rollback(Rollback),
+}
+
 #[must_use = "Service does nothing unless polled"]
 #[allow(missing_debug_implementations)]
 pub struct Service {
-    simple_broker: Pin<Box<dyn Stream<Item = OptimisticEventMessage> + Send>>,
-    event_sender: UnboundedSender<OptimisticEventMessage>,
+    simple_broker: Pin<Box<dyn Stream<Item = EventMessageUpdate> + Send>>,
+    event_sender: UnboundedSender<EventMessageUpdate>,
 }
 
 impl Service {
     pub fn new(subs_manager: Arc<EventMessageManager>) -> Self {
         let (event_sender, event_receiver) = unbounded_channel();
-        let service = Self {
-            simple_broker: Box::pin(SimpleBroker::<OptimisticEventMessage>::subscribe()),
-            event_sender,
-        };
+        let simple_broker = stream::select(
+            SimpleBroker::<OptimisticEventMessage>::subscribe()
+                .map(EventMessageUpdate::EventMessage),
+            SimpleBroker::<Rollback>::subscribe().map(EventMessageUpdate::Rollback),
+        );
+        let service = Self { simple_broker: Box::pin(simple_broker), event_sender };
 
         tokio::spawn(Self::publish_updates(subs_manager, event_receiver));
 
@@ -111,15 +138,50 @@ impl Service {
 
     async fn publish_updates(
         subs: Arc<EventMessageManager>,
-        mut event_receiver: UnboundedReceiver<OptimisticEventMessage>,
+        mut event_receiver: UnboundedReceiver<EventMessageUpdate>,
     ) {
-        while let Some(event) = event_receiver.recv().await {
-            if let Err(e) = Self::process_event_update(&subs, &event).await {
+        while let Some(update) = event_receiver.recv().await {
+            let result = match update {
+                EventMessageUpdate::EventMessage(event) => {
+                    Self::process_event_update(&subs, &event).await
+                }
+                EventMessageUpdate::Rollback(_) => Self::process_rollback(&subs).await,
+            };
+
+            if let Err(e) = result {
                 error!(target = LOG_TARGET, error = %e, "Processing event update.");
             }
         }
     }
 
+    // The event messages held by the subscribers receiving diffs may have been rolled back.
+    async fn process_rollback(subs: &Arc<EventMessageManager>) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        for (idx, sub) in subs.subscribers.read().await.iter() {
+            let Some(diff) = &sub.diff else {
+                continue;
+            };
+            diff.lock().unwrap().clear();
+
+            let resp = SubscribeEntityResponse {
+                subscription_id: *idx,
+                resync: true,
+                ..Default::default()
+            };
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing entity stream.");
+            subs.remove_subscriber(id).await
+        }
+
+        Ok(())
+    }
+
     async fn process_event_update(
         subs: &Arc<EventMessageManager>,
         entity: &OptimisticEventMessage,
@@ -133,8 +195,13 @@ impl Service {
             .map(Felt::from_str)
             .collect::<Result<Vec<_>, _>>()
             .map_err(ParseError::FromStr)?;
+        let world_address = Felt::from_str(&entity.world_address).map_err(ParseError::FromStr)?;
 
         for (idx, sub) in subs.subscribers.read().await.iter() {
+            if sub.world_address.is_some_and(|world| world != world_address) {
+                continue;
+            }
+
             // Check if the subscriber is interested in this historical or non-historical event
             if sub.historical != entity.historical {
                 continue;
@@ -151,13 +218,34 @@ impl Service {
             }
 
             // This should NEVER be None
-            let model = entity.updated_model.as_ref().unwrap().as_struct().unwrap().clone();
+            let model = entity.updated_model.as_ref().unwrap().as_struct().unwrap();
+
+            if let Some(diff) = &sub.diff {
+                // none of the members sent to the subscriber changed
+                let Some(diff) = diff.lock().unwrap().update(world_address, hashed, model) else {
+                    continue;
+                };
+
+                let resp = proto::world::SubscribeEntityResponse {
+                    subscription_id: *idx,
+                    diff: Some(diff),
+                    ..Default::default()
+                };
+                if sub.sender.send(Ok(resp)).await.is_err() {
+                    closed_stream.push(*idx);
+                }
+
+                continue;
+            }
+
             let resp = proto::world::SubscribeEntityResponse {
                 entity: Some(proto::types::Entity {
                     hashed_keys: hashed.to_bytes_be().to_vec(),
-                    models: vec![model.into()],
+                    models: vec![model.clone().into()],
+                    world_address: world_address.to_bytes_be().to_vec(),
                 }),
                 subscription_id: *idx,
+                ..Default::default()
             };
 
             if sub.sender.send(Ok(resp)).await.is_err() {
@@ -5,7 +5,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use futures::{Stream, StreamExt};
+use futures::{stream, Stream, StreamExt};
 use rand::Rng;
 use sqlx::{Pool, Sqlite};
 use starknet::core::types::Felt;
@@ -15,7 +15,7 @@ use tokio::sync::mpsc::{
 use tokio::sync::RwLock;
 use torii_core::error::{Error, ParseError};
 use torii_core::simple_broker::SimpleBroker;
-use torii_core::types::ContractCursor as ContractUpdated;
+use torii_core::types::{ContractCursor as ContractUpdated, Rollback};
 use tracing::{error, trace};
 
 use crate::proto;
@@ -66,6 +66,7 @@ impl IndexerManager {
                     tps: contract.tps,
                     last_block_timestamp: contract.last_block_timestamp,
                     contract_address: contract_address.to_bytes_be().to_vec(),
+                    rollback: false,
                 }))
                 .await;
         }
@@ -79,20 +80,27 @@ impl IndexerManager {
     }
 }
 
+#[derive(Debug, Clone)]
+enum IndexerUpdate {
+    Contract(ContractUpdated),
+    Rollback(Rollback),
+}
+
 #[must_use = "Service does nothing unless polled"]
 #[allow(missing_debug_implementations)]
 pub struct Service {
-    simple_broker: Pin<Box<dyn Stream<Item = ContractUpdated> + Send>>,
-    update_sender: UnboundedSender<ContractUpdated>,
+    simple_broker: Pin<Box<dyn Stream<Item = IndexerUpdate> + Send>>,
+    update_sender: UnboundedSender<IndexerUpdate>,
 }
 
 impl Service {
     pub fn new(subs_manager: Arc<IndexerManager>) -> Self {
         let (update_sender, update_receiver) = unbounded_channel();
-        let service = Self {
-            simple_broker: Box::pin(SimpleBroker::<ContractUpdated>::subscribe()),
-            update_sender,
-        };
+        let simple_broker = stream::select(
+            SimpleBroker::<ContractUpdated>::subscribe().map(IndexerUpdate::Contract),
+            SimpleBroker::<Rollback>::subscribe().map(IndexerUpdate::Rollback),
+        );
+        let service = Self { simple_broker: Box::pin(simple_broker), update_sender };
 
         tokio::spawn(Self::publish_updates(subs_manager, update_receiver));
 
@@ -101,10 +109,15 @@ impl Service {
 
     async fn publish_updates(
         subs: Arc<IndexerManager>,
-        mut update_receiver: UnboundedReceiver<ContractUpdated>,
+        mut update_receiver: UnboundedReceiver<IndexerUpdate>,
     ) {
         while let Some(update) = update_receiver.recv().await {
-            if let Err(e) = Self::process_update(&subs, &update).await {
+            let result = match update {
+                IndexerUpdate::Contract(update) => Self::process_update(&subs, &update).await,
+                IndexerUpdate::Rollback(rollback) => Self::process_rollback(&subs, &rollback).await,
+            };
+
+            if let Err(e) = result {
                 error!(target = LOG_TARGET, error = %e, "Processing indexer update.");
             }
         }
@@ -128,6 +141,35 @@ impl Service {
                 tps: update.tps,
                 last_block_timestamp: update.last_block_timestamp,
                 contract_address: contract_address.to_bytes_be().to_vec(),
+                rollback: false,
+            };
+
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing indexer updates stream.");
+            subs.remove_subscriber(id).await
+        }
+
+        Ok(())
+    }
+
+    // A rollback applies to every indexed contract, so all subscribers are notified.
+    async fn process_rollback(
+        subs: &Arc<IndexerManager>,
+        rollback: &Rollback,
+    ) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+        for (idx, sub) in subs.subscribers.read().await.iter() {
+            let resp = SubscribeIndexerResponse {
+                head: rollback.block_number as i64,
+                tps: 0,
+                last_block_timestamp: 0,
+                contract_address: sub.contract_address.to_bytes_be().to_vec(),
+                rollback: true,
             };
 
             if sub.sender.send(Ok(resp)).await.is_err() {
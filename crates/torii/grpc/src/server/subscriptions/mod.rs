@@ -4,11 +4,15 @@ use starknet_crypto::{poseidon_hash_many, Felt};
 use crate::types::{EntityKeysClause, PatternMatching};
 
 pub mod entity;
+pub mod entity_diff;
 pub mod error;
 pub mod event;
 pub mod event_message;
 pub mod indexer;
 pub mod model_diff;
+pub mod token;
+pub mod transaction;
+pub mod world_resources;
 
 pub(crate) fn match_entity_keys(
     id: Felt,
@@ -30,6 +30,8 @@ pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::model_d
 pub struct ModelMetadata {
     pub selector: Felt,
     pub packed_size: usize,
+    /// The world in the storage of which the model is stored.
+    pub world_address: Felt,
 }
 
 #[derive(Debug)]
@@ -42,8 +44,8 @@ impl ModelDiffRequest {}
 
 #[derive(Debug)]
 pub struct ModelDiffSubscriber {
-    /// The storage addresses that the subscriber is interested in.
-    storage_addresses: HashSet<Felt>,
+    /// The storage addresses that the subscriber is interested in, by world.
+    storage_addresses: HashMap<Felt, HashSet<Felt>>,
     /// The channel to send the response back to the subscriber.
     sender: Sender<Result<proto::world::SubscribeModelsResponse, tonic::Status>>,
 }
@@ -63,28 +65,23 @@ impl StateDiffManager {
         let (sender, receiver) = channel(1);
 
         // convert the list of entites into a list storage addresses
-        let storage_addresses = reqs
-            .into_iter()
-            .map(|req| {
-                let keys: ModelKeysClause = req.keys.into();
-
-                let base = poseidon_hash_many(&[
-                    short_string!("dojo_storage"),
-                    req.model.selector,
-                    poseidon_hash_many(&keys.keys),
-                ]);
-
-                let res = (0..req.model.packed_size)
-                    .into_par_iter()
-                    .map(|i| base + Felt::from_usize(i).expect("failed to convert usize to Felt"))
-                    .collect::<Vec<Felt>>();
-
-                Ok(res)
-            })
-            .collect::<Result<Vec<_>, Error>>()?
-            .into_iter()
-            .flatten()
-            .collect::<HashSet<Felt>>();
+        let mut storage_addresses: HashMap<Felt, HashSet<Felt>> = HashMap::new();
+        for req in reqs {
+            let keys: ModelKeysClause = req.keys.into();
+
+            let base = poseidon_hash_many(&[
+                short_string!("dojo_storage"),
+                req.model.selector,
+                poseidon_hash_many(&keys.keys),
+            ]);
+
+            let res = (0..req.model.packed_size)
+                .into_par_iter()
+                .map(|i| base + Felt::from_usize(i).expect("failed to convert usize to Felt"))
+                .collect::<Vec<Felt>>();
+
+            storage_addresses.entry(req.model.world_address).or_default().extend(res);
+        }
 
         // NOTE: unlock issue with firefox/safari
         // initially send empty stream message to return from
@@ -110,7 +107,6 @@ type RequestStateUpdateResult = Result<MaybePendingStateUpdate, SubscriptionErro
 #[must_use = "Service does nothing unless polled"]
 #[allow(missing_debug_implementations)]
 pub struct Service<P: Provider> {
-    world_address: Felt,
     idle_provider: Option<P>,
     block_num_rcv: Receiver<u64>,
     state_update_queue: VecDeque<u64>,
@@ -125,13 +121,11 @@ where
 {
     pub fn new_with_block_rcv(
         block_num_rcv: Receiver<u64>,
-        world_address: Felt,
         provider: P,
         subs_manager: Arc<StateDiffManager>,
     ) -> Self {
         Self {
             subs_manager,
-            world_address,
             block_num_rcv,
             publish_fut: None,
             state_update_req_fut: None,
@@ -150,38 +144,49 @@ where
 
     async fn publish_updates(
         subs: Arc<StateDiffManager>,
-        contract_address: Felt,
         state_update: StateUpdate,
     ) -> PublishStateUpdateResult {
         let mut closed_stream = Vec::new();
 
-        let Some(ContractStorageDiffItem { storage_entries: diff_entries, .. }) =
-            state_update.state_diff.storage_diffs.iter().find(|d| d.address == contract_address)
-        else {
-            return Ok(());
-        };
-
         for (idx, sub) in subs.subscribers.read().await.iter() {
-            let relevant_storage_entries = diff_entries
+            // the storage diffs of the worlds of the subscribed models
+            let storage_diffs = sub
+                .storage_addresses
                 .iter()
-                .filter(|entry| sub.storage_addresses.contains(&entry.key))
-                .map(|entry| {
-                    let StorageEntry { key, value } = entry;
-                    proto::types::StorageEntry {
-                        key: format!("{key:#x}"),
-                        value: format!("{value:#x}"),
-                    }
+                .filter_map(|(world_address, storage_addresses)| {
+                    let ContractStorageDiffItem { storage_entries: diff_entries, .. } =
+                        state_update
+                            .state_diff
+                            .storage_diffs
+                            .iter()
+                            .find(|d| d.address == *world_address)?;
+
+                    let relevant_storage_entries = diff_entries
+                        .iter()
+                        .filter(|entry| storage_addresses.contains(&entry.key))
+                        .map(|entry| {
+                            let StorageEntry { key, value } = entry;
+                            proto::types::StorageEntry {
+                                key: format!("{key:#x}"),
+                                value: format!("{value:#x}"),
+                            }
+                        })
+                        .collect::<Vec<proto::types::StorageEntry>>();
+
+                    Some(proto::types::StorageDiff {
+                        address: format!("{world_address:#x}"),
+                        storage_entries: relevant_storage_entries,
+                    })
                 })
-                .collect::<Vec<proto::types::StorageEntry>>();
+                .collect::<Vec<_>>();
+
+            if storage_diffs.is_empty() {
+                continue;
+            }
 
             let model_update = proto::types::ModelUpdate {
                 block_hash: format!("{:#x}", state_update.block_hash),
-                model_diff: Some(proto::types::ModelDiff {
-                    storage_diffs: vec![proto::types::StorageDiff {
-                        address: format!("{contract_address:#x}"),
-                        storage_entries: relevant_storage_entries,
-                    }],
-                }),
+                model_diff: Some(proto::types::ModelDiff { storage_diffs }),
             };
 
             let resp = proto::world::SubscribeModelsResponse { model_update: Some(model_update) };
@@ -237,7 +242,6 @@ where
                     Ok(MaybePendingStateUpdate::Update(state_update)) => {
                         pin.publish_fut = Some(Box::pin(Self::publish_updates(
                             Arc::clone(&pin.subs_manager),
-                            pin.world_address,
                             state_update,
                         )));
                     }
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::{stream, Stream, StreamExt};
+use rand::Rng;
+use sqlx::{Pool, Sqlite};
+use starknet::core::types::Felt;
+use tokio::sync::mpsc::{
+    channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender,
+};
+use tokio::sync::RwLock;
+use torii_core::engine::get_transaction_hash_from_event_id;
+use torii_core::error::Error;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::{ErcBalance, ErcTransfer};
+use tracing::{error, trace};
+
+use super::world_resources::felt_bytes;
+use crate::proto;
+use crate::proto::world::{SubscribeTokenBalancesResponse, SubscribeTokenTransfersResponse};
+use crate::server::{contract_type_to_proto, token_id_from_sql_string};
+
+pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::token";
+
+#[derive(Debug)]
+pub struct TokenTransfersSubscriber {
+    /// The accounts sending or receiving the transfers, all of them if empty.
+    account_addresses: Vec<Felt>,
+    /// The contracts of the transferred tokens, all of them if empty.
+    contract_addresses: Vec<Felt>,
+    /// The channel to send the response back to the subscriber.
+    sender: Sender<Result<proto::world::SubscribeTokenTransfersResponse, tonic::Status>>,
+}
+
+#[derive(Debug)]
+pub struct TokenBalancesSubscriber {
+    /// The accounts whose balances the subscriber is interested in, all of them if empty.
+    account_addresses: Vec<Felt>,
+    /// The contracts of the tokens, all of them if empty.
+    contract_addresses: Vec<Felt>,
+    /// The channel to send the response back to the subscriber.
+    sender: Sender<Result<proto::world::SubscribeTokenBalancesResponse, tonic::Status>>,
+}
+
+#[derive(Debug, Default)]
+pub struct TokenManager {
+    transfer_subscribers: RwLock<HashMap<usize, TokenTransfersSubscriber>>,
+    balance_subscribers: RwLock<HashMap<usize, TokenBalancesSubscriber>>,
+}
+
+impl TokenManager {
+    pub async fn add_transfers_subscriber(
+        &self,
+        account_addresses: Vec<Felt>,
+        contract_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTokenTransfersResponse, tonic::Status>> {
+        let id = rand::thread_rng().gen::<usize>();
+        let (sender, receiver) = channel(1);
+
+        self.transfer_subscribers
+            .write()
+            .await
+            .insert(id, TokenTransfersSubscriber { account_addresses, contract_addresses, sender });
+
+        receiver
+    }
+
+    pub async fn add_balances_subscriber(
+        &self,
+        account_addresses: Vec<Felt>,
+        contract_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTokenBalancesResponse, tonic::Status>> {
+        let id = rand::thread_rng().gen::<usize>();
+        let (sender, receiver) = channel(1);
+
+        self.balance_subscribers
+            .write()
+            .await
+            .insert(id, TokenBalancesSubscriber { account_addresses, contract_addresses, sender });
+
+        receiver
+    }
+
+    pub(super) async fn remove_transfers_subscriber(&self, id: usize) {
+        self.transfer_subscribers.write().await.remove(&id);
+    }
+
+    pub(super) async fn remove_balances_subscriber(&self, id: usize) {
+        self.balance_subscribers.write().await.remove(&id);
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TokenUpdate {
+    Transfer(ErcTransfer),
+    Balance(ErcBalance),
+}
+
+#[must_use = "Service does nothing unless polled"]
+#[allow(missing_debug_implementations)]
+pub struct Service {
+    simple_broker: Pin<Box<dyn Stream<Item = TokenUpdate> + Send>>,
+    update_sender: UnboundedSender<TokenUpdate>,
+}
+
+impl Service {
+    pub fn new(subs_manager: Arc<TokenManager>, pool: Pool<Sqlite>) -> Self {
+        let (update_sender, update_receiver) = unbounded_channel();
+
+        let simple_broker = stream::select_all([
+            SimpleBroker::<ErcTransfer>::subscribe().map(TokenUpdate::Transfer).boxed(),
+            SimpleBroker::<ErcBalance>::subscribe().map(TokenUpdate::Balance).boxed(),
+        ]);
+
+        let service = Self { simple_broker: Box::pin(simple_broker), update_sender };
+
+        tokio::spawn(Self::publish_updates(subs_manager, pool, update_receiver));
+
+        service
+    }
+
+    async fn publish_updates(
+        subs: Arc<TokenManager>,
+        pool: Pool<Sqlite>,
+        mut update_receiver: UnboundedReceiver<TokenUpdate>,
+    ) {
+        while let Some(update) = update_receiver.recv().await {
+            let result = match update {
+                TokenUpdate::Transfer(transfer) => {
+                    Self::process_transfer(&subs, &pool, transfer).await
+                }
+                TokenUpdate::Balance(balance) => Self::process_balance(&subs, &pool, balance).await,
+            };
+            if let Err(e) = result {
+                error!(target = LOG_TARGET, error = %e, "Processing token update.");
+            }
+        }
+    }
+
+    pub(crate) async fn process_transfer(
+        subs: &Arc<TokenManager>,
+        pool: &Pool<Sqlite>,
+        transfer: ErcTransfer,
+    ) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        let transfer = proto::types::TokenTransfer {
+            contract_address: felt_bytes(&transfer.contract_address)?,
+            from_address: felt_bytes(&transfer.from_address)?,
+            to_address: felt_bytes(&transfer.to_address)?,
+            amount: transfer.amount,
+            token_id: token_id_from_sql_string(&transfer.token_id),
+            contract_type: contract_type(pool, &transfer.contract_address).await?,
+            transaction_hash: felt_bytes(&get_transaction_hash_from_event_id(&transfer.id))?,
+            executed_at: transfer.executed_at.timestamp() as u64,
+        };
+        let contract_address = Felt::from_bytes_be_slice(&transfer.contract_address);
+        let from_address = Felt::from_bytes_be_slice(&transfer.from_address);
+        let to_address = Felt::from_bytes_be_slice(&transfer.to_address);
+
+        for (idx, sub) in subs.transfer_subscribers.read().await.iter() {
+            if !sub.account_addresses.is_empty()
+                && !sub.account_addresses.contains(&from_address)
+                && !sub.account_addresses.contains(&to_address)
+            {
+                continue;
+            }
+            if !sub.contract_addresses.is_empty()
+                && !sub.contract_addresses.contains(&contract_address)
+            {
+                continue;
+            }
+
+            let resp = SubscribeTokenTransfersResponse { transfer: Some(transfer.clone()) };
+
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing token transfers stream.");
+            subs.remove_transfers_subscriber(id).await
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn process_balance(
+        subs: &Arc<TokenManager>,
+        pool: &Pool<Sqlite>,
+        balance: ErcBalance,
+    ) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        let balance = proto::types::TokenBalance {
+            account_address: felt_bytes(&balance.account_address)?,
+            contract_address: felt_bytes(&balance.contract_address)?,
+            token_id: token_id_from_sql_string(&balance.token_id),
+            balance: balance.balance,
+            contract_type: contract_type(pool, &balance.contract_address).await?,
+        };
+        let account_address = Felt::from_bytes_be_slice(&balance.account_address);
+        let contract_address = Felt::from_bytes_be_slice(&balance.contract_address);
+
+        for (idx, sub) in subs.balance_subscribers.read().await.iter() {
+            if (!sub.account_addresses.is_empty()
+                && !sub.account_addresses.contains(&account_address))
+                || (!sub.contract_addresses.is_empty()
+                    && !sub.contract_addresses.contains(&contract_address))
+            {
+                continue;
+            }
+
+            let resp = SubscribeTokenBalancesResponse { balance: Some(balance.clone()) };
+
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing token balances stream.");
+            subs.remove_balances_subscriber(id).await
+        }
+
+        Ok(())
+    }
+}
+
+impl Future for Service {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        while let Poll::Ready(Some(update)) = this.simple_broker.poll_next_unpin(cx) {
+            if let Err(e) = this.update_sender.send(update) {
+                error!(target = LOG_TARGET, error = %e, "Sending token update to processor.");
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+async fn contract_type(pool: &Pool<Sqlite>, contract_address: &str) -> Result<i32, Error> {
+    let (contract_type,): (String,) =
+        sqlx::query_as("SELECT contract_type FROM contracts WHERE contract_address = ?")
+            .bind(contract_address)
+            .fetch_one(pool)
+            .await?;
+
+    contract_type_to_proto(&contract_type)
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::{Stream, StreamExt};
+use rand::Rng;
+use starknet::core::types::Felt;
+use tokio::sync::mpsc::{
+    channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender,
+};
+use tokio::sync::RwLock;
+use torii_core::error::Error;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::types::Transaction;
+use tracing::{error, trace};
+
+use super::world_resources::{felt_bytes, felts_bytes};
+use crate::proto;
+use crate::proto::world::SubscribeTransactionsResponse;
+
+pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::transaction";
+
+#[derive(Debug)]
+pub struct TransactionSubscriber {
+    /// The senders of the transactions the subscriber is interested in, all of them if empty.
+    sender_addresses: Vec<Felt>,
+    /// The channel to send the response back to the subscriber.
+    sender: Sender<Result<proto::world::SubscribeTransactionsResponse, tonic::Status>>,
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionManager {
+    subscribers: RwLock<HashMap<usize, TransactionSubscriber>>,
+}
+
+impl TransactionManager {
+    pub async fn add_subscriber(
+        &self,
+        sender_addresses: Vec<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeTransactionsResponse, tonic::Status>> {
+        let id = rand::thread_rng().gen::<usize>();
+        let (sender, receiver) = channel(1);
+
+        self.subscribers
+            .write()
+            .await
+            .insert(id, TransactionSubscriber { sender_addresses, sender });
+
+        receiver
+    }
+
+    pub(super) async fn remove_subscriber(&self, id: usize) {
+        self.subscribers.write().await.remove(&id);
+    }
+}
+
+#[must_use = "Service does nothing unless polled"]
+#[allow(missing_debug_implementations)]
+pub struct Service {
+    simple_broker: Pin<Box<dyn Stream<Item = Transaction> + Send>>,
+    transaction_sender: UnboundedSender<Transaction>,
+}
+
+impl Service {
+    pub fn new(subs_manager: Arc<TransactionManager>) -> Self {
+        let (transaction_sender, transaction_receiver) = unbounded_channel();
+        let service = Self {
+            simple_broker: Box::pin(SimpleBroker::<Transaction>::subscribe()),
+            transaction_sender,
+        };
+
+        tokio::spawn(Self::publish_updates(subs_manager, transaction_receiver));
+
+        service
+    }
+
+    async fn publish_updates(
+        subs: Arc<TransactionManager>,
+        mut transaction_receiver: UnboundedReceiver<Transaction>,
+    ) {
+        while let Some(transaction) = transaction_receiver.recv().await {
+            if let Err(e) = Self::process_transaction(&subs, transaction).await {
+                error!(target = LOG_TARGET, error = %e, "Processing transaction.");
+            }
+        }
+    }
+
+    pub(crate) async fn process_transaction(
+        subs: &Arc<TransactionManager>,
+        transaction: Transaction,
+    ) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        let transaction = proto::types::Transaction {
+            transaction_hash: felt_bytes(&transaction.transaction_hash)?,
+            sender_address: felt_bytes(&transaction.sender_address)?,
+            calldata: felts_bytes(&transaction.calldata)?,
+            max_fee: felt_bytes(&transaction.max_fee)?,
+            signature: felts_bytes(&transaction.signature)?,
+            nonce: felt_bytes(&transaction.nonce)?,
+            transaction_type: transaction.transaction_type,
+            executed_at: transaction.executed_at.timestamp() as u64,
+        };
+        let sender_address = Felt::from_bytes_be_slice(&transaction.sender_address);
+
+        for (idx, sub) in subs.subscribers.read().await.iter() {
+            if !sub.sender_addresses.is_empty() && !sub.sender_addresses.contains(&sender_address) {
+                continue;
+            }
+
+            let resp = SubscribeTransactionsResponse { transaction: Some(transaction.clone()) };
+
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing transactions stream.");
+            subs.remove_subscriber(id).await
+        }
+
+        Ok(())
+    }
+}
+
+impl Future for Service {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        while let Poll::Ready(Some(transaction)) = this.simple_broker.poll_next_unpin(cx) {
+            if let Err(e) = this.transaction_sender.send(transaction) {
+                error!(target = LOG_TARGET, error = %e, "Sending transaction to processor.");
+            }
+        }
+
+        Poll::Pending
+    }
+}
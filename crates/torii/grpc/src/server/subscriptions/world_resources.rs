@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::{stream, Stream, StreamExt};
+use rand::Rng;
+use starknet::core::types::Felt;
+use tokio::sync::mpsc::{
+    channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender,
+};
+use tokio::sync::RwLock;
+use torii_core::error::{Error, ParseError, QueryError};
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::sql::FELT_DELIMITER;
+use torii_core::types::{Namespace, Permission, WorldContract};
+use tracing::{error, trace};
+
+use crate::proto;
+use crate::proto::world::subscribe_world_resources_response::Update;
+use crate::proto::world::SubscribeWorldResourcesResponse;
+
+pub(crate) const LOG_TARGET: &str = "torii::grpc::server::subscriptions::world_resources";
+
+#[derive(Debug)]
+pub struct WorldResourcesSubscriber {
+    /// The resource whose permissions the subscriber is interested in, or zero for all.
+    resource: Felt,
+    /// The contract whose permissions the subscriber is interested in, or zero for all.
+    contract_address: Felt,
+    /// The world whose resources the subscriber is interested in, or all of them if unset.
+    world_address: Option<Felt>,
+    /// The channel to send the response back to the subscriber.
+    sender: Sender<Result<proto::world::SubscribeWorldResourcesResponse, tonic::Status>>,
+}
+
+#[derive(Debug, Default)]
+pub struct WorldResourcesManager {
+    subscribers: RwLock<HashMap<usize, WorldResourcesSubscriber>>,
+}
+
+impl WorldResourcesManager {
+    pub async fn add_subscriber(
+        &self,
+        resource: Felt,
+        contract_address: Felt,
+        world_address: Option<Felt>,
+    ) -> Receiver<Result<proto::world::SubscribeWorldResourcesResponse, tonic::Status>> {
+        let id = rand::thread_rng().gen::<usize>();
+        let (sender, receiver) = channel(1);
+
+        self.subscribers.write().await.insert(
+            id,
+            WorldResourcesSubscriber { resource, contract_address, world_address, sender },
+        );
+
+        receiver
+    }
+
+    pub(super) async fn remove_subscriber(&self, id: usize) {
+        self.subscribers.write().await.remove(&id);
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorldResourceUpdate {
+    Namespace(Namespace),
+    Contract(WorldContract),
+    Permission(Permission),
+}
+
+#[must_use = "Service does nothing unless polled"]
+#[allow(missing_debug_implementations)]
+pub struct Service {
+    simple_broker: Pin<Box<dyn Stream<Item = WorldResourceUpdate> + Send>>,
+    update_sender: UnboundedSender<WorldResourceUpdate>,
+}
+
+impl Service {
+    pub fn new(subs_manager: Arc<WorldResourcesManager>) -> Self {
+        let (update_sender, update_receiver) = unbounded_channel();
+
+        let simple_broker = stream::select_all([
+            SimpleBroker::<Namespace>::subscribe().map(WorldResourceUpdate::Namespace).boxed(),
+            SimpleBroker::<WorldContract>::subscribe().map(WorldResourceUpdate::Contract).boxed(),
+            SimpleBroker::<Permission>::subscribe().map(WorldResourceUpdate::Permission).boxed(),
+        ]);
+
+        let service = Self { simple_broker: Box::pin(simple_broker), update_sender };
+
+        tokio::spawn(Self::publish_updates(subs_manager, update_receiver));
+
+        service
+    }
+
+    async fn publish_updates(
+        subs: Arc<WorldResourcesManager>,
+        mut update_receiver: UnboundedReceiver<WorldResourceUpdate>,
+    ) {
+        while let Some(update) = update_receiver.recv().await {
+            if let Err(e) = Self::process_update(&subs, update).await {
+                error!(target = LOG_TARGET, error = %e, "Processing world resource update.");
+            }
+        }
+    }
+
+    async fn process_update(
+        subs: &Arc<WorldResourcesManager>,
+        update: WorldResourceUpdate,
+    ) -> Result<(), Error> {
+        let mut closed_stream = Vec::new();
+
+        // the permission filters only apply to permission updates
+        let (update, world_address, filter) = match update {
+            WorldResourceUpdate::Namespace(namespace) => {
+                let namespace = namespace_to_proto(namespace)?;
+                let world_address = Felt::from_bytes_be_slice(&namespace.world_address);
+                (Update::Namespace(namespace), world_address, None)
+            }
+            WorldResourceUpdate::Contract(contract) => {
+                let contract = contract_to_proto(contract)?;
+                let world_address = Felt::from_bytes_be_slice(&contract.world_address);
+                (Update::Contract(contract), world_address, None)
+            }
+            WorldResourceUpdate::Permission(permission) => {
+                let permission = permission_to_proto(permission)?;
+                let world_address = Felt::from_bytes_be_slice(&permission.world_address);
+                let filter = (
+                    Felt::from_bytes_be_slice(&permission.resource),
+                    Felt::from_bytes_be_slice(&permission.contract_address),
+                );
+                (Update::Permission(permission), world_address, Some(filter))
+            }
+        };
+
+        for (idx, sub) in subs.subscribers.read().await.iter() {
+            if sub.world_address.is_some_and(|world| world != world_address) {
+                continue;
+            }
+
+            if let Some((resource, contract_address)) = filter {
+                if (sub.resource != Felt::ZERO && sub.resource != resource)
+                    || (sub.contract_address != Felt::ZERO
+                        && sub.contract_address != contract_address)
+                {
+                    continue;
+                }
+            }
+
+            let resp = SubscribeWorldResourcesResponse { update: Some(update.clone()) };
+
+            if sub.sender.send(Ok(resp)).await.is_err() {
+                closed_stream.push(*idx);
+            }
+        }
+
+        for id in closed_stream {
+            trace!(target = LOG_TARGET, id = %id, "Closing world resources updates stream.");
+            subs.remove_subscriber(id).await
+        }
+
+        Ok(())
+    }
+}
+
+impl Future for Service {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        while let Poll::Ready(Some(update)) = this.simple_broker.poll_next_unpin(cx) {
+            if let Err(e) = this.update_sender.send(update) {
+                error!(
+                    target = LOG_TARGET,
+                    error = %e,
+                    "Sending world resource update to processor."
+                );
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+pub(crate) fn felt_bytes(felt: &str) -> Result<Vec<u8>, Error> {
+    Ok(Felt::from_str(felt).map_err(ParseError::FromStr)?.to_bytes_be().to_vec())
+}
+
+pub(crate) fn felts_bytes(felts: &str) -> Result<Vec<Vec<u8>>, Error> {
+    felts.split(FELT_DELIMITER).filter(|felt| !felt.is_empty()).map(felt_bytes).collect()
+}
+
+/// The world of the resources indexed before they were scoped by world may be unknown, its bytes
+/// being left empty.
+fn world_bytes(world_address: &str) -> Result<Vec<u8>, Error> {
+    if world_address.is_empty() {
+        Ok(Vec::new())
+    } else {
+        felt_bytes(world_address)
+    }
+}
+
+pub(crate) fn namespace_to_proto(namespace: Namespace) -> Result<proto::types::Namespace, Error> {
+    Ok(proto::types::Namespace {
+        hash: felt_bytes(&namespace.id)?,
+        namespace: namespace.namespace,
+        world_address: world_bytes(&namespace.world_address)?,
+    })
+}
+
+pub(crate) fn contract_to_proto(
+    contract: WorldContract,
+) -> Result<proto::types::WorldContract, Error> {
+    Ok(proto::types::WorldContract {
+        selector: felt_bytes(&contract.id)?,
+        namespace: contract.namespace,
+        name: contract.name,
+        contract_address: felt_bytes(&contract.contract_address)?,
+        class_hash: felt_bytes(&contract.class_hash)?,
+        class_hashes: felts_bytes(&contract.class_hashes)?,
+        salt: felt_bytes(&contract.salt)?,
+        initialized: contract.initialized,
+        init_calldata: felts_bytes(&contract.init_calldata)?,
+        world_address: world_bytes(&contract.world_address)?,
+    })
+}
+
+pub(crate) fn permission_to_proto(
+    permission: Permission,
+) -> Result<proto::types::Permission, Error> {
+    let kind = proto::types::PermissionKind::from_str_name(&permission.permission)
+        .ok_or_else(|| QueryError::UnsupportedValue(permission.permission.clone()))?;
+
+    Ok(proto::types::Permission {
+        resource: felt_bytes(&permission.resource)?,
+        contract_address: felt_bytes(&permission.contract_address)?,
+        permission: kind as i32,
+        granted: permission.granted,
+        world_address: world_bytes(&permission.world_address)?,
+    })
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -15,20 +15,25 @@ use scarb::ops;
 use sozo_scarbext::WorkspaceExt;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use starknet::accounts::Account;
-use starknet::core::types::Call;
+use starknet::core::types::{BlockId, Call, MaybePendingBlockWithTxHashes};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider};
-use starknet_crypto::poseidon_hash_many;
+use starknet_crypto::{poseidon_hash_many, Felt};
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
 use torii_core::engine::{Engine, EngineConfig, Processors};
+use torii_core::error::{Error, QueryError};
 use torii_core::executor::Executor;
+use torii_core::sql::history::{EntityHistoryConfig, HistoryBounds};
 use torii_core::sql::Sql;
 use torii_core::types::ContractType;
 
-use crate::proto::types::KeysClause;
-use crate::server::DojoWorld;
+use crate::proto::types::clause::ClauseType;
+use crate::proto::types::{
+    Clause, HashedKeysClause, KeysClause, MemberClause, OrderBy, OrderDirection,
+};
+use crate::server::{build_cursor_condition, build_order_columns, encode_cursor, DojoWorld};
 use crate::types::schema::Entity;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -92,13 +97,21 @@ async fn test_entities_queries(sequencer: &RunnerCtx) {
     tokio::spawn(async move {
         executor.run().await.unwrap();
     });
-    let db = Sql::new(pool.clone(), sender, &HashMap::from([(world_address, ContractType::WORLD)]))
-        .await
-        .unwrap();
+    let mut db =
+        Sql::new(pool.clone(), sender, &HashMap::from([(world_address, ContractType::WORLD)]))
+            .await
+            .unwrap();
+    db.set_entity_history(EntityHistoryConfig {
+        models: HashSet::from([
+            compute_selector_from_names("ns", "Moves"),
+            compute_selector_from_names("ns", "Position"),
+        ]),
+        retention: None,
+    });
 
     let (shutdown_tx, _) = broadcast::channel(1);
     let mut engine = Engine::new(
-        world_reader,
+        vec![world_reader],
         db.clone(),
         Arc::clone(&provider),
         Processors { ..Processors::default() },
@@ -141,4 +154,244 @@ async fn test_entities_queries(sequencer: &RunnerCtx) {
     assert_eq!(entity.models.first().unwrap().name, "ns-Moves");
     assert_eq!(entity.models.get(1).unwrap().name, "ns-Position");
     assert_eq!(entity.hashed_keys, poseidon_hash_many(&[account.address()]));
+    assert_eq!(entity.world_address, world_address);
+
+    // a full page returns a cursor, the page following it is empty
+    let order_by = vec![OrderBy {
+        model: "ns-Moves".to_string(),
+        member: "remaining".to_string(),
+        direction: OrderDirection::Desc as i32,
+    }];
+    let (entities, total_count, cursor) = grpc
+        .query_ordered(
+            "entities",
+            "entity_model",
+            "entity_id",
+            None,
+            &order_by,
+            None,
+            Some(1),
+            None,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(entities.len(), 1);
+    assert_eq!(total_count, 1);
+    assert!(cursor.is_some());
+
+    let (entities, _, cursor) = grpc
+        .query_ordered(
+            "entities",
+            "entity_model",
+            "entity_id",
+            None,
+            &order_by,
+            cursor.as_deref(),
+            Some(1),
+            None,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(entities.is_empty());
+    assert!(cursor.is_none());
+
+    // entities are selected by their hashed keys, stored as hex
+    let hashed_keys = poseidon_hash_many(&[account.address()]);
+    let (entities, total_count, _) = grpc
+        .query_ordered(
+            "entities",
+            "entity_model",
+            "entity_id",
+            Some(Clause {
+                clause_type: Some(ClauseType::HashedKeys(HashedKeysClause {
+                    hashed_keys: vec![hashed_keys.to_bytes_be().to_vec()],
+                })),
+            }),
+            &order_by,
+            None,
+            None,
+            None,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(entities.len(), 1);
+    assert_eq!(total_count, 1);
+    let entity: Entity = entities.first().unwrap().clone().try_into().unwrap();
+    assert_eq!(entity.hashed_keys, hashed_keys);
+
+    // the entities are the ones of the indexed world
+    for (world, count) in [(world_address, 1), (Felt::ONE, 0)] {
+        let (entities, total_count, _) = grpc
+            .query_ordered(
+                "entities",
+                "entity_model",
+                "entity_id",
+                None,
+                &[],
+                None,
+                None,
+                None,
+                false,
+                Some(world),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(entities.len(), count);
+        assert_eq!(total_count, count as u32);
+    }
+
+    // the entity is spawned in the last block, along with its models
+    let (entities, total_count) = grpc
+        .query_historical(
+            None,
+            HistoryBounds { at_block: Some(to), ..Default::default() },
+            10,
+            0,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(total_count, 1);
+    let entity: Entity = entities.first().unwrap().clone().try_into().unwrap();
+    assert_eq!(entity.hashed_keys, hashed_keys);
+    assert_eq!(entity.models.len(), 2);
+
+    let (entities, total_count) = grpc
+        .query_historical(
+            None,
+            HistoryBounds { at_block: Some(to - 1), ..Default::default() },
+            10,
+            0,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(entities.is_empty());
+    assert_eq!(total_count, 0);
+
+    let block = provider.get_block_with_tx_hashes(BlockId::Number(to)).await.unwrap();
+    let timestamp = match block {
+        MaybePendingBlockWithTxHashes::Block(block) => block.timestamp,
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => block.timestamp,
+    };
+    for (from_timestamp, count) in [(timestamp, 1), (timestamp + 1, 0)] {
+        let bounds = HistoryBounds {
+            at_block: None,
+            from_timestamp: Some(from_timestamp),
+            to_timestamp: Some(timestamp + 1),
+        };
+        let (entities, total_count) =
+            grpc.query_historical(None, bounds, 10, 0, false, None).await.unwrap();
+        assert_eq!(entities.len(), count);
+        assert_eq!(total_count, count as u32);
+    }
+
+    // the members of the versions aren't stored in columns
+    let member = Clause { clause_type: Some(ClauseType::Member(MemberClause::default())) };
+    let result = grpc
+        .query_historical(
+            Some(member),
+            HistoryBounds { at_block: Some(to), ..Default::default() },
+            10,
+            0,
+            false,
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::QueryError(QueryError::UnsupportedQuery))));
+}
+
+#[tokio::test]
+async fn test_cursor_with_null_values() {
+    let pool = SqlitePoolOptions::new().connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE items (id TEXT NOT NULL PRIMARY KEY, value INTEGER)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (id, value) in [("0x1", Some(2)), ("0x2", None), ("0x3", Some(1)), ("0x4", None)] {
+        sqlx::query("INSERT INTO items (id, value) VALUES (?, ?)")
+            .bind(id)
+            .bind(value)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    for (direction, expected) in [
+        (OrderDirection::Asc, ["0x4", "0x2", "0x3", "0x1"]),
+        (OrderDirection::Desc, ["0x1", "0x3", "0x4", "0x2"]),
+    ] {
+        let columns = vec![
+            ("items.value".to_string(), direction),
+            ("items.id".to_string(), OrderDirection::Desc),
+        ];
+        let ordering = format!("items.value {}, items.id DESC", direction.as_str_name());
+
+        // pages of a single item go through every item exactly once
+        let mut cursor = None;
+        let mut ids = Vec::new();
+        loop {
+            let (condition, values) = match &cursor {
+                Some(cursor) => build_cursor_condition(&columns, cursor).unwrap(),
+                None => ("TRUE".to_string(), Vec::new()),
+            };
+            let query = format!(
+                "SELECT id, CAST(value AS TEXT) FROM items WHERE {condition} ORDER BY {ordering} \
+                 LIMIT 1"
+            );
+            let mut query = sqlx::query_as::<_, (String, Option<String>)>(&query);
+            for value in values {
+                query = query.bind(value);
+            }
+            let Some((id, value)) = query.fetch_optional(&pool).await.unwrap() else {
+                break;
+            };
+            cursor = Some(encode_cursor(&[value, Some(id.clone())]).unwrap());
+            ids.push(id);
+        }
+
+        assert_eq!(ids, expected);
+    }
+}
+
+#[test]
+fn test_default_order_columns() {
+    // the most recently updated entities come first, the id and world only break ties
+    let (join_clause, columns) = build_order_columns("entities", "entity_id", &[]).unwrap();
+    assert!(join_clause.is_empty());
+    assert_eq!(
+        columns,
+        vec![
+            ("[entities].event_id".to_string(), OrderDirection::Desc),
+            ("[entities].id".to_string(), OrderDirection::Desc),
+            ("[entities].world_address".to_string(), OrderDirection::Desc),
+        ]
+    );
+
+    let order_by = vec![OrderBy {
+        model: "ns-Moves".to_string(),
+        member: "remaining".to_string(),
+        direction: OrderDirection::Asc as i32,
+    }];
+    let (_, columns) = build_order_columns("entities", "entity_id", &order_by).unwrap();
+    assert_eq!(
+        columns,
+        vec![
+            ("[order_by_0].external_remaining".to_string(), OrderDirection::Asc),
+            ("[entities].id".to_string(), OrderDirection::Desc),
+            ("[entities].world_address".to_string(), OrderDirection::Desc),
+        ]
+    );
 }
@@ -0,0 +1,67 @@
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Member, Struct, Ty};
+use starknet_crypto::Felt;
+
+use crate::server::subscriptions::entity_diff::{EntityDiffState, MAX_ENTITIES};
+
+fn member(name: &str, value: u32) -> Member {
+    Member { name: name.to_string(), ty: Ty::Primitive(Primitive::U32(Some(value))), key: false }
+}
+
+fn model(members: Vec<Member>) -> Struct {
+    Struct { name: "ns-Position".to_string(), children: members }
+}
+
+#[test]
+fn test_entity_diffs() {
+    let mut state = EntityDiffState::default();
+    let (world, entity) = (Felt::ONE, Felt::ONE);
+
+    // all the members are sent on the first update of a model
+    let diff = state.update(world, entity, &model(vec![member("x", 1), member("y", 2)])).unwrap();
+    assert_eq!((diff.sequence, diff.previous_sequence), (1, 0));
+    assert_eq!(diff.models[0].children.len(), 2);
+
+    // only the changed members afterwards
+    let diff = state.update(world, entity, &model(vec![member("x", 1), member("y", 3)])).unwrap();
+    assert_eq!((diff.sequence, diff.previous_sequence), (2, 1));
+    assert_eq!(diff.models[0].children, vec![member("y", 3).into()]);
+
+    // updates of a single member
+    assert!(state.update(world, entity, &model(vec![member("y", 3)])).is_none());
+    let other = state.update(world, Felt::TWO, &model(vec![member("x", 5)])).unwrap();
+    assert_eq!((other.sequence, other.previous_sequence), (3, 0));
+
+    let diff = state.update(world, entity, &model(vec![])).unwrap();
+    assert_eq!(diff.deleted_models, vec!["ns-Position".to_string()]);
+    assert_eq!((diff.sequence, diff.previous_sequence), (4, 2));
+
+    let diff = state.delete(world, entity);
+    assert!(diff.deleted);
+    assert_eq!((diff.sequence, diff.previous_sequence), (5, 4));
+
+    state.clear();
+    let diff = state.update(world, Felt::TWO, &model(vec![member("x", 5)])).unwrap();
+    assert_eq!((diff.sequence, diff.previous_sequence), (6, 0));
+}
+
+#[test]
+fn test_entity_diffs_bounded() {
+    let mut state = EntityDiffState::default();
+    let world = Felt::ONE;
+
+    for entity in 0..=MAX_ENTITIES as u64 {
+        state.update(world, Felt::from(entity), &model(vec![member("x", 1)])).unwrap();
+    }
+    assert_eq!(state.len(), MAX_ENTITIES);
+
+    // the least recently updated entity is forgotten, its next diff holding all its members
+    let diff = state.update(world, Felt::ZERO, &model(vec![member("x", 1)])).unwrap();
+    assert_eq!(diff.previous_sequence, 0);
+    assert_eq!(diff.models[0].children.len(), 1);
+    assert_eq!(state.len(), MAX_ENTITIES);
+
+    // the deleted entities aren't held anymore
+    state.delete(world, Felt::ZERO);
+    assert_eq!(state.len(), MAX_ENTITIES - 1);
+}
@@ -1 +1,4 @@
 mod entities_test;
+mod entity_diff_test;
+mod rest_test;
+mod subscriptions_test;
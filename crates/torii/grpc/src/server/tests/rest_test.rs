@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use dojo_types::naming::compute_selector_from_tag;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Member, Struct, Ty};
+use dojo_world::contracts::abigen::model::Layout;
+use http::{Method, StatusCode};
+use hyper::body::HttpBody;
+use hyper::{Body, Request};
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Sqlite};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::JsonRpcClient;
+use starknet_crypto::{poseidon_hash_many, Felt};
+use tempfile::NamedTempFile;
+use tokio::sync::broadcast;
+use torii_core::executor::Executor;
+use torii_core::simple_broker::SimpleBroker;
+use torii_core::sql::utils::felts_to_sql_string;
+use torii_core::sql::Sql;
+use torii_core::types::{ContractType, OptimisticEntity};
+use url::Url;
+
+use crate::server::rest::RestApi;
+use crate::server::DojoWorld;
+
+fn position(player: Felt, x: Option<u32>) -> Ty {
+    Ty::Struct(Struct {
+        name: "ns-Position".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                ty: Ty::Primitive(Primitive::ContractAddress(x.map(|_| player))),
+                key: true,
+            },
+            Member { name: "x".to_string(), ty: Ty::Primitive(Primitive::U32(x)), key: false },
+        ],
+    })
+}
+
+/// Returns the REST API of a world with a `ns-Position` model set for the players `0x1` and `0x2`.
+async fn setup(world_address: Felt) -> (RestApi, Pool<Sqlite>, NamedTempFile) {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options =
+        SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true).with_regexp();
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) = Executor::new(pool.clone(), shutdown_tx).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let contracts = HashMap::from([(world_address, ContractType::WORLD)]);
+    let mut db = Sql::new(pool.clone(), sender, &contracts).await.unwrap();
+
+    db.register_model(
+        world_address,
+        "ns",
+        position(Felt::ZERO, None),
+        Layout::Fixed(vec![]),
+        Felt::ONE,
+        Felt::ONE,
+        0,
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+
+    let model_id = compute_selector_from_tag("ns-Position");
+    for (player, x) in [(Felt::ONE, 10), (Felt::TWO, 20)] {
+        let keys = vec![player];
+        db.set_entity(
+            world_address,
+            position(player, Some(x)),
+            &format!("{:#064x}:{:#x}:{:#04x}", 1, Felt::ONE, x),
+            1000,
+            poseidon_hash_many(&keys),
+            model_id,
+            Some(&felts_to_sql_string(&keys)),
+        )
+        .await
+        .unwrap();
+    }
+    db.execute().await.unwrap();
+
+    let provider = JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost").unwrap()));
+    let (_, receiver) = tokio::sync::mpsc::channel(1);
+    let world = DojoWorld::new(pool.clone(), receiver, world_address, Arc::new(provider));
+
+    (RestApi::new(world), pool, tempfile)
+}
+
+async fn json_body(body: Body) -> Value {
+    serde_json::from_slice(&hyper::body::to_bytes(body).await.unwrap()).unwrap()
+}
+
+/// Reads the next server-sent event of the stream, and returns its data.
+async fn next_event(body: &mut Body) -> Value {
+    let chunk = body.data().await.expect("stream must not be closed").unwrap();
+    let event = std::str::from_utf8(&chunk).unwrap();
+    let data = event.strip_prefix("data: ").expect("must be a data event").trim_end();
+    serde_json::from_str(data).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rest_entities_query() {
+    let world_address = Felt::from(0x1234);
+    let (api, _pool, _tempfile) = setup(world_address).await;
+
+    // the `Query` and its `Clause` are encoded as the JSON of their serde representation
+    let query = json!({
+        "clause": {
+            "Keys": {
+                "keys": [format!("{:#x}", Felt::ONE)],
+                "pattern_matching": "FixedLen",
+                "models": ["ns-Position"],
+            }
+        },
+        "limit": 10,
+        "offset": 0,
+        "dont_include_hashed_keys": false,
+        "order_by": [],
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/entities")
+        .body(Body::from(query.to_string()))
+        .unwrap();
+
+    let response = api.handle("/entities", request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = json_body(response.into_body()).await;
+    assert_eq!(body["totalCount"], json!(1));
+    assert_eq!(body["nextCursor"], Value::Null);
+
+    let entities = body["entities"].as_array().unwrap();
+    assert_eq!(entities.len(), 1);
+    let hashed_keys = format!("{:#x}", poseidon_hash_many(&[Felt::ONE]));
+    assert_eq!(entities[0]["hashed_keys"], json!(hashed_keys));
+    assert_eq!(entities[0]["models"][0]["name"], json!("ns-Position"));
+
+    // a malformed clause is rejected with the serde error
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/entities")
+        .body(Body::from(json!({ "clause": { "Unknown": {} } }).to_string()))
+        .unwrap();
+
+    let response = api.handle("/entities", request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = json_body(response.into_body()).await;
+    assert!(body["error"].as_str().unwrap().starts_with("Invalid request body"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rest_entities_subscription() {
+    // a world of its own, so that the updates published by the other tests are filtered out
+    let world_address = Felt::from(0x5678);
+    let (api, _pool, _tempfile) = setup(world_address).await;
+
+    let clauses = json!([{
+        "Keys": {
+            "keys": [format!("{:#x}", Felt::TWO)],
+            "pattern_matching": "FixedLen",
+            "models": [],
+        }
+    }]);
+    let uri = format!(
+        "/subscriptions/entities?world_address={world_address:#x}&clauses={}",
+        url::form_urlencoded::byte_serialize(clauses.to_string().as_bytes()).collect::<String>()
+    );
+    let request = Request::builder().method(Method::GET).uri(uri).body(Body::empty()).unwrap();
+
+    let response = api.handle("/subscriptions/entities", request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    let mut body = response.into_body();
+
+    // the subscription is acknowledged with an empty update carrying its id
+    let event = next_event(&mut body).await;
+    let subscription_id = event["subscriptionId"].clone();
+    assert!(subscription_id.is_u64());
+    assert_eq!(event["entity"], Value::Null);
+
+    // only the updates of the entities matching the clauses are streamed
+    let entity = |player: Felt, x: u32| {
+        let keys = vec![player];
+        OptimisticEntity {
+            id: format!("{:#x}", poseidon_hash_many(&keys)),
+            world_address: format!("{world_address:#x}"),
+            keys: felts_to_sql_string(&keys),
+            event_id: String::new(),
+            executed_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            updated_model: Some(position(player, Some(x))),
+            deleted: false,
+        }
+    };
+    SimpleBroker::publish(entity(Felt::ONE, 11));
+    SimpleBroker::publish(entity(Felt::TWO, 21));
+
+    let event = next_event(&mut body).await;
+    assert_eq!(event["subscriptionId"], subscription_id);
+    assert_eq!(
+        event["entity"]["hashed_keys"],
+        json!(format!("{:#x}", poseidon_hash_many(&[Felt::TWO])))
+    );
+    assert_eq!(event["entity"]["models"][0]["name"], json!("ns-Position"));
+    assert_eq!(event["resync"], json!(false));
+}
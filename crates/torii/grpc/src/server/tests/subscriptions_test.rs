@@ -0,0 +1,228 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Sqlite};
+use starknet_crypto::Felt;
+use tempfile::NamedTempFile;
+use tokio::sync::mpsc::Receiver;
+use torii_core::types::{ErcBalance, ErcTransfer, Transaction};
+
+use crate::proto;
+use crate::proto::world::{
+    SubscribeTokenBalancesResponse, SubscribeTokenTransfersResponse, SubscribeTransactionsResponse,
+};
+use crate::server::subscriptions::token::{self, TokenManager};
+use crate::server::subscriptions::transaction::{self, TransactionManager};
+
+const ERC20: Felt = Felt::from_hex_unchecked("0xe20");
+const ERC721: Felt = Felt::from_hex_unchecked("0xe721");
+const ACCOUNT: Felt = Felt::from_hex_unchecked("0xbeef");
+const OTHER: Felt = Felt::from_hex_unchecked("0xcafe");
+
+/// Returns a database indexing the `ERC20` and `ERC721` contracts.
+async fn setup() -> (Pool<Sqlite>, NamedTempFile) {
+    let tempfile = NamedTempFile::new().unwrap();
+    let path = tempfile.path().to_string_lossy();
+    let options =
+        SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true).with_regexp();
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    for (contract, contract_type) in [(ERC20, "ERC20"), (ERC721, "ERC721")] {
+        sqlx::query("INSERT INTO contracts (id, contract_address, contract_type) VALUES (?, ?, ?)")
+            .bind(format!("{contract:#x}"))
+            .bind(format!("{contract:#x}"))
+            .bind(contract_type)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    (pool, tempfile)
+}
+
+/// Returns the `contract_address:token_id` of a token, the contract address alone for ERC20.
+fn token_id(contract: Felt) -> String {
+    if contract == ERC20 {
+        format!("{contract:#x}")
+    } else {
+        format!("{contract:#x}:0x1")
+    }
+}
+
+fn transfer(contract: Felt, from: Felt, to: Felt, transaction_hash: u64) -> ErcTransfer {
+    ErcTransfer {
+        id: format!("0x0:{transaction_hash:#x}:0x0"),
+        contract_address: format!("{contract:#x}"),
+        from_address: format!("{from:#x}"),
+        to_address: format!("{to:#x}"),
+        amount: format!("{:#064x}", 1),
+        token_id: token_id(contract),
+        executed_at: Utc::now(),
+    }
+}
+
+fn balance(account: Felt, contract: Felt) -> ErcBalance {
+    ErcBalance {
+        id: format!("{account:#x}/{}", token_id(contract)),
+        balance: format!("{:#064x}", 1),
+        account_address: format!("{account:#x}"),
+        contract_address: format!("{contract:#x}"),
+        token_id: token_id(contract),
+    }
+}
+
+/// Returns the transaction hash of the transfer sent to the subscriber, if any.
+fn received_transfer(
+    receiver: &mut Receiver<Result<SubscribeTokenTransfersResponse, tonic::Status>>,
+) -> Option<u64> {
+    let transfer = receiver.try_recv().ok()?.unwrap().transfer.unwrap();
+    Some(Felt::from_bytes_be_slice(&transfer.transaction_hash).try_into().unwrap())
+}
+
+/// Returns the account and contract of the balance sent to the subscriber, if any.
+fn received_balance(
+    receiver: &mut Receiver<Result<SubscribeTokenBalancesResponse, tonic::Status>>,
+) -> Option<(Felt, Felt)> {
+    let balance = receiver.try_recv().ok()?.unwrap().balance.unwrap();
+    Some((
+        Felt::from_bytes_be_slice(&balance.account_address),
+        Felt::from_bytes_be_slice(&balance.contract_address),
+    ))
+}
+
+/// Returns the hash of the transaction sent to the subscriber, if any.
+fn received_transaction(
+    receiver: &mut Receiver<Result<SubscribeTransactionsResponse, tonic::Status>>,
+) -> Option<Felt> {
+    let transaction = receiver.try_recv().ok()?.unwrap().transaction.unwrap();
+    Some(Felt::from_bytes_be_slice(&transaction.transaction_hash))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_token_transfers_subscription() {
+    let (pool, _tempfile) = setup().await;
+    let manager = Arc::new(TokenManager::default());
+
+    let mut all = manager.add_transfers_subscriber(vec![], vec![]).await;
+    let mut by_account = manager.add_transfers_subscriber(vec![ACCOUNT], vec![]).await;
+    let mut by_contract = manager.add_transfers_subscriber(vec![], vec![ERC721]).await;
+    let mut by_both = manager.add_transfers_subscriber(vec![ACCOUNT], vec![ERC721]).await;
+
+    // a transfer to the account
+    token::Service::process_transfer(&manager, &pool, transfer(ERC20, Felt::ZERO, ACCOUNT, 1))
+        .await
+        .unwrap();
+    assert_eq!(received_transfer(&mut all), Some(1));
+    assert_eq!(received_transfer(&mut by_account), Some(1));
+    assert_eq!(received_transfer(&mut by_contract), None);
+    assert_eq!(received_transfer(&mut by_both), None);
+
+    // a transfer from the account
+    token::Service::process_transfer(&manager, &pool, transfer(ERC721, ACCOUNT, OTHER, 2))
+        .await
+        .unwrap();
+    let response = all.try_recv().unwrap().unwrap();
+    let expected = proto::types::TokenTransfer {
+        contract_address: ERC721.to_bytes_be().to_vec(),
+        from_address: ACCOUNT.to_bytes_be().to_vec(),
+        to_address: OTHER.to_bytes_be().to_vec(),
+        amount: format!("{:#064x}", 1),
+        token_id: "0x1".to_string(),
+        contract_type: proto::types::ContractType::Erc721 as i32,
+        transaction_hash: Felt::TWO.to_bytes_be().to_vec(),
+        executed_at: response.transfer.as_ref().unwrap().executed_at,
+    };
+    assert_eq!(response.transfer, Some(expected));
+    assert_eq!(received_transfer(&mut by_account), Some(2));
+    assert_eq!(received_transfer(&mut by_contract), Some(2));
+    assert_eq!(received_transfer(&mut by_both), Some(2));
+
+    // a transfer between other accounts
+    token::Service::process_transfer(&manager, &pool, transfer(ERC721, OTHER, Felt::ONE, 3))
+        .await
+        .unwrap();
+    assert_eq!(received_transfer(&mut all), Some(3));
+    assert_eq!(received_transfer(&mut by_account), None);
+    assert_eq!(received_transfer(&mut by_contract), Some(3));
+    assert_eq!(received_transfer(&mut by_both), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_token_balances_subscription() {
+    let (pool, _tempfile) = setup().await;
+    let manager = Arc::new(TokenManager::default());
+
+    let mut all = manager.add_balances_subscriber(vec![], vec![]).await;
+    let mut by_account = manager.add_balances_subscriber(vec![ACCOUNT], vec![]).await;
+    let mut by_contract = manager.add_balances_subscriber(vec![], vec![ERC721]).await;
+    let mut by_both = manager.add_balances_subscriber(vec![ACCOUNT], vec![ERC721]).await;
+
+    token::Service::process_balance(&manager, &pool, balance(ACCOUNT, ERC20)).await.unwrap();
+    let response = all.try_recv().unwrap().unwrap();
+    let expected = proto::types::TokenBalance {
+        account_address: ACCOUNT.to_bytes_be().to_vec(),
+        contract_address: ERC20.to_bytes_be().to_vec(),
+        // ERC20 tokens have no id
+        token_id: String::new(),
+        balance: format!("{:#064x}", 1),
+        contract_type: proto::types::ContractType::Erc20 as i32,
+    };
+    assert_eq!(response.balance, Some(expected));
+    assert_eq!(received_balance(&mut by_account), Some((ACCOUNT, ERC20)));
+    assert_eq!(received_balance(&mut by_contract), None);
+    assert_eq!(received_balance(&mut by_both), None);
+
+    token::Service::process_balance(&manager, &pool, balance(ACCOUNT, ERC721)).await.unwrap();
+    assert_eq!(received_balance(&mut all), Some((ACCOUNT, ERC721)));
+    assert_eq!(received_balance(&mut by_account), Some((ACCOUNT, ERC721)));
+    assert_eq!(received_balance(&mut by_contract), Some((ACCOUNT, ERC721)));
+    assert_eq!(received_balance(&mut by_both), Some((ACCOUNT, ERC721)));
+
+    token::Service::process_balance(&manager, &pool, balance(OTHER, ERC721)).await.unwrap();
+    assert_eq!(received_balance(&mut all), Some((OTHER, ERC721)));
+    assert_eq!(received_balance(&mut by_account), None);
+    assert_eq!(received_balance(&mut by_contract), Some((OTHER, ERC721)));
+    assert_eq!(received_balance(&mut by_both), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transactions_subscription() {
+    let manager = Arc::new(TransactionManager::default());
+    let mut all = manager.add_subscriber(vec![]).await;
+    let mut by_sender = manager.add_subscriber(vec![ACCOUNT]).await;
+
+    let transaction = |sender: Felt, transaction_hash: Felt| Transaction {
+        id: format!("{transaction_hash:#x}"),
+        transaction_hash: format!("{transaction_hash:#x}"),
+        sender_address: format!("{sender:#x}"),
+        calldata: format!("{:#x}/{:#x}/", Felt::ONE, Felt::TWO),
+        max_fee: format!("{:#x}", Felt::ONE),
+        signature: String::new(),
+        nonce: format!("{:#x}", Felt::ZERO),
+        transaction_type: "INVOKE".to_string(),
+        executed_at: Utc::now(),
+        created_at: Utc::now(),
+    };
+
+    transaction::Service::process_transaction(&manager, transaction(ACCOUNT, Felt::ONE))
+        .await
+        .unwrap();
+    let response = all.try_recv().unwrap().unwrap().transaction.unwrap();
+    assert_eq!(response.sender_address, ACCOUNT.to_bytes_be().to_vec());
+    assert_eq!(
+        response.calldata,
+        vec![Felt::ONE.to_bytes_be().to_vec(), Felt::TWO.to_bytes_be().to_vec()]
+    );
+    assert!(response.signature.is_empty());
+    assert_eq!(received_transaction(&mut by_sender), Some(Felt::ONE));
+
+    // the transactions of the other senders are filtered out
+    transaction::Service::process_transaction(&manager, transaction(OTHER, Felt::TWO))
+        .await
+        .unwrap();
+    assert_eq!(received_transaction(&mut all), Some(Felt::TWO));
+    assert_eq!(received_transaction(&mut by_sender), None);
+}
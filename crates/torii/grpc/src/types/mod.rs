@@ -22,6 +22,7 @@ pub struct IndexerUpdate {
     pub tps: i64,
     pub last_block_timestamp: i64,
     pub contract_address: Felt,
+    pub rollback: bool,
 }
 
 impl From<proto::world::SubscribeIndexerResponse> for IndexerUpdate {
@@ -31,6 +32,7 @@ impl From<proto::world::SubscribeIndexerResponse> for IndexerUpdate {
             tps: value.tps,
             last_block_timestamp: value.last_block_timestamp,
             contract_address: Felt::from_bytes_be_slice(&value.contract_address),
+            rollback: value.rollback,
         }
     }
 }
@@ -41,6 +43,38 @@ pub struct Query {
     pub limit: u32,
     pub offset: u32,
     pub dont_include_hashed_keys: bool,
+    /// Block at which to retrieve the entities of the historical models, the latest state if
+    /// `None`.
+    pub at_block: Option<u64>,
+    /// Model members to order the entities by, the most recently updated first if empty.
+    pub order_by: Vec<OrderBy>,
+    /// Cursor of the page to retrieve, returned with the previous page.
+    pub cursor: Option<String>,
+    /// World to retrieve the models of, all the indexed worlds if `None`.
+    pub world_address: Option<Felt>,
+    /// Retrieves the entities of the historical models as they were at the end of the range, only
+    /// with the models updated within it.
+    pub time_range: Option<TimeRange>,
+}
+
+/// Unix timestamps of the start and the end of a range, inclusive.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
+pub struct TimeRange {
+    pub from_timestamp: u64,
+    pub to_timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
+pub struct OrderBy {
+    pub model: String,
+    pub member: String,
+    pub direction: OrderDirection,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
+pub enum OrderDirection {
+    Asc,
+    Desc,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
@@ -115,6 +149,7 @@ pub enum ComparisonOperator {
     Gte,
     Lt,
     Lte,
+    Search,
 }
 
 impl fmt::Display for ComparisonOperator {
@@ -126,6 +161,7 @@ impl fmt::Display for ComparisonOperator {
             ComparisonOperator::Lte => write!(f, "<="),
             ComparisonOperator::Neq => write!(f, "!="),
             ComparisonOperator::Eq => write!(f, "="),
+            ComparisonOperator::Search => write!(f, "MATCH"),
         }
     }
 }
@@ -139,6 +175,7 @@ impl From<proto::types::ComparisonOperator> for ComparisonOperator {
             proto::types::ComparisonOperator::Lt => ComparisonOperator::Lt,
             proto::types::ComparisonOperator::Lte => ComparisonOperator::Lte,
             proto::types::ComparisonOperator::Neq => ComparisonOperator::Neq,
+            proto::types::ComparisonOperator::Search => ComparisonOperator::Search,
         }
     }
 }
@@ -204,6 +241,33 @@ impl From<Query> for proto::types::Query {
             limit: value.limit,
             offset: value.offset,
             dont_include_hashed_keys: value.dont_include_hashed_keys,
+            at_block: value.at_block,
+            order_by: value.order_by.into_iter().map(|order_by| order_by.into()).collect(),
+            cursor: value.cursor.unwrap_or_default(),
+            world_address: value
+                .world_address
+                .map(|world_address| world_address.to_bytes_be().to_vec())
+                .unwrap_or_default(),
+            time_range: value.time_range.map(|time_range| time_range.into()),
+        }
+    }
+}
+
+impl From<TimeRange> for proto::types::TimeRange {
+    fn from(value: TimeRange) -> Self {
+        Self { from_timestamp: value.from_timestamp, to_timestamp: value.to_timestamp }
+    }
+}
+
+impl From<OrderBy> for proto::types::OrderBy {
+    fn from(value: OrderBy) -> Self {
+        Self {
+            model: value.model,
+            member: value.member,
+            direction: match value.direction {
+                OrderDirection::Asc => proto::types::OrderDirection::Asc as i32,
+                OrderDirection::Desc => proto::types::OrderDirection::Desc as i32,
+            },
         }
     }
 }
@@ -413,10 +477,23 @@ pub struct EventQuery {
     pub keys: KeysClause,
     pub limit: u32,
     pub offset: u32,
+    /// Cursor of the page to retrieve, returned with the previous page.
+    pub cursor: Option<String>,
+    /// World which emitted the events, any of the indexed worlds if `None`.
+    pub world_address: Option<Felt>,
 }
 
 impl From<EventQuery> for proto::types::EventQuery {
     fn from(value: EventQuery) -> Self {
-        Self { keys: Some(value.keys.into()), limit: value.limit, offset: value.offset }
+        Self {
+            keys: Some(value.keys.into()),
+            limit: value.limit,
+            offset: value.offset,
+            cursor: value.cursor.unwrap_or_default(),
+            world_address: value
+                .world_address
+                .map(|world_address| world_address.to_bytes_be().to_vec())
+                .unwrap_or_default(),
+        }
     }
 }
@@ -26,6 +26,8 @@ pub enum SchemaError {
 pub struct Entity {
     pub hashed_keys: Felt,
     pub models: Vec<Struct>,
+    /// The world which set the entity.
+    pub world_address: Felt,
 }
 
 impl TryFrom<proto::types::Entity> for Entity {
@@ -38,10 +40,67 @@ impl TryFrom<proto::types::Entity> for Entity {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<Vec<_>, _>>()?,
+            world_address: Felt::from_bytes_be_slice(&entity.world_address),
         })
     }
 }
 
+/// The changes of an entity since its previous diff, received by the entity subscriptions
+/// receiving diffs.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
+pub struct EntityDiff {
+    pub hashed_keys: Felt,
+    /// The updated models, with only their changed members.
+    pub models: Vec<Struct>,
+    /// Names of the models removed from the entity.
+    pub deleted_models: Vec<String>,
+    /// Whether the entity has been deleted, along with all of its models.
+    pub deleted: bool,
+    pub sequence: u64,
+    /// Sequence number of the previous diff of the entity, 0 for its first one.
+    pub previous_sequence: u64,
+    /// The world which set the entity.
+    pub world_address: Felt,
+}
+
+impl TryFrom<proto::types::EntityDiff> for EntityDiff {
+    type Error = SchemaError;
+    fn try_from(diff: proto::types::EntityDiff) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hashed_keys: Felt::from_bytes_be_slice(&diff.hashed_keys),
+            models: diff
+                .models
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?,
+            deleted_models: diff.deleted_models,
+            deleted: diff.deleted,
+            sequence: diff.sequence,
+            previous_sequence: diff.previous_sequence,
+            world_address: Felt::from_bytes_be_slice(&diff.world_address),
+        })
+    }
+}
+
+/// An update of an entity subscription receiving diffs.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Hash, Eq, Clone)]
+pub enum EntityDiffUpdate {
+    Diff(EntityDiff),
+    /// The entities held by the subscriber must be fetched again, as the following diffs only
+    /// apply to their current state. Also received when the subscription starts.
+    Resync,
+}
+
+impl TryFrom<proto::world::SubscribeEntityResponse> for EntityDiffUpdate {
+    type Error = SchemaError;
+    fn try_from(response: proto::world::SubscribeEntityResponse) -> Result<Self, Self::Error> {
+        match response.diff {
+            Some(diff) if !response.resync => Ok(Self::Diff(diff.try_into()?)),
+            _ => Ok(Self::Resync),
+        }
+    }
+}
+
 impl From<Ty> for proto::types::Ty {
     fn from(ty: Ty) -> Self {
         let ty_type = match ty {
@@ -251,13 +251,38 @@ impl<P: Provider + Sync> Relay<P> {
                             let entity_id = poseidon_hash_many(&keys);
                             let model_id = ty_model_id(&ty).unwrap();
 
+                            // the entity is set in the world of the model, which must be the only
+                            // one registering it
+                            let world_address = match self.db.model_worlds(model_id).await {
+                                Ok(worlds) if worlds.len() == 1 => worlds[0],
+                                Ok(worlds) => {
+                                    warn!(
+                                        target: LOG_TARGET,
+                                        model = %ty.name(),
+                                        worlds = worlds.len(),
+                                        "Message model must be registered by a single world."
+                                    );
+                                    continue;
+                                }
+                                Err(e) => {
+                                    warn!(
+                                        target: LOG_TARGET,
+                                        error = %e,
+                                        "Fetching model worlds."
+                                    );
+                                    continue;
+                                }
+                            };
+
                             // select only identity field, if doesn't exist, empty string
                             let query = format!(
-                                "SELECT external_identity FROM [{}] WHERE id = ?",
+                                "SELECT external_identity FROM [{}] WHERE id = ? AND \
+                                 world_address = ?",
                                 ty.name()
                             );
                             let entity_identity: Option<String> = match sqlx::query_scalar(&query)
                                 .bind(format!("{:#x}", entity_id))
+                                .bind(format!("{:#x}", world_address))
                                 .fetch_optional(&mut *pool)
                                 .await
                             {
@@ -331,6 +356,7 @@ impl<P: Provider + Sync> Relay<P> {
 
                             if let Err(e) = set_entity(
                                 &mut self.db,
+                                world_address,
                                 ty,
                                 &message_id.to_string(),
                                 Utc::now().timestamp() as u64,
@@ -506,7 +532,9 @@ fn read_or_create_identity(path: &Path) -> anyhow::Result<identity::Keypair> {
 
         info!(target: LOG_TARGET, path = %path.display(), "Using existing identity.");
 
-        return Ok(identity::Keypair::from_protobuf_encoding(&bytes)?); // This only works for ed25519 but that is what we are using.
+        return Ok(identity::Keypair::from_protobuf_encoding(&bytes)?); // This only works for
+                                                                       // ed25519 but that is what
+                                                                       // we are using.
     }
 
     let identity = identity::Keypair::generate_ed25519();
@@ -550,8 +578,10 @@ fn get_identity_from_ty(ty: &Ty) -> Result<Felt, Error> {
     Ok(identity)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn set_entity(
     db: &mut Sql,
+    world_address: Felt,
     ty: Ty,
     message_id: &str,
     block_timestamp: u64,
@@ -559,7 +589,8 @@ async fn set_entity(
     model_id: Felt,
     keys: &str,
 ) -> anyhow::Result<()> {
-    db.set_entity(ty, message_id, block_timestamp, entity_id, model_id, Some(keys)).await?;
+    db.set_entity(world_address, ty, message_id, block_timestamp, entity_id, model_id, Some(keys))
+        .await?;
     db.executor.send(QueryMessage::execute())?;
     Ok(())
 }
@@ -585,6 +585,7 @@ mod test {
 
         // Register the model of our Message
         db.register_model(
+            Felt::ZERO,
             "types_test",
             Ty::Struct(Struct {
                 name: "Message".to_string(),
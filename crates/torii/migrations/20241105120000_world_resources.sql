@@ -0,0 +1,60 @@
+-- Namespaces registered in the worlds. Their ids are computed from their names, which several
+-- worlds can share, so they are scoped by world.
+CREATE TABLE namespaces (
+    -- The hash of the namespace, used as resource id for permissions.
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, world_address)
+);
+
+CREATE INDEX idx_namespaces_world_address ON namespaces (world_address);
+
+-- Contracts registered in the worlds. Named `world_contracts` to not be confused with the
+-- `contracts` table, which holds the contracts indexed by Torii.
+CREATE TABLE world_contracts (
+    -- The selector of the contract, computed from its namespace and name.
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    name TEXT NOT NULL,
+    contract_address TEXT NOT NULL,
+    -- The current class hash of the contract.
+    class_hash TEXT NOT NULL,
+    -- All the class hashes of the contract, from the registered one to the current one.
+    class_hashes TEXT NOT NULL,
+    salt TEXT NOT NULL,
+    -- Set once the contract has been initialized through `init_contract`.
+    initialized BOOLEAN NOT NULL DEFAULT FALSE,
+    init_calldata TEXT NOT NULL DEFAULT '',
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, world_address)
+);
+
+CREATE INDEX idx_world_contracts_contract_address ON world_contracts (contract_address);
+CREATE INDEX idx_world_contracts_world_address ON world_contracts (world_address);
+
+-- Owner and writer permissions granted on the resources of the worlds.
+CREATE TABLE permissions (
+    -- resource:contract_address:permission
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    -- The resource selector (world, namespace, model, event or contract).
+    resource TEXT NOT NULL,
+    contract_address TEXT NOT NULL,
+    permission TEXT NOT NULL CHECK(permission IN ('OWNER', 'WRITER')),
+    -- Revoked permissions are kept, with `granted` set to false.
+    granted BOOLEAN NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, world_address)
+);
+
+CREATE INDEX idx_permissions_resource ON permissions (resource);
+CREATE INDEX idx_permissions_contract_address ON permissions (contract_address);
+CREATE INDEX idx_permissions_world_address ON permissions (world_address);
@@ -0,0 +1,6 @@
+-- Hashes of the most recently indexed blocks, used to detect chain reorganizations.
+CREATE TABLE blocks (
+    number INTEGER NOT NULL PRIMARY KEY,
+    hash TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Off-chain metadata of ERC721 and ERC1155 tokens, resolved from their URI
+ALTER TABLE tokens ADD COLUMN metadata TEXT;
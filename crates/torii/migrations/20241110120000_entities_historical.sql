@@ -0,0 +1,22 @@
+-- Versions of the entities of the models indexed with history, used to query the state of an
+-- entity at a past block.
+CREATE TABLE entities_historical (
+    -- No primary key, an entity has one row per version and per model.
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    keys TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    -- The serialized data of the model, empty when the entity has been deleted from it.
+    data TEXT NOT NULL,
+    deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    event_id TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_entities_historical_id_model_id ON entities_historical (
+    id, world_address, model_id, event_id
+);
+CREATE INDEX idx_entities_historical_block_number ON entities_historical (block_number);
@@ -0,0 +1,159 @@
+-- no-transaction
+-- Several worlds can be indexed in the same database. They can register models in the same
+-- namespaces and set entities with the same ids, so the entities, the event messages and the rows
+-- of the model tables are keyed by their world.
+--
+-- The rows indexed before belong to the single world the database was indexing. The tables whose
+-- keys change are rebuilt with the foreign keys disabled, as SQLite requires, which can only be
+-- done outside of the transaction a migration otherwise runs in.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TEMP TABLE indexed_world AS
+SELECT CASE WHEN COUNT(*) = 1 THEN MAX(contract_address) ELSE '' END AS address
+FROM contracts
+WHERE contract_type = 'WORLD';
+
+ALTER TABLE entities ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE entities SET world_address = (SELECT address FROM indexed_world);
+
+ALTER TABLE entity_model ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE entity_model SET world_address = (SELECT address FROM indexed_world);
+
+ALTER TABLE event_messages ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE event_messages SET world_address = (SELECT address FROM indexed_world);
+
+ALTER TABLE event_model ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE event_model SET world_address = (SELECT address FROM indexed_world);
+
+ALTER TABLE event_messages_historical ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE event_messages_historical SET world_address = (SELECT address FROM indexed_world);
+
+-- The world which emitted the event.
+ALTER TABLE events ADD COLUMN world_address TEXT NOT NULL DEFAULT '';
+UPDATE events SET world_address = (SELECT address FROM indexed_world);
+
+CREATE INDEX idx_events_world_address ON events (world_address);
+
+CREATE TABLE entities_new (
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    keys TEXT,
+    event_id TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, world_address)
+);
+
+INSERT INTO entities_new (id, world_address, keys, event_id, executed_at, created_at, updated_at)
+SELECT id, world_address, keys, event_id, executed_at, created_at, updated_at FROM entities;
+
+DROP TABLE entities;
+ALTER TABLE entities_new RENAME TO entities;
+
+CREATE INDEX idx_entities_keys ON entities (keys);
+CREATE INDEX idx_entities_event_id ON entities (event_id);
+CREATE INDEX idx_entities_world_address ON entities (world_address);
+
+CREATE TABLE entity_model_new (
+    entity_id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    UNIQUE (entity_id, world_address, model_id),
+    FOREIGN KEY (entity_id, world_address) REFERENCES entities (id, world_address),
+    FOREIGN KEY (model_id) REFERENCES models (id)
+);
+
+INSERT INTO entity_model_new (entity_id, world_address, model_id)
+SELECT entity_id, world_address, model_id FROM entity_model;
+
+DROP TABLE entity_model;
+ALTER TABLE entity_model_new RENAME TO entity_model;
+
+CREATE INDEX idx_entity_model_entity_id ON entity_model (entity_id);
+CREATE INDEX idx_entity_model_model_id ON entity_model (model_id);
+CREATE INDEX idx_entity_model_world_address ON entity_model (world_address);
+
+CREATE TABLE event_messages_new (
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    keys TEXT,
+    event_id TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id, world_address)
+);
+
+INSERT INTO event_messages_new (
+    id, world_address, keys, event_id, executed_at, created_at, updated_at
+)
+SELECT id, world_address, keys, event_id, executed_at, created_at, updated_at
+FROM event_messages;
+
+DROP TABLE event_messages;
+ALTER TABLE event_messages_new RENAME TO event_messages;
+
+CREATE INDEX idx_event_messages_keys ON event_messages (keys);
+CREATE INDEX idx_event_messages_event_id ON event_messages (event_id);
+CREATE INDEX idx_event_messages_world_address ON event_messages (world_address);
+
+CREATE TABLE event_model_new (
+    entity_id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    historical_counter BIGINT DEFAULT 0,
+    UNIQUE (entity_id, world_address, model_id),
+    FOREIGN KEY (entity_id, world_address) REFERENCES event_messages (id, world_address),
+    FOREIGN KEY (model_id) REFERENCES models (id)
+);
+
+INSERT INTO event_model_new (entity_id, world_address, model_id, historical_counter)
+SELECT entity_id, world_address, model_id, historical_counter FROM event_model;
+
+DROP TABLE event_model;
+ALTER TABLE event_model_new RENAME TO event_model;
+
+CREATE INDEX idx_event_model_event_id ON event_model (entity_id);
+CREATE INDEX idx_event_model_model_id ON event_model (model_id);
+CREATE INDEX idx_event_model_world_address ON event_model (world_address);
+
+-- The worlds which registered each model, a model being shared by the worlds registering it in
+-- the same namespace.
+CREATE TABLE world_models (
+    world_address TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    class_hash TEXT NOT NULL,
+    contract_address TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (world_address, model_id),
+    FOREIGN KEY (model_id) REFERENCES models (id)
+);
+
+CREATE INDEX idx_world_models_model_id ON world_models (model_id);
+
+INSERT INTO world_models (world_address, model_id, class_hash, contract_address, executed_at)
+SELECT indexed_world.address, id, class_hash, contract_address, executed_at
+FROM models, indexed_world
+WHERE indexed_world.address != '';
+
+-- The model tables are named after their models, so they are rebuilt by Torii once migrated, see
+-- `torii_core::sql::world_scope`.
+CREATE TABLE world_scope_model_tables (
+    name TEXT NOT NULL PRIMARY KEY,
+    world_address TEXT NOT NULL
+);
+
+INSERT INTO world_scope_model_tables (name, world_address)
+SELECT name, indexed_world.address
+FROM sqlite_master, indexed_world
+WHERE type = 'table' AND name IN (SELECT id FROM model_members);
+
+DROP TABLE indexed_world;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
@@ -0,0 +1,8 @@
+-- Tables storing the events of the custom contracts, decoded from their ABI. A table is created
+-- when the first event of its type is indexed.
+CREATE TABLE custom_event_tables (
+    name TEXT NOT NULL PRIMARY KEY,
+    contract_address TEXT NOT NULL,
+    event_name TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,19 @@
+-- Values of the model members indexed for full-text search, `id` being the one of the row of the
+-- model table storing the member, whose rows are keyed by world.
+CREATE TABLE search_documents (
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    model TEXT NOT NULL,
+    member TEXT NOT NULL,
+    value TEXT NOT NULL,
+    UNIQUE (id, world_address, model, member)
+);
+
+-- Full-text index of the documents, with prefix indexes for the search as you type.
+CREATE VIRTUAL TABLE search_index USING fts5(
+    value,
+    content = 'search_documents',
+    content_rowid = 'rowid',
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
@@ -0,0 +1,13 @@
+-- Indexes of the model tables declared in the config file, created when their model is
+-- registered or upgraded.
+CREATE TABLE model_indexes (
+    name TEXT NOT NULL PRIMARY KEY,
+    model_id TEXT NOT NULL,
+    table_name TEXT NOT NULL,
+    -- Comma-separated paths of the indexed members
+    members TEXT NOT NULL,
+    condition TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_model_indexes_model_id ON model_indexes (model_id);
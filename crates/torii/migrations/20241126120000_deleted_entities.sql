@@ -0,0 +1,12 @@
+-- Tombstones of the entities deleted from their models, one per model, so that a reorganization
+-- can restore them. Only those of the blocks a reorganization can roll back are kept.
+CREATE TABLE deleted_entities (
+    id TEXT NOT NULL,
+    world_address TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    keys TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    PRIMARY KEY (id, world_address, model_id)
+);
+
+CREATE INDEX idx_deleted_entities_event_id ON deleted_entities (event_id);
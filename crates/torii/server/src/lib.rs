@@ -1 +1,2 @@
 pub mod proxy;
+pub mod sql;
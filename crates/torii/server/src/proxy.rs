@@ -1,9 +1,10 @@
 use std::convert::Infallible;
 use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
-use http::header::CONTENT_TYPE;
+use http::header::{CONTENT_SECURITY_POLICY, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS};
 use http::{HeaderName, Method};
 use hyper::client::connect::dns::GaiResolver;
 use hyper::client::HttpConnector;
@@ -13,10 +14,15 @@ use hyper::{Body, Client, Request, Response, Server, StatusCode};
 use hyper_reverse_proxy::ReverseProxy;
 use serde_json::json;
 use tokio::sync::RwLock;
+use torii_grpc::server::rest::RestApi;
 use tower::ServiceBuilder;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::error;
 
+use crate::sql::SqlEndpoint;
+
+pub(crate) const LOG_TARGET: &str = "torii::server::proxy";
+
 const DEFAULT_ALLOW_HEADERS: [&str; 13] = [
     "accept",
     "origin",
@@ -59,6 +65,9 @@ pub struct Proxy {
     allowed_origins: Option<Vec<String>>,
     grpc_addr: Option<SocketAddr>,
     graphql_addr: Arc<RwLock<Option<SocketAddr>>>,
+    artifacts_dir: Option<PathBuf>,
+    sql: Option<Arc<SqlEndpoint>>,
+    rest: Option<Arc<RestApi>>,
 }
 
 impl Proxy {
@@ -67,8 +76,19 @@ impl Proxy {
         allowed_origins: Option<Vec<String>>,
         grpc_addr: Option<SocketAddr>,
         graphql_addr: Option<SocketAddr>,
+        artifacts_dir: Option<PathBuf>,
+        sql: Option<SqlEndpoint>,
+        rest: Option<RestApi>,
     ) -> Self {
-        Self { addr, allowed_origins, grpc_addr, graphql_addr: Arc::new(RwLock::new(graphql_addr)) }
+        Self {
+            addr,
+            allowed_origins,
+            grpc_addr,
+            graphql_addr: Arc::new(RwLock::new(graphql_addr)),
+            artifacts_dir,
+            sql: sql.map(Arc::new),
+            rest: rest.map(Arc::new),
+        }
     }
 
     pub async fn set_graphql_addr(&self, addr: SocketAddr) {
@@ -84,6 +104,9 @@ impl Proxy {
         let allowed_origins = self.allowed_origins.clone();
         let grpc_addr = self.grpc_addr;
         let graphql_addr = self.graphql_addr.clone();
+        let artifacts_dir = self.artifacts_dir.clone();
+        let sql = self.sql.clone();
+        let rest = self.rest.clone();
 
         let make_svc = make_service_fn(move |conn: &AddrStream| {
             let remote_addr = conn.remote_addr().ip();
@@ -121,11 +144,26 @@ impl Proxy {
                 });
 
             let graphql_addr_clone = graphql_addr.clone();
+            let artifacts_dir_clone = artifacts_dir.clone();
+            let sql_clone = sql.clone();
+            let rest_clone = rest.clone();
             let service = ServiceBuilder::new().option_layer(cors).service_fn(move |req| {
                 let graphql_addr = graphql_addr_clone.clone();
+                let artifacts_dir = artifacts_dir_clone.clone();
+                let sql = sql_clone.clone();
+                let rest = rest_clone.clone();
                 async move {
                     let graphql_addr = graphql_addr.read().await;
-                    handle(remote_addr, grpc_addr, *graphql_addr, req).await
+                    handle(
+                        remote_addr,
+                        grpc_addr,
+                        *graphql_addr,
+                        artifacts_dir.as_deref(),
+                        sql.as_deref(),
+                        rest.as_deref(),
+                        req,
+                    )
+                    .await
                 }
             });
 
@@ -146,8 +184,29 @@ async fn handle(
     client_ip: IpAddr,
     grpc_addr: Option<SocketAddr>,
     graphql_addr: Option<SocketAddr>,
+    artifacts_dir: Option<&Path>,
+    sql: Option<&SqlEndpoint>,
+    rest: Option<&RestApi>,
     req: Request<Body>,
 ) -> Result<Response<Body>, Infallible> {
+    if let Some(path) = req.uri().path().strip_prefix("/static/") {
+        return Ok(serve_artifact(artifacts_dir, path).await);
+    }
+
+    if let Some(path) = req.uri().path().strip_prefix("/sql") {
+        return Ok(match sql {
+            Some(sql) => sql.handle(&path.to_string(), req).await,
+            None => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap(),
+        });
+    }
+
+    if let Some(path) = req.uri().path().strip_prefix("/api") {
+        return Ok(match rest {
+            Some(rest) => rest.handle(&path.to_string(), req).await,
+            None => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap(),
+        });
+    }
+
     if req.uri().path().starts_with("/graphql") {
         if let Some(graphql_addr) = graphql_addr {
             let graphql_addr = format!("http://{}", graphql_addr);
@@ -204,3 +263,71 @@ async fn handle(
         .unwrap();
     Ok(response)
 }
+
+async fn serve_artifact(artifacts_dir: Option<&Path>, path: &str) -> Response<Body> {
+    let not_found =
+        || Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap();
+
+    let Some(artifacts_dir) = artifacts_dir else {
+        return not_found();
+    };
+
+    // Only plain path components are accepted, so nothing outside of the artifacts directory can
+    // be served.
+    let components = path.split('/').collect::<Vec<_>>();
+    if components.iter().any(|c| c.is_empty() || *c == "." || *c == ".." || c.contains('\\')) {
+        return not_found();
+    }
+    let path = components.iter().fold(artifacts_dir.to_path_buf(), |path, c| path.join(c));
+
+    let Some(path) = find_artifact(path).await else {
+        return not_found();
+    };
+
+    match tokio::fs::read(&path).await {
+        // The artifacts are fetched from third parties, so they're sandboxed to prevent an SVG or
+        // a sniffed document from running scripts on the torii origin.
+        Ok(content) => Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, content_type(&path))
+            .header(CONTENT_SECURITY_POLICY, "sandbox")
+            .header(X_CONTENT_TYPE_OPTIONS, "nosniff")
+            .body(Body::from(content))
+            .unwrap(),
+        Err(e) => {
+            error!(target: LOG_TARGET, path = %path.display(), error = %e, "Reading artifact.");
+            not_found()
+        }
+    }
+}
+
+// Token images are stored with an extension depending on their format, but are requested without
+// it, e.g. `/static/<contract_address>/<token_id>/image`.
+async fn find_artifact(path: PathBuf) -> Option<PathBuf> {
+    if tokio::fs::metadata(&path).await.map(|metadata| metadata.is_file()).unwrap_or(false) {
+        return Some(path);
+    }
+
+    let name = path.file_name()?.to_str()?.to_string();
+    let mut entries = tokio::fs::read_dir(path.parent()?).await.ok()?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let entry_path = entry.path();
+        if entry_path.file_stem().and_then(|stem| stem.to_str()) == Some(name.as_str()) {
+            return Some(entry_path);
+        }
+    }
+
+    None
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,402 @@
+use std::time::{Duration, Instant};
+
+use futures_util::TryStreamExt;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::body::HttpBody;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Executor, Row, SqlitePool, Statement, TypeInfo, ValueRef};
+
+/// Number of virtual machine instructions between two checks of the statement timeout.
+const PROGRESS_HANDLER_OPS: i32 = 1000;
+
+/// Result code of a statement interrupted by the progress handler.
+const SQLITE_INTERRUPT: &str = "9";
+
+/// Maximum size in bytes of the body of a `POST /sql` request.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Read-only SQL endpoint, for ad-hoc queries on the indexed data.
+///
+/// - `/sql?query=<query>` (or `POST /sql` with the query as body) runs a query, returned as JSON or
+///   as CSV with `format=csv`.
+/// - `/sql/schema` describes the tables and their columns, with the models they store.
+#[derive(Debug, Clone)]
+pub struct SqlEndpoint {
+    // the connections of the pool must be read-only
+    pool: SqlitePool,
+    timeout: Duration,
+    row_limit: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Debug)]
+struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    truncated: bool,
+}
+
+impl SqlEndpoint {
+    /// Creates the endpoint from a pool of read-only connections. Statements running longer than
+    /// `timeout` are interrupted, and at most `row_limit` rows are returned by a query.
+    pub fn new(pool: SqlitePool, timeout: Duration, row_limit: usize) -> Self {
+        Self { pool, timeout, row_limit }
+    }
+
+    pub async fn handle(&self, path: &str, req: Request<Body>) -> Response<Body> {
+        match path {
+            "" | "/" => self.handle_query(req).await,
+            "/schema" => match self.schema().await {
+                Ok(schema) => json_response(StatusCode::OK, &schema),
+                Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+            },
+            _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap(),
+        }
+    }
+
+    async fn handle_query(&self, req: Request<Body>) -> Response<Body> {
+        let params = req
+            .uri()
+            .query()
+            .map(|query| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+            .unwrap_or_else(Vec::<(String, String)>::new);
+        let param = |name: &str| params.iter().find(|(key, _)| key == name).map(|(_, v)| v.clone());
+
+        let format = match param("format").as_deref() {
+            None | Some("json") => Format::Json,
+            Some("csv") => Format::Csv,
+            Some(format) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unsupported format `{format}`, expected `json` or `csv`"),
+                );
+            }
+        };
+
+        let query = if req.method() == Method::POST {
+            match read_body(req).await {
+                Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+                Err(response) => return response,
+            }
+        } else {
+            param("query").unwrap_or_default()
+        };
+
+        if query.trim().is_empty() {
+            return error_response(StatusCode::BAD_REQUEST, "Missing query".to_string());
+        }
+
+        match self.query(&query).await {
+            Ok(result) => match format {
+                Format::Json => json_response(
+                    StatusCode::OK,
+                    &json!({
+                        "columns": result.columns,
+                        "rows": result.rows,
+                        "truncated": result.truncated,
+                    }),
+                ),
+                Format::Csv => Response::builder()
+                    .status(StatusCode::OK)
+                    .header(CONTENT_TYPE, "text/csv")
+                    .header("x-torii-truncated", result.truncated.to_string())
+                    .body(Body::from(to_csv(&result)))
+                    .unwrap(),
+            },
+            // the statement was interrupted by the progress handler
+            Err(error) if is_interrupted(&error) => error_response(
+                StatusCode::REQUEST_TIMEOUT,
+                format!("Query timed out after {}ms", self.timeout.as_millis()),
+            ),
+            Err(error) => error_response(StatusCode::BAD_REQUEST, error.to_string()),
+        }
+    }
+
+    async fn query(&self, query: &str) -> sqlx::Result<QueryResult> {
+        let mut conn = self.pool.acquire().await?;
+
+        let deadline = Instant::now() + self.timeout;
+        conn.lock_handle()
+            .await?
+            .set_progress_handler(PROGRESS_HANDLER_OPS, move || Instant::now() < deadline);
+
+        let result = async {
+            // the columns are known even if the query returns no rows
+            let statement = (&mut *conn).prepare(query).await?;
+            let columns = statement.columns().iter().map(|c| c.name().to_string()).collect();
+
+            let mut rows = Vec::new();
+            let mut truncated = false;
+
+            let mut stream = statement.query().fetch(&mut *conn);
+            while let Some(row) = stream.try_next().await? {
+                if rows.len() == self.row_limit {
+                    truncated = true;
+                    break;
+                }
+
+                rows.push((0..row.len()).map(|i| column_value(&row, i)).collect());
+            }
+
+            Ok(QueryResult { columns, rows, truncated })
+        }
+        .await;
+
+        // a connection still holding the handler would interrupt all the following queries
+        let removed = conn.lock_handle().await.map(|mut h| h.remove_progress_handler()).is_ok();
+        if !removed {
+            conn.close_on_drop();
+        }
+
+        result
+    }
+
+    async fn schema(&self) -> sqlx::Result<Value> {
+        let mut conn = self.pool.acquire().await?;
+
+        let tables: Vec<(String,)> = sqlx::query_as(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND \
+             name NOT LIKE '_sqlx_%' ORDER BY name",
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        let models: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT id, namespace, name FROM models").fetch_all(&mut *conn).await?;
+
+        let mut schema = Vec::with_capacity(tables.len());
+        for (table,) in tables {
+            let columns: Vec<(String, String, bool, i64)> = sqlx::query_as(
+                "SELECT name, type, \"notnull\", pk FROM pragma_table_info(?) ORDER BY cid",
+            )
+            .bind(&table)
+            .fetch_all(&mut *conn)
+            .await?;
+
+            // the tables of a model are named after its tag, with the path of the nested
+            // members stored in their own tables separated by `$`
+            let model = models.iter().find(|(_, namespace, name)| {
+                let tag = format!("{namespace}-{name}");
+                table == tag || table.starts_with(&format!("{tag}$"))
+            });
+
+            schema.push(json!({
+                "name": table,
+                "model": model.map(|(id, namespace, name)| json!({
+                    "id": id,
+                    "namespace": namespace,
+                    "name": name,
+                })),
+                "columns": columns
+                    .into_iter()
+                    .map(|(name, r#type, not_null, pk)| json!({
+                        "name": name,
+                        "type": r#type,
+                        "notNull": not_null,
+                        "primaryKey": pk > 0,
+                    }))
+                    .collect::<Vec<_>>(),
+            }));
+        }
+
+        Ok(Value::Array(schema))
+    }
+}
+
+fn is_interrupted(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(error) => error.code().as_deref() == Some(SQLITE_INTERRUPT),
+        _ => false,
+    }
+}
+
+// Values are typed after their storage class, blobs being returned as hex strings.
+fn column_value(row: &SqliteRow, index: usize) -> Value {
+    let type_name = match row.try_get_raw(index) {
+        Ok(value) if !value.is_null() => value.type_info().name().to_string(),
+        _ => return Value::Null,
+    };
+
+    let value = match type_name.as_str() {
+        "INTEGER" => row.try_get_unchecked::<i64, _>(index).map(Value::from),
+        "REAL" => row.try_get_unchecked::<f64, _>(index).map(Value::from),
+        "BLOB" => row.try_get_unchecked::<Vec<u8>, _>(index).map(|bytes| {
+            Value::from(bytes.iter().fold("0x".to_string(), |hex, b| hex + &format!("{b:02x}")))
+        }),
+        _ => row.try_get_unchecked::<String, _>(index).map(Value::from),
+    };
+
+    value.unwrap_or(Value::Null)
+}
+
+fn to_csv(result: &QueryResult) -> String {
+    let mut csv = String::new();
+    let mut push_record = |fields: Vec<String>| {
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push_str("\r\n");
+    };
+
+    push_record(result.columns.clone());
+    for row in &result.rows {
+        push_record(
+            row.iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                })
+                .collect(),
+        );
+    }
+
+    csv
+}
+
+// Reads the body of a request, rejecting it once larger than `MAX_BODY_SIZE` rather than
+// buffering whatever the client sends.
+async fn read_body(req: Request<Body>) -> Result<Vec<u8>, Response<Body>> {
+    let too_large = || {
+        error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Query larger than {MAX_BODY_SIZE} bytes"),
+        )
+    };
+
+    let content_length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok());
+    if content_length.is_some_and(|length| length > MAX_BODY_SIZE as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = req.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk =
+            chunk.map_err(|error| error_response(StatusCode::BAD_REQUEST, error.to_string()))?;
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+// Fields are quoted when they contain a delimiter, a quote or a line break (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, error: String) -> Response<Body> {
+    json_response(status, &json!({ "error": error }))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn endpoint(timeout: Duration, row_limit: usize) -> SqlEndpoint {
+        let pool =
+            SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        SqlEndpoint::new(pool, timeout, row_limit)
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let endpoint = endpoint(Duration::from_secs(5), 2).await;
+
+        let result = endpoint
+            .query("SELECT 1 AS id, 'a,b' AS name, NULL AS value, x'beef' AS data")
+            .await
+            .unwrap();
+        assert_eq!(result.columns, vec!["id", "name", "value", "data"]);
+        assert_eq!(result.rows, vec![vec![json!(1), json!("a,b"), Value::Null, json!("0xbeef")]]);
+        assert!(!result.truncated);
+        assert_eq!(to_csv(&result), "id,name,value,data\r\n1,\"a,b\",,0xbeef\r\n");
+
+        // the columns of a query without rows
+        let result = endpoint.query("SELECT 1 AS id WHERE FALSE").await.unwrap();
+        assert_eq!(result.columns, vec!["id"]);
+        assert!(result.rows.is_empty());
+
+        let result = endpoint
+            .query(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n LIMIT 3) SELECT x \
+                 FROM n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![json!(1)], vec![json!(2)]]);
+        assert!(result.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout() {
+        let endpoint = endpoint(Duration::from_millis(10), 10).await;
+
+        let error = endpoint
+            .query(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT COUNT(*) \
+                 FROM n",
+            )
+            .await
+            .unwrap_err();
+        assert!(is_interrupted(&error));
+
+        // other errors aren't timeouts, and the handler doesn't interrupt the next queries
+        let error = endpoint.query("SELECT * FROM missing").await.unwrap_err();
+        assert!(!is_interrupted(&error));
+        assert_eq!(endpoint.query("SELECT 1").await.unwrap().rows, vec![vec![json!(1)]]);
+    }
+
+    #[tokio::test]
+    async fn test_query_body_limit() {
+        let endpoint = endpoint(Duration::from_secs(5), 10).await;
+        let post = |body: Body| Request::builder().method(Method::POST).body(body).unwrap();
+
+        let response = endpoint.handle("", post(Body::from("SELECT 1"))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // rejected from its length, or once read when the length isn't given
+        let query = format!("SELECT '{}'", "a".repeat(MAX_BODY_SIZE));
+        let mut request = post(Body::from(query.clone()));
+        request.headers_mut().insert(CONTENT_LENGTH, query.len().into());
+        let response = endpoint.handle("", request).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            for chunk in query.into_bytes().chunks(1024) {
+                // the receiver is dropped once the limit is exceeded
+                if sender.send_data(chunk.to_vec().into()).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let response = endpoint.handle("", post(body)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}